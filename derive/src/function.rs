@@ -0,0 +1,111 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{Error, FnArg, ItemFn, Pat, Result, ReturnType};
+
+pub fn attribute(item: ItemFn) -> Result<TokenStream> {
+    let ItemFn {
+        attrs,
+        vis,
+        sig,
+        block,
+    } = item;
+
+    if !sig.generics.params.is_empty() || sig.generics.where_clause.is_some() {
+        return Err(Error::new_spanned(
+            &sig.generics,
+            "posh functions cannot be generic",
+        ));
+    }
+
+    if let Some(token) = &sig.asyncness {
+        return Err(Error::new_spanned(token, "posh functions cannot be async"));
+    }
+
+    if let Some(token) = &sig.unsafety {
+        return Err(Error::new_spanned(token, "posh functions cannot be unsafe"));
+    }
+
+    if let Some(variadic) = &sig.variadic {
+        return Err(Error::new_spanned(
+            variadic,
+            "posh functions cannot be variadic",
+        ));
+    }
+
+    if let ReturnType::Default = &sig.output {
+        return Err(Error::new_spanned(
+            &sig,
+            "posh functions must return a value",
+        ));
+    }
+
+    let mut param_idents = Vec::new();
+    let mut param_types = Vec::new();
+
+    for input in &sig.inputs {
+        let input = match input {
+            FnArg::Typed(input) => input,
+            FnArg::Receiver(receiver) => {
+                return Err(Error::new_spanned(
+                    receiver,
+                    "posh functions cannot take `self`",
+                ))
+            }
+        };
+
+        let ident = match &*input.pat {
+            Pat::Ident(pat) if pat.by_ref.is_none() && pat.subpat.is_none() => &pat.ident,
+            pat => {
+                return Err(Error::new_spanned(
+                    pat,
+                    "posh function parameters must be identifiers",
+                ))
+            }
+        };
+
+        param_idents.push(ident.clone());
+        param_types.push((*input.ty).clone());
+    }
+
+    let ident = &sig.ident;
+    let ident_str = ident.to_string();
+    let inputs = &sig.inputs;
+    let output = &sig.output;
+
+    // Parameter names are prefixed to avoid clashing with GLSL keywords.
+    let param_strings: Vec<_> = param_idents
+        .iter()
+        .map(|ident| format!("arg_{ident}"))
+        .collect();
+    let body_ident = format_ident!("{}_body", ident);
+
+    Ok(quote! {
+        #(#attrs)*
+        #vis #sig {
+            // The function body is traced only once. `Marker` identifies this
+            // function in the cache of traced function definitions.
+            struct Marker;
+
+            fn #body_ident(#inputs) #output #block
+
+            ::posh::internal::call_func_def::<Marker, _>(
+                #ident_str,
+                || vec![
+                    #(
+                        (#param_strings, <#param_types as ::posh::sl::Object>::ty())
+                    ),*
+                ],
+                || #body_ident(
+                    #(
+                        <#param_types as ::posh::sl::Object>::from_arg(#param_strings)
+                    ),*
+                ),
+                vec![
+                    #(
+                        ::posh::sl::Object::expr(&#param_idents)
+                    ),*
+                ],
+            )
+        }
+    })
+}
@@ -1,6 +1,7 @@
 mod block;
 mod r#const;
 mod fs_interface;
+mod function;
 mod interpolant;
 mod uniform;
 mod utils;
@@ -8,7 +9,7 @@ mod value;
 mod vs_interface;
 
 use proc_macro::TokenStream;
-use syn::{parse_macro_input, DeriveInput};
+use syn::{parse_macro_input, DeriveInput, ItemFn};
 
 /// Derives `Block` for a struct that is generic in `BlockDom`.
 #[proc_macro_derive(Block)]
@@ -43,6 +44,29 @@ pub fn derive_fs_interface(input: TokenStream) -> TokenStream {
     .into()
 }
 
+/// Turns a function in the shading language into a GLSL function.
+///
+/// The function body is traced only once, and calls to the function are
+/// emitted as calls to a function definition in the generated shader source.
+#[proc_macro_attribute]
+pub fn function(attr: TokenStream, item: TokenStream) -> TokenStream {
+    if !attr.is_empty() {
+        return syn::Error::new(
+            proc_macro2::Span::call_site(),
+            "posh functions do not take arguments",
+        )
+        .to_compile_error()
+        .into();
+    }
+
+    let item = parse_macro_input!(item as ItemFn);
+    match function::attribute(item) {
+        Ok(ts) => ts,
+        Err(e) => e.to_compile_error(),
+    }
+    .into()
+}
+
 /// Derives `Interpolant` for a struct.
 #[proc_macro_derive(Interpolant)]
 pub fn derive_interpolant(input: TokenStream) -> TokenStream {
@@ -36,6 +36,7 @@ fn vertex_shader(input: sl::VsInput<()>) -> sl::Vec4 {
     VERTICES.to_sl().get(input.vertex_id)
 }
 
+#[sl::function]
 fn palette(t: sl::F32) -> sl::Vec3 {
     let a = sl::vec3(0.5, 0.5, 0.5);
     let b = sl::vec3(0.5, 0.5, 0.5);
@@ -49,21 +50,20 @@ fn fragment_shader(Globals { time, resolution }: Globals<Sl>, input: sl::FsInput
     let uv = (input.fragment_coord.xy() * 2.0 - resolution.as_vec2()) / resolution.y.as_f32();
     let len = uv.length();
 
-    (0..4)
-        .scan(uv, |uv, i| {
-            *uv = (*uv * 1.5).fract() - 0.5;
-            Some((i, *uv))
-        })
-        .map(|(i, uv)| {
-            let d = uv.length() * (-len).exp();
-            let d = (d * 8.0 + time).sin() / 8.0;
-            let d = d.abs();
-            let d = (0.01f32 / d).powf(1.2);
+    let init = (uv, sl::Vec3::ZERO);
 
-            d * palette(len + ((i as f32).to_sl() + time) * 0.4)
-        })
-        .sum::<sl::Vec3>()
-        .extend(1.0)
+    let (_, color) = sl::fold_range(0u32, 4u32, init, |(uv, color), i| {
+        let uv = (uv * 1.5).fract() - 0.5;
+
+        let d = uv.length() * (-len).exp();
+        let d = (d * 8.0 + time).sin() / 8.0;
+        let d = d.abs();
+        let d = (0.01f32 / d).powf(1.2);
+
+        (uv, color + d * palette(len + (i.as_f32() + time) * 0.4))
+    });
+
+    color.extend(1.0)
 }
 
 // Host code
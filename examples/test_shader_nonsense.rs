@@ -92,6 +92,8 @@ fn vertex_shader_2(mode: sl::U32, vertex: MyVertex<Sl>) -> sl::VsOutput<MyVertex
 
 fn main() {
     let program_def = posh::sl::transpile::transpile_to_program_def::<sl::U32, _, _, _, _>(
+        Default::default(),
+        Default::default(),
         vertex_shader_2,
         |_: (), _: MyVertex<Sl>| sl::Vec4::ZERO,
     );
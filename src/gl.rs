@@ -5,10 +5,14 @@ mod element_buffer;
 mod framebuffer;
 mod image;
 mod mat;
+mod pixel_pack_buffer;
 mod program;
+mod query;
 mod raw;
+mod renderbuffer;
 mod texture;
 mod uniform_buffer;
+mod uniform_ring;
 mod vec;
 mod vertex_buffer;
 mod vertex_spec;
@@ -23,19 +27,31 @@ pub use context::{CacheDrawBuilder, Context};
 pub use element_buffer::{Element, ElementBuffer, ElementBufferBinding};
 pub use framebuffer::{ColorAttachment, DepthAttachment, Framebuffer};
 pub use mat::{Mat2, Mat3, Mat4};
+pub use pixel_pack_buffer::PixelPackBuffer;
 pub use program::{
-    DrawBuilder, DrawBuilderWithFramebuffer, DrawBuilderWithUniforms,
-    DrawBuilderWithUniformsAndFramebuffer, Program,
+    CaptureBuilderWithUniforms, DrawBuilder, DrawBuilderWithFramebuffer, DrawBuilderWithUniforms,
+    DrawBuilderWithUniformsAndFramebuffer, Program, TransformFeedbackProgram,
 };
+pub use query::{AnySamplesPassed, AnySamplesPassedConservative, Query, QueryKind, QueryScope};
 pub use raw::{
-    BlendEquation, BlendFunc, Blending, BufferError, BufferUsage, Caps, Comparison, ContextError,
-    CreateError, CullFace, DrawError, DrawParams, ElementType, FramebufferError, ImageFormat,
-    ImageInternalFormat, PrimitiveMode, ProgramError, ProgramValidationError, Rect,
+    BlendEquation, BlendFunc, Blending, BufferError, BufferUsage, Caps, ClearColor, ClearValues,
+    Comparison, ContextError, CreateError, CubeFace, CullFace, DrawError, DrawParams, ElementType,
+    Fence, FenceError, FramebufferError, ImageFormat, ImageInternalFormat, PrimitiveMode,
+    ProgramError, ProgramValidationError, QueryError, QueryTarget, Rect, RenderbufferError,
     Sampler2dParams, SamplerMagFilter, SamplerMinFilter, SamplerWrap, StencilOp, StencilOps,
     StencilTest, TextureError, VertexArrayError,
 };
-pub use texture::{ColorSampler2d, ColorTexture2d, ComparisonSampler2d, DepthTexture2d};
+#[cfg(not(target_family = "wasm"))]
+pub use raw::{ObjectKind, RecordedCall, Recorder};
+pub use renderbuffer::{ColorRenderbuffer, DepthRenderbuffer};
+pub use texture::{
+    ColorSampler2d, ColorSampler2dArray, ColorSampler3d, ColorSamplerCube, ColorTexture2d,
+    ColorTexture2dArray, ColorTexture3d, ColorTextureCube, ComparisonSampler2d,
+    ComparisonSampler2dArray, ComparisonSamplerCube, DepthTexture2d, DepthTexture2dArray,
+    DepthTextureCube,
+};
 pub use uniform_buffer::{UniformBuffer, UniformBufferBinding};
+pub use uniform_ring::UniformRing;
 pub use vec::{BVec2, BVec3, BVec4, IVec2, IVec3, IVec4, UVec2, UVec3, UVec4, Vec2, Vec3, Vec4};
 pub use vertex_buffer::{VertexBuffer, VertexBufferBinding};
 pub use vertex_spec::VertexSpec;
@@ -3,6 +3,7 @@ use std::{
     cell::{Cell, RefCell},
     collections::hash_map,
     marker::PhantomData,
+    mem::size_of,
     rc::Rc,
 };
 
@@ -10,24 +11,36 @@ use fxhash::FxHashMap;
 
 use crate::{
     sl::{
-        transpile::{transpile_to_program_def, transpile_to_program_def_with_consts},
+        transpile::{
+            transpile_to_program_def, transpile_to_program_def_with_consts,
+            transpile_to_transform_feedback_program_def, DefaultPrecision, ShaderTarget,
+        },
         ColorSample, FsFunc, FsSig, VsFunc, VsSig,
     },
-    Block, Gl, Sl, Uniform, UniformUnion,
+    Block, FsInterface, Gl, Sl, Uniform, UniformUnion,
 };
 
 use super::{
     program::{DrawBuilder, DrawBuilderWithUniforms},
-    raw, BufferError, BufferUsage, Caps, ColorImage, ColorTexture2d, ContextError, CreateError,
-    DepthImage, DepthTexture2d, DrawError, Element, ElementBuffer, Program, ProgramError,
-    TextureError, UniformBuffer, VertexBuffer,
+    raw, BufferError, BufferUsage, Caps, ClearValues, ColorImage, ColorRenderbuffer,
+    ColorTexture2d, ColorTexture2dArray, ColorTexture3d, ColorTextureCube, ContextError,
+    CreateError, DepthImage, DepthRenderbuffer, DepthTexture2d, DepthTexture2dArray,
+    DepthTextureCube, DrawError, Element, ElementBuffer, Fence, FenceError, Framebuffer,
+    FramebufferError, PixelPackBuffer, Program, ProgramError, Query, QueryError, QueryKind, Rect,
+    RenderbufferError, SamplerMagFilter, TextureError, TransformFeedbackProgram, UniformBuffer,
+    UniformRing, VertexBuffer,
 };
 
+#[cfg(not(target_family = "wasm"))]
+use super::Recorder;
+
 #[derive(Debug, Clone, Hash, PartialEq, Eq)]
 struct ProgramCacheKey {
     vertex_shader: TypeId,
     fragment_shader: TypeId,
     uniform_union: TypeId,
+    shader_target: ShaderTarget,
+    default_precision: DefaultPrecision,
 }
 
 #[derive(Default)]
@@ -39,6 +52,8 @@ impl ProgramCache {
         raw: &raw::Context,
         vertex_shader: VFunc,
         fragment_shader: FFunc,
+        shader_target: ShaderTarget,
+        default_precision: DefaultPrecision,
         log_sources: bool,
     ) -> Result<Program<U, VSig::V, FSig::F>, ProgramError>
     where
@@ -52,12 +67,16 @@ impl ProgramCache {
             vertex_shader: TypeId::of::<VFunc>(),
             fragment_shader: TypeId::of::<FFunc>(),
             uniform_union: TypeId::of::<U>(),
+            shader_target,
+            default_precision,
         };
 
         let raw = match self.0.entry(key) {
             hash_map::Entry::Occupied(entry) => entry.get().clone(),
             hash_map::Entry::Vacant(entry) => {
                 let program_def = transpile_to_program_def::<U, VSig, VFunc, FSig, FFunc>(
+                    shader_target,
+                    default_precision,
                     vertex_shader,
                     fragment_shader,
                 );
@@ -112,6 +131,8 @@ where
                 &self.gl.raw,
                 self.vertex_shader,
                 self.fragment_shader,
+                self.gl.shader_target.get(),
+                self.gl.default_precision.get(),
                 self.gl.enable_program_source_logging.get(),
             );
 
@@ -134,6 +155,8 @@ where
 pub struct Context {
     raw: Rc<raw::Context>,
     program_cache: Rc<RefCell<ProgramCache>>,
+    shader_target: Cell<ShaderTarget>,
+    default_precision: Cell<DefaultPrecision>,
     enable_program_source_logging: Cell<bool>,
 }
 
@@ -144,10 +167,46 @@ impl Context {
         Ok(Self {
             raw: Rc::new(raw),
             program_cache: Default::default(),
+            shader_target: Default::default(),
+            default_precision: Default::default(),
+            enable_program_source_logging: Cell::new(false),
+        })
+    }
+
+    /// Creates a context whose OpenGL calls are recorded by `recorder`
+    /// instead of being executed.
+    ///
+    /// This is meant for testing which GL calls `posh` makes, for example to
+    /// check for redundant state changes or for leaked objects. See
+    /// [`Recorder`] for how the recorder behaves.
+    #[cfg(not(target_family = "wasm"))]
+    pub fn new_recording(recorder: Recorder) -> Result<Self, ContextError> {
+        let raw = raw::Context::new_recording(recorder)?;
+
+        Ok(Self {
+            raw: Rc::new(raw),
+            program_cache: Default::default(),
+            shader_target: Default::default(),
+            default_precision: Default::default(),
             enable_program_source_logging: Cell::new(false),
         })
     }
 
+    /// Creates a context that renders on the CPU instead of using OpenGL.
+    ///
+    /// Shaders are run by evaluating their expressions, so this is slow, but
+    /// it does not need a GPU or a window. Rendered pixels can be read with
+    /// [`Self::read_default_framebuffer_rgba_u8`].
+    pub fn new_software(default_framebuffer_size: [u32; 2]) -> Self {
+        Self {
+            raw: Rc::new(raw::Context::new_software(default_framebuffer_size)),
+            program_cache: Default::default(),
+            shader_target: Default::default(),
+            default_precision: Default::default(),
+            enable_program_source_logging: Cell::new(false),
+        }
+    }
+
     pub fn caps(&self) -> &Caps {
         self.raw.caps()
     }
@@ -195,6 +254,54 @@ impl Context {
         UniformBuffer::new(&self.raw, &data, usage)
     }
 
+    /// Creates a buffer that can store `len` components of pixels that are
+    /// read from textures.
+    ///
+    /// The buffer is initialized with zeros. Its usage should usually be
+    /// [`BufferUsage::StreamRead`].
+    pub fn create_pixel_pack_buffer<T>(
+        &self,
+        len: usize,
+        usage: BufferUsage,
+    ) -> Result<PixelPackBuffer<T>, BufferError>
+    where
+        T: bytemuck::Pod,
+    {
+        let raw = self.raw.create_buffer(
+            &vec![0; len * size_of::<T>()],
+            glow::PIXEL_PACK_BUFFER,
+            usage,
+        )?;
+
+        Ok(PixelPackBuffer::from_raw(raw))
+    }
+
+    /// Creates a buffer that stores multiple uniform blocks, each of which
+    /// can be bound separately.
+    ///
+    /// Each block is padded to a multiple of
+    /// [`Caps::uniform_buffer_offset_alignment`].
+    pub fn create_uniform_buffer_slice<B>(
+        &self,
+        data: &[B::Gl],
+        usage: BufferUsage,
+    ) -> Result<UniformBuffer<[B]>, BufferError>
+    where
+        B: Block<Gl>,
+    {
+        UniformBuffer::new_slice(&self.raw, data, usage)
+    }
+
+    /// Creates a ring allocator for transient uniform blocks with a buffer of
+    /// `capacity` bytes.
+    ///
+    /// The capacity should be large enough to hold the uniform blocks of all
+    /// frames that the GPU may be processing at the same time, usually two or
+    /// three.
+    pub fn create_uniform_ring(&self, capacity: usize) -> Result<UniformRing, BufferError> {
+        UniformRing::new(self.raw.clone(), capacity)
+    }
+
     pub fn create_color_texture_2d<S: ColorSample>(
         &self,
         image: ColorImage<'_, S>,
@@ -224,6 +331,173 @@ impl Context {
         Ok(DepthTexture2d::from_raw(raw))
     }
 
+    /// Creates a two-dimensional array texture with one layer per image.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the images differ in size.
+    pub fn create_color_texture_2d_array<'a, S: ColorSample>(
+        &self,
+        layers: impl IntoIterator<Item = ColorImage<'a, S>>,
+    ) -> Result<ColorTexture2dArray<S>, TextureError> {
+        let layers: Vec<_> = layers.into_iter().map(ColorImage::raw).collect();
+        let raw = self.raw.create_texture_2d_array(&layers)?;
+
+        Ok(ColorTexture2dArray::from_raw(raw))
+    }
+
+    /// Creates a two-dimensional array texture with one layer per image and
+    /// generates mipmaps for each layer.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the images differ in size.
+    pub fn create_color_texture_2d_array_with_mipmap<'a, S: ColorSample>(
+        &self,
+        layers: impl IntoIterator<Item = ColorImage<'a, S>>,
+    ) -> Result<ColorTexture2dArray<S>, TextureError> {
+        let layers: Vec<_> = layers.into_iter().map(ColorImage::raw).collect();
+        let raw = self.raw.create_texture_2d_array_with_mipmap(&layers)?;
+
+        Ok(ColorTexture2dArray::from_raw(raw))
+    }
+
+    /// Creates a two-dimensional array depth texture with one layer per image.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the images differ in size.
+    pub fn create_depth_texture_2d_array<'a>(
+        &self,
+        layers: impl IntoIterator<Item = DepthImage<'a>>,
+    ) -> Result<DepthTexture2dArray, TextureError> {
+        let layers: Vec<_> = layers.into_iter().map(DepthImage::raw).collect();
+        let raw = self.raw.create_texture_2d_array(&layers)?;
+
+        Ok(DepthTexture2dArray::from_raw(raw))
+    }
+
+    /// Creates a three-dimensional texture whose slices along the third
+    /// dimension are given by the images.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the images differ in size.
+    pub fn create_color_texture_3d<'a, S: ColorSample>(
+        &self,
+        slices: impl IntoIterator<Item = ColorImage<'a, S>>,
+    ) -> Result<ColorTexture3d<S>, TextureError> {
+        let slices: Vec<_> = slices.into_iter().map(ColorImage::raw).collect();
+        let raw = self.raw.create_texture_3d(&slices)?;
+
+        Ok(ColorTexture3d::from_raw(raw))
+    }
+
+    /// Creates a three-dimensional texture whose slices along the third
+    /// dimension are given by the images, and generates mipmaps for it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the images differ in size.
+    pub fn create_color_texture_3d_with_mipmap<'a, S: ColorSample>(
+        &self,
+        slices: impl IntoIterator<Item = ColorImage<'a, S>>,
+    ) -> Result<ColorTexture3d<S>, TextureError> {
+        let slices: Vec<_> = slices.into_iter().map(ColorImage::raw).collect();
+        let raw = self.raw.create_texture_3d_with_mipmap(&slices)?;
+
+        Ok(ColorTexture3d::from_raw(raw))
+    }
+
+    /// Creates a cube map whose faces are given by the images, in the order of
+    /// [`CubeFace::ALL`](super::CubeFace::ALL).
+    ///
+    /// # Panics
+    ///
+    /// Panics if the images differ in size, or if they are not square.
+    pub fn create_color_texture_cube<S: ColorSample>(
+        &self,
+        faces: [ColorImage<'_, S>; 6],
+    ) -> Result<ColorTextureCube<S>, TextureError> {
+        let raw = self.raw.create_texture_cube(&faces.map(ColorImage::raw))?;
+
+        Ok(ColorTextureCube::from_raw(raw))
+    }
+
+    /// Creates a cube map whose faces are given by the images, and generates
+    /// mipmaps for it.
+    ///
+    /// See [`Self::create_color_texture_cube`].
+    pub fn create_color_texture_cube_with_mipmap<S: ColorSample>(
+        &self,
+        faces: [ColorImage<'_, S>; 6],
+    ) -> Result<ColorTextureCube<S>, TextureError> {
+        let raw = self
+            .raw
+            .create_texture_cube_with_mipmap(&faces.map(ColorImage::raw))?;
+
+        Ok(ColorTextureCube::from_raw(raw))
+    }
+
+    /// Creates a depth cube map whose faces are given by the images, in the
+    /// order of [`CubeFace::ALL`](super::CubeFace::ALL).
+    ///
+    /// # Panics
+    ///
+    /// Panics if the images differ in size, or if they are not square.
+    pub fn create_depth_texture_cube(
+        &self,
+        faces: [DepthImage<'_>; 6],
+    ) -> Result<DepthTextureCube, TextureError> {
+        let raw = self.raw.create_texture_cube(&faces.map(DepthImage::raw))?;
+
+        Ok(DepthTextureCube::from_raw(raw))
+    }
+
+    /// Creates a color renderbuffer with `samples` samples per pixel, or a
+    /// single-sampled one if `samples` is zero.
+    ///
+    /// Only the size and internal format of `image` are used, so it is
+    /// typically created with a `_zero` constructor.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `image` has data.
+    pub fn create_color_renderbuffer<S: ColorSample>(
+        &self,
+        image: ColorImage<'_, S>,
+        samples: u32,
+    ) -> Result<ColorRenderbuffer<S>, RenderbufferError> {
+        let image = image.raw();
+
+        assert!(image.data.is_none(), "renderbuffers cannot have data");
+
+        let raw = self
+            .raw
+            .create_renderbuffer(image.size, image.internal_format, samples)?;
+
+        Ok(ColorRenderbuffer::from_raw(raw))
+    }
+
+    /// Creates a depth renderbuffer with `samples` samples per pixel.
+    ///
+    /// See [`Self::create_color_renderbuffer`].
+    pub fn create_depth_renderbuffer(
+        &self,
+        image: DepthImage<'_>,
+        samples: u32,
+    ) -> Result<DepthRenderbuffer, RenderbufferError> {
+        let image = image.raw();
+
+        assert!(image.data.is_none(), "renderbuffers cannot have data");
+
+        let raw = self
+            .raw
+            .create_renderbuffer(image.size, image.internal_format, samples)?;
+
+        Ok(DepthRenderbuffer::from_raw(raw))
+    }
+
     pub fn create_program<U, VSig, VFunc, FSig, FFunc>(
         &self,
         vertex_shader: VFunc,
@@ -236,8 +510,12 @@ impl Context {
         FSig: FsSig<C = (), W = VSig::W>,
         FFunc: FsFunc<FSig>,
     {
-        let program_def =
-            transpile_to_program_def::<U, VSig, VFunc, FSig, FFunc>(vertex_shader, fragment_shader);
+        let program_def = transpile_to_program_def::<U, VSig, VFunc, FSig, FFunc>(
+            self.shader_target.get(),
+            self.default_precision.get(),
+            vertex_shader,
+            fragment_shader,
+        );
 
         if self.enable_program_source_logging.get() {
             log::info!("Vertex shader:\n{}", program_def.vertex_shader_source);
@@ -263,6 +541,8 @@ impl Context {
         FFunc: FsFunc<FSig>,
     {
         let program_def = transpile_to_program_def_with_consts::<U, VSig, VFunc, FSig, FFunc>(
+            self.shader_target.get(),
+            self.default_precision.get(),
             consts,
             vertex_shader,
             fragment_shader,
@@ -278,6 +558,38 @@ impl Context {
         Ok(Program::unchecked_from_raw(Rc::new(raw)))
     }
 
+    /// Creates a program whose vertex shader output is captured into a vertex
+    /// buffer with transform feedback.
+    ///
+    /// The output of `vertex_shader` is a [`Block`], so that it is written to
+    /// the buffer with the same layout as the vertices of a
+    /// [`VertexBuffer`].
+    pub fn create_transform_feedback_program<U, VSig, VFunc>(
+        &self,
+        vertex_shader: VFunc,
+    ) -> Result<TransformFeedbackProgram<U, VSig::V, VSig::W>, ProgramError>
+    where
+        U: UniformUnion<VSig::U, ()>,
+        VSig: VsSig<C = ()>,
+        VSig::W: Block<Sl>,
+        VFunc: VsFunc<VSig>,
+    {
+        let program_def = transpile_to_transform_feedback_program_def::<U, VSig, VFunc>(
+            self.shader_target.get(),
+            self.default_precision.get(),
+            vertex_shader,
+        );
+
+        if self.enable_program_source_logging.get() {
+            log::info!("Vertex shader:\n{}", program_def.vertex_shader_source);
+            log::info!("Fragment shader:\n{}", program_def.fragment_shader_source);
+        }
+
+        let raw = self.raw.create_program(program_def)?;
+
+        Ok(TransformFeedbackProgram::unchecked_from_raw(Rc::new(raw)))
+    }
+
     pub fn program<VSig, VFunc, FSig, FFunc>(
         &self,
         vertex_shader: VFunc,
@@ -297,6 +609,70 @@ impl Context {
         }
     }
 
+    /// Creates a fence that is signaled once all commands that have been
+    /// issued so far have completed.
+    pub fn create_fence(&self) -> Result<Fence, FenceError> {
+        self.raw.create_fence()
+    }
+
+    /// Creates an occlusion query, which measures whether the draws in its
+    /// scope passed any samples.
+    pub fn create_query<K: QueryKind>(&self) -> Result<Query<K>, QueryError> {
+        let raw = self.raw.create_query(K::TARGET)?;
+
+        Ok(Query::from_raw(raw))
+    }
+
+    /// Copies `source_rect` of `source` into `destination_rect` of
+    /// `destination`, scaling the pixels with `filter`.
+    ///
+    /// The framebuffers have the same fragment output type, so their color
+    /// attachments are copied pairwise. Depth attachments are copied with
+    /// nearest filtering if both framebuffers have one. Blitting from a
+    /// multisampled renderbuffer resolves it, in which case the rectangles
+    /// must be equal.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a rectangle is not contained in its framebuffer, or if the
+    /// framebuffers belong to a different context.
+    pub fn blit<F: FsInterface<Sl>>(
+        &self,
+        source: &Framebuffer<F>,
+        source_rect: Rect,
+        destination: &Framebuffer<F>,
+        destination_rect: Rect,
+        filter: SamplerMagFilter,
+    ) -> Result<(), FramebufferError> {
+        self.raw.blit(
+            &source.raw(),
+            source_rect,
+            &destination.raw(),
+            destination_rect,
+            filter,
+        )
+    }
+
+    /// Clears the attachments of `framebuffer` to `values`.
+    ///
+    /// This does not need a program, and it is not affected by the draw
+    /// parameters of previous draws. The colors of `values` are given in the
+    /// order of the color attachments of `F`, and each must match the
+    /// component type of its attachment, so that integer attachments are
+    /// cleared with integer values.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `values` has more colors than the framebuffer has color
+    /// attachments.
+    pub fn clear<F: FsInterface<Sl>>(
+        &self,
+        framebuffer: &Framebuffer<F>,
+        values: ClearValues,
+    ) -> Result<(), FramebufferError> {
+        self.raw.clear(&framebuffer.raw(), &values)
+    }
+
     pub fn finish(&self) {
         self.raw.finish();
     }
@@ -309,6 +685,44 @@ impl Context {
         self.raw.set_default_framebuffer_size(size);
     }
 
+    /// Reads RGBA pixels with 8-bit components from the default framebuffer.
+    ///
+    /// Rows are returned from bottom to top.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `rect` is not contained in the default framebuffer.
+    pub fn read_default_framebuffer_rgba_u8(&self, rect: Rect) -> Result<Vec<u8>, TextureError> {
+        self.raw.read_default_framebuffer_rgba_u8(rect)
+    }
+
+    pub fn shader_target(&self) -> ShaderTarget {
+        self.shader_target.get()
+    }
+
+    /// Sets the GLSL dialect that programs created by this context are
+    /// transpiled to.
+    ///
+    /// The default is [`ShaderTarget::GlslEs300`]. Desktop OpenGL core
+    /// profiles may need one of the other targets, since some drivers reject
+    /// GLSL ES shaders.
+    pub fn set_shader_target(&self, target: ShaderTarget) {
+        self.shader_target.set(target);
+    }
+
+    pub fn default_precision(&self) -> DefaultPrecision {
+        self.default_precision.get()
+    }
+
+    /// Sets the default precision qualifiers of the fragment shaders of
+    /// programs that are created afterwards by this context.
+    ///
+    /// This allows choosing the default precision separately for each
+    /// program. The default is `highp` for all types.
+    pub fn set_default_precision(&self, default_precision: DefaultPrecision) {
+        self.default_precision.set(default_precision);
+    }
+
     pub fn set_enable_program_source_logging(&self, value: bool) {
         self.enable_program_source_logging.set(value);
     }
@@ -60,6 +60,27 @@ impl<E: Element> ElementBuffer<E> {
         self.raw.set(bytemuck::cast_slice(data));
     }
 
+    /// Replaces the elements starting at index `offset` with `data`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range is out of bounds.
+    pub fn set_range(&self, offset: usize, data: &[E]) {
+        assert!(offset + data.len() <= self.len());
+
+        self.raw
+            .set_sub_data(offset * size_of::<E>(), bytemuck::cast_slice(data));
+    }
+
+    /// Grows the buffer so that it can hold at least `len` elements.
+    ///
+    /// See [`VertexBuffer::grow`](super::VertexBuffer::grow).
+    pub fn grow(&self, len: usize) {
+        if len > self.len() {
+            self.raw.orphan(len * size_of::<E>());
+        }
+    }
+
     pub fn as_binding(&self) -> ElementBufferBinding {
         ElementBufferBinding {
             raw: self.raw.clone(),
@@ -6,7 +6,10 @@ use crate::{
     FsInterface, Gl, Sl,
 };
 
-use super::{raw, ColorSampler2d, Sampler2dParams};
+use super::{
+    raw::{self, ImageComponentType, ImageFormat},
+    ColorSampler2d, FramebufferError, PixelPackBuffer, Rect, Sampler2dParams, TextureError,
+};
 
 #[derive(Clone)]
 pub struct ColorAttachment<S = sl::Vec4> {
@@ -22,12 +25,194 @@ impl<S> ColorAttachment<S> {
         }
     }
 
+    /// Returns a sampler for the texture of the attachment.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the attachment is a layer of an array texture, a 3D texture or
+    /// a cube map, or if it is a renderbuffer.
     pub fn as_color_sampler(&self, params: Sampler2dParams) -> ColorSampler2d<S> {
         ColorSampler2d::from_raw(self.raw.sampler(params, None))
     }
+
+    /// Reads RGBA pixels with 8-bit components from the attachment.
+    ///
+    /// This is supported for normalized fixed-point internal formats, such as
+    /// [`ImageInternalFormat::RgbaU8`](super::ImageInternalFormat::RgbaU8).
+    /// Channels that the internal format does not have are read as zero,
+    /// except for alpha, which is read as one. Rows are returned from bottom
+    /// to top.
+    pub fn read_rgba_u8(&self, rect: Rect) -> Result<Vec<u8>, TextureError> {
+        let mut data = vec![0; num_pixels(rect) * 4];
+
+        self.raw
+            .read_pixels(rect, ImageFormat::Rgba, ImageComponentType::U8, &mut data)?;
+
+        Ok(data)
+    }
+
+    /// Reads RGBA pixels with 32-bit floating-point components from the
+    /// attachment.
+    ///
+    /// This is supported for floating-point internal formats, such as
+    /// [`ImageInternalFormat::RgbaF32`](super::ImageInternalFormat::RgbaF32).
+    /// OpenGL ES requires the extension `EXT_color_buffer_float`. See
+    /// [`Self::read_rgba_u8`] for how the pixels are returned.
+    pub fn read_rgba_f32(&self, rect: Rect) -> Result<Vec<f32>, TextureError> {
+        let mut data = vec![0.0; num_pixels(rect) * 4];
+
+        self.raw.read_pixels(
+            rect,
+            ImageFormat::Rgba,
+            ImageComponentType::F32,
+            bytemuck::cast_slice_mut(&mut data),
+        )?;
+
+        Ok(data)
+    }
+
+    /// Starts reading RGBA pixels with 8-bit components from the attachment
+    /// into `buffer`, without waiting for the GPU.
+    ///
+    /// See [`Self::read_rgba_u8`] for which formats are supported, and
+    /// [`PixelPackBuffer`] for how to obtain the pixels.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `buffer` is too small to hold the pixels of `rect`.
+    pub fn read_rgba_u8_to_buffer(
+        &self,
+        rect: Rect,
+        buffer: &PixelPackBuffer<u8>,
+    ) -> Result<(), TextureError> {
+        self.raw.read_pixels_to_buffer(
+            rect,
+            ImageFormat::Rgba,
+            ImageComponentType::U8,
+            buffer.raw(),
+            0,
+        )
+    }
+
+    /// Starts reading RGBA pixels with 32-bit floating-point components from
+    /// the attachment into `buffer`, without waiting for the GPU.
+    ///
+    /// See [`Self::read_rgba_f32`] for which formats are supported, and
+    /// [`PixelPackBuffer`] for how to obtain the pixels.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `buffer` is too small to hold the pixels of `rect`.
+    pub fn read_rgba_f32_to_buffer(
+        &self,
+        rect: Rect,
+        buffer: &PixelPackBuffer<f32>,
+    ) -> Result<(), TextureError> {
+        self.raw.read_pixels_to_buffer(
+            rect,
+            ImageFormat::Rgba,
+            ImageComponentType::F32,
+            buffer.raw(),
+            0,
+        )
+    }
+
+    /// Reads RGBA pixels with 32-bit signed integer components from the
+    /// attachment.
+    ///
+    /// This is supported for signed integer internal formats, such as
+    /// [`ImageInternalFormat::RgbaI32Int`](super::ImageInternalFormat::RgbaI32Int).
+    /// See [`Self::read_rgba_u8`] for how the pixels are returned.
+    pub fn read_rgba_i32(&self, rect: Rect) -> Result<Vec<i32>, TextureError> {
+        let mut data = vec![0; num_pixels(rect) * 4];
+
+        self.raw.read_pixels(
+            rect,
+            ImageFormat::RgbaInteger,
+            ImageComponentType::I32,
+            bytemuck::cast_slice_mut(&mut data),
+        )?;
+
+        Ok(data)
+    }
+
+    /// Reads RGBA pixels with 32-bit unsigned integer components from the
+    /// attachment.
+    ///
+    /// This is supported for unsigned integer internal formats, such as
+    /// [`ImageInternalFormat::RU32Int`](super::ImageInternalFormat::RU32Int).
+    /// See [`Self::read_rgba_u8`] for how the pixels are returned.
+    pub fn read_rgba_u32(&self, rect: Rect) -> Result<Vec<u32>, TextureError> {
+        let mut data = vec![0; num_pixels(rect) * 4];
+
+        self.raw.read_pixels(
+            rect,
+            ImageFormat::RgbaInteger,
+            ImageComponentType::U32,
+            bytemuck::cast_slice_mut(&mut data),
+        )?;
+
+        Ok(data)
+    }
+
+    /// Starts reading RGBA pixels with 32-bit signed integer components from
+    /// the attachment into `buffer`, without waiting for the GPU.
+    ///
+    /// See [`Self::read_rgba_i32`] for which formats are supported, and
+    /// [`PixelPackBuffer`] for how to obtain the pixels.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `buffer` is too small to hold the pixels of `rect`.
+    pub fn read_rgba_i32_to_buffer(
+        &self,
+        rect: Rect,
+        buffer: &PixelPackBuffer<i32>,
+    ) -> Result<(), TextureError> {
+        self.raw.read_pixels_to_buffer(
+            rect,
+            ImageFormat::RgbaInteger,
+            ImageComponentType::I32,
+            buffer.raw(),
+            0,
+        )
+    }
+
+    /// Starts reading RGBA pixels with 32-bit unsigned integer components
+    /// from the attachment into `buffer`, without waiting for the GPU.
+    ///
+    /// See [`Self::read_rgba_u32`] for which formats are supported, and
+    /// [`PixelPackBuffer`] for how to obtain the pixels.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `buffer` is too small to hold the pixels of `rect`.
+    pub fn read_rgba_u32_to_buffer(
+        &self,
+        rect: Rect,
+        buffer: &PixelPackBuffer<u32>,
+    ) -> Result<(), TextureError> {
+        self.raw.read_pixels_to_buffer(
+            rect,
+            ImageFormat::RgbaInteger,
+            ImageComponentType::U32,
+            buffer.raw(),
+            0,
+        )
+    }
 }
 
 impl<S: ColorSample> ColorAttachment<S> {
+    /// Resolves the pixels of the attachment into `destination`, combining
+    /// the samples of each pixel if the attachment is a multisampled
+    /// renderbuffer.
+    ///
+    /// The destination must be single-sampled, e.g. a texture level, and it
+    /// must have the same size and internal format as the attachment.
+    pub fn resolve_into(&self, destination: &ColorAttachment<S>) -> Result<(), FramebufferError> {
+        self.raw.resolve_into(&destination.raw)
+    }
+
     pub fn with_depth(&self, depth: DepthAttachment) -> Framebuffer<S> {
         Framebuffer(FramebufferInternal::ColorDepth {
             color: self.clone(),
@@ -52,6 +237,54 @@ impl DepthAttachment {
         Self { raw }
     }
 
+    /// Reads depth values from the attachment.
+    ///
+    /// Rows are returned from bottom to top. Reading depth is supported by
+    /// desktop OpenGL, but not by OpenGL ES or WebGL, where this returns an
+    /// error.
+    pub fn read_f32(&self, rect: Rect) -> Result<Vec<f32>, TextureError> {
+        let mut data = vec![0.0; num_pixels(rect)];
+
+        self.raw.read_pixels(
+            rect,
+            ImageFormat::Depth,
+            ImageComponentType::F32,
+            bytemuck::cast_slice_mut(&mut data),
+        )?;
+
+        Ok(data)
+    }
+
+    /// Starts reading depth values from the attachment into `buffer`, without
+    /// waiting for the GPU.
+    ///
+    /// See [`Self::read_f32`] for where this is supported, and
+    /// [`PixelPackBuffer`] for how to obtain the values.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `buffer` is too small to hold the values of `rect`.
+    pub fn read_f32_to_buffer(
+        &self,
+        rect: Rect,
+        buffer: &PixelPackBuffer<f32>,
+    ) -> Result<(), TextureError> {
+        self.raw.read_pixels_to_buffer(
+            rect,
+            ImageFormat::Depth,
+            ImageComponentType::F32,
+            buffer.raw(),
+            0,
+        )
+    }
+
+    /// Resolves the depth values of the attachment into `destination`.
+    ///
+    /// See [`ColorAttachment::resolve_into`].
+    pub fn resolve_into(&self, destination: &DepthAttachment) -> Result<(), FramebufferError> {
+        self.raw.resolve_into(&destination.raw)
+    }
+
     pub fn with_color<F: FsInterface<Sl>>(&self, color: F::Gl) -> Framebuffer<F> {
         Framebuffer(FramebufferInternal::ColorDepth {
             color,
@@ -121,6 +354,10 @@ impl Default for Framebuffer<sl::Vec4> {
     }
 }
 
+fn num_pixels(rect: Rect) -> usize {
+    rect.size[0] as usize * rect.size[1] as usize
+}
+
 fn raw_color_attachments<F: FsInterface<Gl>>(attachments: &F) -> Vec<raw::Attachment> {
     struct Visitor(Vec<raw::Attachment>);
 
@@ -13,6 +13,30 @@ impl<'a, S: ColorSample> ColorImage<'a, S> {
     pub fn raw(self) -> raw::Image<'a> {
         self.raw
     }
+
+    /// Creates a compressed image from the data of its mipmap levels, starting
+    /// with the base level. If `levels` is empty, the image is zeroed.
+    fn compressed(
+        size: [u32; 2],
+        internal_format: ImageInternalFormat,
+        levels: &'a [&'a [u8]],
+    ) -> Self {
+        let (data, mipmap_data) = match levels.split_first() {
+            Some((data, mipmap_data)) => (Some(*data), mipmap_data),
+            None => (None, levels),
+        };
+
+        ColorImage {
+            raw: raw::Image {
+                size,
+                ty: ImageComponentType::U8,
+                internal_format,
+                data,
+                mipmap_data,
+            },
+            _phantom: PhantomData,
+        }
+    }
 }
 
 impl<'a> ColorImage<'a, sl::Vec4> {
@@ -23,6 +47,7 @@ impl<'a> ColorImage<'a, sl::Vec4> {
                 ty: ImageComponentType::U8,
                 internal_format: ImageInternalFormat::RgbaU8,
                 data: Some(data),
+                mipmap_data: &[],
             },
             _phantom: PhantomData,
         }
@@ -35,6 +60,7 @@ impl<'a> ColorImage<'a, sl::Vec4> {
                 ty: ImageComponentType::U8,
                 internal_format: ImageInternalFormat::RgbaU8Gamma,
                 data: Some(data),
+                mipmap_data: &[],
             },
             _phantom: PhantomData,
         }
@@ -47,6 +73,7 @@ impl<'a> ColorImage<'a, sl::Vec4> {
                 ty: ImageComponentType::I8,
                 internal_format: ImageInternalFormat::RgbaI8Snorm,
                 data: Some(bytemuck::cast_slice(data)),
+                mipmap_data: &[],
             },
             _phantom: PhantomData,
         }
@@ -59,6 +86,7 @@ impl<'a> ColorImage<'a, sl::Vec4> {
                 ty: ImageComponentType::F32,
                 internal_format: ImageInternalFormat::RgbaF32,
                 data: Some(bytemuck::cast_slice(data)),
+                mipmap_data: &[],
             },
             _phantom: PhantomData,
         }
@@ -71,6 +99,7 @@ impl<'a> ColorImage<'a, sl::Vec4> {
                 ty: ImageComponentType::U8,
                 internal_format: ImageInternalFormat::RgbaU8,
                 data: None,
+                mipmap_data: &[],
             },
             _phantom: PhantomData,
         }
@@ -83,6 +112,7 @@ impl<'a> ColorImage<'a, sl::Vec4> {
                 ty: ImageComponentType::U8,
                 internal_format: ImageInternalFormat::RgbaU8Gamma,
                 data: None,
+                mipmap_data: &[],
             },
             _phantom: PhantomData,
         }
@@ -95,6 +125,7 @@ impl<'a> ColorImage<'a, sl::Vec4> {
                 ty: ImageComponentType::I8,
                 internal_format: ImageInternalFormat::RgbaI8Snorm,
                 data: None,
+                mipmap_data: &[],
             },
             _phantom: PhantomData,
         }
@@ -107,6 +138,7 @@ impl<'a> ColorImage<'a, sl::Vec4> {
                 ty: ImageComponentType::F16,
                 internal_format: ImageInternalFormat::RgbaF16,
                 data: None,
+                mipmap_data: &[],
             },
             _phantom: PhantomData,
         }
@@ -119,10 +151,151 @@ impl<'a> ColorImage<'a, sl::Vec4> {
                 ty: ImageComponentType::F32,
                 internal_format: ImageInternalFormat::RgbaF32,
                 data: None,
+                mipmap_data: &[],
             },
             _phantom: PhantomData,
         }
     }
+
+    pub fn etc2_rgb_u8_a1_levels(size: [u32; 2], levels: &'a [&'a [u8]]) -> Self {
+        Self::compressed(size, ImageInternalFormat::Etc2RgbU8A1, levels)
+    }
+
+    pub fn etc2_rgb_u8_a1_gamma_levels(size: [u32; 2], levels: &'a [&'a [u8]]) -> Self {
+        Self::compressed(size, ImageInternalFormat::Etc2RgbU8A1Gamma, levels)
+    }
+
+    pub fn etc2_rgba_u8_levels(size: [u32; 2], levels: &'a [&'a [u8]]) -> Self {
+        Self::compressed(size, ImageInternalFormat::Etc2RgbaU8, levels)
+    }
+
+    pub fn etc2_rgba_u8_gamma_levels(size: [u32; 2], levels: &'a [&'a [u8]]) -> Self {
+        Self::compressed(size, ImageInternalFormat::Etc2RgbaU8Gamma, levels)
+    }
+
+    pub fn astc_4x4_levels(size: [u32; 2], levels: &'a [&'a [u8]]) -> Self {
+        Self::compressed(size, ImageInternalFormat::Astc4x4, levels)
+    }
+
+    pub fn astc_4x4_gamma_levels(size: [u32; 2], levels: &'a [&'a [u8]]) -> Self {
+        Self::compressed(size, ImageInternalFormat::Astc4x4Gamma, levels)
+    }
+
+    pub fn astc_5x4_levels(size: [u32; 2], levels: &'a [&'a [u8]]) -> Self {
+        Self::compressed(size, ImageInternalFormat::Astc5x4, levels)
+    }
+
+    pub fn astc_5x4_gamma_levels(size: [u32; 2], levels: &'a [&'a [u8]]) -> Self {
+        Self::compressed(size, ImageInternalFormat::Astc5x4Gamma, levels)
+    }
+
+    pub fn astc_5x5_levels(size: [u32; 2], levels: &'a [&'a [u8]]) -> Self {
+        Self::compressed(size, ImageInternalFormat::Astc5x5, levels)
+    }
+
+    pub fn astc_5x5_gamma_levels(size: [u32; 2], levels: &'a [&'a [u8]]) -> Self {
+        Self::compressed(size, ImageInternalFormat::Astc5x5Gamma, levels)
+    }
+
+    pub fn astc_6x5_levels(size: [u32; 2], levels: &'a [&'a [u8]]) -> Self {
+        Self::compressed(size, ImageInternalFormat::Astc6x5, levels)
+    }
+
+    pub fn astc_6x5_gamma_levels(size: [u32; 2], levels: &'a [&'a [u8]]) -> Self {
+        Self::compressed(size, ImageInternalFormat::Astc6x5Gamma, levels)
+    }
+
+    pub fn astc_6x6_levels(size: [u32; 2], levels: &'a [&'a [u8]]) -> Self {
+        Self::compressed(size, ImageInternalFormat::Astc6x6, levels)
+    }
+
+    pub fn astc_6x6_gamma_levels(size: [u32; 2], levels: &'a [&'a [u8]]) -> Self {
+        Self::compressed(size, ImageInternalFormat::Astc6x6Gamma, levels)
+    }
+
+    pub fn astc_8x5_levels(size: [u32; 2], levels: &'a [&'a [u8]]) -> Self {
+        Self::compressed(size, ImageInternalFormat::Astc8x5, levels)
+    }
+
+    pub fn astc_8x5_gamma_levels(size: [u32; 2], levels: &'a [&'a [u8]]) -> Self {
+        Self::compressed(size, ImageInternalFormat::Astc8x5Gamma, levels)
+    }
+
+    pub fn astc_8x6_levels(size: [u32; 2], levels: &'a [&'a [u8]]) -> Self {
+        Self::compressed(size, ImageInternalFormat::Astc8x6, levels)
+    }
+
+    pub fn astc_8x6_gamma_levels(size: [u32; 2], levels: &'a [&'a [u8]]) -> Self {
+        Self::compressed(size, ImageInternalFormat::Astc8x6Gamma, levels)
+    }
+
+    pub fn astc_8x8_levels(size: [u32; 2], levels: &'a [&'a [u8]]) -> Self {
+        Self::compressed(size, ImageInternalFormat::Astc8x8, levels)
+    }
+
+    pub fn astc_8x8_gamma_levels(size: [u32; 2], levels: &'a [&'a [u8]]) -> Self {
+        Self::compressed(size, ImageInternalFormat::Astc8x8Gamma, levels)
+    }
+
+    pub fn astc_10x5_levels(size: [u32; 2], levels: &'a [&'a [u8]]) -> Self {
+        Self::compressed(size, ImageInternalFormat::Astc10x5, levels)
+    }
+
+    pub fn astc_10x5_gamma_levels(size: [u32; 2], levels: &'a [&'a [u8]]) -> Self {
+        Self::compressed(size, ImageInternalFormat::Astc10x5Gamma, levels)
+    }
+
+    pub fn astc_10x6_levels(size: [u32; 2], levels: &'a [&'a [u8]]) -> Self {
+        Self::compressed(size, ImageInternalFormat::Astc10x6, levels)
+    }
+
+    pub fn astc_10x6_gamma_levels(size: [u32; 2], levels: &'a [&'a [u8]]) -> Self {
+        Self::compressed(size, ImageInternalFormat::Astc10x6Gamma, levels)
+    }
+
+    pub fn astc_10x8_levels(size: [u32; 2], levels: &'a [&'a [u8]]) -> Self {
+        Self::compressed(size, ImageInternalFormat::Astc10x8, levels)
+    }
+
+    pub fn astc_10x8_gamma_levels(size: [u32; 2], levels: &'a [&'a [u8]]) -> Self {
+        Self::compressed(size, ImageInternalFormat::Astc10x8Gamma, levels)
+    }
+
+    pub fn astc_10x10_levels(size: [u32; 2], levels: &'a [&'a [u8]]) -> Self {
+        Self::compressed(size, ImageInternalFormat::Astc10x10, levels)
+    }
+
+    pub fn astc_10x10_gamma_levels(size: [u32; 2], levels: &'a [&'a [u8]]) -> Self {
+        Self::compressed(size, ImageInternalFormat::Astc10x10Gamma, levels)
+    }
+
+    pub fn astc_12x10_levels(size: [u32; 2], levels: &'a [&'a [u8]]) -> Self {
+        Self::compressed(size, ImageInternalFormat::Astc12x10, levels)
+    }
+
+    pub fn astc_12x10_gamma_levels(size: [u32; 2], levels: &'a [&'a [u8]]) -> Self {
+        Self::compressed(size, ImageInternalFormat::Astc12x10Gamma, levels)
+    }
+
+    pub fn astc_12x12_levels(size: [u32; 2], levels: &'a [&'a [u8]]) -> Self {
+        Self::compressed(size, ImageInternalFormat::Astc12x12, levels)
+    }
+
+    pub fn astc_12x12_gamma_levels(size: [u32; 2], levels: &'a [&'a [u8]]) -> Self {
+        Self::compressed(size, ImageInternalFormat::Astc12x12Gamma, levels)
+    }
+
+    pub fn s3tc_rgba_dxt1_levels(size: [u32; 2], levels: &'a [&'a [u8]]) -> Self {
+        Self::compressed(size, ImageInternalFormat::S3tcRgbaDxt1, levels)
+    }
+
+    pub fn s3tc_rgba_dxt3_levels(size: [u32; 2], levels: &'a [&'a [u8]]) -> Self {
+        Self::compressed(size, ImageInternalFormat::S3tcRgbaDxt3, levels)
+    }
+
+    pub fn s3tc_rgba_dxt5_levels(size: [u32; 2], levels: &'a [&'a [u8]]) -> Self {
+        Self::compressed(size, ImageInternalFormat::S3tcRgbaDxt5, levels)
+    }
 }
 
 impl<'a> ColorImage<'a, sl::Vec3> {
@@ -133,6 +306,7 @@ impl<'a> ColorImage<'a, sl::Vec3> {
                 ty: ImageComponentType::U8,
                 internal_format: ImageInternalFormat::RgbU8,
                 data: Some(data),
+                mipmap_data: &[],
             },
             _phantom: PhantomData,
         }
@@ -145,6 +319,7 @@ impl<'a> ColorImage<'a, sl::Vec3> {
                 ty: ImageComponentType::U8,
                 internal_format: ImageInternalFormat::RgbU8Gamma,
                 data: Some(data),
+                mipmap_data: &[],
             },
             _phantom: PhantomData,
         }
@@ -157,6 +332,7 @@ impl<'a> ColorImage<'a, sl::Vec3> {
                 ty: ImageComponentType::I8,
                 internal_format: ImageInternalFormat::RgbI8Snorm,
                 data: Some(bytemuck::cast_slice(data)),
+                mipmap_data: &[],
             },
             _phantom: PhantomData,
         }
@@ -169,6 +345,7 @@ impl<'a> ColorImage<'a, sl::Vec3> {
                 ty: ImageComponentType::F32,
                 internal_format: ImageInternalFormat::RgbF32,
                 data: Some(bytemuck::cast_slice(data)),
+                mipmap_data: &[],
             },
             _phantom: PhantomData,
         }
@@ -181,6 +358,7 @@ impl<'a> ColorImage<'a, sl::Vec3> {
                 ty: ImageComponentType::U8,
                 internal_format: ImageInternalFormat::RgbU8,
                 data: None,
+                mipmap_data: &[],
             },
             _phantom: PhantomData,
         }
@@ -193,6 +371,7 @@ impl<'a> ColorImage<'a, sl::Vec3> {
                 ty: ImageComponentType::U8,
                 internal_format: ImageInternalFormat::RgbU8Gamma,
                 data: None,
+                mipmap_data: &[],
             },
             _phantom: PhantomData,
         }
@@ -205,6 +384,7 @@ impl<'a> ColorImage<'a, sl::Vec3> {
                 ty: ImageComponentType::I8,
                 internal_format: ImageInternalFormat::RgbI8Snorm,
                 data: None,
+                mipmap_data: &[],
             },
             _phantom: PhantomData,
         }
@@ -217,6 +397,7 @@ impl<'a> ColorImage<'a, sl::Vec3> {
                 ty: ImageComponentType::F16,
                 internal_format: ImageInternalFormat::RgbF16,
                 data: None,
+                mipmap_data: &[],
             },
             _phantom: PhantomData,
         }
@@ -229,10 +410,23 @@ impl<'a> ColorImage<'a, sl::Vec3> {
                 ty: ImageComponentType::F32,
                 internal_format: ImageInternalFormat::RgbF32,
                 data: None,
+                mipmap_data: &[],
             },
             _phantom: PhantomData,
         }
     }
+
+    pub fn etc2_rgb_u8_levels(size: [u32; 2], levels: &'a [&'a [u8]]) -> Self {
+        Self::compressed(size, ImageInternalFormat::Etc2RgbU8, levels)
+    }
+
+    pub fn etc2_rgb_u8_gamma_levels(size: [u32; 2], levels: &'a [&'a [u8]]) -> Self {
+        Self::compressed(size, ImageInternalFormat::Etc2RgbU8Gamma, levels)
+    }
+
+    pub fn s3tc_rgb_dxt1_levels(size: [u32; 2], levels: &'a [&'a [u8]]) -> Self {
+        Self::compressed(size, ImageInternalFormat::S3tcRgbDxt1, levels)
+    }
 }
 
 impl<'a> ColorImage<'a, sl::Vec2> {
@@ -243,6 +437,7 @@ impl<'a> ColorImage<'a, sl::Vec2> {
                 ty: ImageComponentType::U8,
                 internal_format: ImageInternalFormat::RgU8,
                 data: Some(data),
+                mipmap_data: &[],
             },
             _phantom: PhantomData,
         }
@@ -255,6 +450,7 @@ impl<'a> ColorImage<'a, sl::Vec2> {
                 ty: ImageComponentType::I8,
                 internal_format: ImageInternalFormat::RgI8Snorm,
                 data: Some(bytemuck::cast_slice(data)),
+                mipmap_data: &[],
             },
             _phantom: PhantomData,
         }
@@ -267,6 +463,7 @@ impl<'a> ColorImage<'a, sl::Vec2> {
                 ty: ImageComponentType::F32,
                 internal_format: ImageInternalFormat::RgF32,
                 data: Some(bytemuck::cast_slice(data)),
+                mipmap_data: &[],
             },
             _phantom: PhantomData,
         }
@@ -279,6 +476,7 @@ impl<'a> ColorImage<'a, sl::Vec2> {
                 ty: ImageComponentType::U8,
                 internal_format: ImageInternalFormat::RgU8,
                 data: None,
+                mipmap_data: &[],
             },
             _phantom: PhantomData,
         }
@@ -291,6 +489,7 @@ impl<'a> ColorImage<'a, sl::Vec2> {
                 ty: ImageComponentType::I8,
                 internal_format: ImageInternalFormat::RgI8Snorm,
                 data: None,
+                mipmap_data: &[],
             },
             _phantom: PhantomData,
         }
@@ -303,6 +502,7 @@ impl<'a> ColorImage<'a, sl::Vec2> {
                 ty: ImageComponentType::F16,
                 internal_format: ImageInternalFormat::RgF16,
                 data: None,
+                mipmap_data: &[],
             },
             _phantom: PhantomData,
         }
@@ -315,10 +515,19 @@ impl<'a> ColorImage<'a, sl::Vec2> {
                 ty: ImageComponentType::F32,
                 internal_format: ImageInternalFormat::RgF32,
                 data: None,
+                mipmap_data: &[],
             },
             _phantom: PhantomData,
         }
     }
+
+    pub fn eac_rg_u11_levels(size: [u32; 2], levels: &'a [&'a [u8]]) -> Self {
+        Self::compressed(size, ImageInternalFormat::EacRgU11, levels)
+    }
+
+    pub fn eac_rg_i11_snorm_levels(size: [u32; 2], levels: &'a [&'a [u8]]) -> Self {
+        Self::compressed(size, ImageInternalFormat::EacRgI11Snorm, levels)
+    }
 }
 
 impl<'a> ColorImage<'a, sl::F32> {
@@ -329,6 +538,7 @@ impl<'a> ColorImage<'a, sl::F32> {
                 ty: ImageComponentType::U8,
                 internal_format: ImageInternalFormat::RU8,
                 data: Some(data),
+                mipmap_data: &[],
             },
             _phantom: PhantomData,
         }
@@ -341,6 +551,7 @@ impl<'a> ColorImage<'a, sl::F32> {
                 ty: ImageComponentType::I8,
                 internal_format: ImageInternalFormat::RI8Snorm,
                 data: Some(bytemuck::cast_slice(data)),
+                mipmap_data: &[],
             },
             _phantom: PhantomData,
         }
@@ -353,6 +564,7 @@ impl<'a> ColorImage<'a, sl::F32> {
                 ty: ImageComponentType::F32,
                 internal_format: ImageInternalFormat::RF32,
                 data: Some(bytemuck::cast_slice(data)),
+                mipmap_data: &[],
             },
             _phantom: PhantomData,
         }
@@ -365,6 +577,7 @@ impl<'a> ColorImage<'a, sl::F32> {
                 ty: ImageComponentType::U8,
                 internal_format: ImageInternalFormat::RU8,
                 data: None,
+                mipmap_data: &[],
             },
             _phantom: PhantomData,
         }
@@ -377,6 +590,7 @@ impl<'a> ColorImage<'a, sl::F32> {
                 ty: ImageComponentType::I8,
                 internal_format: ImageInternalFormat::RI8Snorm,
                 data: None,
+                mipmap_data: &[],
             },
             _phantom: PhantomData,
         }
@@ -389,6 +603,7 @@ impl<'a> ColorImage<'a, sl::F32> {
                 ty: ImageComponentType::F16,
                 internal_format: ImageInternalFormat::RF16,
                 data: None,
+                mipmap_data: &[],
             },
             _phantom: PhantomData,
         }
@@ -401,6 +616,655 @@ impl<'a> ColorImage<'a, sl::F32> {
                 ty: ImageComponentType::F32,
                 internal_format: ImageInternalFormat::RF32,
                 data: None,
+                mipmap_data: &[],
+            },
+            _phantom: PhantomData,
+        }
+    }
+
+    pub fn eac_r_u11_levels(size: [u32; 2], levels: &'a [&'a [u8]]) -> Self {
+        Self::compressed(size, ImageInternalFormat::EacRU11, levels)
+    }
+
+    pub fn eac_r_i11_snorm_levels(size: [u32; 2], levels: &'a [&'a [u8]]) -> Self {
+        Self::compressed(size, ImageInternalFormat::EacRI11Snorm, levels)
+    }
+}
+
+impl<'a> ColorImage<'a, sl::IVec4> {
+    pub fn rgba_i8_int_slice(size: [u32; 2], data: &'a [i8]) -> Self {
+        ColorImage {
+            raw: raw::Image {
+                size,
+                ty: ImageComponentType::I8,
+                internal_format: ImageInternalFormat::RgbaI8Int,
+                data: Some(bytemuck::cast_slice(data)),
+                mipmap_data: &[],
+            },
+            _phantom: PhantomData,
+        }
+    }
+
+    pub fn rgba_i16_int_slice(size: [u32; 2], data: &'a [i16]) -> Self {
+        ColorImage {
+            raw: raw::Image {
+                size,
+                ty: ImageComponentType::I16,
+                internal_format: ImageInternalFormat::RgbaI16Int,
+                data: Some(bytemuck::cast_slice(data)),
+                mipmap_data: &[],
+            },
+            _phantom: PhantomData,
+        }
+    }
+
+    pub fn rgba_i32_int_slice(size: [u32; 2], data: &'a [i32]) -> Self {
+        ColorImage {
+            raw: raw::Image {
+                size,
+                ty: ImageComponentType::I32,
+                internal_format: ImageInternalFormat::RgbaI32Int,
+                data: Some(bytemuck::cast_slice(data)),
+                mipmap_data: &[],
+            },
+            _phantom: PhantomData,
+        }
+    }
+
+    pub fn rgba_i8_int_zero(size: [u32; 2]) -> Self {
+        ColorImage {
+            raw: raw::Image {
+                size,
+                ty: ImageComponentType::I8,
+                internal_format: ImageInternalFormat::RgbaI8Int,
+                data: None,
+                mipmap_data: &[],
+            },
+            _phantom: PhantomData,
+        }
+    }
+
+    pub fn rgba_i16_int_zero(size: [u32; 2]) -> Self {
+        ColorImage {
+            raw: raw::Image {
+                size,
+                ty: ImageComponentType::I16,
+                internal_format: ImageInternalFormat::RgbaI16Int,
+                data: None,
+                mipmap_data: &[],
+            },
+            _phantom: PhantomData,
+        }
+    }
+
+    pub fn rgba_i32_int_zero(size: [u32; 2]) -> Self {
+        ColorImage {
+            raw: raw::Image {
+                size,
+                ty: ImageComponentType::I32,
+                internal_format: ImageInternalFormat::RgbaI32Int,
+                data: None,
+                mipmap_data: &[],
+            },
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<'a> ColorImage<'a, sl::UVec4> {
+    pub fn rgba_u8_int_slice(size: [u32; 2], data: &'a [u8]) -> Self {
+        ColorImage {
+            raw: raw::Image {
+                size,
+                ty: ImageComponentType::U8,
+                internal_format: ImageInternalFormat::RgbaU8Int,
+                data: Some(data),
+                mipmap_data: &[],
+            },
+            _phantom: PhantomData,
+        }
+    }
+
+    pub fn rgba_u16_int_slice(size: [u32; 2], data: &'a [u16]) -> Self {
+        ColorImage {
+            raw: raw::Image {
+                size,
+                ty: ImageComponentType::U16,
+                internal_format: ImageInternalFormat::RgbaU16Int,
+                data: Some(bytemuck::cast_slice(data)),
+                mipmap_data: &[],
+            },
+            _phantom: PhantomData,
+        }
+    }
+
+    pub fn rgba_u32_int_slice(size: [u32; 2], data: &'a [u32]) -> Self {
+        ColorImage {
+            raw: raw::Image {
+                size,
+                ty: ImageComponentType::U32,
+                internal_format: ImageInternalFormat::RgbaU32Int,
+                data: Some(bytemuck::cast_slice(data)),
+                mipmap_data: &[],
+            },
+            _phantom: PhantomData,
+        }
+    }
+
+    pub fn rgba_u8_int_zero(size: [u32; 2]) -> Self {
+        ColorImage {
+            raw: raw::Image {
+                size,
+                ty: ImageComponentType::U8,
+                internal_format: ImageInternalFormat::RgbaU8Int,
+                data: None,
+                mipmap_data: &[],
+            },
+            _phantom: PhantomData,
+        }
+    }
+
+    pub fn rgba_u16_int_zero(size: [u32; 2]) -> Self {
+        ColorImage {
+            raw: raw::Image {
+                size,
+                ty: ImageComponentType::U16,
+                internal_format: ImageInternalFormat::RgbaU16Int,
+                data: None,
+                mipmap_data: &[],
+            },
+            _phantom: PhantomData,
+        }
+    }
+
+    pub fn rgba_u32_int_zero(size: [u32; 2]) -> Self {
+        ColorImage {
+            raw: raw::Image {
+                size,
+                ty: ImageComponentType::U32,
+                internal_format: ImageInternalFormat::RgbaU32Int,
+                data: None,
+                mipmap_data: &[],
+            },
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<'a> ColorImage<'a, sl::IVec3> {
+    pub fn rgb_i8_int_slice(size: [u32; 2], data: &'a [i8]) -> Self {
+        ColorImage {
+            raw: raw::Image {
+                size,
+                ty: ImageComponentType::I8,
+                internal_format: ImageInternalFormat::RgbI8Int,
+                data: Some(bytemuck::cast_slice(data)),
+                mipmap_data: &[],
+            },
+            _phantom: PhantomData,
+        }
+    }
+
+    pub fn rgb_i16_int_slice(size: [u32; 2], data: &'a [i16]) -> Self {
+        ColorImage {
+            raw: raw::Image {
+                size,
+                ty: ImageComponentType::I16,
+                internal_format: ImageInternalFormat::RgbI16Int,
+                data: Some(bytemuck::cast_slice(data)),
+                mipmap_data: &[],
+            },
+            _phantom: PhantomData,
+        }
+    }
+
+    pub fn rgb_i32_int_slice(size: [u32; 2], data: &'a [i32]) -> Self {
+        ColorImage {
+            raw: raw::Image {
+                size,
+                ty: ImageComponentType::I32,
+                internal_format: ImageInternalFormat::RgbI32Int,
+                data: Some(bytemuck::cast_slice(data)),
+                mipmap_data: &[],
+            },
+            _phantom: PhantomData,
+        }
+    }
+
+    pub fn rgb_i8_int_zero(size: [u32; 2]) -> Self {
+        ColorImage {
+            raw: raw::Image {
+                size,
+                ty: ImageComponentType::I8,
+                internal_format: ImageInternalFormat::RgbI8Int,
+                data: None,
+                mipmap_data: &[],
+            },
+            _phantom: PhantomData,
+        }
+    }
+
+    pub fn rgb_i16_int_zero(size: [u32; 2]) -> Self {
+        ColorImage {
+            raw: raw::Image {
+                size,
+                ty: ImageComponentType::I16,
+                internal_format: ImageInternalFormat::RgbI16Int,
+                data: None,
+                mipmap_data: &[],
+            },
+            _phantom: PhantomData,
+        }
+    }
+
+    pub fn rgb_i32_int_zero(size: [u32; 2]) -> Self {
+        ColorImage {
+            raw: raw::Image {
+                size,
+                ty: ImageComponentType::I32,
+                internal_format: ImageInternalFormat::RgbI32Int,
+                data: None,
+                mipmap_data: &[],
+            },
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<'a> ColorImage<'a, sl::UVec3> {
+    pub fn rgb_u8_int_slice(size: [u32; 2], data: &'a [u8]) -> Self {
+        ColorImage {
+            raw: raw::Image {
+                size,
+                ty: ImageComponentType::U8,
+                internal_format: ImageInternalFormat::RgbU8Int,
+                data: Some(data),
+                mipmap_data: &[],
+            },
+            _phantom: PhantomData,
+        }
+    }
+
+    pub fn rgb_u16_int_slice(size: [u32; 2], data: &'a [u16]) -> Self {
+        ColorImage {
+            raw: raw::Image {
+                size,
+                ty: ImageComponentType::U16,
+                internal_format: ImageInternalFormat::RgbU16Int,
+                data: Some(bytemuck::cast_slice(data)),
+                mipmap_data: &[],
+            },
+            _phantom: PhantomData,
+        }
+    }
+
+    pub fn rgb_u32_int_slice(size: [u32; 2], data: &'a [u32]) -> Self {
+        ColorImage {
+            raw: raw::Image {
+                size,
+                ty: ImageComponentType::U32,
+                internal_format: ImageInternalFormat::RgbU32Int,
+                data: Some(bytemuck::cast_slice(data)),
+                mipmap_data: &[],
+            },
+            _phantom: PhantomData,
+        }
+    }
+
+    pub fn rgb_u8_int_zero(size: [u32; 2]) -> Self {
+        ColorImage {
+            raw: raw::Image {
+                size,
+                ty: ImageComponentType::U8,
+                internal_format: ImageInternalFormat::RgbU8Int,
+                data: None,
+                mipmap_data: &[],
+            },
+            _phantom: PhantomData,
+        }
+    }
+
+    pub fn rgb_u16_int_zero(size: [u32; 2]) -> Self {
+        ColorImage {
+            raw: raw::Image {
+                size,
+                ty: ImageComponentType::U16,
+                internal_format: ImageInternalFormat::RgbU16Int,
+                data: None,
+                mipmap_data: &[],
+            },
+            _phantom: PhantomData,
+        }
+    }
+
+    pub fn rgb_u32_int_zero(size: [u32; 2]) -> Self {
+        ColorImage {
+            raw: raw::Image {
+                size,
+                ty: ImageComponentType::U32,
+                internal_format: ImageInternalFormat::RgbU32Int,
+                data: None,
+                mipmap_data: &[],
+            },
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<'a> ColorImage<'a, sl::IVec2> {
+    pub fn rg_i8_int_slice(size: [u32; 2], data: &'a [i8]) -> Self {
+        ColorImage {
+            raw: raw::Image {
+                size,
+                ty: ImageComponentType::I8,
+                internal_format: ImageInternalFormat::RgI8Int,
+                data: Some(bytemuck::cast_slice(data)),
+                mipmap_data: &[],
+            },
+            _phantom: PhantomData,
+        }
+    }
+
+    pub fn rg_i16_int_slice(size: [u32; 2], data: &'a [i16]) -> Self {
+        ColorImage {
+            raw: raw::Image {
+                size,
+                ty: ImageComponentType::I16,
+                internal_format: ImageInternalFormat::RgI16Int,
+                data: Some(bytemuck::cast_slice(data)),
+                mipmap_data: &[],
+            },
+            _phantom: PhantomData,
+        }
+    }
+
+    pub fn rg_i32_int_slice(size: [u32; 2], data: &'a [i32]) -> Self {
+        ColorImage {
+            raw: raw::Image {
+                size,
+                ty: ImageComponentType::I32,
+                internal_format: ImageInternalFormat::RgI32Int,
+                data: Some(bytemuck::cast_slice(data)),
+                mipmap_data: &[],
+            },
+            _phantom: PhantomData,
+        }
+    }
+
+    pub fn rg_i8_int_zero(size: [u32; 2]) -> Self {
+        ColorImage {
+            raw: raw::Image {
+                size,
+                ty: ImageComponentType::I8,
+                internal_format: ImageInternalFormat::RgI8Int,
+                data: None,
+                mipmap_data: &[],
+            },
+            _phantom: PhantomData,
+        }
+    }
+
+    pub fn rg_i16_int_zero(size: [u32; 2]) -> Self {
+        ColorImage {
+            raw: raw::Image {
+                size,
+                ty: ImageComponentType::I16,
+                internal_format: ImageInternalFormat::RgI16Int,
+                data: None,
+                mipmap_data: &[],
+            },
+            _phantom: PhantomData,
+        }
+    }
+
+    pub fn rg_i32_int_zero(size: [u32; 2]) -> Self {
+        ColorImage {
+            raw: raw::Image {
+                size,
+                ty: ImageComponentType::I32,
+                internal_format: ImageInternalFormat::RgI32Int,
+                data: None,
+                mipmap_data: &[],
+            },
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<'a> ColorImage<'a, sl::UVec2> {
+    pub fn rg_u8_int_slice(size: [u32; 2], data: &'a [u8]) -> Self {
+        ColorImage {
+            raw: raw::Image {
+                size,
+                ty: ImageComponentType::U8,
+                internal_format: ImageInternalFormat::RgU8Int,
+                data: Some(data),
+                mipmap_data: &[],
+            },
+            _phantom: PhantomData,
+        }
+    }
+
+    pub fn rg_u16_int_slice(size: [u32; 2], data: &'a [u16]) -> Self {
+        ColorImage {
+            raw: raw::Image {
+                size,
+                ty: ImageComponentType::U16,
+                internal_format: ImageInternalFormat::RgU16Int,
+                data: Some(bytemuck::cast_slice(data)),
+                mipmap_data: &[],
+            },
+            _phantom: PhantomData,
+        }
+    }
+
+    pub fn rg_u32_int_slice(size: [u32; 2], data: &'a [u32]) -> Self {
+        ColorImage {
+            raw: raw::Image {
+                size,
+                ty: ImageComponentType::U32,
+                internal_format: ImageInternalFormat::RgU32Int,
+                data: Some(bytemuck::cast_slice(data)),
+                mipmap_data: &[],
+            },
+            _phantom: PhantomData,
+        }
+    }
+
+    pub fn rg_u8_int_zero(size: [u32; 2]) -> Self {
+        ColorImage {
+            raw: raw::Image {
+                size,
+                ty: ImageComponentType::U8,
+                internal_format: ImageInternalFormat::RgU8Int,
+                data: None,
+                mipmap_data: &[],
+            },
+            _phantom: PhantomData,
+        }
+    }
+
+    pub fn rg_u16_int_zero(size: [u32; 2]) -> Self {
+        ColorImage {
+            raw: raw::Image {
+                size,
+                ty: ImageComponentType::U16,
+                internal_format: ImageInternalFormat::RgU16Int,
+                data: None,
+                mipmap_data: &[],
+            },
+            _phantom: PhantomData,
+        }
+    }
+
+    pub fn rg_u32_int_zero(size: [u32; 2]) -> Self {
+        ColorImage {
+            raw: raw::Image {
+                size,
+                ty: ImageComponentType::U32,
+                internal_format: ImageInternalFormat::RgU32Int,
+                data: None,
+                mipmap_data: &[],
+            },
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<'a> ColorImage<'a, sl::I32> {
+    pub fn r_i8_int_slice(size: [u32; 2], data: &'a [i8]) -> Self {
+        ColorImage {
+            raw: raw::Image {
+                size,
+                ty: ImageComponentType::I8,
+                internal_format: ImageInternalFormat::RI8Int,
+                data: Some(bytemuck::cast_slice(data)),
+                mipmap_data: &[],
+            },
+            _phantom: PhantomData,
+        }
+    }
+
+    pub fn r_i16_int_slice(size: [u32; 2], data: &'a [i16]) -> Self {
+        ColorImage {
+            raw: raw::Image {
+                size,
+                ty: ImageComponentType::I16,
+                internal_format: ImageInternalFormat::RI16Int,
+                data: Some(bytemuck::cast_slice(data)),
+                mipmap_data: &[],
+            },
+            _phantom: PhantomData,
+        }
+    }
+
+    pub fn r_i32_int_slice(size: [u32; 2], data: &'a [i32]) -> Self {
+        ColorImage {
+            raw: raw::Image {
+                size,
+                ty: ImageComponentType::I32,
+                internal_format: ImageInternalFormat::RI32Int,
+                data: Some(bytemuck::cast_slice(data)),
+                mipmap_data: &[],
+            },
+            _phantom: PhantomData,
+        }
+    }
+
+    pub fn r_i8_int_zero(size: [u32; 2]) -> Self {
+        ColorImage {
+            raw: raw::Image {
+                size,
+                ty: ImageComponentType::I8,
+                internal_format: ImageInternalFormat::RI8Int,
+                data: None,
+                mipmap_data: &[],
+            },
+            _phantom: PhantomData,
+        }
+    }
+
+    pub fn r_i16_int_zero(size: [u32; 2]) -> Self {
+        ColorImage {
+            raw: raw::Image {
+                size,
+                ty: ImageComponentType::I16,
+                internal_format: ImageInternalFormat::RI16Int,
+                data: None,
+                mipmap_data: &[],
+            },
+            _phantom: PhantomData,
+        }
+    }
+
+    pub fn r_i32_int_zero(size: [u32; 2]) -> Self {
+        ColorImage {
+            raw: raw::Image {
+                size,
+                ty: ImageComponentType::I32,
+                internal_format: ImageInternalFormat::RI32Int,
+                data: None,
+                mipmap_data: &[],
+            },
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<'a> ColorImage<'a, sl::U32> {
+    pub fn r_u8_int_slice(size: [u32; 2], data: &'a [u8]) -> Self {
+        ColorImage {
+            raw: raw::Image {
+                size,
+                ty: ImageComponentType::U8,
+                internal_format: ImageInternalFormat::RU8Int,
+                data: Some(data),
+                mipmap_data: &[],
+            },
+            _phantom: PhantomData,
+        }
+    }
+
+    pub fn r_u16_int_slice(size: [u32; 2], data: &'a [u16]) -> Self {
+        ColorImage {
+            raw: raw::Image {
+                size,
+                ty: ImageComponentType::U16,
+                internal_format: ImageInternalFormat::RU16Int,
+                data: Some(bytemuck::cast_slice(data)),
+                mipmap_data: &[],
+            },
+            _phantom: PhantomData,
+        }
+    }
+
+    pub fn r_u32_int_slice(size: [u32; 2], data: &'a [u32]) -> Self {
+        ColorImage {
+            raw: raw::Image {
+                size,
+                ty: ImageComponentType::U32,
+                internal_format: ImageInternalFormat::RU32Int,
+                data: Some(bytemuck::cast_slice(data)),
+                mipmap_data: &[],
+            },
+            _phantom: PhantomData,
+        }
+    }
+
+    pub fn r_u8_int_zero(size: [u32; 2]) -> Self {
+        ColorImage {
+            raw: raw::Image {
+                size,
+                ty: ImageComponentType::U8,
+                internal_format: ImageInternalFormat::RU8Int,
+                data: None,
+                mipmap_data: &[],
+            },
+            _phantom: PhantomData,
+        }
+    }
+
+    pub fn r_u16_int_zero(size: [u32; 2]) -> Self {
+        ColorImage {
+            raw: raw::Image {
+                size,
+                ty: ImageComponentType::U16,
+                internal_format: ImageInternalFormat::RU16Int,
+                data: None,
+                mipmap_data: &[],
+            },
+            _phantom: PhantomData,
+        }
+    }
+
+    pub fn r_u32_int_zero(size: [u32; 2]) -> Self {
+        ColorImage {
+            raw: raw::Image {
+                size,
+                ty: ImageComponentType::U32,
+                internal_format: ImageInternalFormat::RU32Int,
+                data: None,
+                mipmap_data: &[],
             },
             _phantom: PhantomData,
         }
@@ -425,6 +1289,7 @@ impl<'a> DepthImage<'a> {
                 ty: ImageComponentType::F32,
                 internal_format: ImageInternalFormat::DepthF32,
                 data: Some(bytemuck::cast_slice(data)),
+                mipmap_data: &[],
             },
         }
     }
@@ -436,6 +1301,7 @@ impl<'a> DepthImage<'a> {
                 ty: ImageComponentType::U16,
                 internal_format: ImageInternalFormat::DepthU16,
                 data: Some(bytemuck::cast_slice(data)),
+                mipmap_data: &[],
             },
         }
     }
@@ -447,6 +1313,7 @@ impl<'a> DepthImage<'a> {
                 ty: ImageComponentType::F32,
                 internal_format: ImageInternalFormat::DepthF32,
                 data: None,
+                mipmap_data: &[],
             },
         }
     }
@@ -458,6 +1325,7 @@ impl<'a> DepthImage<'a> {
                 ty: ImageComponentType::U16,
                 internal_format: ImageInternalFormat::DepthU16,
                 data: None,
+                mipmap_data: &[],
             },
         }
     }
@@ -469,20 +1337,13 @@ impl<'a> DepthImage<'a> {
                 ty: ImageComponentType::U24U8,
                 internal_format: ImageInternalFormat::DepthU24StencilU8,
                 data: None,
+                mipmap_data: &[],
             },
         }
     }
 }
 
 // TODO:
-// - RgbaIntFormat
-// - RgbaUnsignedIntFormat
-// - RgbIntFormat
-// - RgbUnsignedIntFormat
-// - RgIntFormat
-// - RgUnsignedIntFormat
-// - RedIntFormat
-// - RedUnsignedIntFormat
 // - DepthStencilFormat
 
 /*
@@ -503,21 +1364,33 @@ RED  - UNSIGNED_BYTE - R8
 RED  - BYTE          - R8_SNORM
 RED  - FLOAT         - R32F, R16F
 
-RGBA_INTEGER - UNSIGNED_BYTE - RGBA8UI
-RGBA_INTEGER - BYTE          - RGBA8I
-...
+RGBA_INTEGER - UNSIGNED_BYTE  - RGBA8UI
+RGBA_INTEGER - BYTE           - RGBA8I
+RGBA_INTEGER - UNSIGNED_SHORT - RGBA16UI
+RGBA_INTEGER - SHORT          - RGBA16I
+RGBA_INTEGER - UNSIGNED_INT   - RGBA32UI
+RGBA_INTEGER - INT            - RGBA32I
 
 RGB_INTEGER  - UNSIGNED_BYTE  - RGB8UI
 RGB_INTEGER  - BYTE           - RGB8I
-...
+RGB_INTEGER  - UNSIGNED_SHORT - RGB16UI
+RGB_INTEGER  - SHORT          - RGB16I
+RGB_INTEGER  - UNSIGNED_INT   - RGB32UI
+RGB_INTEGER  - INT            - RGB32I
 
 RG_INTEGER   - UNSIGNED_BYTE  - RG8UI
-RG_INTEGER   - BYTE           - RG 8I
-...
+RG_INTEGER   - BYTE           - RG8I
+RG_INTEGER   - UNSIGNED_SHORT - RG16UI
+RG_INTEGER   - SHORT          - RG16I
+RG_INTEGER   - UNSIGNED_INT   - RG32UI
+RG_INTEGER   - INT            - RG32I
 
 RED_INTEGER  - UNSIGNED_BYTE  - R8UI
 RED_INTEGER  - BYTE           - R8I
-...
+RED_INTEGER  - UNSIGNED_SHORT - R16UI
+RED_INTEGER  - SHORT          - R16I
+RED_INTEGER  - UNSIGNED_INT   - R32UI
+RED_INTEGER  - INT            - R32I
 
 DEPTH_COMPONENT - UNSIGNED_SHORT - DEPTH_COMPONENT16
 DEPTH_COMPONENT - UNSIGNED_INT   - DEPTH_COMPONENT24
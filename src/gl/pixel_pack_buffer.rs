@@ -0,0 +1,59 @@
+use std::{marker::PhantomData, mem::size_of};
+
+use bytemuck::Pod;
+
+use super::{raw, BufferError, BufferUsage};
+
+/// Stores pixels that are read from textures in a buffer on the GPU.
+///
+/// Reading pixels into a `PixelPackBuffer` does not stall until the GPU has
+/// finished rendering. Instead, create a [`Fence`](super::Fence) after
+/// starting the read and call [`Self::read`] once the fence is signaled.
+///
+/// Instances of `PixelPackBuffer` can be created with
+/// [`Context::create_pixel_pack_buffer`](crate::gl::Context::create_pixel_pack_buffer).
+pub struct PixelPackBuffer<T> {
+    raw: raw::Buffer,
+    _phantom: PhantomData<T>,
+}
+
+impl<T: Pod> PixelPackBuffer<T> {
+    pub(super) fn from_raw(raw: raw::Buffer) -> Self {
+        assert!(size_of::<T>() > 0);
+        assert_eq!(raw.len() % size_of::<T>(), 0);
+
+        Self {
+            raw,
+            _phantom: PhantomData,
+        }
+    }
+
+    pub(super) fn raw(&self) -> &raw::Buffer {
+        &self.raw
+    }
+
+    pub fn usage(&self) -> BufferUsage {
+        self.raw.usage()
+    }
+
+    /// Returns the number of components that the buffer can store.
+    pub fn len(&self) -> usize {
+        self.raw.len() / size_of::<T>()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the contents of the buffer.
+    ///
+    /// This blocks until pending reads into the buffer have completed.
+    pub fn read(&self) -> Result<Vec<T>, BufferError> {
+        let mut data = vec![T::zeroed(); self.len()];
+
+        self.raw
+            .get_sub_data(0, bytemuck::cast_slice_mut(&mut data))?;
+
+        Ok(data)
+    }
+}
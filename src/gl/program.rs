@@ -7,8 +7,9 @@ use crate::{
 };
 
 use super::{
-    raw, ColorSampler2d, ComparisonSampler2d, DrawError, DrawParams, Framebuffer,
-    UniformBufferBinding, VertexSpec,
+    raw, ColorSampler2d, ColorSampler2dArray, ColorSampler3d, ColorSamplerCube,
+    ComparisonSampler2d, ComparisonSampler2dArray, ComparisonSamplerCube, DrawError, DrawParams,
+    Framebuffer, Query, QueryKind, UniformBufferBinding, VertexBuffer, VertexSpec,
 };
 
 type RawProgram = Result<Rc<raw::Program>, DrawError>;
@@ -71,6 +72,20 @@ where
 
         Ok(inner)
     }
+
+    /// Draws like [`Self::draw`], with `query` measuring the draw.
+    ///
+    /// The query ends when the draw returns, so its result covers only this
+    /// draw. See [`Query::begin`] for measuring multiple draws.
+    pub fn draw_measured<K: QueryKind>(
+        self,
+        vertex_spec: VertexSpec<V>,
+        query: &Query<K>,
+    ) -> Result<Self, DrawError> {
+        let _scope = query.begin()?;
+
+        self.draw(vertex_spec)
+    }
 }
 
 pub struct DrawBuilderWithUniforms<U, V, F>
@@ -131,6 +146,20 @@ where
 
         Ok(Self { inner, uniforms })
     }
+
+    /// Draws like [`Self::draw`], with `query` measuring the draw.
+    ///
+    /// The query ends when the draw returns, so its result covers only this
+    /// draw. See [`Query::begin`] for measuring multiple draws.
+    pub fn draw_measured<K: QueryKind>(
+        self,
+        vertex_spec: VertexSpec<V>,
+        query: &Query<K>,
+    ) -> Result<Self, DrawError> {
+        let _scope = query.begin()?;
+
+        self.draw(vertex_spec)
+    }
 }
 
 pub struct DrawBuilderWithFramebuffer<U, V, F>
@@ -188,6 +217,20 @@ where
 
         Ok(Self { inner, framebuffer })
     }
+
+    /// Draws like [`Self::draw`], with `query` measuring the draw.
+    ///
+    /// The query ends when the draw returns, so its result covers only this
+    /// draw. See [`Query::begin`] for measuring multiple draws.
+    pub fn draw_measured<K: QueryKind>(
+        self,
+        vertex_spec: VertexSpec<V>,
+        query: &Query<K>,
+    ) -> Result<Self, DrawError> {
+        let _scope = query.begin()?;
+
+        self.draw(vertex_spec)
+    }
 }
 
 pub struct DrawBuilderWithUniformsAndFramebuffer<U, V, F>
@@ -248,6 +291,20 @@ where
 
         Ok(self)
     }
+
+    /// Draws like [`Self::draw`], with `query` measuring the draw.
+    ///
+    /// The query ends when the draw returns, so its result covers only this
+    /// draw. See [`Query::begin`] for measuring multiple draws.
+    pub fn draw_measured<K: QueryKind>(
+        self,
+        vertex_spec: VertexSpec<V>,
+        query: &Query<K>,
+    ) -> Result<Self, DrawError> {
+        let _scope = query.begin()?;
+
+        self.draw(vertex_spec)
+    }
 }
 
 pub struct Program<U, V, F = sl::Vec4> {
@@ -311,20 +368,126 @@ where
     ) -> Result<DrawBuilder<(), V, sl::Vec4>, DrawError> {
         self.with_params(DrawParams::new()).draw(vertex_spec)
     }
+
+    /// Draws like [`Self::draw`], with `query` measuring the draw.
+    pub fn draw_measured<K: QueryKind>(
+        &self,
+        vertex_spec: VertexSpec<V>,
+        query: &Query<K>,
+    ) -> Result<DrawBuilder<(), V, sl::Vec4>, DrawError> {
+        self.with_params(DrawParams::new())
+            .draw_measured(vertex_spec, query)
+    }
+}
+
+/// A program whose vertex shader output is captured into a vertex buffer with
+/// transform feedback, instead of being rasterized.
+///
+/// Instances of `TransformFeedbackProgram` can be created with
+/// [`Context::create_transform_feedback_program`](crate::gl::Context::create_transform_feedback_program).
+pub struct TransformFeedbackProgram<U, V, W> {
+    raw: Rc<raw::Program>,
+    _phantom: PhantomData<(U, V, W)>,
+}
+
+impl<U, V, W> TransformFeedbackProgram<U, V, W>
+where
+    U: Uniform<Sl>,
+    V: VsInterface<Sl>,
+    W: Block<Sl>,
+{
+    pub(super) fn unchecked_from_raw(raw: Rc<raw::Program>) -> Self {
+        TransformFeedbackProgram {
+            raw,
+            _phantom: PhantomData,
+        }
+    }
+
+    #[must_use]
+    pub fn with_uniforms(&self, uniforms: U::Gl) -> CaptureBuilderWithUniforms<U, V, W> {
+        CaptureBuilderWithUniforms {
+            raw: self.raw.clone(),
+            uniforms,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<V, W> TransformFeedbackProgram<(), V, W>
+where
+    V: VsInterface<Sl>,
+    W: Block<Sl>,
+{
+    /// Runs the vertex shader on `vertex_spec` and writes its outputs to
+    /// `buffer`, starting at the first vertex.
+    ///
+    /// Only points, lines and triangles can be captured, and `vertex_spec` must
+    /// not have an element buffer.
+    pub fn capture(
+        &self,
+        vertex_spec: VertexSpec<V>,
+        buffer: &VertexBuffer<W::Gl>,
+    ) -> Result<CaptureBuilderWithUniforms<(), V, W>, DrawError> {
+        self.with_uniforms(()).capture(vertex_spec, buffer)
+    }
+}
+
+pub struct CaptureBuilderWithUniforms<U, V, W>
+where
+    U: Uniform<Sl>,
+    V: VsInterface<Sl>,
+    W: Block<Sl>,
+{
+    raw: Rc<raw::Program>,
+    uniforms: U::Gl,
+    _phantom: PhantomData<(V, W)>,
+}
+
+impl<U, V, W> CaptureBuilderWithUniforms<U, V, W>
+where
+    U: Uniform<Sl>,
+    V: VsInterface<Sl>,
+    W: Block<Sl>,
+{
+    #[must_use]
+    pub fn with_uniforms(mut self, uniforms: U::Gl) -> Self {
+        self.uniforms = uniforms;
+        self
+    }
+
+    /// See [`TransformFeedbackProgram::capture`].
+    pub fn capture(
+        self,
+        vertex_spec: VertexSpec<V>,
+        buffer: &VertexBuffer<W::Gl>,
+    ) -> Result<Self, DrawError> {
+        // TODO: These allocations can be avoided once stable has allocators.
+        // TODO: Remove hardcoded path names.
+        let mut uniform_visitor = CollectUniforms::default();
+        self.uniforms.visit("", &mut uniform_visitor);
+
+        unsafe {
+            self.raw.capture(
+                &uniform_visitor.raw_uniform_buffers,
+                &uniform_visitor.raw_samplers,
+                &vertex_spec.raw(),
+                buffer.as_binding().raw(),
+                0,
+            )
+        }?;
+
+        Ok(self)
+    }
 }
 
 #[derive(Default)]
-struct CollectUniforms<'a> {
-    raw_uniform_buffers: Vec<&'a raw::Buffer>,
+struct CollectUniforms {
+    raw_uniform_buffers: Vec<raw::UniformBufferBinding>,
     raw_samplers: Vec<raw::Sampler>,
 }
 
-impl<'a> UniformVisitor<'a, Gl> for CollectUniforms<'a> {
-    fn accept_block<B: Block<Sl, Sl = B>>(
-        &mut self,
-        _: &str,
-        uniform: &'a UniformBufferBinding<B>,
-    ) {
+impl<'a> UniformVisitor<'a, Gl> for CollectUniforms {
+    fn accept_block<B: Block<Sl, Sl = B>>(&mut self, _: &str, uniform: &UniformBufferBinding<B>) {
         self.raw_uniform_buffers.push(uniform.raw());
     }
 
@@ -337,4 +500,37 @@ impl<'a> UniformVisitor<'a, Gl> for CollectUniforms<'a> {
         self.raw_samplers
             .push(raw::Sampler::Sampler2d(sampler.raw().clone()))
     }
+
+    fn accept_color_sampler_2d_array<S: ColorSample>(
+        &mut self,
+        _: &str,
+        sampler: &ColorSampler2dArray<S>,
+    ) {
+        self.raw_samplers
+            .push(raw::Sampler::Sampler3d(sampler.raw().clone()))
+    }
+
+    fn accept_comparison_sampler_2d_array(&mut self, _: &str, sampler: &ComparisonSampler2dArray) {
+        self.raw_samplers
+            .push(raw::Sampler::Sampler3d(sampler.raw().clone()))
+    }
+
+    fn accept_color_sampler_3d<S: ColorSample>(&mut self, _: &str, sampler: &ColorSampler3d<S>) {
+        self.raw_samplers
+            .push(raw::Sampler::Sampler3d(sampler.raw().clone()))
+    }
+
+    fn accept_color_sampler_cube<S: ColorSample>(
+        &mut self,
+        _: &str,
+        sampler: &ColorSamplerCube<S>,
+    ) {
+        self.raw_samplers
+            .push(raw::Sampler::Sampler3d(sampler.raw().clone()))
+    }
+
+    fn accept_comparison_sampler_cube(&mut self, _: &str, sampler: &ComparisonSamplerCube) {
+        self.raw_samplers
+            .push(raw::Sampler::Sampler3d(sampler.raw().clone()))
+    }
 }
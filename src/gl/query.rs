@@ -0,0 +1,107 @@
+use std::marker::PhantomData;
+
+use sealed::sealed;
+
+use super::{raw, QueryError, QueryTarget};
+
+/// The kind of value that a [`Query`] measures.
+#[sealed]
+pub trait QueryKind {
+    const TARGET: QueryTarget;
+}
+
+/// Measures whether any samples passed the depth and stencil tests.
+pub struct AnySamplesPassed;
+
+/// Measures whether any samples passed the depth and stencil tests, allowing
+/// the implementation to report false positives in exchange for a faster test.
+pub struct AnySamplesPassedConservative;
+
+#[sealed]
+impl QueryKind for AnySamplesPassed {
+    const TARGET: QueryTarget = QueryTarget::AnySamplesPassed;
+}
+
+#[sealed]
+impl QueryKind for AnySamplesPassedConservative {
+    const TARGET: QueryTarget = QueryTarget::AnySamplesPassedConservative;
+}
+
+/// Measures whether the draws in a scope passed any samples.
+///
+/// This allows skipping expensive objects whose bounding boxes were fully
+/// occluded in a previous frame. Results arrive asynchronously, so they are
+/// polled with [`Self::try_result`].
+///
+/// Instances of `Query` can be created with
+/// [`Context::create_query`](crate::gl::Context::create_query).
+pub struct Query<K = AnySamplesPassed> {
+    raw: raw::Query,
+    _phantom: PhantomData<K>,
+}
+
+impl<K: QueryKind> Query<K> {
+    pub(super) fn from_raw(raw: raw::Query) -> Self {
+        assert_eq!(raw.target(), K::TARGET);
+
+        Self {
+            raw,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Starts measuring the draws that follow, until the returned scope is
+    /// dropped.
+    ///
+    /// The previous result of the query is discarded. At most one query can
+    /// measure at a time, so this fails while the scope of another query is
+    /// alive.
+    pub fn begin(&self) -> Result<QueryScope<'_, K>, QueryError> {
+        self.raw.begin()?;
+
+        Ok(QueryScope { query: self })
+    }
+
+    /// Measures the draws that are issued by `f`.
+    ///
+    /// See [`Self::begin`].
+    pub fn scope<R>(&self, f: impl FnOnce() -> R) -> Result<R, QueryError> {
+        let scope = self.begin()?;
+        let result = f();
+
+        scope.end();
+
+        Ok(result)
+    }
+
+    /// Returns whether any samples passed in the last scope of the query, or
+    /// `None` if the result is not available yet.
+    ///
+    /// This does not block.
+    pub fn try_result(&self) -> Option<bool> {
+        self.raw.try_result()
+    }
+}
+
+/// The scope in which a [`Query`] measures draws.
+///
+/// The query ends when the scope is dropped, even if this happens during a
+/// panic. Single draws can also be measured with the `draw_measured` methods
+/// of the draw builders, e.g. [`DrawBuilder::draw_measured`].
+///
+/// [`DrawBuilder::draw_measured`]: super::DrawBuilder::draw_measured
+#[must_use = "the query stops measuring when the scope is dropped"]
+pub struct QueryScope<'a, K> {
+    query: &'a Query<K>,
+}
+
+impl<K> QueryScope<'_, K> {
+    /// Stops measuring draws.
+    pub fn end(self) {}
+}
+
+impl<K> Drop for QueryScope<'_, K> {
+    fn drop(&mut self) {
+        self.query.raw.end();
+    }
+}
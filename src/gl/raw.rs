@@ -2,29 +2,41 @@ mod buffer;
 mod caps;
 mod context;
 mod error;
+mod fence;
 mod framebuffer;
+mod gl_api;
 mod image;
 mod params;
 mod program;
+mod query;
+#[cfg(not(target_family = "wasm"))]
+mod recorder;
+mod renderbuffer;
 mod sampler_params;
+mod soft;
 mod texture;
 mod vertex_layout;
 mod vertex_spec;
 
 pub use self::image::{Image, ImageComponentType, ImageFormat, ImageInternalFormat};
-pub use buffer::{Buffer, BufferUsage};
+pub use buffer::{Buffer, BufferUsage, UniformBufferBinding};
 pub use caps::Caps;
 pub use context::Context;
 pub use error::{
-    BufferError, ContextError, CreateError, DrawError, FramebufferError, ProgramError,
-    ProgramValidationError, TextureError, VertexArrayError,
+    BufferError, ContextError, CreateError, DrawError, FenceError, FramebufferError, ProgramError,
+    ProgramValidationError, QueryError, RenderbufferError, TextureError, VertexArrayError,
 };
+pub use fence::Fence;
 pub use framebuffer::{Attachment, Framebuffer};
 pub use params::{
-    BlendEquation, BlendFunc, Blending, Comparison, CullFace, DrawParams, Rect, StencilOp,
-    StencilOps, StencilTest,
+    BlendEquation, BlendFunc, Blending, ClearColor, ClearValues, Comparison, CullFace, DrawParams,
+    Rect, StencilOp, StencilOps, StencilTest,
 };
 pub use program::Program;
+pub use query::{Query, QueryTarget};
+#[cfg(not(target_family = "wasm"))]
+pub use recorder::{ObjectKind, RecordedCall, Recorder};
+pub use renderbuffer::Renderbuffer;
 pub use sampler_params::{Sampler2dParams, SamplerMagFilter, SamplerMinFilter, SamplerWrap};
-pub use texture::{Sampler, Sampler2d, Texture2d};
+pub use texture::{CubeFace, Sampler, Sampler2d, Sampler3d, Texture2d, Texture3d};
 pub use vertex_spec::{ElementType, PrimitiveMode, VertexBufferBinding, VertexSpec};
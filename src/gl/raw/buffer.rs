@@ -1,8 +1,14 @@
-use std::{cell::Cell, rc::Rc};
+use std::{
+    cell::{Cell, Ref, RefCell, RefMut},
+    ops::Range,
+    rc::Rc,
+};
 
-use glow::HasContext;
-
-use super::{context::ContextShared, error::check_gl_error, BufferError};
+use super::{
+    context::{Backend, ContextShared},
+    error::check_gl_error,
+    BufferError,
+};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum BufferUsage {
@@ -29,9 +35,21 @@ impl BufferUsage {
     }
 }
 
+/// A range of a buffer that is bound to a uniform block.
+#[derive(Clone)]
+pub struct UniformBufferBinding {
+    pub buffer: Rc<Buffer>,
+    pub range: Range<usize>,
+}
+
+enum Storage {
+    Gl(glow::Buffer),
+    Soft(RefCell<Vec<u8>>),
+}
+
 pub struct Buffer {
     ctx: Rc<ContextShared>,
-    id: glow::Buffer,
+    storage: Storage,
     target: u32,
     usage: BufferUsage,
     len: Cell<usize>,
@@ -44,12 +62,18 @@ impl Buffer {
         target: u32,
         usage: BufferUsage,
     ) -> Result<Self, BufferError> {
-        let gl = ctx.gl();
-        let id = unsafe { gl.create_buffer() }.map_err(BufferError::ObjectCreation)?;
+        let storage = match ctx.backend() {
+            Backend::Gl { gl, .. } => {
+                let id = unsafe { gl.create_buffer() }.map_err(BufferError::ObjectCreation)?;
+
+                Storage::Gl(id)
+            }
+            Backend::Soft(_) => Storage::Soft(RefCell::new(Vec::new())),
+        };
 
         let buffer = Buffer {
             ctx: ctx.clone(),
-            id,
+            storage,
             target,
             usage,
             len: Cell::new(0),
@@ -57,7 +81,9 @@ impl Buffer {
 
         buffer.set(data);
 
-        check_gl_error(gl, "after new buffer").map_err(BufferError::Unexpected)?;
+        if let Backend::Gl { gl, .. } = ctx.backend() {
+            check_gl_error(&**gl, "after new buffer").map_err(BufferError::Unexpected)?;
+        }
 
         Ok(buffer)
     }
@@ -66,8 +92,31 @@ impl Buffer {
         &self.ctx
     }
 
+    /// # Panics
+    ///
+    /// Panics if this buffer belongs to a software context.
     pub fn id(&self) -> glow::Buffer {
-        self.id
+        match &self.storage {
+            Storage::Gl(id) => *id,
+            Storage::Soft(_) => panic!("expected an OpenGL buffer"),
+        }
+    }
+
+    /// Returns the contents of a buffer that belongs to a software context.
+    pub(super) fn soft_data(&self) -> Ref<'_, [u8]> {
+        match &self.storage {
+            Storage::Gl(_) => panic!("expected a software buffer"),
+            Storage::Soft(data) => Ref::map(data.borrow(), Vec::as_slice),
+        }
+    }
+
+    /// Returns the mutable contents of a buffer that belongs to a software
+    /// context.
+    pub(super) fn soft_data_mut(&self) -> RefMut<'_, [u8]> {
+        match &self.storage {
+            Storage::Gl(_) => panic!("expected a software buffer"),
+            Storage::Soft(data) => RefMut::map(data.borrow_mut(), Vec::as_mut_slice),
+        }
     }
 
     pub fn usage(&self) -> BufferUsage {
@@ -83,29 +132,160 @@ impl Buffer {
     }
 
     pub fn set(&self, data: &[u8]) {
-        let gl = self.ctx.gl();
+        match &self.storage {
+            Storage::Gl(id) => {
+                let gl = self.ctx.gl();
 
-        unsafe {
-            gl.bind_buffer(self.target, Some(self.id));
-            gl.buffer_data_u8_slice(self.target, data, self.usage.to_gl());
+                unsafe {
+                    gl.bind_buffer(self.target, Some(*id));
+                    gl.buffer_data_u8_slice(self.target, data, self.usage.to_gl());
 
-            // TODO: Could avoid unbinding here by using `ContextShared`.
-            gl.bind_buffer(self.target, None);
+                    // TODO: Could avoid unbinding here by using `ContextShared`.
+                    gl.bind_buffer(self.target, None);
+                }
+
+                #[cfg(debug_assertions)]
+                check_gl_error(gl, "after buffer set").expect("OpenGL error after Buffer::set");
+            }
+            Storage::Soft(storage) => {
+                *storage.borrow_mut() = data.to_vec();
+            }
         }
 
         self.len.set(data.len());
+    }
+
+    /// Replaces the contents of the buffer starting at `offset` with `data`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range is out of bounds of the buffer.
+    pub fn set_sub_data(&self, offset: usize, data: &[u8]) {
+        assert!(offset + data.len() <= self.len());
 
-        #[cfg(debug_assertions)]
-        check_gl_error(gl, "after buffer set").expect("OpenGL error after Buffer::set");
+        match &self.storage {
+            Storage::Gl(id) => {
+                let gl = self.ctx.gl();
+                let offset = offset.try_into().expect("offset is out of i32 range");
+
+                unsafe {
+                    gl.bind_buffer(self.target, Some(*id));
+                    gl.buffer_sub_data_u8_slice(self.target, offset, data);
+                    gl.bind_buffer(self.target, None);
+                }
+
+                #[cfg(debug_assertions)]
+                check_gl_error(gl, "after buffer set_sub_data")
+                    .expect("OpenGL error after Buffer::set_sub_data");
+            }
+            Storage::Soft(storage) => {
+                storage.borrow_mut()[offset..offset + data.len()].copy_from_slice(data);
+            }
+        }
+    }
+
+    /// Reallocates the buffer with `len` bytes of undefined contents.
+    ///
+    /// The previous storage is orphaned: commands that still use it are not
+    /// waited for, since the driver can keep the old storage alive until they
+    /// have completed.
+    pub fn orphan(&self, len: usize) {
+        match &self.storage {
+            Storage::Gl(id) => {
+                let gl = self.ctx.gl();
+                let size = len.try_into().expect("length is out of i32 range");
+
+                unsafe {
+                    gl.bind_buffer(self.target, Some(*id));
+                    gl.buffer_data_size(self.target, size, self.usage.to_gl());
+                    gl.bind_buffer(self.target, None);
+                }
+
+                #[cfg(debug_assertions)]
+                check_gl_error(gl, "after buffer orphan")
+                    .expect("OpenGL error after Buffer::orphan");
+            }
+            Storage::Soft(storage) => {
+                *storage.borrow_mut() = vec![0; len];
+            }
+        }
+
+        self.len.set(len);
+    }
+
+    /// Reads the contents of the buffer starting at `offset` into `data`.
+    ///
+    /// This waits until all previous GL commands that write to the buffer have
+    /// completed. Buffers that are read from regularly should use one of the
+    /// `*Read` variants of [`BufferUsage`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range is out of bounds of the buffer.
+    pub fn get_sub_data(&self, offset: usize, data: &mut [u8]) -> Result<(), BufferError> {
+        assert!(offset + data.len() <= self.len());
+
+        let id = match &self.storage {
+            Storage::Gl(id) => *id,
+            Storage::Soft(storage) => {
+                data.copy_from_slice(&storage.borrow()[offset..offset + data.len()]);
+
+                return Ok(());
+            }
+        };
+
+        if data.is_empty() {
+            return Ok(());
+        }
+
+        let gl = self.ctx.gl();
+        let gl_offset = offset.try_into().expect("offset is out of i32 range");
+
+        unsafe { gl.bind_buffer(self.target, Some(id)) };
+
+        // WebGL does not support mapping buffers, while OpenGL ES does not
+        // support `glGetBufferSubData`.
+        #[cfg(target_family = "wasm")]
+        let result = {
+            unsafe { gl.get_buffer_sub_data(self.target, gl_offset, data) };
+
+            Ok(())
+        };
+
+        #[cfg(not(target_family = "wasm"))]
+        let result = {
+            let length = data.len().try_into().expect("length is out of i32 range");
+            let ptr =
+                unsafe { gl.map_buffer_range(self.target, gl_offset, length, glow::MAP_READ_BIT) };
+
+            if ptr.is_null() {
+                Err(BufferError::Unexpected("could not map buffer".to_string()))
+            } else {
+                unsafe {
+                    data.copy_from_slice(std::slice::from_raw_parts(ptr, data.len()));
+                    gl.unmap_buffer(self.target);
+                }
+
+                Ok(())
+            }
+        };
+
+        unsafe { gl.bind_buffer(self.target, None) };
+
+        check_gl_error(gl, "after buffer get_sub_data").map_err(BufferError::Unexpected)?;
+
+        result
     }
 }
 
 impl Drop for Buffer {
     fn drop(&mut self) {
-        let gl = self.ctx.gl();
+        if let Storage::Gl(id) = &self.storage {
+            let gl = self.ctx.gl();
 
-        unsafe {
-            gl.delete_buffer(self.id);
+            unsafe {
+                gl.delete_buffer(*id);
+            }
         }
     }
 }
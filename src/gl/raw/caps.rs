@@ -1,26 +1,135 @@
-use glow::HasContext;
+use super::{gl_api::GlApi, ImageInternalFormat};
 
 #[derive(Debug, Copy, Clone)]
 pub struct Caps {
     pub max_texture_size: u32,
+    pub max_3d_texture_size: u32,
+    pub max_array_texture_layers: u32,
+    pub max_cube_map_texture_size: u32,
     pub max_color_attachments: u32,
     pub max_draw_buffers: u32,
+    pub max_renderbuffer_size: u32,
+    pub max_samples: u32,
+    pub uniform_buffer_offset_alignment: u32,
+    pub max_transform_feedback_interleaved_components: u32,
+
+    /// Whether ETC2 and EAC compressed formats are supported. These are core
+    /// in OpenGL ES 3.0 and OpenGL 4.3, while older desktop contexts require
+    /// `GL_ARB_ES3_compatibility` and WebGL 2 requires
+    /// `WEBGL_compressed_texture_etc`.
+    pub texture_compression_etc2: bool,
+
+    /// Whether ASTC compressed formats are supported, which requires
+    /// `KHR_texture_compression_astc_ldr`.
+    pub texture_compression_astc: bool,
+
+    /// Whether S3TC compressed formats are supported, which requires
+    /// `EXT_texture_compression_s3tc`.
+    pub texture_compression_s3tc: bool,
 }
 
 impl Caps {
-    pub fn new(gl: &glow::Context) -> Self {
+    pub fn new(gl: &dyn GlApi) -> Self {
         let max_texture_size = unsafe { gl.get_parameter_i32(glow::MAX_TEXTURE_SIZE) };
+        let max_3d_texture_size = unsafe { gl.get_parameter_i32(glow::MAX_3D_TEXTURE_SIZE) };
+        let max_array_texture_layers =
+            unsafe { gl.get_parameter_i32(glow::MAX_ARRAY_TEXTURE_LAYERS) };
+        let max_cube_map_texture_size =
+            unsafe { gl.get_parameter_i32(glow::MAX_CUBE_MAP_TEXTURE_SIZE) };
         let max_color_attachments = unsafe { gl.get_parameter_i32(glow::MAX_COLOR_ATTACHMENTS) };
         let max_draw_buffers = unsafe { gl.get_parameter_i32(glow::MAX_DRAW_BUFFERS) };
+        let max_renderbuffer_size = unsafe { gl.get_parameter_i32(glow::MAX_RENDERBUFFER_SIZE) };
+        let max_samples = unsafe { gl.get_parameter_i32(glow::MAX_SAMPLES) };
+        let uniform_buffer_offset_alignment =
+            unsafe { gl.get_parameter_i32(glow::UNIFORM_BUFFER_OFFSET_ALIGNMENT) };
+        let max_transform_feedback_interleaved_components =
+            unsafe { gl.get_parameter_i32(glow::MAX_TRANSFORM_FEEDBACK_INTERLEAVED_COMPONENTS) };
+
+        let version = unsafe { gl.version() };
+        let extensions = unsafe { gl.supported_extensions() };
+
+        // WebGL 2 reports itself as OpenGL ES 3.0, but only exposes ETC2
+        // through an extension.
+        let texture_compression_etc2 = if cfg!(target_family = "wasm") {
+            extensions.contains("WEBGL_compressed_texture_etc")
+        } else {
+            version.is_embedded
+                || (version.major, version.minor) >= (4, 3)
+                || extensions.contains("GL_ARB_ES3_compatibility")
+        };
+        let texture_compression_astc = extensions.contains("GL_KHR_texture_compression_astc_ldr")
+            || extensions.contains("WEBGL_compressed_texture_astc");
+        let texture_compression_s3tc = extensions.contains("GL_EXT_texture_compression_s3tc")
+            || extensions.contains("WEBGL_compressed_texture_s3tc");
 
         assert!(max_texture_size > 0);
+        assert!(max_3d_texture_size > 0);
+        assert!(max_array_texture_layers > 0);
+        assert!(max_cube_map_texture_size > 0);
         assert!(max_color_attachments > 0);
         assert!(max_draw_buffers > 0);
+        assert!(max_renderbuffer_size > 0);
+        assert!(max_samples > 0);
+        assert!(uniform_buffer_offset_alignment > 0);
+        assert!(max_transform_feedback_interleaved_components > 0);
 
         Caps {
             max_texture_size: max_texture_size.try_into().unwrap(),
+            max_3d_texture_size: max_3d_texture_size.try_into().unwrap(),
+            max_array_texture_layers: max_array_texture_layers.try_into().unwrap(),
+            max_cube_map_texture_size: max_cube_map_texture_size.try_into().unwrap(),
             max_color_attachments: max_color_attachments.try_into().unwrap(),
             max_draw_buffers: max_draw_buffers.try_into().unwrap(),
+            max_renderbuffer_size: max_renderbuffer_size.try_into().unwrap(),
+            max_samples: max_samples.try_into().unwrap(),
+            uniform_buffer_offset_alignment: uniform_buffer_offset_alignment.try_into().unwrap(),
+            max_transform_feedback_interleaved_components:
+                max_transform_feedback_interleaved_components
+                    .try_into()
+                    .unwrap(),
+            texture_compression_etc2,
+            texture_compression_astc,
+            texture_compression_s3tc,
+        }
+    }
+
+    /// Returns the capabilities of the software backend, which does not
+    /// support compressed formats.
+    ///
+    /// Multisampled renderbuffers are accepted, but the software backend
+    /// renders them with a single sample, so resolving them copies pixels.
+    pub(super) fn software() -> Self {
+        Caps {
+            max_texture_size: 8192,
+            max_3d_texture_size: 2048,
+            max_array_texture_layers: 2048,
+            max_cube_map_texture_size: 8192,
+            max_color_attachments: 8,
+            max_draw_buffers: 8,
+            max_renderbuffer_size: 8192,
+            max_samples: 4,
+            uniform_buffer_offset_alignment: 16,
+            max_transform_feedback_interleaved_components: 64,
+            texture_compression_etc2: false,
+            texture_compression_astc: false,
+            texture_compression_s3tc: false,
+        }
+    }
+
+    /// Returns whether textures of the given internal format can be created.
+    pub fn supports_format(&self, internal_format: ImageInternalFormat) -> bool {
+        use ImageInternalFormat::*;
+
+        match internal_format {
+            Etc2RgbU8 | Etc2RgbU8Gamma | Etc2RgbU8A1 | Etc2RgbU8A1Gamma | Etc2RgbaU8
+            | Etc2RgbaU8Gamma | EacRU11 | EacRI11Snorm | EacRgU11 | EacRgI11Snorm => {
+                self.texture_compression_etc2
+            }
+            S3tcRgbDxt1 | S3tcRgbaDxt1 | S3tcRgbaDxt3 | S3tcRgbaDxt5 => {
+                self.texture_compression_s3tc
+            }
+            format if format.is_compressed() => self.texture_compression_astc,
+            _ => true,
         }
     }
 }
@@ -1,20 +1,35 @@
 use std::{cell::Cell, rc::Rc};
 
-use glow::HasContext;
-
 use crate::{
     gl::{BufferError, BufferUsage, ProgramError},
     sl::program_def::ProgramDef,
 };
 
-use super::{Buffer, Caps, ContextError, DrawParams, Image, Program, Texture2d, TextureError};
+use super::{
+    error::check_gl_error, gl_api::GlApi, soft, Buffer, Caps, ClearValues, ContextError,
+    DrawParams, Fence, FenceError, Framebuffer, FramebufferError, Image, ImageInternalFormat,
+    Program, Query, QueryError, QueryTarget, Rect, Renderbuffer, RenderbufferError,
+    SamplerMagFilter, Texture2d, Texture3d, TextureError,
+};
+
+#[cfg(not(target_family = "wasm"))]
+use super::Recorder;
+
+pub(super) enum Backend {
+    Gl {
+        gl: Box<dyn GlApi>,
+        draw_fbo: glow::Framebuffer,
+        read_fbo: glow::Framebuffer,
+    },
+    Soft(soft::Device),
+}
 
 pub(super) struct ContextShared {
-    gl: glow::Context,
+    backend: Backend,
     caps: Caps,
     draw_params: Cell<DrawParams>,
-    draw_fbo: glow::Framebuffer,
     default_framebuffer_size: Cell<[u32; 2]>,
+    occlusion_query_active: Cell<bool>,
 }
 
 pub struct Context {
@@ -26,16 +41,33 @@ impl ContextShared {
         std::ptr::eq(self as *const ContextShared, other as *const ContextShared)
     }
 
-    pub fn gl(&self) -> &glow::Context {
-        &self.gl
+    pub fn backend(&self) -> &Backend {
+        &self.backend
+    }
+
+    /// Returns the OpenGL context.
+    ///
+    /// # Panics
+    ///
+    /// Panics if this is a software context. Objects only call this if they
+    /// were created by an OpenGL context.
+    pub fn gl(&self) -> &dyn GlApi {
+        match &self.backend {
+            Backend::Gl { gl, .. } => &**gl,
+            Backend::Soft(_) => panic!("expected an OpenGL context"),
+        }
     }
 
     pub fn caps(&self) -> &Caps {
         &self.caps
     }
 
+    pub(super) fn draw_params(&self) -> DrawParams {
+        self.draw_params.get()
+    }
+
     pub(super) fn set_draw_params(&self, new: &DrawParams, framebuffer_size: [u32; 2]) {
-        let gl = &self.gl;
+        let gl = self.gl();
 
         let current = self.draw_params.get();
         new.set_delta(gl, &current, framebuffer_size);
@@ -43,17 +75,46 @@ impl ContextShared {
     }
 
     pub(super) fn draw_fbo(&self) -> glow::Framebuffer {
-        self.draw_fbo
+        match &self.backend {
+            Backend::Gl { draw_fbo, .. } => *draw_fbo,
+            Backend::Soft(_) => panic!("expected an OpenGL context"),
+        }
+    }
+
+    pub(super) fn read_fbo(&self) -> glow::Framebuffer {
+        match &self.backend {
+            Backend::Gl { read_fbo, .. } => *read_fbo,
+            Backend::Soft(_) => panic!("expected an OpenGL context"),
+        }
     }
 
     pub(super) fn default_framebuffer_size(&self) -> [u32; 2] {
         self.default_framebuffer_size.get()
     }
+
+    /// Returns whether an occlusion query is active. At most one occlusion
+    /// query can be active at a time, regardless of its target.
+    pub(super) fn occlusion_query_active(&self) -> bool {
+        self.occlusion_query_active.get()
+    }
+
+    pub(super) fn set_occlusion_query_active(&self, active: bool) {
+        self.occlusion_query_active.set(active);
+    }
 }
 
 impl Context {
     pub fn new(gl: glow::Context) -> Result<Self, ContextError> {
-        let caps = Caps::new(&gl);
+        Self::new_with_api(Box::new(gl))
+    }
+
+    #[cfg(not(target_family = "wasm"))]
+    pub fn new_recording(recorder: Recorder) -> Result<Self, ContextError> {
+        Self::new_with_api(Box::new(recorder))
+    }
+
+    fn new_with_api(gl: Box<dyn GlApi>) -> Result<Self, ContextError> {
+        let caps = Caps::new(&*gl);
 
         // All vertex bindings are made through a single vertex array object
         // that is bound at the start. The vertex array object binding must not
@@ -66,6 +127,17 @@ impl Context {
         // that is created at the start.
         let draw_fbo = unsafe { gl.create_framebuffer() }.map_err(ContextError::ObjectCreation)?;
 
+        // Copies between framebuffers additionally need a framebuffer object
+        // to read from.
+        let read_fbo = unsafe { gl.create_framebuffer() }.map_err(ContextError::ObjectCreation)?;
+
+        // Cube maps are always sampled across faces in OpenGL ES and WebGL,
+        // whereas desktop OpenGL needs to be told to do so. OpenGL ES does not
+        // know the capability.
+        if !unsafe { gl.version() }.is_embedded {
+            unsafe { gl.enable(glow::TEXTURE_CUBE_MAP_SEAMLESS) };
+        }
+
         let default_framebuffer_size = {
             let mut viewport = [0, 0, 0, 0];
 
@@ -78,16 +150,32 @@ impl Context {
         };
 
         let shared = Rc::new(ContextShared {
-            gl,
+            backend: Backend::Gl {
+                gl,
+                draw_fbo,
+                read_fbo,
+            },
             caps,
             draw_params: Cell::new(DrawParams::new()),
-            draw_fbo,
             default_framebuffer_size: Cell::new(default_framebuffer_size),
+            occlusion_query_active: Cell::new(false),
         });
 
         Ok(Self { shared })
     }
 
+    pub fn new_software(default_framebuffer_size: [u32; 2]) -> Self {
+        let shared = Rc::new(ContextShared {
+            backend: Backend::Soft(soft::Device::new(default_framebuffer_size)),
+            caps: Caps::software(),
+            draw_params: Cell::new(DrawParams::new()),
+            default_framebuffer_size: Cell::new(default_framebuffer_size),
+            occlusion_query_active: Cell::new(false),
+        });
+
+        Self { shared }
+    }
+
     pub fn caps(&self) -> &Caps {
         &self.shared.caps
     }
@@ -109,12 +197,128 @@ impl Context {
         Texture2d::new_with_mipmap(self.shared.clone(), image)
     }
 
+    /// Creates a two-dimensional array texture with one layer per image.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the images differ in size or format.
+    pub fn create_texture_2d_array(&self, layers: &[Image]) -> Result<Texture3d, TextureError> {
+        Texture3d::new(self.shared.clone(), glow::TEXTURE_2D_ARRAY, layers)
+    }
+
+    pub fn create_texture_2d_array_with_mipmap(
+        &self,
+        layers: &[Image],
+    ) -> Result<Texture3d, TextureError> {
+        Texture3d::new_with_mipmap(self.shared.clone(), glow::TEXTURE_2D_ARRAY, layers)
+    }
+
+    /// Creates a three-dimensional texture with one slice per image.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the images differ in size or format.
+    pub fn create_texture_3d(&self, slices: &[Image]) -> Result<Texture3d, TextureError> {
+        Texture3d::new(self.shared.clone(), glow::TEXTURE_3D, slices)
+    }
+
+    pub fn create_texture_3d_with_mipmap(
+        &self,
+        slices: &[Image],
+    ) -> Result<Texture3d, TextureError> {
+        Texture3d::new_with_mipmap(self.shared.clone(), glow::TEXTURE_3D, slices)
+    }
+
+    /// Creates a cube map whose faces are given by the images, in the order of
+    /// [`CubeFace::ALL`](super::CubeFace::ALL).
+    ///
+    /// # Panics
+    ///
+    /// Panics if the images differ in size or format, or if they are not
+    /// square.
+    pub fn create_texture_cube(&self, faces: &[Image; 6]) -> Result<Texture3d, TextureError> {
+        Texture3d::new(self.shared.clone(), glow::TEXTURE_CUBE_MAP, faces)
+    }
+
+    pub fn create_texture_cube_with_mipmap(
+        &self,
+        faces: &[Image; 6],
+    ) -> Result<Texture3d, TextureError> {
+        Texture3d::new_with_mipmap(self.shared.clone(), glow::TEXTURE_CUBE_MAP, faces)
+    }
+
+    /// Creates a renderbuffer with `samples` samples per pixel. Zero samples
+    /// create a single-sampled renderbuffer.
+    pub fn create_renderbuffer(
+        &self,
+        size: [u32; 2],
+        internal_format: ImageInternalFormat,
+        samples: u32,
+    ) -> Result<Renderbuffer, RenderbufferError> {
+        Renderbuffer::new(self.shared.clone(), size, internal_format, samples)
+    }
+
+    /// Copies `source_rect` of `source` into `destination_rect` of
+    /// `destination`, scaling the pixels with `filter`.
+    ///
+    /// The color images of the framebuffers are copied pairwise in order, and
+    /// the depth-stencil images are copied with nearest filtering if both
+    /// framebuffers have one. Multisampled images are resolved.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the framebuffers differ in their number of color images, if a
+    /// rectangle is not contained in an image, or if an attachment belongs to
+    /// a different context.
+    pub fn blit(
+        &self,
+        source: &Framebuffer,
+        source_rect: Rect,
+        destination: &Framebuffer,
+        destination_rect: Rect,
+        filter: SamplerMagFilter,
+    ) -> Result<(), FramebufferError> {
+        source.blit(
+            &self.shared,
+            source_rect,
+            destination,
+            destination_rect,
+            filter,
+        )
+    }
+
+    /// Clears the images of `framebuffer` to `values`, independently of any
+    /// draw call.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `values` has more colors than the framebuffer has color
+    /// images.
+    pub fn clear(
+        &self,
+        framebuffer: &Framebuffer,
+        values: &ClearValues,
+    ) -> Result<(), FramebufferError> {
+        framebuffer.clear(&self.shared, values)
+    }
+
     pub fn create_program(&self, def: ProgramDef) -> Result<Program, ProgramError> {
         Program::new(self.shared.clone(), def)
     }
 
+    pub fn create_fence(&self) -> Result<Fence, FenceError> {
+        Fence::new(self.shared.clone())
+    }
+
+    pub fn create_query(&self, target: QueryTarget) -> Result<Query, QueryError> {
+        Query::new(self.shared.clone(), target)
+    }
+
     pub fn finish(&self) {
-        unsafe { self.shared.gl.finish() };
+        match &self.shared.backend {
+            Backend::Gl { gl, .. } => unsafe { gl.finish() },
+            Backend::Soft(_) => (),
+        }
     }
 
     pub fn default_framebuffer_size(&self) -> [u32; 2] {
@@ -123,5 +327,52 @@ impl Context {
 
     pub fn set_default_framebuffer_size(&self, size: [u32; 2]) {
         self.shared.default_framebuffer_size.set(size);
+
+        if let Backend::Soft(device) = &self.shared.backend {
+            device.resize_default_framebuffer(size);
+        }
+    }
+
+    /// Reads RGBA pixels with 8-bit components from the default framebuffer.
+    ///
+    /// Rows are returned from bottom to top.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `rect` is not contained in the default framebuffer.
+    pub fn read_default_framebuffer_rgba_u8(&self, rect: Rect) -> Result<Vec<u8>, TextureError> {
+        let size = self.default_framebuffer_size();
+
+        assert!(rect.lower_left_corner[0] + rect.size[0] <= size[0]);
+        assert!(rect.lower_left_corner[1] + rect.size[1] <= size[1]);
+
+        let mut data = vec![0; rect.size[0] as usize * rect.size[1] as usize * 4];
+
+        match &self.shared.backend {
+            Backend::Gl { gl, .. } => {
+                let x = rect.lower_left_corner[0].try_into().unwrap();
+                let y = rect.lower_left_corner[1].try_into().unwrap();
+                let width = rect.size[0].try_into().unwrap();
+                let height = rect.size[1].try_into().unwrap();
+
+                unsafe {
+                    gl.pixel_store_i32(glow::PACK_ALIGNMENT, 1);
+                    gl.read_pixels(
+                        x,
+                        y,
+                        width,
+                        height,
+                        glow::RGBA,
+                        glow::UNSIGNED_BYTE,
+                        glow::PixelPackData::Slice(&mut data),
+                    );
+                }
+
+                check_gl_error(&**gl, "after reading pixels").map_err(TextureError::Unexpected)?;
+            }
+            Backend::Soft(device) => device.read_default_framebuffer_rgba_u8(rect, &mut data),
+        }
+
+        Ok(data)
     }
 }
@@ -1,7 +1,13 @@
-use glow::HasContext;
 use thiserror::Error;
 
-pub(super) fn check_gl_error(gl: &glow::Context, name: &str) -> Result<(), String> {
+use crate::sl::eval::EvalError;
+
+use super::{
+    gl_api::GlApi, ClearColor, ImageComponentType, ImageFormat, ImageInternalFormat, PrimitiveMode,
+    Rect,
+};
+
+pub(super) fn check_gl_error(gl: &dyn GlApi, name: &str) -> Result<(), String> {
     let error_info = unsafe { gl.get_error() };
 
     if error_info == glow::NO_ERROR {
@@ -34,16 +40,36 @@ pub enum ContextError {
     Unexpected(String),
 }
 
-/// An error that occurred while creating a buffer.
+/// An error that occurred while creating or reading a buffer.
 #[derive(Debug, Clone, Error)]
 pub enum BufferError {
     #[error("could not create buffer object: {0}")]
     ObjectCreation(String),
 
-    #[error("unexpected error while creating buffer: {0}")]
+    #[error("buffer is full: cannot allocate {requested} bytes")]
+    Full { requested: usize },
+
+    #[error("unexpected buffer error: {0}")]
     Unexpected(String),
 }
 
+/// An error that occurred while creating a fence.
+#[derive(Debug, Clone, Error)]
+pub enum FenceError {
+    #[error("could not create sync object: {0}")]
+    ObjectCreation(String),
+}
+
+/// An error that occurred while creating or beginning a query.
+#[derive(Debug, Clone, Error)]
+pub enum QueryError {
+    #[error("could not create query object: {0}")]
+    ObjectCreation(String),
+
+    #[error("another occlusion query is already active")]
+    AlreadyActive,
+}
+
 /// An error that occurred while creating a texture.
 #[derive(Debug, Clone, Error)]
 pub enum TextureError {
@@ -59,10 +85,53 @@ pub enum TextureError {
     #[error("invalid data size: expected {expected} bytes, but got {got}")]
     DataSizeMismatch { expected: usize, got: usize },
 
+    #[error("cannot read pixels of internal format {internal_format:?} as {format:?} with component type {ty:?}")]
+    UnsupportedRead {
+        internal_format: ImageInternalFormat,
+        format: ImageFormat,
+        ty: ImageComponentType,
+    },
+
+    #[error("internal format {0:?} is not supported")]
+    UnsupportedFormat(ImageInternalFormat),
+
+    #[error("cannot generate mipmaps for internal format {0:?}")]
+    MipmapUnsupported(ImageInternalFormat),
+
+    #[error("cannot read pixels from a multisampled renderbuffer")]
+    MultisampledRead,
+
     #[error("unexpected error while creating texture: {0}")]
     Unexpected(String),
 }
 
+/// An error that occurred while creating a renderbuffer.
+#[derive(Debug, Clone, Error)]
+pub enum RenderbufferError {
+    #[error("could not create renderbuffer object: {0}")]
+    ObjectCreation(String),
+
+    #[error("renderbuffer is empty")]
+    Empty,
+
+    #[error("renderbuffer too large: requested {requested}, but the maximum size is {max}")]
+    Oversized { requested: u32, max: u32 },
+
+    #[error("too many samples: requested {requested}, but the maximum number of samples is {max}")]
+    TooManySamples { requested: u32, max: u32 },
+
+    #[error("internal format {0:?} is not renderable")]
+    UnsupportedFormat(ImageInternalFormat),
+
+    #[error("renderbuffers of internal format {internal_format:?} cannot be multisampled")]
+    UnsupportedMultisample {
+        internal_format: ImageInternalFormat,
+    },
+
+    #[error("unexpected error while creating renderbuffer: {0}")]
+    Unexpected(String),
+}
+
 /// An error that occurred while creating a vertex array.
 #[derive(Debug, Clone, Error)]
 pub enum VertexArrayError {
@@ -102,7 +171,7 @@ pub enum FramebufferIncompleteError {
 }
 
 pub(super) fn check_framebuffer_completeness(
-    gl: &glow::Context,
+    gl: &dyn GlApi,
 ) -> Result<(), FramebufferIncompleteError> {
     let status = unsafe { gl.check_framebuffer_status(glow::FRAMEBUFFER) };
 
@@ -134,6 +203,9 @@ pub enum FramebufferError {
     #[error("texture level is too large: requested {requested}, but the maximum level is {max}")]
     LevelTooLarge { requested: u32, max: u32 },
 
+    #[error("texture layer is too large: requested {requested}, but the maximum layer is {max}")]
+    LayerTooLarge { requested: u32, max: u32 },
+
     #[error("too many color attachments: requested {requested}, but the maximum number of color attachments is {max}")]
     TooManyColorAttachments { requested: u32, max: u32 },
 
@@ -149,6 +221,30 @@ pub enum FramebufferError {
     #[error("framebuffer is incomplete unexpectedly: {0}")]
     Incomplete(FramebufferIncompleteError),
 
+    #[error("cannot blit {read:?} into {draw:?}: the rectangles must match when resolving multisampled pixels")]
+    BlitRectMismatch { read: Rect, draw: Rect },
+
+    #[error("cannot copy pixels of internal format {read:?} into {draw:?}")]
+    BlitFormatMismatch {
+        read: ImageInternalFormat,
+        draw: ImageInternalFormat,
+    },
+
+    #[error("cannot blit into a multisampled attachment")]
+    BlitIntoMultisampled,
+
+    #[error("linear filtering is not supported when blitting integer or depth-stencil images")]
+    BlitLinearFilter,
+
+    #[error("cannot copy from a multisampled attachment into a texture")]
+    CopyFromMultisampled,
+
+    #[error("cannot copy pixels into a texture of internal format {0:?}")]
+    CopyUnsupported(ImageInternalFormat),
+
+    #[error("cannot clear color image {index} to {color:?}, which does not match the component type of the image")]
+    ClearColorMismatch { index: usize, color: ClearColor },
+
     #[error("unexpected error while creating framebuffer: {0}")]
     Unexpected(String),
 }
@@ -167,6 +263,14 @@ pub enum ProgramValidationError {
 
     #[error("duplicate uniform block location: {0}")]
     DuplicateUniformBlockLocation(usize),
+
+    #[error("transform feedback attribute does not match the varyings: {0}")]
+    TransformFeedbackMismatch(String),
+
+    #[error(
+        "too many transform feedback components: requested {requested}, but the maximum is {max}"
+    )]
+    TooManyTransformFeedbackComponents { requested: usize, max: u32 },
 }
 
 /// An error that occurred while creating a program.
@@ -207,11 +311,20 @@ pub enum CreateError {
     #[error("texture error: {0}")]
     Texture(#[from] TextureError),
 
+    #[error("renderbuffer error: {0}")]
+    Renderbuffer(#[from] RenderbufferError),
+
     #[error("framebuffer error: {0}")]
     Framebuffer(#[from] FramebufferError),
 
     #[error("vertex array error: {0}")]
     VertexArray(#[from] VertexArrayError),
+
+    #[error("fence error: {0}")]
+    Fence(#[from] FenceError),
+
+    #[error("query error: {0}")]
+    Query(#[from] QueryError),
 }
 
 /// An error that occurred while drawing.
@@ -222,6 +335,18 @@ pub enum DrawError {
 
     #[error("general OpenGL error: {0}")]
     Error(String),
+
+    #[error("failed to evaluate shader in software: {0}")]
+    Eval(#[from] EvalError),
+
+    #[error("primitive mode {0:?} cannot be captured with transform feedback")]
+    TransformFeedbackMode(PrimitiveMode),
+
+    #[error("indexed drawing cannot be captured with transform feedback")]
+    TransformFeedbackIndexed,
+
+    #[error("transform feedback buffer is too small: requires {required} bytes, but only {available} are available")]
+    TransformFeedbackOverflow { required: usize, available: usize },
 }
 
 impl From<BufferError> for DrawError {
@@ -242,12 +367,24 @@ impl From<TextureError> for DrawError {
     }
 }
 
+impl From<RenderbufferError> for DrawError {
+    fn from(value: RenderbufferError) -> Self {
+        Self::Create(value.into())
+    }
+}
+
 impl From<FramebufferError> for DrawError {
     fn from(value: FramebufferError) -> Self {
         Self::Create(value.into())
     }
 }
 
+impl From<QueryError> for DrawError {
+    fn from(value: QueryError) -> Self {
+        Self::Create(value.into())
+    }
+}
+
 impl From<VertexArrayError> for DrawError {
     fn from(value: VertexArrayError) -> Self {
         Self::Create(value.into())
@@ -0,0 +1,92 @@
+use std::{cell::Cell, rc::Rc, time::Duration};
+
+use super::{
+    context::{Backend, ContextShared},
+    FenceError,
+};
+
+/// A sync object that is signaled once the GPU has completed all commands that
+/// were issued before the fence was created.
+///
+/// Fences allow polling for the completion of asynchronous operations, such as
+/// reading pixels into a pixel pack buffer, without stalling like
+/// [`Context::finish`](super::Context::finish) does.
+pub struct Fence {
+    ctx: Rc<ContextShared>,
+    sync: Option<glow::Fence>,
+    signaled: Cell<bool>,
+}
+
+impl Fence {
+    pub(super) fn new(ctx: Rc<ContextShared>) -> Result<Self, FenceError> {
+        // The software backend executes commands immediately, so its fences
+        // are signaled right away.
+        let sync = match ctx.backend() {
+            Backend::Gl { gl, .. } => Some(
+                unsafe { gl.fence_sync(glow::SYNC_GPU_COMMANDS_COMPLETE, 0) }
+                    .map_err(FenceError::ObjectCreation)?,
+            ),
+            Backend::Soft(_) => None,
+        };
+
+        Ok(Self {
+            ctx,
+            signaled: Cell::new(sync.is_none()),
+            sync,
+        })
+    }
+
+    /// Returns whether the fence has been signaled, without blocking.
+    pub fn is_signaled(&self) -> bool {
+        self.wait(Duration::ZERO)
+    }
+
+    /// Waits for at most `timeout` until the fence is signaled, and returns
+    /// whether it has been signaled.
+    ///
+    /// The timeout is clamped to about two seconds. WebGL does not allow
+    /// waiting with a non-zero timeout, so there this only polls.
+    pub fn wait(&self, timeout: Duration) -> bool {
+        if self.signaled.get() {
+            return true;
+        }
+
+        let sync = self.sync.expect("unsignaled fences have a sync object");
+        let gl = self.ctx.gl();
+
+        #[cfg(target_family = "wasm")]
+        let timeout = {
+            let _ = timeout;
+
+            0
+        };
+
+        #[cfg(not(target_family = "wasm"))]
+        let timeout = timeout.as_nanos().min(i32::MAX as u128) as i32;
+
+        let status = unsafe { gl.client_wait_sync(sync, glow::SYNC_FLUSH_COMMANDS_BIT, timeout) };
+
+        // If waiting fails, e.g. because the context was lost, there is
+        // nothing left to wait for.
+        let signaled = matches!(
+            status,
+            glow::ALREADY_SIGNALED | glow::CONDITION_SATISFIED | glow::WAIT_FAILED
+        );
+
+        self.signaled.set(signaled);
+
+        signaled
+    }
+}
+
+impl Drop for Fence {
+    fn drop(&mut self) {
+        if let Some(sync) = self.sync {
+            let gl = self.ctx.gl();
+
+            unsafe {
+                gl.delete_sync(sync);
+            }
+        }
+    }
+}
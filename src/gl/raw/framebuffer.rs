@@ -1,16 +1,32 @@
-use std::rc::Rc;
-
-use glow::HasContext;
+use std::{cell::RefMut, rc::Rc, slice};
 
 use super::{
-    context::ContextShared,
-    error::{check_framebuffer_completeness, check_gl_error, FramebufferError},
-    Caps, Comparison, ImageInternalFormat, Sampler2d, Sampler2dParams, Texture2d,
+    context::{Backend, ContextShared},
+    error::{
+        check_framebuffer_completeness, check_gl_error, FramebufferError,
+        FramebufferIncompleteError,
+    },
+    soft, Buffer, Caps, ClearColor, ClearValues, Comparison, DrawParams, ImageComponentType,
+    ImageFormat, ImageInternalFormat, Rect, Renderbuffer, Sampler2d, Sampler2dParams,
+    SamplerMagFilter, Texture2d, Texture3d, TextureError,
 };
 
 #[derive(Clone)]
 pub enum Attachment {
-    Texture2d { texture: Rc<Texture2d>, level: u32 },
+    Texture2d {
+        texture: Rc<Texture2d>,
+        level: u32,
+    },
+    /// A layer of a texture with three-dimensional storage, such as a face of
+    /// a cube map.
+    Texture3d {
+        texture: Rc<Texture3d>,
+        layer: u32,
+        level: u32,
+    },
+    Renderbuffer {
+        renderbuffer: Rc<Renderbuffer>,
+    },
 }
 
 impl Attachment {
@@ -19,6 +35,12 @@ impl Attachment {
 
         match self {
             Texture2d { texture, .. } => texture.size(),
+            Texture3d { texture, level, .. } => {
+                let [width, height, _] = texture.size();
+
+                [width, height].map(|n| (n >> level).max(1))
+            }
+            Renderbuffer { renderbuffer } => renderbuffer.size(),
         }
     }
 
@@ -27,18 +49,225 @@ impl Attachment {
 
         match self {
             Texture2d { texture, .. } => texture.internal_format(),
+            Texture3d { texture, .. } => texture.internal_format(),
+            Renderbuffer { renderbuffer } => renderbuffer.internal_format(),
+        }
+    }
+
+    /// Returns the number of samples per pixel, which is zero unless the
+    /// attachment is a multisampled renderbuffer.
+    pub fn samples(&self) -> u32 {
+        use Attachment::*;
+
+        match self {
+            Texture2d { .. } | Texture3d { .. } => 0,
+            Renderbuffer { renderbuffer } => renderbuffer.samples(),
+        }
+    }
+
+    /// Reads the pixels of `rect` into `data`.
+    ///
+    /// See [`Texture2d::read_pixels`].
+    pub fn read_pixels(
+        &self,
+        rect: Rect,
+        format: ImageFormat,
+        ty: ImageComponentType,
+        data: &mut [u8],
+    ) -> Result<(), TextureError> {
+        use Attachment::*;
+
+        match self {
+            Texture2d { texture, level } => {
+                texture.read_pixels(*level as usize, rect, format, ty, data)
+            }
+            Texture3d {
+                texture,
+                layer,
+                level,
+            } => texture.read_pixels(*level as usize, *layer, rect, format, ty, data),
+            Renderbuffer { renderbuffer } => renderbuffer.read_pixels(rect, format, ty, data),
         }
     }
 
+    /// Starts reading the pixels of `rect` into `buffer`.
+    ///
+    /// See [`Texture2d::read_pixels_to_buffer`].
+    pub fn read_pixels_to_buffer(
+        &self,
+        rect: Rect,
+        format: ImageFormat,
+        ty: ImageComponentType,
+        buffer: &Buffer,
+        offset: usize,
+    ) -> Result<(), TextureError> {
+        use Attachment::*;
+
+        match self {
+            Texture2d { texture, level } => {
+                texture.read_pixels_to_buffer(*level as usize, rect, format, ty, buffer, offset)
+            }
+            Texture3d {
+                texture,
+                layer,
+                level,
+            } => texture.read_pixels_to_buffer(
+                *level as usize,
+                *layer,
+                rect,
+                format,
+                ty,
+                buffer,
+                offset,
+            ),
+            Renderbuffer { renderbuffer } => {
+                renderbuffer.read_pixels_to_buffer(rect, format, ty, buffer, offset)
+            }
+        }
+    }
+
+    /// Returns a sampler for the texture of the attachment.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the attachment is a layer of a texture with three-dimensional
+    /// storage, which cannot be sampled as a two-dimensional texture, or if it
+    /// is a renderbuffer.
     pub fn sampler(&self, params: Sampler2dParams, comparison: Option<Comparison>) -> Sampler2d {
         use Attachment::*;
 
         match self {
             Texture2d { texture, .. } => Sampler2d {
                 texture: texture.clone(),
-                params,
+                params: params.for_internal_format(texture.internal_format()),
                 comparison,
             },
+            Texture3d { .. } | Renderbuffer { .. } => {
+                panic!("expected a two-dimensional texture attachment")
+            }
+        }
+    }
+
+    /// Resolves the pixels of this attachment into `destination`, combining
+    /// the samples of each pixel if this attachment is multisampled.
+    ///
+    /// The destination must be single-sampled and match this attachment in
+    /// size and internal format.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the attachments belong to different contexts.
+    pub fn resolve_into(&self, destination: &Attachment) -> Result<(), FramebufferError> {
+        let ctx = self.context();
+
+        assert!(ctx.ref_eq(destination.context()));
+
+        validate_attachments(ctx.caps(), slice::from_ref(self))?;
+        validate_attachments(ctx.caps(), slice::from_ref(destination))?;
+
+        if self.internal_format() != destination.internal_format() {
+            return Err(FramebufferError::BlitFormatMismatch {
+                read: self.internal_format(),
+                draw: destination.internal_format(),
+            });
+        }
+
+        let read_rect = Rect {
+            lower_left_corner: [0, 0],
+            size: self.size(),
+        };
+        let draw_rect = Rect {
+            lower_left_corner: [0, 0],
+            size: destination.size(),
+        };
+
+        if read_rect != draw_rect {
+            return Err(FramebufferError::BlitRectMismatch {
+                read: read_rect,
+                draw: draw_rect,
+            });
+        }
+
+        blit_image(
+            ctx,
+            FramebufferImage::Attachment(self),
+            read_rect,
+            FramebufferImage::Attachment(destination),
+            draw_rect,
+            SamplerMagFilter::Nearest,
+        )
+    }
+
+    pub(super) fn context(&self) -> &ContextShared {
+        use Attachment::*;
+
+        match self {
+            Texture2d { texture, .. } => texture.context(),
+            Texture3d { texture, .. } => texture.context(),
+            Renderbuffer { renderbuffer } => renderbuffer.context(),
+        }
+    }
+
+    /// # Panics
+    ///
+    /// Panics if the attachment belongs to an OpenGL context.
+    pub(super) fn soft_surface_mut(&self) -> RefMut<'_, soft::Surface> {
+        use Attachment::*;
+
+        match self {
+            Texture2d { texture, level } => texture.soft().level_mut(*level as usize),
+            Texture3d {
+                texture,
+                layer,
+                level,
+            } => texture.soft().slice_mut(*level as usize, *layer as usize),
+            Renderbuffer { renderbuffer } => renderbuffer.soft().borrow_mut(),
+        }
+    }
+
+    /// Attaches this to `location` of the framebuffer object that is bound to
+    /// `FRAMEBUFFER`, or detaches it if `attached` is false.
+    fn framebuffer_attach(&self, location: u32, attached: bool) {
+        use Attachment::*;
+
+        let gl = self.context().gl();
+
+        match self {
+            Texture2d { texture, level } => {
+                let level = (*level).try_into().expect("level is out of i32 range");
+
+                unsafe {
+                    gl.framebuffer_texture_2d(
+                        glow::FRAMEBUFFER,
+                        location,
+                        glow::TEXTURE_2D,
+                        attached.then(|| texture.id()),
+                        level,
+                    )
+                };
+            }
+            Texture3d {
+                texture,
+                layer,
+                level,
+            } => {
+                let level = (*level).try_into().expect("level is out of i32 range");
+
+                texture.framebuffer_texture(
+                    location,
+                    attached.then(|| texture.id()),
+                    level,
+                    *layer,
+                );
+            }
+            Renderbuffer { renderbuffer } => unsafe {
+                gl.framebuffer_renderbuffer(
+                    glow::FRAMEBUFFER,
+                    location,
+                    glow::RENDERBUFFER,
+                    attached.then(|| renderbuffer.id()),
+                )
+            },
         }
     }
 }
@@ -61,35 +290,587 @@ impl Framebuffer {
                 .fold([0, 0], |[x1, y1], [x2, y2]| [x1.max(x2), y1.max(y2)]),
         }
     }
+
+    /// Copies `read_rect` of this framebuffer into `draw_rect` of
+    /// `destination`, scaling the pixels with `filter`.
+    ///
+    /// The color images of the framebuffers are copied pairwise in order. The
+    /// depth-stencil images are copied if both framebuffers have one, which is
+    /// always done with nearest filtering.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the framebuffers differ in their number of color images, if a
+    /// rectangle is not contained in an image, or if an attachment belongs to
+    /// a different context.
+    pub(super) fn blit(
+        &self,
+        ctx: &ContextShared,
+        read_rect: Rect,
+        destination: &Framebuffer,
+        draw_rect: Rect,
+        filter: SamplerMagFilter,
+    ) -> Result<(), FramebufferError> {
+        self.validate(ctx.caps())?;
+        destination.validate(ctx.caps())?;
+
+        let read_colors = self.color_images();
+        let draw_colors = destination.color_images();
+
+        assert_eq!(read_colors.len(), draw_colors.len());
+
+        for (read, draw) in read_colors.into_iter().zip(draw_colors) {
+            blit_image(ctx, read, read_rect, draw, draw_rect, filter)?;
+        }
+
+        if let (Some(read), Some(draw)) = (
+            self.depth_stencil_image(),
+            destination.depth_stencil_image(),
+        ) {
+            blit_image(
+                ctx,
+                read,
+                read_rect,
+                draw,
+                draw_rect,
+                SamplerMagFilter::Nearest,
+            )?;
+        }
+
+        Ok(())
+    }
+
+    /// Copies `rect` of the first color image of this framebuffer into mipmap
+    /// level `level` of `texture`, starting at `lower_left_corner`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the framebuffer has no color image, if `level` does not
+    /// exist, if a rectangle is not contained in its image, or if an
+    /// attachment belongs to a different context.
+    pub(super) fn copy_into_texture_2d(
+        &self,
+        rect: Rect,
+        texture: &Texture2d,
+        level: usize,
+        lower_left_corner: [u32; 2],
+    ) -> Result<(), FramebufferError> {
+        let ctx = texture.context();
+
+        self.validate(ctx.caps())?;
+
+        let read = *self
+            .color_images()
+            .first()
+            .expect("expected a framebuffer with a color image");
+
+        assert!(level < texture.levels());
+        assert!(contains(read.size(ctx), rect));
+        assert!(contains(
+            texture.size().map(|n| (n >> level).max(1)),
+            Rect {
+                lower_left_corner,
+                size: rect.size,
+            }
+        ));
+
+        // OpenGL ES 3.0.6: 3.8.5 Alternate Texture Image Specification Commands
+        // > An `INVALID_OPERATION` error is generated if [...] the value of
+        // > `SAMPLE_BUFFERS` for the read framebuffer is greater than zero.
+        if read.samples() > 0 {
+            return Err(FramebufferError::CopyFromMultisampled);
+        }
+
+        // As with blits, integer values can only be copied into images of the
+        // same signedness.
+        let draw_format = texture.internal_format();
+
+        if draw_format.is_compressed()
+            || draw_format.is_depth_renderable()
+            || draw_format.is_stencil_renderable()
+        {
+            return Err(FramebufferError::CopyUnsupported(draw_format));
+        }
+
+        if let Some(read_format) = read.internal_format() {
+            if integer_class(read_format) != integer_class(draw_format) {
+                return Err(FramebufferError::BlitFormatMismatch {
+                    read: read_format,
+                    draw: draw_format,
+                });
+            }
+        }
+
+        if let Backend::Soft(device) = ctx.backend() {
+            let source = read.soft_surface_mut(device).clone();
+            let draw_rect = Rect {
+                lower_left_corner,
+                size: rect.size,
+            };
+
+            texture
+                .soft()
+                .level_mut(level)
+                .blit(&source, rect, draw_rect, false);
+
+            return Ok(());
+        }
+
+        let gl = ctx.gl();
+
+        let level_i32 = level.try_into().unwrap();
+        let [x_offset, y_offset] = lower_left_corner.map(|n| n.try_into().unwrap());
+        let [x, y] = rect.lower_left_corner.map(|n| n.try_into().unwrap());
+        let [width, height] = rect.size.map(|n| n.try_into().unwrap());
+
+        let read_fbo = read.attach(ctx, ctx.read_fbo());
+
+        unsafe {
+            gl.bind_framebuffer(glow::READ_FRAMEBUFFER, read_fbo);
+            gl.bind_texture(glow::TEXTURE_2D, Some(texture.id()));
+            gl.copy_tex_sub_image_2d(
+                glow::TEXTURE_2D,
+                level_i32,
+                x_offset,
+                y_offset,
+                x,
+                y,
+                width,
+                height,
+            );
+            gl.bind_texture(glow::TEXTURE_2D, None);
+        }
+
+        // TODO: Remove overly conservative unbinding.
+        read.detach(ctx, ctx.read_fbo());
+
+        unsafe { gl.bind_framebuffer(glow::FRAMEBUFFER, None) };
+
+        check_gl_error(gl, "after copying into texture").map_err(FramebufferError::Unexpected)?;
+
+        Ok(())
+    }
+
+    /// Clears the images of this framebuffer to `values`.
+    ///
+    /// Color images are cleared individually, so integer and non-integer
+    /// images can be cleared together. The scissor rectangle and the write
+    /// masks of previous draw calls do not apply.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `values` has more colors than the framebuffer has color
+    /// images.
+    pub(super) fn clear(
+        &self,
+        ctx: &ContextShared,
+        values: &ClearValues,
+    ) -> Result<(), FramebufferError> {
+        self.validate(ctx.caps())?;
+
+        let colors = self.color_images();
+
+        assert!(values.colors.len() <= colors.len());
+
+        // OpenGL ES 3.0.6: 4.2.3 Clearing the Buffers
+        // > The `ClearBufferfv`, `ClearBufferiv`, and `ClearBufferuiv` commands
+        // > should be used to clear fixed- and floating-point, signed integer,
+        // > and unsigned integer color buffers respectively.
+        //
+        // The color buffer of the default framebuffer is never an integer
+        // buffer.
+        for (index, (image, color)) in colors.into_iter().zip(&values.colors).enumerate() {
+            let Some(color) = *color else {
+                continue;
+            };
+
+            let matches = match image.internal_format() {
+                Some(format) => color.matches(format),
+                None => matches!(color, ClearColor::F32(_)),
+            };
+
+            if !matches {
+                return Err(FramebufferError::ClearColorMismatch { index, color });
+            }
+        }
+
+        if let Backend::Soft(device) = ctx.backend() {
+            soft::clear(device, self, values);
+
+            return Ok(());
+        }
+
+        let gl = ctx.gl();
+
+        // Clears are subject to the scissor test and the write masks, which
+        // may still be set by the draw parameters of a previous draw call.
+        ctx.set_draw_params(&DrawParams::new(), self.size(ctx));
+
+        self.bind(ctx)?;
+
+        for (draw_buffer, color) in values.colors.iter().enumerate() {
+            let draw_buffer = draw_buffer.try_into().unwrap();
+
+            match color {
+                Some(ClearColor::F32(color)) => unsafe {
+                    gl.clear_buffer_f32_slice(glow::COLOR, draw_buffer, color)
+                },
+                Some(ClearColor::I32(color)) => unsafe {
+                    gl.clear_buffer_i32_slice(glow::COLOR, draw_buffer, color)
+                },
+                Some(ClearColor::U32(color)) => unsafe {
+                    gl.clear_buffer_u32_slice(glow::COLOR, draw_buffer, color)
+                },
+                None => (),
+            }
+        }
+
+        match (values.depth, values.stencil) {
+            (Some(depth), Some(stencil)) => unsafe {
+                gl.clear_buffer_depth_stencil(glow::DEPTH_STENCIL, 0, depth, stencil.into())
+            },
+            (Some(depth), None) => unsafe { gl.clear_buffer_f32_slice(glow::DEPTH, 0, &[depth]) },
+            (None, Some(stencil)) => unsafe {
+                gl.clear_buffer_i32_slice(glow::STENCIL, 0, &[stencil.into()])
+            },
+            (None, None) => (),
+        }
+
+        self.unbind(ctx);
+
+        check_gl_error(gl, "after clear").map_err(FramebufferError::Unexpected)?;
+
+        Ok(())
+    }
+
+    fn color_images(&self) -> Vec<FramebufferImage<'_>> {
+        use Framebuffer::*;
+
+        match self {
+            Default => vec![FramebufferImage::Default {
+                depth_stencil: false,
+            }],
+            Attachments { attachments } => attachments
+                .iter()
+                .filter(|attachment| attachment.internal_format().is_color_renderable())
+                .map(FramebufferImage::Attachment)
+                .collect(),
+        }
+    }
+
+    fn depth_stencil_image(&self) -> Option<FramebufferImage<'_>> {
+        use Framebuffer::*;
+
+        match self {
+            Default => Some(FramebufferImage::Default {
+                depth_stencil: true,
+            }),
+            Attachments { attachments } => attachments
+                .iter()
+                .find(|attachment| !attachment.internal_format().is_color_renderable())
+                .map(FramebufferImage::Attachment),
+        }
+    }
+}
+
+/// A single image of a framebuffer that pixels can be copied from or into.
+#[derive(Clone, Copy)]
+enum FramebufferImage<'a> {
+    /// The color buffer or the depth-stencil buffer of the default
+    /// framebuffer.
+    Default {
+        depth_stencil: bool,
+    },
+    Attachment(&'a Attachment),
+}
+
+impl<'a> FramebufferImage<'a> {
+    fn size(self, ctx: &ContextShared) -> [u32; 2] {
+        match self {
+            FramebufferImage::Default { .. } => ctx.default_framebuffer_size(),
+            FramebufferImage::Attachment(attachment) => attachment.size(),
+        }
+    }
+
+    /// Returns the internal format of the image, which is unknown for the
+    /// default framebuffer.
+    fn internal_format(self) -> Option<ImageInternalFormat> {
+        match self {
+            FramebufferImage::Default { .. } => None,
+            FramebufferImage::Attachment(attachment) => Some(attachment.internal_format()),
+        }
+    }
+
+    fn samples(self) -> u32 {
+        match self {
+            FramebufferImage::Default { .. } => 0,
+            FramebufferImage::Attachment(attachment) => attachment.samples(),
+        }
+    }
+
+    fn is_depth_stencil(self) -> bool {
+        match self {
+            FramebufferImage::Default { depth_stencil } => depth_stencil,
+            FramebufferImage::Attachment(attachment) => {
+                !attachment.internal_format().is_color_renderable()
+            }
+        }
+    }
+
+    /// Attaches the image to `fbo`, which is left bound to `FRAMEBUFFER`, and
+    /// returns the framebuffer object that holds the image.
+    fn attach(self, ctx: &ContextShared, fbo: glow::Framebuffer) -> Option<glow::Framebuffer> {
+        let FramebufferImage::Attachment(attachment) = self else {
+            return None;
+        };
+
+        let gl = ctx.gl();
+        let location = location(attachment.internal_format(), 0);
+
+        unsafe { gl.bind_framebuffer(glow::FRAMEBUFFER, Some(fbo)) };
+
+        attachment.framebuffer_attach(location, true);
+
+        if location == glow::COLOR_ATTACHMENT0 {
+            unsafe {
+                gl.read_buffer(location);
+                gl.draw_buffers(&[location]);
+            }
+        }
+
+        Some(fbo)
+    }
+
+    fn detach(self, ctx: &ContextShared, fbo: glow::Framebuffer) {
+        let FramebufferImage::Attachment(attachment) = self else {
+            return;
+        };
+
+        unsafe { ctx.gl().bind_framebuffer(glow::FRAMEBUFFER, Some(fbo)) };
+
+        attachment.framebuffer_attach(location(attachment.internal_format(), 0), false);
+    }
+
+    fn soft_surface_mut(self, device: &'a soft::Device) -> RefMut<'a, soft::Surface> {
+        match self {
+            FramebufferImage::Default {
+                depth_stencil: false,
+            } => device.default_color_mut(),
+            FramebufferImage::Default {
+                depth_stencil: true,
+            } => device.default_depth_stencil_mut(),
+            FramebufferImage::Attachment(attachment) => attachment.soft_surface_mut(),
+        }
+    }
+}
+
+/// Copies `read_rect` of `read` into `draw_rect` of `draw`, which are either
+/// both color images or both depth-stencil images.
+fn blit_image(
+    ctx: &ContextShared,
+    read: FramebufferImage,
+    read_rect: Rect,
+    draw: FramebufferImage,
+    draw_rect: Rect,
+    filter: SamplerMagFilter,
+) -> Result<(), FramebufferError> {
+    assert!(contains(read.size(ctx), read_rect));
+    assert!(contains(draw.size(ctx), draw_rect));
+
+    let depth_stencil = read.is_depth_stencil();
+
+    assert_eq!(depth_stencil, draw.is_depth_stencil());
+
+    // OpenGL ES 3.0.6: 4.3.3 Copying Pixels
+    // > If `SAMPLE_BUFFERS` for the draw framebuffer is greater than zero, an
+    // > `INVALID_OPERATION` error is generated.
+    if draw.samples() > 0 {
+        return Err(FramebufferError::BlitIntoMultisampled);
+    }
+
+    // > If `SAMPLE_BUFFERS` for the read framebuffer is greater than zero and
+    // > the source and destination rectangles are not defined with the same
+    // > (X0, Y0) and (X1, Y1) bounds, an `INVALID_OPERATION` error is
+    // > generated.
+    if read.samples() > 0 && read_rect != draw_rect {
+        return Err(FramebufferError::BlitRectMismatch {
+            read: read_rect,
+            draw: draw_rect,
+        });
+    }
+
+    if let (Some(read_format), Some(draw_format)) = (read.internal_format(), draw.internal_format())
+    {
+        // > If `SAMPLE_BUFFERS` for the read framebuffer is greater than zero
+        // > and the formats of draw and read framebuffers are not identical, an
+        // > `INVALID_OPERATION` error is generated.
+        //
+        // > If `mask` includes `DEPTH_BUFFER_BIT` or `STENCIL_BUFFER_BIT`, and
+        // > the source and destination depth and stencil buffer formats do not
+        // > match, an `INVALID_OPERATION` error is generated.
+        //
+        // Furthermore, integer values can only be copied into images of the
+        // same signedness.
+        if ((read.samples() > 0 || depth_stencil) && read_format != draw_format)
+            || integer_class(read_format) != integer_class(draw_format)
+        {
+            return Err(FramebufferError::BlitFormatMismatch {
+                read: read_format,
+                draw: draw_format,
+            });
+        }
+
+        // > If `filter` is `LINEAR` and the read buffer contains integer data,
+        // > an `INVALID_OPERATION` error is generated.
+        if filter == SamplerMagFilter::Linear && read_format.is_integer() {
+            return Err(FramebufferError::BlitLinearFilter);
+        }
+    }
+
+    // > If `filter` is `LINEAR` and `mask` includes `DEPTH_BUFFER_BIT` or
+    // > `STENCIL_BUFFER_BIT`, no data is transferred and an
+    // > `INVALID_OPERATION` error is generated.
+    if filter == SamplerMagFilter::Linear && depth_stencil {
+        return Err(FramebufferError::BlitLinearFilter);
+    }
+
+    if let Backend::Soft(device) = ctx.backend() {
+        // The software backend renders with a single sample, so there is
+        // nothing to combine when resolving. The source is copied first since
+        // it may be stored in the same texture as the destination.
+        let source = read.soft_surface_mut(device).clone();
+
+        draw.soft_surface_mut(device).blit(
+            &source,
+            read_rect,
+            draw_rect,
+            filter == SamplerMagFilter::Linear,
+        );
+
+        return Ok(());
+    }
+
+    let gl = ctx.gl();
+
+    let mask = if !depth_stencil {
+        glow::COLOR_BUFFER_BIT
+    } else if let Some(format) = read.internal_format().or(draw.internal_format()) {
+        let depth = if format.is_depth_renderable() {
+            glow::DEPTH_BUFFER_BIT
+        } else {
+            0
+        };
+        let stencil = if format.is_stencil_renderable() {
+            glow::STENCIL_BUFFER_BIT
+        } else {
+            0
+        };
+
+        depth | stencil
+    } else {
+        glow::DEPTH_BUFFER_BIT | glow::STENCIL_BUFFER_BIT
+    };
+
+    let bounds = |rect: Rect| {
+        let [x0, y0]: [i32; 2] = rect.lower_left_corner.map(|n| n.try_into().unwrap());
+        let [width, height]: [i32; 2] = rect.size.map(|n| n.try_into().unwrap());
+
+        [x0, y0, x0 + width, y0 + height]
+    };
+    let [src_x0, src_y0, src_x1, src_y1] = bounds(read_rect);
+    let [dst_x0, dst_y0, dst_x1, dst_y1] = bounds(draw_rect);
+
+    // Blits are subject to the scissor test, which may still be enabled by
+    // the draw parameters of a previous draw call.
+    let scissor = ctx.draw_params().scissor.is_some();
+
+    if scissor {
+        unsafe { gl.disable(glow::SCISSOR_TEST) };
+    }
+
+    let read_fbo = read.attach(ctx, ctx.read_fbo());
+    let draw_fbo = draw.attach(ctx, ctx.draw_fbo());
+
+    unsafe {
+        gl.bind_framebuffer(glow::READ_FRAMEBUFFER, read_fbo);
+        gl.bind_framebuffer(glow::DRAW_FRAMEBUFFER, draw_fbo);
+        gl.blit_framebuffer(
+            src_x0,
+            src_y0,
+            src_x1,
+            src_y1,
+            dst_x0,
+            dst_y0,
+            dst_x1,
+            dst_y1,
+            mask,
+            filter.to_gl(),
+        );
+    }
+
+    // TODO: Remove overly conservative unbinding.
+    draw.detach(ctx, ctx.draw_fbo());
+    read.detach(ctx, ctx.read_fbo());
+
+    unsafe {
+        gl.bind_framebuffer(glow::FRAMEBUFFER, None);
+
+        if scissor {
+            gl.enable(glow::SCISSOR_TEST);
+        }
+    }
+
+    check_gl_error(gl, "after blit").map_err(FramebufferError::Unexpected)?;
+
+    Ok(())
+}
+
+/// Returns whether the values of `format` are signed integers, unsigned
+/// integers, or neither.
+fn integer_class(format: ImageInternalFormat) -> Option<bool> {
+    format.is_integer().then(|| format.is_signed_integer())
+}
+
+fn contains(size: [u32; 2], rect: Rect) -> bool {
+    rect.lower_left_corner[0] + rect.size[0] <= size[0]
+        && rect.lower_left_corner[1] + rect.size[1] <= size[1]
 }
 
 fn with_locations(attachments: &[Attachment]) -> impl Iterator<Item = (u32, &Attachment)> {
     attachments.iter().scan(0, |num_color, attachment| {
         let format = attachment.internal_format();
-        let location = if format.is_color_renderable() {
-            let location = glow::COLOR_ATTACHMENT0 + *num_color as u32;
+        let location = location(format, *num_color);
+
+        if format.is_color_renderable() {
             *num_color += 1;
-            location
-        } else if format.is_depth_renderable() && format.is_stencil_renderable() {
-            glow::DEPTH_STENCIL_ATTACHMENT
-        } else if format.is_depth_renderable() {
-            glow::DEPTH_ATTACHMENT
-        } else if format.is_stencil_renderable() {
-            glow::STENCIL_ATTACHMENT
-        } else {
-            // FIXME: This does not actually hold! Also, `is_color_renderable`
-            // depends on available extensions. All of this needs to be checked
-            // here.
-            panic!(
-                "every ImageInternalFormat must satisfy at least one of color renderable, depth \
-                 renderable, or stencil renderable"
-            );
-        };
+        }
 
         Some((location, attachment))
     })
 }
 
+/// Returns the location that an attachment of the given internal format is
+/// attached to, where color attachments are numbered by `color_index`.
+fn location(format: ImageInternalFormat, color_index: u32) -> u32 {
+    if format.is_color_renderable() {
+        glow::COLOR_ATTACHMENT0 + color_index
+    } else if format.is_depth_renderable() && format.is_stencil_renderable() {
+        glow::DEPTH_STENCIL_ATTACHMENT
+    } else if format.is_depth_renderable() {
+        glow::DEPTH_ATTACHMENT
+    } else if format.is_stencil_renderable() {
+        glow::STENCIL_ATTACHMENT
+    } else {
+        // FIXME: This does not actually hold! Also, `is_color_renderable`
+        // depends on available extensions. All of this needs to be checked
+        // here.
+        panic!(
+            "every ImageInternalFormat must satisfy at least one of color renderable, depth \
+             renderable, or stencil renderable"
+        );
+    }
+}
+
 fn bind_attachments(
     ctx: &ContextShared,
     attachments: &[Attachment],
@@ -97,21 +878,7 @@ fn bind_attachments(
     let gl = ctx.gl();
 
     for (location, attachment) in with_locations(attachments) {
-        match attachment {
-            Attachment::Texture2d { texture, level } => {
-                let level = (*level).try_into().expect("level is out of i32 range");
-
-                unsafe {
-                    gl.framebuffer_texture_2d(
-                        glow::FRAMEBUFFER,
-                        location,
-                        glow::TEXTURE_2D,
-                        Some(texture.id()),
-                        level,
-                    )
-                };
-            }
-        };
+        attachment.framebuffer_attach(location, true);
     }
 
     let draw_buffers: Vec<_> = with_locations(attachments)
@@ -130,37 +897,32 @@ fn bind_attachments(
     Ok(())
 }
 
-fn unbind_attachments(ctx: &ContextShared, attachments: &[Attachment]) {
-    let gl = ctx.gl();
-
+fn unbind_attachments(attachments: &[Attachment]) {
     for (location, attachment) in with_locations(attachments) {
-        match attachment {
-            Attachment::Texture2d { level, .. } => {
-                let level = (*level).try_into().expect("level is out of i32 range");
-
-                unsafe {
-                    gl.framebuffer_texture_2d(
-                        glow::FRAMEBUFFER,
-                        location,
-                        glow::TEXTURE_2D,
-                        None,
-                        level,
-                    )
-                };
-            }
-        };
+        attachment.framebuffer_attach(location, false);
     }
 }
 
 impl Framebuffer {
+    /// Checks that the attachments can be used together.
+    ///
+    /// This is done for both backends, since the software backend relies on
+    /// the attachments referring to existing texture images.
+    pub(super) fn validate(&self, caps: &Caps) -> Result<(), FramebufferError> {
+        use Framebuffer::*;
+
+        match self {
+            Default => Ok(()),
+            Attachments { attachments } => validate_attachments(caps, attachments),
+        }
+    }
+
     pub(super) fn bind(&self, ctx: &ContextShared) -> Result<(), FramebufferError> {
         use Framebuffer::*;
 
         match self {
             Framebuffer::Default => Ok(()),
             Attachments { attachments } => {
-                validate_attachments(ctx.caps(), attachments)?;
-
                 unsafe {
                     ctx.gl()
                         .bind_framebuffer(glow::FRAMEBUFFER, Some(ctx.draw_fbo()))
@@ -178,7 +940,7 @@ impl Framebuffer {
             Framebuffer::Default => {}
             Attachments { attachments } => {
                 // TODO: Remove overly conservative unbinding.
-                unbind_attachments(ctx, attachments);
+                unbind_attachments(attachments);
 
                 // TODO: Remove overly conservative unbinding.
                 unsafe { ctx.gl().bind_framebuffer(glow::FRAMEBUFFER, None) };
@@ -208,6 +970,67 @@ fn validate_attachments(caps: &Caps, attachments: &[Attachment]) -> Result<(), F
                     });
                 }
             }
+            Texture3d {
+                texture,
+                layer,
+                level,
+            } => {
+                // OpenGL ES 3.0.6: 4.4.2.4 Attaching Texture Images to a
+                // Framebuffer
+                // > If `texture` is a three-dimensional texture, then `level`
+                // > must be greater than or equal to zero and less than or equal
+                // > to `log_2` of the value of `MAX_3D_TEXTURE_SIZE`. If
+                // > `texture` is a two-dimensional array texture, then `level`
+                // > must be greater than or equal to zero and no larger than
+                // > `log_2` of the value of `MAX_TEXTURE_SIZE`.
+                let max_size = if texture.is_cube() {
+                    caps.max_cube_map_texture_size
+                } else if texture.is_array() {
+                    caps.max_texture_size
+                } else {
+                    caps.max_3d_texture_size
+                };
+                let max_level = (max_size as f64).log2() as u32;
+
+                if *level > max_level {
+                    return Err(FramebufferError::LevelTooLarge {
+                        requested: *level,
+                        max: max_level,
+                    });
+                }
+
+                if *level as usize >= texture.levels() {
+                    return Err(FramebufferError::LevelTooLarge {
+                        requested: *level,
+                        max: texture.levels() as u32 - 1,
+                    });
+                }
+
+                let depth = texture.level_depth(*level as usize);
+
+                if *layer >= depth {
+                    return Err(FramebufferError::LayerTooLarge {
+                        requested: *layer,
+                        max: depth - 1,
+                    });
+                }
+            }
+            Renderbuffer { .. } => {}
+        }
+    }
+
+    // OpenGL ES 3.0.6: 4.4.4.2 Whole Framebuffer Completeness
+    // > The value of `RENDERBUFFER_SAMPLES` is the same for all attached
+    // > renderbuffers; and, if the attached images are a mix of renderbuffers
+    // > and textures, the value of `RENDERBUFFER_SAMPLES` is zero.
+    if let Some(first) = attachments.first() {
+        if attachments
+            .iter()
+            .any(|attachment| attachment.samples() != first.samples())
+        {
+            return Err(FramebufferError::Incomplete(
+                FramebufferIncompleteError::IncompleteMultisample,
+            ));
         }
     }
 
@@ -0,0 +1,312 @@
+use std::collections::HashSet;
+
+use glow::{CompressedPixelUnpackData, HasContext, PixelPackData, PixelUnpackData};
+
+macro_rules! gl_api {
+    ($(fn $name:ident(&self $(, $arg:ident: $ty:ty)* $(,)?) $(-> $ret:ty)?;)*) => {
+        /// The OpenGL functions that are used by `raw`.
+        ///
+        /// All GL calls made by `raw` go through this trait. It is implemented
+        /// for `glow::Context`, and by [`Recorder`](super::Recorder) for
+        /// inspecting the calls that are made without a driver.
+        ///
+        /// The functions mirror the corresponding functions of
+        /// `glow::HasContext`.
+        #[allow(clippy::missing_safety_doc, clippy::too_many_arguments)]
+        pub trait GlApi {
+            $(unsafe fn $name(&self $(, $arg: $ty)*) $(-> $ret)?;)*
+        }
+
+        impl GlApi for glow::Context {
+            $(
+                unsafe fn $name(&self $(, $arg: $ty)*) $(-> $ret)? {
+                    HasContext::$name(self $(, $arg)*)
+                }
+            )*
+        }
+    };
+}
+
+gl_api! {
+    fn version(&self) -> &glow::Version;
+    fn supported_extensions(&self) -> &HashSet<String>;
+    fn get_parameter_i32(&self, parameter: u32) -> i32;
+    fn get_parameter_i32_slice(&self, parameter: u32, out: &mut [i32]);
+    fn get_error(&self) -> u32;
+    fn finish(&self);
+    fn enable(&self, parameter: u32);
+    fn disable(&self, parameter: u32);
+    fn pixel_store_i32(&self, parameter: u32, value: i32);
+
+    fn create_buffer(&self) -> Result<glow::Buffer, String>;
+    fn delete_buffer(&self, buffer: glow::Buffer);
+    fn bind_buffer(&self, target: u32, buffer: Option<glow::Buffer>);
+    fn bind_buffer_base(&self, target: u32, index: u32, buffer: Option<glow::Buffer>);
+    fn bind_buffer_range(
+        &self,
+        target: u32,
+        index: u32,
+        buffer: Option<glow::Buffer>,
+        offset: i32,
+        size: i32,
+    );
+    fn buffer_data_u8_slice(&self, target: u32, data: &[u8], usage: u32);
+    fn buffer_data_size(&self, target: u32, size: i32, usage: u32);
+    fn buffer_sub_data_u8_slice(&self, target: u32, offset: i32, src_data: &[u8]);
+    fn get_buffer_sub_data(&self, target: u32, offset: i32, dst_data: &mut [u8]);
+    fn map_buffer_range(&self, target: u32, offset: i32, length: i32, access: u32) -> *mut u8;
+    fn unmap_buffer(&self, target: u32);
+
+    fn fence_sync(&self, condition: u32, flags: u32) -> Result<glow::Fence, String>;
+    fn client_wait_sync(&self, fence: glow::Fence, flags: u32, timeout: i32) -> u32;
+    fn delete_sync(&self, fence: glow::Fence);
+
+    fn create_query(&self) -> Result<glow::Query, String>;
+    fn delete_query(&self, query: glow::Query);
+    fn begin_query(&self, target: u32, query: glow::Query);
+    fn end_query(&self, target: u32);
+    fn get_query_parameter_u32(&self, query: glow::Query, parameter: u32) -> u32;
+
+    fn create_vertex_array(&self) -> Result<glow::VertexArray, String>;
+    fn bind_vertex_array(&self, vertex_array: Option<glow::VertexArray>);
+    fn enable_vertex_attrib_array(&self, index: u32);
+    fn disable_vertex_attrib_array(&self, index: u32);
+    fn vertex_attrib_pointer_f32(
+        &self,
+        index: u32,
+        size: i32,
+        data_type: u32,
+        normalized: bool,
+        stride: i32,
+        offset: i32,
+    );
+    fn vertex_attrib_pointer_i32(
+        &self,
+        index: u32,
+        size: i32,
+        data_type: u32,
+        stride: i32,
+        offset: i32,
+    );
+    fn vertex_attrib_divisor(&self, index: u32, divisor: u32);
+
+    fn create_texture(&self) -> Result<glow::Texture, String>;
+    fn delete_texture(&self, texture: glow::Texture);
+    fn active_texture(&self, unit: u32);
+    fn bind_texture(&self, target: u32, texture: Option<glow::Texture>);
+    fn tex_parameter_i32(&self, target: u32, parameter: u32, value: i32);
+    fn tex_storage_2d(
+        &self,
+        target: u32,
+        levels: i32,
+        internal_format: u32,
+        width: i32,
+        height: i32,
+    );
+    fn tex_sub_image_2d(
+        &self,
+        target: u32,
+        level: i32,
+        x_offset: i32,
+        y_offset: i32,
+        width: i32,
+        height: i32,
+        format: u32,
+        ty: u32,
+        pixels: PixelUnpackData,
+    );
+    fn compressed_tex_sub_image_2d(
+        &self,
+        target: u32,
+        level: i32,
+        x_offset: i32,
+        y_offset: i32,
+        width: i32,
+        height: i32,
+        format: u32,
+        pixels: CompressedPixelUnpackData,
+    );
+    fn tex_storage_3d(
+        &self,
+        target: u32,
+        levels: i32,
+        internal_format: u32,
+        width: i32,
+        height: i32,
+        depth: i32,
+    );
+    fn tex_sub_image_3d(
+        &self,
+        target: u32,
+        level: i32,
+        x_offset: i32,
+        y_offset: i32,
+        z_offset: i32,
+        width: i32,
+        height: i32,
+        depth: i32,
+        format: u32,
+        ty: u32,
+        pixels: PixelUnpackData,
+    );
+    fn compressed_tex_sub_image_3d(
+        &self,
+        target: u32,
+        level: i32,
+        x_offset: i32,
+        y_offset: i32,
+        z_offset: i32,
+        width: i32,
+        height: i32,
+        depth: i32,
+        format: u32,
+        pixels: CompressedPixelUnpackData,
+    );
+    fn copy_tex_sub_image_2d(
+        &self,
+        target: u32,
+        level: i32,
+        x_offset: i32,
+        y_offset: i32,
+        x: i32,
+        y: i32,
+        width: i32,
+        height: i32,
+    );
+    fn generate_mipmap(&self, target: u32);
+
+    fn create_renderbuffer(&self) -> Result<glow::Renderbuffer, String>;
+    fn delete_renderbuffer(&self, renderbuffer: glow::Renderbuffer);
+    fn bind_renderbuffer(&self, target: u32, renderbuffer: Option<glow::Renderbuffer>);
+    fn renderbuffer_storage_multisample(
+        &self,
+        target: u32,
+        samples: i32,
+        internal_format: u32,
+        width: i32,
+        height: i32,
+    );
+
+    fn create_framebuffer(&self) -> Result<glow::Framebuffer, String>;
+    fn bind_framebuffer(&self, target: u32, framebuffer: Option<glow::Framebuffer>);
+    fn framebuffer_texture_2d(
+        &self,
+        target: u32,
+        attachment: u32,
+        texture_target: u32,
+        texture: Option<glow::Texture>,
+        level: i32,
+    );
+    fn framebuffer_texture_layer(
+        &self,
+        target: u32,
+        attachment: u32,
+        texture: Option<glow::Texture>,
+        level: i32,
+        layer: i32,
+    );
+    fn framebuffer_renderbuffer(
+        &self,
+        target: u32,
+        attachment: u32,
+        renderbuffer_target: u32,
+        renderbuffer: Option<glow::Renderbuffer>,
+    );
+    fn check_framebuffer_status(&self, target: u32) -> u32;
+    fn draw_buffers(&self, buffers: &[u32]);
+    fn read_buffer(&self, src: u32);
+    fn blit_framebuffer(
+        &self,
+        src_x0: i32,
+        src_y0: i32,
+        src_x1: i32,
+        src_y1: i32,
+        dst_x0: i32,
+        dst_y0: i32,
+        dst_x1: i32,
+        dst_y1: i32,
+        mask: u32,
+        filter: u32,
+    );
+    fn read_pixels(
+        &self,
+        x: i32,
+        y: i32,
+        width: i32,
+        height: i32,
+        format: u32,
+        gltype: u32,
+        pixels: PixelPackData,
+    );
+
+    fn create_shader(&self, shader_type: u32) -> Result<glow::Shader, String>;
+    fn delete_shader(&self, shader: glow::Shader);
+    fn shader_source(&self, shader: glow::Shader, source: &str);
+    fn compile_shader(&self, shader: glow::Shader);
+    fn get_shader_info_log(&self, shader: glow::Shader) -> String;
+
+    fn create_program(&self) -> Result<glow::Program, String>;
+    fn delete_program(&self, program: glow::Program);
+    fn attach_shader(&self, program: glow::Program, shader: glow::Shader);
+    fn detach_shader(&self, program: glow::Program, shader: glow::Shader);
+    fn bind_attrib_location(&self, program: glow::Program, index: u32, name: &str);
+    fn link_program(&self, program: glow::Program);
+    fn get_program_link_status(&self, program: glow::Program) -> bool;
+    fn get_program_info_log(&self, program: glow::Program) -> String;
+    fn use_program(&self, program: Option<glow::Program>);
+    fn get_uniform_location(
+        &self,
+        program: glow::Program,
+        name: &str,
+    ) -> Option<glow::UniformLocation>;
+    fn get_uniform_block_index(&self, program: glow::Program, name: &str) -> Option<u32>;
+    fn uniform_block_binding(&self, program: glow::Program, index: u32, binding: u32);
+    fn uniform_1_i32(&self, location: Option<&glow::UniformLocation>, x: i32);
+    fn transform_feedback_varyings(
+        &self,
+        program: glow::Program,
+        varyings: &[&str],
+        buffer_mode: u32,
+    );
+
+    fn create_transform_feedback(&self) -> Result<glow::TransformFeedback, String>;
+    fn delete_transform_feedback(&self, transform_feedback: glow::TransformFeedback);
+    fn bind_transform_feedback(
+        &self,
+        target: u32,
+        transform_feedback: Option<glow::TransformFeedback>,
+    );
+    fn begin_transform_feedback(&self, primitive_mode: u32);
+    fn end_transform_feedback(&self);
+
+    fn draw_arrays_instanced(&self, mode: u32, first: i32, count: i32, instance_count: i32);
+    fn draw_elements_instanced(
+        &self,
+        mode: u32,
+        count: i32,
+        element_type: u32,
+        offset: i32,
+        instance_count: i32,
+    );
+
+    fn clear_color(&self, red: f32, green: f32, blue: f32, alpha: f32);
+    fn clear_depth_f32(&self, depth: f32);
+    fn clear_stencil(&self, stencil: i32);
+    fn clear(&self, mask: u32);
+    fn clear_buffer_f32_slice(&self, target: u32, draw_buffer: u32, values: &[f32]);
+    fn clear_buffer_i32_slice(&self, target: u32, draw_buffer: u32, values: &[i32]);
+    fn clear_buffer_u32_slice(&self, target: u32, draw_buffer: u32, values: &[u32]);
+    fn clear_buffer_depth_stencil(&self, target: u32, draw_buffer: u32, depth: f32, stencil: i32);
+    fn viewport(&self, x: i32, y: i32, width: i32, height: i32);
+    fn scissor(&self, x: i32, y: i32, width: i32, height: i32);
+    fn cull_face(&self, value: u32);
+    fn depth_func(&self, func: u32);
+    fn depth_mask(&self, value: bool);
+    fn color_mask(&self, red: bool, green: bool, blue: bool, alpha: bool);
+    fn blend_color(&self, red: f32, green: f32, blue: f32, alpha: f32);
+    fn blend_equation_separate(&self, mode_rgb: u32, mode_alpha: u32);
+    fn blend_func_separate(&self, src_rgb: u32, dst_rgb: u32, src_alpha: u32, dst_alpha: u32);
+    fn stencil_func_separate(&self, face: u32, func: u32, reference: i32, mask: u32);
+    fn stencil_op_separate(&self, face: u32, stencil_fail: u32, depth_fail: u32, pass: u32);
+    fn stencil_mask_separate(&self, face: u32, mask: u32);
+}
@@ -56,6 +56,9 @@ pub enum ImageComponentType {
     U8,
     I8,
     U16,
+    I16,
+    U32,
+    I32,
     U24U8,
     F16,
     F32,
@@ -69,6 +72,9 @@ impl ImageComponentType {
             U8 => glow::UNSIGNED_BYTE,
             I8 => glow::BYTE,
             U16 => glow::UNSIGNED_SHORT,
+            I16 => glow::SHORT,
+            U32 => glow::UNSIGNED_INT,
+            I32 => glow::INT,
             U24U8 => glow::UNSIGNED_INT_24_8,
             F16 => glow::HALF_FLOAT,
             F32 => glow::FLOAT,
@@ -82,6 +88,9 @@ impl ImageComponentType {
             U8 => 1,
             I8 => 1,
             U16 => 2,
+            I16 => 2,
+            U32 => 4,
+            I32 => 4,
             U24U8 => 4,
             F16 => 2,
             F32 => 4,
@@ -109,6 +118,72 @@ pub enum ImageInternalFormat {
     RI8Snorm,
     RF16,
     RF32,
+    RgbaU8Int,
+    RgbaI8Int,
+    RgbaU16Int,
+    RgbaI16Int,
+    RgbaU32Int,
+    RgbaI32Int,
+    RgbU8Int,
+    RgbI8Int,
+    RgbU16Int,
+    RgbI16Int,
+    RgbU32Int,
+    RgbI32Int,
+    RgU8Int,
+    RgI8Int,
+    RgU16Int,
+    RgI16Int,
+    RgU32Int,
+    RgI32Int,
+    RU8Int,
+    RI8Int,
+    RU16Int,
+    RI16Int,
+    RU32Int,
+    RI32Int,
+    Etc2RgbU8,
+    Etc2RgbU8Gamma,
+    Etc2RgbU8A1,
+    Etc2RgbU8A1Gamma,
+    Etc2RgbaU8,
+    Etc2RgbaU8Gamma,
+    EacRU11,
+    EacRI11Snorm,
+    EacRgU11,
+    EacRgI11Snorm,
+    Astc4x4,
+    Astc4x4Gamma,
+    Astc5x4,
+    Astc5x4Gamma,
+    Astc5x5,
+    Astc5x5Gamma,
+    Astc6x5,
+    Astc6x5Gamma,
+    Astc6x6,
+    Astc6x6Gamma,
+    Astc8x5,
+    Astc8x5Gamma,
+    Astc8x6,
+    Astc8x6Gamma,
+    Astc8x8,
+    Astc8x8Gamma,
+    Astc10x5,
+    Astc10x5Gamma,
+    Astc10x6,
+    Astc10x6Gamma,
+    Astc10x8,
+    Astc10x8Gamma,
+    Astc10x10,
+    Astc10x10Gamma,
+    Astc12x10,
+    Astc12x10Gamma,
+    Astc12x12,
+    Astc12x12Gamma,
+    S3tcRgbDxt1,
+    S3tcRgbaDxt1,
+    S3tcRgbaDxt3,
+    S3tcRgbaDxt5,
     DepthU16,
     DepthF32,
     DepthU24StencilU8,
@@ -137,6 +212,72 @@ impl ImageInternalFormat {
             RI8Snorm => glow::R8_SNORM,
             RF16 => glow::R16F,
             RF32 => glow::R32F,
+            RgbaU8Int => glow::RGBA8UI,
+            RgbaI8Int => glow::RGBA8I,
+            RgbaU16Int => glow::RGBA16UI,
+            RgbaI16Int => glow::RGBA16I,
+            RgbaU32Int => glow::RGBA32UI,
+            RgbaI32Int => glow::RGBA32I,
+            RgbU8Int => glow::RGB8UI,
+            RgbI8Int => glow::RGB8I,
+            RgbU16Int => glow::RGB16UI,
+            RgbI16Int => glow::RGB16I,
+            RgbU32Int => glow::RGB32UI,
+            RgbI32Int => glow::RGB32I,
+            RgU8Int => glow::RG8UI,
+            RgI8Int => glow::RG8I,
+            RgU16Int => glow::RG16UI,
+            RgI16Int => glow::RG16I,
+            RgU32Int => glow::RG32UI,
+            RgI32Int => glow::RG32I,
+            RU8Int => glow::R8UI,
+            RI8Int => glow::R8I,
+            RU16Int => glow::R16UI,
+            RI16Int => glow::R16I,
+            RU32Int => glow::R32UI,
+            RI32Int => glow::R32I,
+            Etc2RgbU8 => glow::COMPRESSED_RGB8_ETC2,
+            Etc2RgbU8Gamma => glow::COMPRESSED_SRGB8_ETC2,
+            Etc2RgbU8A1 => glow::COMPRESSED_RGB8_PUNCHTHROUGH_ALPHA1_ETC2,
+            Etc2RgbU8A1Gamma => glow::COMPRESSED_SRGB8_PUNCHTHROUGH_ALPHA1_ETC2,
+            Etc2RgbaU8 => glow::COMPRESSED_RGBA8_ETC2_EAC,
+            Etc2RgbaU8Gamma => glow::COMPRESSED_SRGB8_ALPHA8_ETC2_EAC,
+            EacRU11 => glow::COMPRESSED_R11_EAC,
+            EacRI11Snorm => glow::COMPRESSED_SIGNED_R11_EAC,
+            EacRgU11 => glow::COMPRESSED_RG11_EAC,
+            EacRgI11Snorm => glow::COMPRESSED_SIGNED_RG11_EAC,
+            Astc4x4 => glow::COMPRESSED_RGBA_ASTC_4x4_KHR,
+            Astc4x4Gamma => glow::COMPRESSED_SRGB8_ALPHA8_ASTC_4x4_KHR,
+            Astc5x4 => glow::COMPRESSED_RGBA_ASTC_5x4_KHR,
+            Astc5x4Gamma => glow::COMPRESSED_SRGB8_ALPHA8_ASTC_5x4_KHR,
+            Astc5x5 => glow::COMPRESSED_RGBA_ASTC_5x5_KHR,
+            Astc5x5Gamma => glow::COMPRESSED_SRGB8_ALPHA8_ASTC_5x5_KHR,
+            Astc6x5 => glow::COMPRESSED_RGBA_ASTC_6x5_KHR,
+            Astc6x5Gamma => glow::COMPRESSED_SRGB8_ALPHA8_ASTC_6x5_KHR,
+            Astc6x6 => glow::COMPRESSED_RGBA_ASTC_6x6_KHR,
+            Astc6x6Gamma => glow::COMPRESSED_SRGB8_ALPHA8_ASTC_6x6_KHR,
+            Astc8x5 => glow::COMPRESSED_RGBA_ASTC_8x5_KHR,
+            Astc8x5Gamma => glow::COMPRESSED_SRGB8_ALPHA8_ASTC_8x5_KHR,
+            Astc8x6 => glow::COMPRESSED_RGBA_ASTC_8x6_KHR,
+            Astc8x6Gamma => glow::COMPRESSED_SRGB8_ALPHA8_ASTC_8x6_KHR,
+            Astc8x8 => glow::COMPRESSED_RGBA_ASTC_8x8_KHR,
+            Astc8x8Gamma => glow::COMPRESSED_SRGB8_ALPHA8_ASTC_8x8_KHR,
+            Astc10x5 => glow::COMPRESSED_RGBA_ASTC_10x5_KHR,
+            Astc10x5Gamma => glow::COMPRESSED_SRGB8_ALPHA8_ASTC_10x5_KHR,
+            Astc10x6 => glow::COMPRESSED_RGBA_ASTC_10x6_KHR,
+            Astc10x6Gamma => glow::COMPRESSED_SRGB8_ALPHA8_ASTC_10x6_KHR,
+            Astc10x8 => glow::COMPRESSED_RGBA_ASTC_10x8_KHR,
+            Astc10x8Gamma => glow::COMPRESSED_SRGB8_ALPHA8_ASTC_10x8_KHR,
+            Astc10x10 => glow::COMPRESSED_RGBA_ASTC_10x10_KHR,
+            Astc10x10Gamma => glow::COMPRESSED_SRGB8_ALPHA8_ASTC_10x10_KHR,
+            Astc12x10 => glow::COMPRESSED_RGBA_ASTC_12x10_KHR,
+            Astc12x10Gamma => glow::COMPRESSED_SRGB8_ALPHA8_ASTC_12x10_KHR,
+            Astc12x12 => glow::COMPRESSED_RGBA_ASTC_12x12_KHR,
+            Astc12x12Gamma => glow::COMPRESSED_SRGB8_ALPHA8_ASTC_12x12_KHR,
+            S3tcRgbDxt1 => glow::COMPRESSED_RGB_S3TC_DXT1_EXT,
+            S3tcRgbaDxt1 => glow::COMPRESSED_RGBA_S3TC_DXT1_EXT,
+            S3tcRgbaDxt3 => glow::COMPRESSED_RGBA_S3TC_DXT3_EXT,
+            S3tcRgbaDxt5 => glow::COMPRESSED_RGBA_S3TC_DXT5_EXT,
             DepthU16 => glow::DEPTH_COMPONENT16,
             DepthF32 => glow::DEPTH_COMPONENT32F,
             DepthU24StencilU8 => glow::DEPTH24_STENCIL8,
@@ -151,6 +292,24 @@ impl ImageInternalFormat {
             RgbU8 | RgbU8Gamma | RgbI8Snorm | RgbF16 | RgbF32 => ImageFormat::Rgb,
             RgU8 | RgI8Snorm | RgF16 | RgF32 => ImageFormat::Rg,
             RU8 | RI8Snorm | RF16 | RF32 => ImageFormat::R,
+            RgbaU8Int | RgbaI8Int | RgbaU16Int | RgbaI16Int | RgbaU32Int | RgbaI32Int => {
+                ImageFormat::RgbaInteger
+            }
+            RgbU8Int | RgbI8Int | RgbU16Int | RgbI16Int | RgbU32Int | RgbI32Int => {
+                ImageFormat::RgbInteger
+            }
+            RgU8Int | RgI8Int | RgU16Int | RgI16Int | RgU32Int | RgI32Int => ImageFormat::RgInteger,
+            RU8Int | RI8Int | RU16Int | RI16Int | RU32Int | RI32Int => ImageFormat::RedInteger,
+            Etc2RgbU8A1 | Etc2RgbU8A1Gamma | Etc2RgbaU8 | Etc2RgbaU8Gamma | Astc4x4
+            | Astc4x4Gamma | Astc5x4 | Astc5x4Gamma | Astc5x5 | Astc5x5Gamma | Astc6x5
+            | Astc6x5Gamma | Astc6x6 | Astc6x6Gamma | Astc8x5 | Astc8x5Gamma | Astc8x6
+            | Astc8x6Gamma | Astc8x8 | Astc8x8Gamma | Astc10x5 | Astc10x5Gamma | Astc10x6
+            | Astc10x6Gamma | Astc10x8 | Astc10x8Gamma | Astc10x10 | Astc10x10Gamma | Astc12x10
+            | Astc12x10Gamma | Astc12x12 | Astc12x12Gamma | S3tcRgbaDxt1 | S3tcRgbaDxt3
+            | S3tcRgbaDxt5 => ImageFormat::Rgba,
+            Etc2RgbU8 | Etc2RgbU8Gamma | S3tcRgbDxt1 => ImageFormat::Rgb,
+            EacRgU11 | EacRgI11Snorm => ImageFormat::Rg,
+            EacRU11 | EacRI11Snorm => ImageFormat::R,
             DepthU16 => ImageFormat::Depth,
             DepthF32 => ImageFormat::Depth,
             DepthU24StencilU8 => ImageFormat::DepthStencil,
@@ -167,6 +326,20 @@ impl ImageInternalFormat {
             RgbaI8Snorm | RgbI8Snorm | RgI8Snorm | RI8Snorm => ty == ImageComponentType::I8,
             RgbaF16 | RgbF16 | RgF16 | RF16 => ty == ImageComponentType::F16,
             RgbaF32 | RgbF32 | RgF32 | RF32 => ty == ImageComponentType::F32,
+            RgbaU8Int | RgbU8Int | RgU8Int | RU8Int => ty == ImageComponentType::U8,
+            RgbaI8Int | RgbI8Int | RgI8Int | RI8Int => ty == ImageComponentType::I8,
+            RgbaU16Int | RgbU16Int | RgU16Int | RU16Int => ty == ImageComponentType::U16,
+            RgbaI16Int | RgbI16Int | RgI16Int | RI16Int => ty == ImageComponentType::I16,
+            RgbaU32Int | RgbU32Int | RgU32Int | RU32Int => ty == ImageComponentType::U32,
+            RgbaI32Int | RgbI32Int | RgI32Int | RI32Int => ty == ImageComponentType::I32,
+            Etc2RgbU8 | Etc2RgbU8Gamma | Etc2RgbU8A1 | Etc2RgbU8A1Gamma | Etc2RgbaU8
+            | Etc2RgbaU8Gamma | EacRU11 | EacRI11Snorm | EacRgU11 | EacRgI11Snorm | Astc4x4
+            | Astc4x4Gamma | Astc5x4 | Astc5x4Gamma | Astc5x5 | Astc5x5Gamma | Astc6x5
+            | Astc6x5Gamma | Astc6x6 | Astc6x6Gamma | Astc8x5 | Astc8x5Gamma | Astc8x6
+            | Astc8x6Gamma | Astc8x8 | Astc8x8Gamma | Astc10x5 | Astc10x5Gamma | Astc10x6
+            | Astc10x6Gamma | Astc10x8 | Astc10x8Gamma | Astc10x10 | Astc10x10Gamma | Astc12x10
+            | Astc12x10Gamma | Astc12x12 | Astc12x12Gamma | S3tcRgbDxt1 | S3tcRgbaDxt1
+            | S3tcRgbaDxt3 | S3tcRgbaDxt5 => ty == ImageComponentType::U8,
             DepthU16 => ty == ImageComponentType::U16,
             DepthF32 => ty == ImageComponentType::F32,
             DepthU24StencilU8 => ty == ImageComponentType::U24U8,
@@ -195,12 +368,67 @@ impl ImageInternalFormat {
                 true
             }
             RgbF32 => false,
+            RgbaU8Int | RgbaI8Int | RgbaU16Int | RgbaI16Int | RgbaU32Int | RgbaI32Int => true,
+            RgbU8Int | RgbI8Int | RgbU16Int | RgbI16Int | RgbU32Int | RgbI32Int => false,
+            RgU8Int | RgI8Int | RgU16Int | RgI16Int | RgU32Int | RgI32Int => true,
+            RU8Int | RI8Int | RU16Int | RI16Int | RU32Int | RI32Int => true,
+            Etc2RgbU8 | Etc2RgbU8Gamma | Etc2RgbU8A1 | Etc2RgbU8A1Gamma | Etc2RgbaU8
+            | Etc2RgbaU8Gamma | EacRU11 | EacRI11Snorm | EacRgU11 | EacRgI11Snorm | Astc4x4
+            | Astc4x4Gamma | Astc5x4 | Astc5x4Gamma | Astc5x5 | Astc5x5Gamma | Astc6x5
+            | Astc6x5Gamma | Astc6x6 | Astc6x6Gamma | Astc8x5 | Astc8x5Gamma | Astc8x6
+            | Astc8x6Gamma | Astc8x8 | Astc8x8Gamma | Astc10x5 | Astc10x5Gamma | Astc10x6
+            | Astc10x6Gamma | Astc10x8 | Astc10x8Gamma | Astc10x10 | Astc10x10Gamma | Astc12x10
+            | Astc12x10Gamma | Astc12x12 | Astc12x12Gamma | S3tcRgbDxt1 | S3tcRgbaDxt1
+            | S3tcRgbaDxt3 | S3tcRgbaDxt5 => false,
             DepthU16 => false,
             DepthF32 => false,
             DepthU24StencilU8 => false,
         }
     }
 
+    /// Returns the size in texels and the size in bytes of the blocks that
+    /// images of this format consist of, if it is a compressed format.
+    pub const fn compressed_block_size(self) -> Option<([u32; 2], usize)> {
+        use ImageInternalFormat::*;
+
+        match self {
+            Etc2RgbU8 | Etc2RgbU8Gamma | Etc2RgbU8A1 | Etc2RgbU8A1Gamma | EacRU11
+            | EacRI11Snorm | S3tcRgbDxt1 | S3tcRgbaDxt1 => Some(([4, 4], 8)),
+            Etc2RgbaU8 | Etc2RgbaU8Gamma | EacRgU11 | EacRgI11Snorm | Astc4x4 | Astc4x4Gamma
+            | S3tcRgbaDxt3 | S3tcRgbaDxt5 => Some(([4, 4], 16)),
+            Astc5x4 | Astc5x4Gamma => Some(([5, 4], 16)),
+            Astc5x5 | Astc5x5Gamma => Some(([5, 5], 16)),
+            Astc6x5 | Astc6x5Gamma => Some(([6, 5], 16)),
+            Astc6x6 | Astc6x6Gamma => Some(([6, 6], 16)),
+            Astc8x5 | Astc8x5Gamma => Some(([8, 5], 16)),
+            Astc8x6 | Astc8x6Gamma => Some(([8, 6], 16)),
+            Astc8x8 | Astc8x8Gamma => Some(([8, 8], 16)),
+            Astc10x5 | Astc10x5Gamma => Some(([10, 5], 16)),
+            Astc10x6 | Astc10x6Gamma => Some(([10, 6], 16)),
+            Astc10x8 | Astc10x8Gamma => Some(([10, 8], 16)),
+            Astc10x10 | Astc10x10Gamma => Some(([10, 10], 16)),
+            Astc12x10 | Astc12x10Gamma => Some(([12, 10], 16)),
+            Astc12x12 | Astc12x12Gamma => Some(([12, 12], 16)),
+            _ => None,
+        }
+    }
+
+    pub fn is_compressed(&self) -> bool {
+        self.compressed_block_size().is_some()
+    }
+
+    /// Returns true if this is an integer format, which is read as integers
+    /// in shaders instead of being normalized.
+    pub fn is_integer(&self) -> bool {
+        matches!(
+            self.to_format(),
+            ImageFormat::RgbaInteger
+                | ImageFormat::RgbInteger
+                | ImageFormat::RgInteger
+                | ImageFormat::RedInteger
+        )
+    }
+
     pub fn is_depth_renderable(&self) -> bool {
         use ImageInternalFormat::*;
 
@@ -216,6 +444,62 @@ impl ImageInternalFormat {
 
         matches!(self, DepthU24StencilU8)
     }
+
+    /// Returns true if pixels of this format can be read back to the CPU with
+    /// the given format and component type.
+    pub fn is_readable_as(&self, format: ImageFormat, ty: ImageComponentType) -> bool {
+        use ImageInternalFormat::*;
+
+        // OpenGL ES 3.0.6: 4.3.1 Reading Pixels
+        //
+        // Normalized fixed-point color buffers can always be read as RGBA
+        // with unsigned bytes. `EXT_color_buffer_float` allows reading
+        // floating-point color buffers as RGBA with floats. Signed and unsigned
+        // integer color buffers can be read as RGBA integers with 32-bit
+        // components of the same signedness. Reading depth is not supported
+        // by OpenGL ES, but it is supported by desktop OpenGL.
+        match (format, ty) {
+            (ImageFormat::Rgba, ImageComponentType::U8) => {
+                self.is_color_renderable()
+                    && matches!(self, RgbaU8 | RgbaU8Gamma | RgbU8 | RgU8 | RU8)
+            }
+            (ImageFormat::Rgba, ImageComponentType::F32) => {
+                self.is_color_renderable()
+                    && matches!(
+                        self,
+                        RgbaF16 | RgbaF32 | RgbF16 | RgbF32 | RgF16 | RgF32 | RF16 | RF32
+                    )
+            }
+            (ImageFormat::RgbaInteger, ImageComponentType::I32) => {
+                self.is_color_renderable() && self.is_integer() && self.is_signed_integer()
+            }
+            (ImageFormat::RgbaInteger, ImageComponentType::U32) => {
+                self.is_color_renderable() && self.is_integer() && !self.is_signed_integer()
+            }
+            (ImageFormat::Depth, ImageComponentType::F32) => self.is_depth_renderable(),
+            _ => false,
+        }
+    }
+
+    pub(super) fn is_signed_integer(&self) -> bool {
+        use ImageInternalFormat::*;
+
+        matches!(
+            self,
+            RgbaI8Int
+                | RgbaI16Int
+                | RgbaI32Int
+                | RgbI8Int
+                | RgbI16Int
+                | RgbI32Int
+                | RgI8Int
+                | RgI16Int
+                | RgI32Int
+                | RI8Int
+                | RI16Int
+                | RI32Int
+        )
+    }
 }
 
 #[doc(hidden)]
@@ -225,10 +509,26 @@ pub struct Image<'a> {
     pub ty: ImageComponentType,
     pub internal_format: ImageInternalFormat,
     pub data: Option<&'a [u8]>,
+
+    /// The data of the mipmap levels that follow the base level.
+    ///
+    /// This is used for compressed images, whose mipmap levels can not be
+    /// generated.
+    pub mipmap_data: &'a [&'a [u8]],
 }
 
 impl<'a> Image<'a> {
     pub fn required_data_len(&self) -> usize {
+        if let Some((block_size, block_len)) = self.internal_format.compressed_block_size() {
+            let [width, height] = [0, 1].map(|i| self.size[i].div_ceil(block_size[i]) as usize);
+
+            return width
+                .checked_mul(height)
+                .unwrap()
+                .checked_mul(block_len)
+                .unwrap();
+        }
+
         let width = self.size[0] as usize;
         let height = self.size[1] as usize;
         let bytes = self.ty.size_of();
@@ -242,4 +542,21 @@ impl<'a> Image<'a> {
             .checked_mul(num_components)
             .unwrap()
     }
+
+    /// Returns the base level of the image, followed by one image for each of
+    /// the mipmap levels in `mipmap_data`.
+    pub fn levels(&self) -> impl Iterator<Item = Image<'a>> + '_ {
+        let base = Image {
+            mipmap_data: &[],
+            ..self.clone()
+        };
+        let mipmaps = self.mipmap_data.iter().enumerate().map(|(i, data)| Image {
+            size: self.size.map(|n| (n >> (i + 1)).max(1)),
+            data: Some(data),
+            mipmap_data: &[],
+            ..self.clone()
+        });
+
+        std::iter::once(base).chain(mipmaps)
+    }
 }
@@ -1,4 +1,4 @@
-use glow::HasContext;
+use super::{gl_api::GlApi, ImageInternalFormat};
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub struct Rect {
@@ -270,7 +270,7 @@ impl Default for StencilTest {
 }
 
 impl StencilTest {
-    fn set(&self, gl: &glow::Context, face: u32) {
+    fn set(&self, gl: &dyn GlApi, face: u32) {
         let comparison = self.comparison.to_gl();
 
         unsafe { gl.stencil_func_separate(face, comparison, self.reference, self.mask) };
@@ -295,7 +295,7 @@ impl Default for StencilOps {
 }
 
 impl StencilOps {
-    fn set(&self, gl: &glow::Context, face: u32) {
+    fn set(&self, gl: &dyn GlApi, face: u32) {
         let stencil_fail = self.stencil_fail.to_gl();
         let depth_fail = self.depth_fail.to_gl();
         let depth_pass = self.depth_pass.to_gl();
@@ -354,7 +354,7 @@ impl DrawParams {
 
     pub(super) fn set_delta(
         &self,
-        gl: &glow::Context,
+        gl: &dyn GlApi,
         current: &DrawParams,
         framebuffer_size: [u32; 2],
     ) {
@@ -604,3 +604,88 @@ impl DrawParams {
         self
     }
 }
+
+/// The value that a color image is cleared to.
+///
+/// The variant must match the component type of the image: integer images are
+/// cleared with [`ClearColor::I32`] or [`ClearColor::U32`] according to their
+/// signedness, and all other images are cleared with [`ClearColor::F32`].
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum ClearColor {
+    F32([f32; 4]),
+    I32([i32; 4]),
+    U32([u32; 4]),
+}
+
+impl ClearColor {
+    /// Returns true if images of `format` can be cleared to this value.
+    pub fn matches(&self, format: ImageInternalFormat) -> bool {
+        use ClearColor::*;
+
+        match self {
+            F32(_) => !format.is_integer(),
+            I32(_) => format.is_integer() && format.is_signed_integer(),
+            U32(_) => format.is_integer() && !format.is_signed_integer(),
+        }
+    }
+}
+
+impl From<[f32; 4]> for ClearColor {
+    fn from(value: [f32; 4]) -> Self {
+        Self::F32(value)
+    }
+}
+
+impl From<[i32; 4]> for ClearColor {
+    fn from(value: [i32; 4]) -> Self {
+        Self::I32(value)
+    }
+}
+
+impl From<[u32; 4]> for ClearColor {
+    fn from(value: [u32; 4]) -> Self {
+        Self::U32(value)
+    }
+}
+
+/// The values that the images of a framebuffer are cleared to.
+///
+/// In contrast to the clear values of [`DrawParams`], clearing with these
+/// values does not require a draw call, and it is not affected by the scissor
+/// rectangle or the write masks of previous draw calls.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ClearValues {
+    /// The values of the color images in the order of the framebuffer. Images
+    /// without a value are left unchanged.
+    pub colors: Vec<Option<ClearColor>>,
+    pub depth: Option<f32>,
+    pub stencil: Option<u8>,
+}
+
+impl ClearValues {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Sets the value of the next color image, starting with the first one.
+    pub fn with_color(mut self, color: impl Into<ClearColor>) -> Self {
+        self.colors.push(Some(color.into()));
+        self
+    }
+
+    /// Leaves the next color image unchanged, starting with the first one.
+    pub fn with_color_unchanged(mut self) -> Self {
+        self.colors.push(None);
+        self
+    }
+
+    pub fn with_depth(mut self, depth: f32) -> Self {
+        self.depth = Some(depth);
+        self
+    }
+
+    pub fn with_stencil(mut self, stencil: u8) -> Self {
+        self.stencil = Some(stencil);
+        self
+    }
+}
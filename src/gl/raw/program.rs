@@ -1,35 +1,64 @@
 use std::{collections::BTreeSet, rc::Rc};
 
-use glow::HasContext;
-
-use crate::sl::program_def::{ProgramDef, UniformSamplerDef};
+use crate::sl::{
+    dag::Type,
+    program_def::{ProgramDef, UniformSamplerDef, VertexBlockDef},
+};
 
 use super::{
-    context::ContextShared, error::check_gl_error, vertex_layout::VertexAttributeLayout, Buffer,
-    DrawError, DrawParams, Framebuffer, ProgramError, ProgramValidationError, Sampler, VertexSpec,
+    context::{Backend, ContextShared},
+    error::check_gl_error,
+    soft,
+    vertex_layout::VertexAttributeLayout,
+    Buffer, Caps, DrawError, DrawParams, Framebuffer, PrimitiveMode, ProgramError,
+    ProgramValidationError, Sampler, UniformBufferBinding, VertexSpec,
 };
 
 pub struct Program {
     ctx: Rc<ContextShared>,
     def: ProgramDef,
-    id: glow::Program,
+
+    // Programs of software contexts are evaluated directly from `def`, so they
+    // do not have an OpenGL object.
+    id: Option<glow::Program>,
+
+    // The transform feedback object that captures the output of the program,
+    // if `def` has a transform feedback block.
+    transform_feedback: Option<glow::TransformFeedback>,
 }
 
 impl Program {
     pub(super) fn new(ctx: Rc<ContextShared>, def: ProgramDef) -> Result<Self, ProgramError> {
-        validate_program_def(&def)?;
+        validate_program_def(ctx.caps(), &def)?;
+
+        if let Backend::Soft(_) = ctx.backend() {
+            return Ok(Program {
+                ctx,
+                def,
+                id: None,
+                transform_feedback: None,
+            });
+        }
 
         let gl = ctx.gl();
 
         check_gl_error(gl, "before creating program").map_err(ProgramError::Unexpected)?;
 
         let id = unsafe { gl.create_program() }.map_err(ProgramError::ProgramCreation)?;
-        let program = Program {
+        let mut program = Program {
             ctx: ctx.clone(),
             def,
-            id,
+            id: Some(id),
+            transform_feedback: None,
         };
 
+        if program.def.transform_feedback_block_def.is_some() {
+            let transform_feedback =
+                unsafe { gl.create_transform_feedback() }.map_err(ProgramError::ProgramCreation)?;
+
+            program.transform_feedback = Some(transform_feedback);
+        }
+
         check_gl_error(gl, "after creating program").map_err(ProgramError::Unexpected)?;
 
         // Compile and attach shaders.
@@ -38,7 +67,7 @@ impl Program {
             glow::VERTEX_SHADER,
             &program.def.vertex_shader_source,
         )?
-        .attach(program.id);
+        .attach(id);
 
         check_gl_error(gl, "after compiling vertex shader").map_err(ProgramError::Unexpected)?;
 
@@ -47,7 +76,7 @@ impl Program {
             glow::FRAGMENT_SHADER,
             &program.def.fragment_shader_source,
         )?
-        .attach(program.id);
+        .attach(id);
 
         check_gl_error(gl, "after compiling fragment shader").map_err(ProgramError::Unexpected)?;
 
@@ -59,11 +88,7 @@ impl Program {
             for block_def in &program.def.vertex_block_defs {
                 for attribute in &block_def.attributes {
                     unsafe {
-                        gl.bind_attrib_location(
-                            program.id,
-                            index.try_into().unwrap(),
-                            &attribute.name,
-                        );
+                        gl.bind_attrib_location(id, index.try_into().unwrap(), &attribute.name);
                     }
 
                     let attribute_info = VertexAttributeLayout::new(attribute.ty)
@@ -79,14 +104,31 @@ impl Program {
 
         check_gl_error(gl, "after binding vertex attributes").map_err(ProgramError::Unexpected)?;
 
+        // Declare the varyings that are captured with transform feedback. This
+        // also needs to be done before linking the program.
+        if let Some(block_def) = &program.def.transform_feedback_block_def {
+            let names: Vec<_> = block_def
+                .attributes
+                .iter()
+                .map(|attribute| attribute.name.as_str())
+                .collect();
+
+            unsafe {
+                gl.transform_feedback_varyings(id, &names, glow::INTERLEAVED_ATTRIBS);
+            }
+        }
+
+        check_gl_error(gl, "after declaring transform feedback varyings")
+            .map_err(ProgramError::Unexpected)?;
+
         // Link the program.
         let link_status = unsafe {
-            gl.link_program(program.id);
+            gl.link_program(id);
 
             // Note that we do not check shader compile status before checking
             // the program link status, since this would break pipelining and
             // thereby potentially slow down compilation.
-            gl.get_program_link_status(program.id)
+            gl.get_program_link_status(id)
         };
 
         check_gl_error(gl, "after linking the program").map_err(ProgramError::Unexpected)?;
@@ -94,7 +136,7 @@ impl Program {
         if !link_status {
             let vertex_shader_info = unsafe { gl.get_shader_info_log(vertex_shader.shader.id) };
             let fragment_shader_info = unsafe { gl.get_shader_info_log(fragment_shader.shader.id) };
-            let program_info = unsafe { gl.get_program_info_log(program.id) };
+            let program_info = unsafe { gl.get_program_info_log(id) };
 
             return Err(ProgramError::Compiler {
                 vertex_shader_info,
@@ -105,11 +147,11 @@ impl Program {
 
         // Set texture units.
         unsafe {
-            gl.use_program(Some(program.id));
+            gl.use_program(Some(id));
         }
 
         for sampler_def in &program.def.uniform_sampler_defs {
-            let location = unsafe { gl.get_uniform_location(program.id, &sampler_def.name) };
+            let location = unsafe { gl.get_uniform_location(id, &sampler_def.name) };
 
             // We silently ignore location lookup failures here, since program
             // linking is allowed to remove uniforms that are not used by the
@@ -130,14 +172,14 @@ impl Program {
 
         // Set uniform block locations.
         for uniform_def in &program.def.uniform_block_defs {
-            let index = unsafe { gl.get_uniform_block_index(program.id, &uniform_def.block_name) };
+            let index = unsafe { gl.get_uniform_block_index(id, &uniform_def.block_name) };
 
             // As with texture units, we silently ignore uniform block index
             // lookup failures here.
             if let Some(index) = index {
                 unsafe {
                     gl.uniform_block_binding(
-                        program.id,
+                        id,
                         index,
                         u32::try_from(uniform_def.location).unwrap(),
                     );
@@ -155,7 +197,8 @@ impl Program {
     ///
     /// Panics under any of the following conditions:
     /// 1. The supplied objects do not belong to the same `glow::Context`.
-    /// 2. The wrong number of uniform buffers is supplied.
+    /// 2. The wrong number of uniform buffers is supplied, or a uniform buffer
+    ///    range is not aligned to `UNIFORM_BUFFER_OFFSET_ALIGNMENT`.
     /// 3. The wrong number of samplers is supplied.
     /// 4. The vertex stream is not compatible with the program.
     ///
@@ -164,19 +207,32 @@ impl Program {
     /// TODO
     pub unsafe fn draw(
         &self,
-        uniform_buffers: &[&Buffer],
+        uniform_buffers: &[UniformBufferBinding],
         samplers: &[Sampler],
         vertex_spec: &VertexSpec,
         framebuffer: &Framebuffer,
         params: &DrawParams,
     ) -> Result<(), DrawError> {
         let ctx = &self.ctx;
-        let gl = ctx.gl();
         let def = &self.def;
 
-        assert_eq!(uniform_buffers.len(), def.uniform_block_defs.len());
-        assert_eq!(samplers.len(), def.uniform_sampler_defs.len());
-        assert!(vertex_spec.is_compatible(&self.def.vertex_block_defs));
+        self.assert_inputs(uniform_buffers, samplers, vertex_spec);
+
+        framebuffer.validate(ctx.caps())?;
+
+        if let Backend::Soft(device) = ctx.backend() {
+            return soft::draw(
+                device,
+                def,
+                uniform_buffers,
+                samplers,
+                vertex_spec,
+                framebuffer,
+                params,
+            );
+        }
+
+        let gl = ctx.gl();
 
         framebuffer.bind(&self.ctx)?;
 
@@ -186,17 +242,186 @@ impl Program {
         // binding the framebuffer.
         ctx.set_draw_params(params, framebuffer_size);
 
+        self.bind_inputs(uniform_buffers, samplers);
+
+        vertex_spec.draw(ctx);
+
+        self.unbind_inputs(samplers);
+
+        // TODO: Remove overly conservative unbinding.
+        framebuffer.unbind(&self.ctx);
+
+        #[cfg(debug_assertions)]
+        check_gl_error(gl, "after draw").map_err(DrawError::Error)?;
+
+        Ok(())
+    }
+
+    /// Runs the vertex shader on `vertex_spec` and captures its output into
+    /// `buffer`, starting at byte `offset`.
+    ///
+    /// Primitives are discarded before rasterization, so no framebuffer is
+    /// written to. Only complete primitives are captured.
+    ///
+    /// # Panics
+    ///
+    /// Panics under any of the following conditions:
+    /// 1. The program does not have a transform feedback block.
+    /// 2. Any of the conditions listed in [`Program::draw`], except for those
+    ///    concerning the framebuffer.
+    /// 3. `buffer` does not belong to the same `glow::Context`, `offset` is not
+    ///    a multiple of four, or `buffer` is also used as vertex input.
+    ///
+    /// # Safety
+    ///
+    /// TODO
+    pub unsafe fn capture(
+        &self,
+        uniform_buffers: &[UniformBufferBinding],
+        samplers: &[Sampler],
+        vertex_spec: &VertexSpec,
+        buffer: &Buffer,
+        offset: usize,
+    ) -> Result<(), DrawError> {
+        let ctx = &self.ctx;
+        let def = &self.def;
+        let block_def = def
+            .transform_feedback_block_def
+            .as_ref()
+            .expect("program does not capture its output");
+
+        self.assert_inputs(uniform_buffers, samplers, vertex_spec);
+        assert!(buffer.context().ref_eq(ctx));
+        assert_eq!(offset % 4, 0);
+        assert!(vertex_spec
+            .vertex_data
+            .iter()
+            .all(|binding| !std::ptr::eq(&*binding.buffer, buffer)));
+
+        // OpenGL ES 3.0.6: 2.15.2 Transform Feedback Primitive Capture
+        // > The error `INVALID_ENUM` is generated by `BeginTransformFeedback`
+        // > if `primitiveMode` is not `TRIANGLES`, `LINES`, or `POINTS`.
+        let vertices_per_primitive = match vertex_spec.mode {
+            PrimitiveMode::Points => 1,
+            PrimitiveMode::Lines => 2,
+            PrimitiveMode::Triangles => 3,
+            mode => return Err(DrawError::TransformFeedbackMode(mode)),
+        };
+
+        // > The error `INVALID_OPERATION` is also generated by `DrawElements`,
+        // > `DrawElementsInstanced`, and `DrawRangeElements` while transform
+        // > feedback is active and not paused.
+        if vertex_spec.element_data.is_some() {
+            return Err(DrawError::TransformFeedbackIndexed);
+        }
+
+        // > An `INVALID_OPERATION` error is generated by `DrawArrays` and
+        // > `DrawArraysInstanced` if recording the vertices of a primitive to
+        // > the buffer objects being used for transform feedback purposes would
+        // > result in [...] exceeding the limits of any buffer object's size.
+        let num_vertices = vertex_spec.index_range.len() / vertices_per_primitive
+            * vertices_per_primitive
+            * vertex_spec.num_instances;
+        let size = num_vertices * captured_stride(block_def);
+
+        if offset + size > buffer.len() {
+            return Err(DrawError::TransformFeedbackOverflow {
+                required: offset + size,
+                available: buffer.len(),
+            });
+        }
+
+        if let Backend::Soft(_) = ctx.backend() {
+            return soft::capture(def, uniform_buffers, samplers, vertex_spec, buffer, offset);
+        }
+
+        if size == 0 {
+            return Ok(());
+        }
+
+        let gl = ctx.gl();
+
+        self.bind_inputs(uniform_buffers, samplers);
+
+        unsafe {
+            gl.bind_transform_feedback(glow::TRANSFORM_FEEDBACK, self.transform_feedback);
+            gl.bind_buffer_range(
+                glow::TRANSFORM_FEEDBACK_BUFFER,
+                0,
+                Some(buffer.id()),
+                offset.try_into().unwrap(),
+                size.try_into().unwrap(),
+            );
+            gl.enable(glow::RASTERIZER_DISCARD);
+            gl.begin_transform_feedback(vertex_spec.mode.to_gl());
+        }
+
+        vertex_spec.draw(ctx);
+
+        unsafe {
+            gl.end_transform_feedback();
+            gl.disable(glow::RASTERIZER_DISCARD);
+        }
+
+        // TODO: Remove overly conservative unbinding.
+        unsafe {
+            gl.bind_buffer_base(glow::TRANSFORM_FEEDBACK_BUFFER, 0, None);
+            gl.bind_buffer(glow::TRANSFORM_FEEDBACK_BUFFER, None);
+            gl.bind_transform_feedback(glow::TRANSFORM_FEEDBACK, None);
+        }
+
+        self.unbind_inputs(samplers);
+
+        #[cfg(debug_assertions)]
+        check_gl_error(gl, "after capture").map_err(DrawError::Error)?;
+
+        Ok(())
+    }
+
+    fn assert_inputs(
+        &self,
+        uniform_buffers: &[UniformBufferBinding],
+        samplers: &[Sampler],
+        vertex_spec: &VertexSpec,
+    ) {
+        let ctx = &self.ctx;
+        let def = &self.def;
+
+        assert_eq!(uniform_buffers.len(), def.uniform_block_defs.len());
+        assert!(uniform_buffers.iter().all(|binding| {
+            binding.range.start % ctx.caps().uniform_buffer_offset_alignment as usize == 0
+                && binding.range.end <= binding.buffer.len()
+        }));
+        assert_eq!(samplers.len(), def.uniform_sampler_defs.len());
+        assert!(vertex_spec.is_compatible(&def.vertex_block_defs));
+    }
+
+    fn bind_inputs(&self, uniform_buffers: &[UniformBufferBinding], samplers: &[Sampler]) {
+        let ctx = &self.ctx;
+        let def = &self.def;
+        let gl = ctx.gl();
+
         unsafe {
-            gl.use_program(Some(self.id));
+            gl.use_program(self.id);
         }
 
-        for (buffer, block_def) in uniform_buffers.iter().zip(&def.uniform_block_defs) {
+        for (binding, block_def) in uniform_buffers.iter().zip(&def.uniform_block_defs) {
+            let buffer = &binding.buffer;
+
             assert!(buffer.context().ref_eq(ctx));
 
             let location = u32::try_from(block_def.location).unwrap();
+            let offset = binding.range.start.try_into().unwrap();
+            let size = binding.range.len().try_into().unwrap();
 
             unsafe {
-                gl.bind_buffer_base(glow::UNIFORM_BUFFER, location, Some(buffer.id()));
+                gl.bind_buffer_range(
+                    glow::UNIFORM_BUFFER,
+                    location,
+                    Some(buffer.id()),
+                    offset,
+                    size,
+                );
             }
         }
 
@@ -209,8 +434,11 @@ impl Program {
             }
             sampler.bind();
         }
+    }
 
-        vertex_spec.draw(ctx);
+    fn unbind_inputs(&self, samplers: &[Sampler]) {
+        let def = &self.def;
+        let gl = self.ctx.gl();
 
         // TODO: Remove overly conservative unbinding.
         for (sampler, sampler_def) in samplers.iter().zip(&def.uniform_sampler_defs) {
@@ -233,29 +461,33 @@ impl Program {
         }
 
         // TODO: Remove overly conservative unbinding.
-        gl.bind_buffer(glow::UNIFORM_BUFFER, None);
+        unsafe {
+            gl.bind_buffer(glow::UNIFORM_BUFFER, None);
+        }
 
         // TODO: Remove overly conservative unbinding.
         unsafe {
             gl.use_program(None);
         }
-
-        // TODO: Remove overly conservative unbinding.
-        framebuffer.unbind(&self.ctx);
-
-        #[cfg(debug_assertions)]
-        check_gl_error(gl, "after draw").map_err(DrawError::Error)?;
-
-        Ok(())
     }
 }
 
 impl Drop for Program {
     fn drop(&mut self) {
-        let gl = self.ctx.gl();
+        if let Some(id) = self.id {
+            let gl = self.ctx.gl();
 
-        unsafe {
-            gl.delete_program(self.id);
+            unsafe {
+                gl.delete_program(id);
+            }
+        }
+
+        if let Some(transform_feedback) = self.transform_feedback {
+            let gl = self.ctx.gl();
+
+            unsafe {
+                gl.delete_transform_feedback(transform_feedback);
+            }
         }
     }
 }
@@ -317,7 +549,7 @@ impl Drop for AttachedShader {
     }
 }
 
-fn validate_program_def(def: &ProgramDef) -> Result<(), ProgramValidationError> {
+fn validate_program_def(caps: &Caps, def: &ProgramDef) -> Result<(), ProgramValidationError> {
     {
         let mut names: BTreeSet<_> = BTreeSet::new();
 
@@ -364,11 +596,77 @@ fn validate_program_def(def: &ProgramDef) -> Result<(), ProgramValidationError>
         }
     }
 
+    if let Some(block_def) = &def.transform_feedback_block_def {
+        let mut offset = 0;
+        let mut components = 0;
+
+        for attribute in &block_def.attributes {
+            let varying_def = def
+                .varying_defs
+                .iter()
+                .find(|varying_def| varying_def.name == attribute.name)
+                .ok_or_else(|| {
+                    ProgramValidationError::TransformFeedbackMismatch(format!(
+                        "no varying named `{}`",
+                        attribute.name
+                    ))
+                })?;
+
+            if varying_def.ty != Type::BuiltIn(attribute.ty) {
+                return Err(ProgramValidationError::TransformFeedbackMismatch(format!(
+                    "`{}` has type `{}`, but the varying has type `{}`",
+                    attribute.name, attribute.ty, varying_def.ty,
+                )));
+            }
+
+            // Varyings are captured in interleaved mode, which writes them
+            // without any padding.
+            if attribute.offset != offset {
+                return Err(ProgramValidationError::TransformFeedbackMismatch(format!(
+                    "`{}` has offset {}, but it is captured at offset {offset}",
+                    attribute.name, attribute.offset,
+                )));
+            }
+
+            let layout = VertexAttributeLayout::new(attribute.ty)
+                .map_err(ProgramValidationError::TransformFeedbackMismatch)?;
+
+            offset += layout.locations * layout.location_size();
+            components += layout.locations * layout.components;
+        }
+
+        // OpenGL ES 3.0.6: 2.12.8 Transform Feedback Variables
+        // > The program will fail to link if [...] the buffer mode is
+        // > `INTERLEAVED_ATTRIBS` and the total number of components of all
+        // > output variables to be written exceeds the value of
+        // > `MAX_TRANSFORM_FEEDBACK_INTERLEAVED_COMPONENTS`.
+        if components > caps.max_transform_feedback_interleaved_components as usize {
+            return Err(ProgramValidationError::TooManyTransformFeedbackComponents {
+                requested: components,
+                max: caps.max_transform_feedback_interleaved_components,
+            });
+        }
+    }
+
     // FIXME: Check that the number of fragment fields is <= MAX_DRAW_BUFFERS.
 
     Ok(())
 }
 
+/// Returns the number of bytes that are captured per vertex.
+fn captured_stride(block_def: &VertexBlockDef) -> usize {
+    block_def
+        .attributes
+        .iter()
+        .map(|attribute| {
+            let layout =
+                VertexAttributeLayout::new(attribute.ty).expect("invalid vertex attribute");
+
+            layout.locations * layout.location_size()
+        })
+        .sum()
+}
+
 fn texture_unit_gl(sampler_def: &UniformSamplerDef) -> u32 {
     u32::try_from(sampler_def.texture_unit)
         .unwrap()
@@ -0,0 +1,178 @@
+use std::{cell::Cell, rc::Rc};
+
+use super::{
+    context::{Backend, ContextShared},
+    QueryError,
+};
+
+/// The kind of value that a query measures.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum QueryTarget {
+    /// Whether any samples passed the depth and stencil tests.
+    AnySamplesPassed,
+
+    /// Like [`QueryTarget::AnySamplesPassed`], but the implementation may
+    /// report samples as passed when none did, in exchange for a faster test.
+    AnySamplesPassedConservative,
+}
+
+impl QueryTarget {
+    pub const fn to_gl(self) -> u32 {
+        use QueryTarget::*;
+
+        match self {
+            AnySamplesPassed => glow::ANY_SAMPLES_PASSED,
+            AnySamplesPassedConservative => glow::ANY_SAMPLES_PASSED_CONSERVATIVE,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum QueryState {
+    Unused,
+
+    // The software backend answers queries by counting the samples that pass,
+    // so it remembers the count from when the query began.
+    Active { samples_passed: u64 },
+
+    Pending,
+    Available(bool),
+}
+
+/// A query object that measures whether draws issued between
+/// [`Query::begin`] and [`Query::end`] passed any samples.
+///
+/// Query results become available asynchronously, so they can be polled with
+/// [`Query::try_result`] without stalling.
+pub struct Query {
+    ctx: Rc<ContextShared>,
+    target: QueryTarget,
+    id: Option<glow::Query>,
+    state: Cell<QueryState>,
+}
+
+impl Query {
+    pub(super) fn new(ctx: Rc<ContextShared>, target: QueryTarget) -> Result<Self, QueryError> {
+        // Queries of software contexts are answered directly, so they do not
+        // have an OpenGL object.
+        let id = match ctx.backend() {
+            Backend::Gl { gl, .. } => {
+                Some(unsafe { gl.create_query() }.map_err(QueryError::ObjectCreation)?)
+            }
+            Backend::Soft(_) => None,
+        };
+
+        Ok(Self {
+            ctx,
+            target,
+            id,
+            state: Cell::new(QueryState::Unused),
+        })
+    }
+
+    pub fn target(&self) -> QueryTarget {
+        self.target
+    }
+
+    /// Starts measuring the draws that follow.
+    ///
+    /// If the query has been used before, its previous result is discarded.
+    pub fn begin(&self) -> Result<(), QueryError> {
+        // OpenGL ES 3.0.6: 2.14 Asynchronous Queries
+        // > The error `INVALID_OPERATION` is generated if `BeginQuery` is
+        // > called when a query of the given `target` is already active, or
+        // > where `target` is `ANY_SAMPLES_PASSED` and a query of target
+        // > `ANY_SAMPLES_PASSED_CONSERVATIVE` is active, or vice versa.
+        if self.ctx.occlusion_query_active() {
+            return Err(QueryError::AlreadyActive);
+        }
+
+        let samples_passed = match self.ctx.backend() {
+            Backend::Gl { gl, .. } => {
+                unsafe {
+                    gl.begin_query(self.target.to_gl(), self.id.unwrap());
+                }
+
+                0
+            }
+            Backend::Soft(device) => device.samples_passed(),
+        };
+
+        self.ctx.set_occlusion_query_active(true);
+        self.state.set(QueryState::Active { samples_passed });
+
+        Ok(())
+    }
+
+    /// Stops measuring draws.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the query is not active.
+    pub fn end(&self) {
+        let QueryState::Active { samples_passed } = self.state.get() else {
+            panic!("query is not active");
+        };
+
+        let state = match self.ctx.backend() {
+            Backend::Gl { gl, .. } => {
+                unsafe {
+                    gl.end_query(self.target.to_gl());
+                }
+
+                QueryState::Pending
+            }
+            Backend::Soft(device) => {
+                QueryState::Available(device.samples_passed() > samples_passed)
+            }
+        };
+
+        self.ctx.set_occlusion_query_active(false);
+        self.state.set(state);
+    }
+
+    /// Returns the result of the query if it is available, without blocking.
+    ///
+    /// Returns `None` if the query has not ended yet, or if the GPU has not
+    /// finished the measured draws. In WebGL, results only become available
+    /// after control has been returned to the browser.
+    pub fn try_result(&self) -> Option<bool> {
+        match self.state.get() {
+            QueryState::Unused | QueryState::Active { .. } => None,
+            QueryState::Available(result) => Some(result),
+            QueryState::Pending => {
+                let gl = self.ctx.gl();
+                let id = self.id.unwrap();
+
+                let available =
+                    unsafe { gl.get_query_parameter_u32(id, glow::QUERY_RESULT_AVAILABLE) } != 0;
+
+                if !available {
+                    return None;
+                }
+
+                let result = unsafe { gl.get_query_parameter_u32(id, glow::QUERY_RESULT) } != 0;
+
+                self.state.set(QueryState::Available(result));
+
+                Some(result)
+            }
+        }
+    }
+}
+
+impl Drop for Query {
+    fn drop(&mut self) {
+        if let QueryState::Active { .. } = self.state.get() {
+            self.end();
+        }
+
+        if let Some(id) = self.id {
+            let gl = self.ctx.gl();
+
+            unsafe {
+                gl.delete_query(id);
+            }
+        }
+    }
+}
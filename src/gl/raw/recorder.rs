@@ -0,0 +1,876 @@
+use std::{
+    cell::{Cell, RefCell},
+    collections::{BTreeMap, HashSet},
+    fmt::{self, Debug},
+    num::NonZeroU32,
+    rc::Rc,
+};
+
+use glow::{
+    CompressedPixelUnpackData, NativeBuffer, NativeFence, NativeFramebuffer, NativeProgram,
+    NativeQuery, NativeRenderbuffer, NativeShader, NativeTexture, NativeTransformFeedback,
+    NativeUniformLocation, NativeVertexArray, PixelPackData, PixelUnpackData,
+};
+
+use super::gl_api::GlApi;
+
+/// A single call that was made to a [`Recorder`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecordedCall {
+    /// The name of the function, as in `glow::HasContext`, e.g.
+    /// `bind_buffer`.
+    pub name: &'static str,
+
+    /// The arguments of the call, formatted with [`Debug`].
+    ///
+    /// Pixel and buffer data are abbreviated to their length in bytes.
+    pub args: Vec<String>,
+}
+
+impl fmt::Display for RecordedCall {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}({})", self.name, self.args.join(", "))
+    }
+}
+
+/// The kind of an OpenGL object that is tracked by a [`Recorder`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ObjectKind {
+    Buffer,
+    Texture,
+    Framebuffer,
+    Renderbuffer,
+    VertexArray,
+    Shader,
+    Program,
+    TransformFeedback,
+    Query,
+    Sync,
+}
+
+/// A fake OpenGL implementation that records calls instead of executing them.
+///
+/// This allows inspecting the exact sequence of GL calls that `posh` makes,
+/// e.g. to check for redundant state changes or for leaked objects, without
+/// requiring a driver. See [`crate::gl::Context::new_recording`].
+///
+/// Object lifetimes are simulated: created objects stay alive until they are
+/// deleted, and using an object that is not alive generates an
+/// `INVALID_OPERATION` error. Shaders always compile, programs always link,
+/// and framebuffers are always complete. Fences are always signaled, and query
+/// results are always available and nonzero. Pixels and buffer contents that
+/// are read back are zero. The context reports OpenGL ES 3.0, and the
+/// extensions for ASTC and S3TC texture compression are reported as supported.
+///
+/// Cloning a `Recorder` returns a handle to the same recording.
+#[derive(Clone)]
+pub struct Recorder {
+    shared: Rc<RecorderShared>,
+}
+
+struct RecorderShared {
+    default_framebuffer_size: [u32; 2],
+    version: glow::Version,
+    extensions: HashSet<String>,
+    calls: RefCell<Vec<RecordedCall>>,
+    objects: RefCell<BTreeMap<u32, ObjectKind>>,
+    next_id: Cell<u32>,
+    error: Cell<u32>,
+    names: RefCell<BTreeMap<(u32, String), u32>>,
+    mapped: RefCell<Vec<u8>>,
+}
+
+impl Recorder {
+    pub fn new(default_framebuffer_size: [u32; 2]) -> Self {
+        Self {
+            shared: Rc::new(RecorderShared {
+                default_framebuffer_size,
+                version: glow::Version {
+                    major: 3,
+                    minor: 0,
+                    is_embedded: true,
+                    revision: None,
+                    vendor_info: "posh recorder".to_string(),
+                },
+                extensions: [
+                    "GL_KHR_texture_compression_astc_ldr",
+                    "GL_EXT_texture_compression_s3tc",
+                ]
+                .map(String::from)
+                .into(),
+                calls: Default::default(),
+                objects: Default::default(),
+                next_id: Cell::new(1),
+                error: Cell::new(glow::NO_ERROR),
+                names: Default::default(),
+                mapped: Default::default(),
+            }),
+        }
+    }
+
+    /// Returns all calls that have been recorded so far.
+    pub fn calls(&self) -> Vec<RecordedCall> {
+        self.shared.calls.borrow().clone()
+    }
+
+    /// Returns all calls that have been recorded so far, and clears the
+    /// recording.
+    pub fn take_calls(&self) -> Vec<RecordedCall> {
+        self.shared.calls.take()
+    }
+
+    /// Returns the objects that have been created but not yet deleted, in
+    /// order of creation.
+    pub fn live_objects(&self) -> Vec<(ObjectKind, u32)> {
+        self.shared
+            .objects
+            .borrow()
+            .iter()
+            .map(|(id, kind)| (*kind, *id))
+            .collect()
+    }
+
+    fn record(&self, name: &'static str, args: &[&dyn Debug]) {
+        let args = args.iter().map(|arg| format!("{arg:?}")).collect();
+
+        self.shared
+            .calls
+            .borrow_mut()
+            .push(RecordedCall { name, args });
+    }
+
+    fn create(&self, kind: ObjectKind) -> NonZeroU32 {
+        let id = self.shared.next_id.get();
+        self.shared.next_id.set(id + 1);
+        self.shared.objects.borrow_mut().insert(id, kind);
+
+        NonZeroU32::new(id).unwrap()
+    }
+
+    fn delete(&self, kind: ObjectKind, id: NonZeroU32) {
+        let mut objects = self.shared.objects.borrow_mut();
+
+        // Like OpenGL, we silently ignore names that do not refer to objects.
+        if objects.get(&id.get()) == Some(&kind) {
+            objects.remove(&id.get());
+        }
+    }
+
+    fn check(&self, kind: ObjectKind, id: Option<NonZeroU32>) {
+        let Some(id) = id else {
+            return;
+        };
+
+        if self.shared.objects.borrow().get(&id.get()) != Some(&kind) {
+            self.set_error(glow::INVALID_OPERATION);
+        }
+    }
+
+    // Uniform locations and block indices are assigned per program, in the
+    // order in which they are queried.
+    fn name_index(&self, program: glow::Program, name: &str) -> u32 {
+        let mut names = self.shared.names.borrow_mut();
+        let index = names
+            .keys()
+            .filter(|(id, _)| *id == program.0.get())
+            .count();
+
+        *names
+            .entry((program.0.get(), name.to_string()))
+            .or_insert(index as u32)
+    }
+
+    fn set_error(&self, error: u32) {
+        // Only the first error is kept until it is queried.
+        if self.shared.error.get() == glow::NO_ERROR {
+            self.shared.error.set(error);
+        }
+    }
+}
+
+struct Bytes(usize);
+
+impl Debug for Bytes {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "<{} bytes>", self.0)
+    }
+}
+
+// Records a call whose only effect is being recorded.
+macro_rules! record {
+    ($(fn $name:ident(&self $(, $arg:ident: $ty:ty)* $(,)?);)*) => {
+        $(
+            unsafe fn $name(&self $(, $arg: $ty)*) {
+                self.record(stringify!($name), &[$(&$arg),*]);
+            }
+        )*
+    };
+}
+
+impl GlApi for Recorder {
+    record! {
+        fn finish(&self);
+        fn enable(&self, parameter: u32);
+        fn disable(&self, parameter: u32);
+        fn buffer_data_size(&self, target: u32, size: i32, usage: u32);
+        fn pixel_store_i32(&self, parameter: u32, value: i32);
+        fn enable_vertex_attrib_array(&self, index: u32);
+        fn disable_vertex_attrib_array(&self, index: u32);
+        fn vertex_attrib_pointer_f32(
+            &self,
+            index: u32,
+            size: i32,
+            data_type: u32,
+            normalized: bool,
+            stride: i32,
+            offset: i32,
+        );
+        fn vertex_attrib_pointer_i32(
+            &self,
+            index: u32,
+            size: i32,
+            data_type: u32,
+            stride: i32,
+            offset: i32,
+        );
+        fn vertex_attrib_divisor(&self, index: u32, divisor: u32);
+        fn active_texture(&self, unit: u32);
+        fn tex_parameter_i32(&self, target: u32, parameter: u32, value: i32);
+        fn tex_storage_2d(
+            &self,
+            target: u32,
+            levels: i32,
+            internal_format: u32,
+            width: i32,
+            height: i32,
+        );
+        fn tex_storage_3d(
+            &self,
+            target: u32,
+            levels: i32,
+            internal_format: u32,
+            width: i32,
+            height: i32,
+            depth: i32,
+        );
+        fn copy_tex_sub_image_2d(
+            &self,
+            target: u32,
+            level: i32,
+            x_offset: i32,
+            y_offset: i32,
+            x: i32,
+            y: i32,
+            width: i32,
+            height: i32,
+        );
+        fn generate_mipmap(&self, target: u32);
+        fn renderbuffer_storage_multisample(
+            &self,
+            target: u32,
+            samples: i32,
+            internal_format: u32,
+            width: i32,
+            height: i32,
+        );
+        fn draw_buffers(&self, buffers: &[u32]);
+        fn read_buffer(&self, src: u32);
+        fn blit_framebuffer(
+            &self,
+            src_x0: i32,
+            src_y0: i32,
+            src_x1: i32,
+            src_y1: i32,
+            dst_x0: i32,
+            dst_y0: i32,
+            dst_x1: i32,
+            dst_y1: i32,
+            mask: u32,
+            filter: u32,
+        );
+        fn uniform_1_i32(&self, location: Option<&glow::UniformLocation>, x: i32);
+        fn begin_transform_feedback(&self, primitive_mode: u32);
+        fn end_transform_feedback(&self);
+        fn end_query(&self, target: u32);
+        fn draw_arrays_instanced(&self, mode: u32, first: i32, count: i32, instance_count: i32);
+        fn draw_elements_instanced(
+            &self,
+            mode: u32,
+            count: i32,
+            element_type: u32,
+            offset: i32,
+            instance_count: i32,
+        );
+        fn clear_color(&self, red: f32, green: f32, blue: f32, alpha: f32);
+        fn clear_depth_f32(&self, depth: f32);
+        fn clear_stencil(&self, stencil: i32);
+        fn clear(&self, mask: u32);
+        fn clear_buffer_f32_slice(&self, target: u32, draw_buffer: u32, values: &[f32]);
+        fn clear_buffer_i32_slice(&self, target: u32, draw_buffer: u32, values: &[i32]);
+        fn clear_buffer_u32_slice(&self, target: u32, draw_buffer: u32, values: &[u32]);
+        fn clear_buffer_depth_stencil(&self, target: u32, draw_buffer: u32, depth: f32, stencil: i32);
+        fn viewport(&self, x: i32, y: i32, width: i32, height: i32);
+        fn scissor(&self, x: i32, y: i32, width: i32, height: i32);
+        fn cull_face(&self, value: u32);
+        fn depth_func(&self, func: u32);
+        fn depth_mask(&self, value: bool);
+        fn color_mask(&self, red: bool, green: bool, blue: bool, alpha: bool);
+        fn blend_color(&self, red: f32, green: f32, blue: f32, alpha: f32);
+        fn blend_equation_separate(&self, mode_rgb: u32, mode_alpha: u32);
+        fn blend_func_separate(&self, src_rgb: u32, dst_rgb: u32, src_alpha: u32, dst_alpha: u32);
+        fn stencil_func_separate(&self, face: u32, func: u32, reference: i32, mask: u32);
+        fn stencil_op_separate(&self, face: u32, stencil_fail: u32, depth_fail: u32, pass: u32);
+        fn stencil_mask_separate(&self, face: u32, mask: u32);
+    }
+
+    unsafe fn version(&self) -> &glow::Version {
+        self.record("version", &[]);
+
+        &self.shared.version
+    }
+
+    unsafe fn supported_extensions(&self) -> &HashSet<String> {
+        self.record("supported_extensions", &[]);
+
+        &self.shared.extensions
+    }
+
+    unsafe fn get_parameter_i32(&self, parameter: u32) -> i32 {
+        self.record("get_parameter_i32", &[&parameter]);
+
+        match parameter {
+            glow::MAX_TEXTURE_SIZE => 4096,
+            glow::MAX_COLOR_ATTACHMENTS => 8,
+            glow::MAX_DRAW_BUFFERS => 8,
+            glow::UNIFORM_BUFFER_OFFSET_ALIGNMENT => 256,
+            glow::MAX_3D_TEXTURE_SIZE => 256,
+            glow::MAX_ARRAY_TEXTURE_LAYERS => 256,
+            glow::MAX_CUBE_MAP_TEXTURE_SIZE => 4096,
+            glow::MAX_RENDERBUFFER_SIZE => 4096,
+            glow::MAX_SAMPLES => 4,
+            glow::MAX_TRANSFORM_FEEDBACK_INTERLEAVED_COMPONENTS => 64,
+            _ => 0,
+        }
+    }
+
+    unsafe fn get_parameter_i32_slice(&self, parameter: u32, out: &mut [i32]) {
+        self.record("get_parameter_i32_slice", &[&parameter]);
+
+        out.fill(0);
+
+        if parameter == glow::VIEWPORT {
+            let [width, height] = self.shared.default_framebuffer_size;
+
+            out[2] = width.try_into().unwrap();
+            out[3] = height.try_into().unwrap();
+        }
+    }
+
+    unsafe fn get_error(&self) -> u32 {
+        self.record("get_error", &[]);
+
+        self.shared.error.replace(glow::NO_ERROR)
+    }
+
+    unsafe fn create_buffer(&self) -> Result<glow::Buffer, String> {
+        let buffer = NativeBuffer(self.create(ObjectKind::Buffer));
+        self.record("create_buffer", &[&buffer]);
+
+        Ok(buffer)
+    }
+
+    unsafe fn delete_buffer(&self, buffer: glow::Buffer) {
+        self.record("delete_buffer", &[&buffer]);
+        self.delete(ObjectKind::Buffer, buffer.0);
+    }
+
+    unsafe fn bind_buffer(&self, target: u32, buffer: Option<glow::Buffer>) {
+        self.record("bind_buffer", &[&target, &buffer]);
+        self.check(ObjectKind::Buffer, buffer.map(|buffer| buffer.0));
+    }
+
+    unsafe fn bind_buffer_base(&self, target: u32, index: u32, buffer: Option<glow::Buffer>) {
+        self.record("bind_buffer_base", &[&target, &index, &buffer]);
+        self.check(ObjectKind::Buffer, buffer.map(|buffer| buffer.0));
+    }
+
+    unsafe fn bind_buffer_range(
+        &self,
+        target: u32,
+        index: u32,
+        buffer: Option<glow::Buffer>,
+        offset: i32,
+        size: i32,
+    ) {
+        self.record(
+            "bind_buffer_range",
+            &[&target, &index, &buffer, &offset, &size],
+        );
+        self.check(ObjectKind::Buffer, buffer.map(|buffer| buffer.0));
+    }
+
+    unsafe fn buffer_data_u8_slice(&self, target: u32, data: &[u8], usage: u32) {
+        self.record(
+            "buffer_data_u8_slice",
+            &[&target, &Bytes(data.len()), &usage],
+        );
+    }
+
+    unsafe fn buffer_sub_data_u8_slice(&self, target: u32, offset: i32, src_data: &[u8]) {
+        self.record(
+            "buffer_sub_data_u8_slice",
+            &[&target, &offset, &Bytes(src_data.len())],
+        );
+    }
+
+    unsafe fn get_buffer_sub_data(&self, target: u32, offset: i32, dst_data: &mut [u8]) {
+        self.record(
+            "get_buffer_sub_data",
+            &[&target, &offset, &Bytes(dst_data.len())],
+        );
+
+        dst_data.fill(0);
+    }
+
+    unsafe fn map_buffer_range(
+        &self,
+        target: u32,
+        offset: i32,
+        length: i32,
+        access: u32,
+    ) -> *mut u8 {
+        self.record("map_buffer_range", &[&target, &offset, &length, &access]);
+
+        // The mapping stays valid until the next call to `map_buffer_range`.
+        let mut mapped = self.shared.mapped.borrow_mut();
+        *mapped = vec![0; length.try_into().unwrap()];
+
+        mapped.as_mut_ptr()
+    }
+
+    unsafe fn unmap_buffer(&self, target: u32) {
+        self.record("unmap_buffer", &[&target]);
+    }
+
+    unsafe fn fence_sync(&self, condition: u32, flags: u32) -> Result<glow::Fence, String> {
+        let id = self.create(ObjectKind::Sync);
+        let fence = NativeFence(id.get() as usize as _);
+        self.record("fence_sync", &[&condition, &flags, &fence]);
+
+        Ok(fence)
+    }
+
+    unsafe fn client_wait_sync(&self, fence: glow::Fence, flags: u32, timeout: i32) -> u32 {
+        self.record("client_wait_sync", &[&fence, &flags, &timeout]);
+        self.check(ObjectKind::Sync, NonZeroU32::new(fence.0 as usize as u32));
+
+        glow::ALREADY_SIGNALED
+    }
+
+    unsafe fn delete_sync(&self, fence: glow::Fence) {
+        self.record("delete_sync", &[&fence]);
+
+        if let Some(id) = NonZeroU32::new(fence.0 as usize as u32) {
+            self.delete(ObjectKind::Sync, id);
+        }
+    }
+
+    unsafe fn create_query(&self) -> Result<glow::Query, String> {
+        let query = NativeQuery(self.create(ObjectKind::Query));
+        self.record("create_query", &[&query]);
+
+        Ok(query)
+    }
+
+    unsafe fn delete_query(&self, query: glow::Query) {
+        self.record("delete_query", &[&query]);
+        self.delete(ObjectKind::Query, query.0);
+    }
+
+    unsafe fn begin_query(&self, target: u32, query: glow::Query) {
+        self.record("begin_query", &[&target, &query]);
+        self.check(ObjectKind::Query, Some(query.0));
+    }
+
+    unsafe fn get_query_parameter_u32(&self, query: glow::Query, parameter: u32) -> u32 {
+        self.record("get_query_parameter_u32", &[&query, &parameter]);
+        self.check(ObjectKind::Query, Some(query.0));
+
+        1
+    }
+
+    unsafe fn create_vertex_array(&self) -> Result<glow::VertexArray, String> {
+        let vertex_array = NativeVertexArray(self.create(ObjectKind::VertexArray));
+        self.record("create_vertex_array", &[&vertex_array]);
+
+        Ok(vertex_array)
+    }
+
+    unsafe fn bind_vertex_array(&self, vertex_array: Option<glow::VertexArray>) {
+        self.record("bind_vertex_array", &[&vertex_array]);
+        self.check(ObjectKind::VertexArray, vertex_array.map(|v| v.0));
+    }
+
+    unsafe fn create_texture(&self) -> Result<glow::Texture, String> {
+        let texture = NativeTexture(self.create(ObjectKind::Texture));
+        self.record("create_texture", &[&texture]);
+
+        Ok(texture)
+    }
+
+    unsafe fn delete_texture(&self, texture: glow::Texture) {
+        self.record("delete_texture", &[&texture]);
+        self.delete(ObjectKind::Texture, texture.0);
+    }
+
+    unsafe fn bind_texture(&self, target: u32, texture: Option<glow::Texture>) {
+        self.record("bind_texture", &[&target, &texture]);
+        self.check(ObjectKind::Texture, texture.map(|texture| texture.0));
+    }
+
+    unsafe fn tex_sub_image_2d(
+        &self,
+        target: u32,
+        level: i32,
+        x_offset: i32,
+        y_offset: i32,
+        width: i32,
+        height: i32,
+        format: u32,
+        ty: u32,
+        pixels: PixelUnpackData,
+    ) {
+        let pixels: &dyn Debug = match &pixels {
+            PixelUnpackData::BufferOffset(offset) => offset,
+            PixelUnpackData::Slice(data) => &Bytes(data.len()),
+        };
+
+        self.record(
+            "tex_sub_image_2d",
+            &[
+                &target, &level, &x_offset, &y_offset, &width, &height, &format, &ty, pixels,
+            ],
+        );
+    }
+
+    unsafe fn tex_sub_image_3d(
+        &self,
+        target: u32,
+        level: i32,
+        x_offset: i32,
+        y_offset: i32,
+        z_offset: i32,
+        width: i32,
+        height: i32,
+        depth: i32,
+        format: u32,
+        ty: u32,
+        pixels: PixelUnpackData,
+    ) {
+        let pixels: &dyn Debug = match &pixels {
+            PixelUnpackData::BufferOffset(offset) => offset,
+            PixelUnpackData::Slice(data) => &Bytes(data.len()),
+        };
+
+        self.record(
+            "tex_sub_image_3d",
+            &[
+                &target, &level, &x_offset, &y_offset, &z_offset, &width, &height, &depth, &format,
+                &ty, pixels,
+            ],
+        );
+    }
+
+    unsafe fn compressed_tex_sub_image_2d(
+        &self,
+        target: u32,
+        level: i32,
+        x_offset: i32,
+        y_offset: i32,
+        width: i32,
+        height: i32,
+        format: u32,
+        pixels: CompressedPixelUnpackData,
+    ) {
+        let pixels: &dyn Debug = match &pixels {
+            CompressedPixelUnpackData::BufferRange(range) => range,
+            CompressedPixelUnpackData::Slice(data) => &Bytes(data.len()),
+        };
+
+        self.record(
+            "compressed_tex_sub_image_2d",
+            &[
+                &target, &level, &x_offset, &y_offset, &width, &height, &format, pixels,
+            ],
+        );
+    }
+
+    unsafe fn compressed_tex_sub_image_3d(
+        &self,
+        target: u32,
+        level: i32,
+        x_offset: i32,
+        y_offset: i32,
+        z_offset: i32,
+        width: i32,
+        height: i32,
+        depth: i32,
+        format: u32,
+        pixels: CompressedPixelUnpackData,
+    ) {
+        let pixels: &dyn Debug = match &pixels {
+            CompressedPixelUnpackData::BufferRange(range) => range,
+            CompressedPixelUnpackData::Slice(data) => &Bytes(data.len()),
+        };
+
+        self.record(
+            "compressed_tex_sub_image_3d",
+            &[
+                &target, &level, &x_offset, &y_offset, &z_offset, &width, &height, &depth, &format,
+                pixels,
+            ],
+        );
+    }
+
+    unsafe fn create_renderbuffer(&self) -> Result<glow::Renderbuffer, String> {
+        let renderbuffer = NativeRenderbuffer(self.create(ObjectKind::Renderbuffer));
+        self.record("create_renderbuffer", &[&renderbuffer]);
+
+        Ok(renderbuffer)
+    }
+
+    unsafe fn delete_renderbuffer(&self, renderbuffer: glow::Renderbuffer) {
+        self.record("delete_renderbuffer", &[&renderbuffer]);
+        self.delete(ObjectKind::Renderbuffer, renderbuffer.0);
+    }
+
+    unsafe fn bind_renderbuffer(&self, target: u32, renderbuffer: Option<glow::Renderbuffer>) {
+        self.record("bind_renderbuffer", &[&target, &renderbuffer]);
+        self.check(ObjectKind::Renderbuffer, renderbuffer.map(|r| r.0));
+    }
+
+    unsafe fn create_framebuffer(&self) -> Result<glow::Framebuffer, String> {
+        let framebuffer = NativeFramebuffer(self.create(ObjectKind::Framebuffer));
+        self.record("create_framebuffer", &[&framebuffer]);
+
+        Ok(framebuffer)
+    }
+
+    unsafe fn bind_framebuffer(&self, target: u32, framebuffer: Option<glow::Framebuffer>) {
+        self.record("bind_framebuffer", &[&target, &framebuffer]);
+        self.check(ObjectKind::Framebuffer, framebuffer.map(|f| f.0));
+    }
+
+    unsafe fn framebuffer_texture_2d(
+        &self,
+        target: u32,
+        attachment: u32,
+        texture_target: u32,
+        texture: Option<glow::Texture>,
+        level: i32,
+    ) {
+        self.record(
+            "framebuffer_texture_2d",
+            &[&target, &attachment, &texture_target, &texture, &level],
+        );
+        self.check(ObjectKind::Texture, texture.map(|texture| texture.0));
+    }
+
+    unsafe fn framebuffer_texture_layer(
+        &self,
+        target: u32,
+        attachment: u32,
+        texture: Option<glow::Texture>,
+        level: i32,
+        layer: i32,
+    ) {
+        self.record(
+            "framebuffer_texture_layer",
+            &[&target, &attachment, &texture, &level, &layer],
+        );
+        self.check(ObjectKind::Texture, texture.map(|texture| texture.0));
+    }
+
+    unsafe fn framebuffer_renderbuffer(
+        &self,
+        target: u32,
+        attachment: u32,
+        renderbuffer_target: u32,
+        renderbuffer: Option<glow::Renderbuffer>,
+    ) {
+        self.record(
+            "framebuffer_renderbuffer",
+            &[&target, &attachment, &renderbuffer_target, &renderbuffer],
+        );
+        self.check(ObjectKind::Renderbuffer, renderbuffer.map(|r| r.0));
+    }
+
+    unsafe fn check_framebuffer_status(&self, target: u32) -> u32 {
+        self.record("check_framebuffer_status", &[&target]);
+
+        glow::FRAMEBUFFER_COMPLETE
+    }
+
+    unsafe fn read_pixels(
+        &self,
+        x: i32,
+        y: i32,
+        width: i32,
+        height: i32,
+        format: u32,
+        gltype: u32,
+        pixels: PixelPackData,
+    ) {
+        let pixels: &dyn Debug = match pixels {
+            PixelPackData::BufferOffset(offset) => &Some(offset),
+            PixelPackData::Slice(data) => {
+                data.fill(0);
+
+                &None::<u32>
+            }
+        };
+
+        self.record(
+            "read_pixels",
+            &[&x, &y, &width, &height, &format, &gltype, pixels],
+        );
+    }
+
+    unsafe fn create_shader(&self, shader_type: u32) -> Result<glow::Shader, String> {
+        let shader = NativeShader(self.create(ObjectKind::Shader));
+        self.record("create_shader", &[&shader_type, &shader]);
+
+        Ok(shader)
+    }
+
+    unsafe fn delete_shader(&self, shader: glow::Shader) {
+        self.record("delete_shader", &[&shader]);
+        self.delete(ObjectKind::Shader, shader.0);
+    }
+
+    unsafe fn shader_source(&self, shader: glow::Shader, source: &str) {
+        self.record("shader_source", &[&shader, &source]);
+        self.check(ObjectKind::Shader, Some(shader.0));
+    }
+
+    unsafe fn compile_shader(&self, shader: glow::Shader) {
+        self.record("compile_shader", &[&shader]);
+        self.check(ObjectKind::Shader, Some(shader.0));
+    }
+
+    unsafe fn get_shader_info_log(&self, shader: glow::Shader) -> String {
+        self.record("get_shader_info_log", &[&shader]);
+
+        String::new()
+    }
+
+    unsafe fn create_program(&self) -> Result<glow::Program, String> {
+        let program = NativeProgram(self.create(ObjectKind::Program));
+        self.record("create_program", &[&program]);
+
+        Ok(program)
+    }
+
+    unsafe fn delete_program(&self, program: glow::Program) {
+        self.record("delete_program", &[&program]);
+        self.delete(ObjectKind::Program, program.0);
+    }
+
+    unsafe fn attach_shader(&self, program: glow::Program, shader: glow::Shader) {
+        self.record("attach_shader", &[&program, &shader]);
+        self.check(ObjectKind::Program, Some(program.0));
+        self.check(ObjectKind::Shader, Some(shader.0));
+    }
+
+    unsafe fn detach_shader(&self, program: glow::Program, shader: glow::Shader) {
+        self.record("detach_shader", &[&program, &shader]);
+        self.check(ObjectKind::Program, Some(program.0));
+        self.check(ObjectKind::Shader, Some(shader.0));
+    }
+
+    unsafe fn bind_attrib_location(&self, program: glow::Program, index: u32, name: &str) {
+        self.record("bind_attrib_location", &[&program, &index, &name]);
+        self.check(ObjectKind::Program, Some(program.0));
+    }
+
+    unsafe fn link_program(&self, program: glow::Program) {
+        self.record("link_program", &[&program]);
+        self.check(ObjectKind::Program, Some(program.0));
+    }
+
+    unsafe fn get_program_link_status(&self, program: glow::Program) -> bool {
+        self.record("get_program_link_status", &[&program]);
+
+        true
+    }
+
+    unsafe fn get_program_info_log(&self, program: glow::Program) -> String {
+        self.record("get_program_info_log", &[&program]);
+
+        String::new()
+    }
+
+    unsafe fn use_program(&self, program: Option<glow::Program>) {
+        self.record("use_program", &[&program]);
+        self.check(ObjectKind::Program, program.map(|program| program.0));
+    }
+
+    unsafe fn get_uniform_location(
+        &self,
+        program: glow::Program,
+        name: &str,
+    ) -> Option<glow::UniformLocation> {
+        self.record("get_uniform_location", &[&program, &name]);
+
+        Some(NativeUniformLocation(self.name_index(program, name)))
+    }
+
+    unsafe fn get_uniform_block_index(&self, program: glow::Program, name: &str) -> Option<u32> {
+        self.record("get_uniform_block_index", &[&program, &name]);
+
+        Some(self.name_index(program, name))
+    }
+
+    unsafe fn uniform_block_binding(&self, program: glow::Program, index: u32, binding: u32) {
+        self.record("uniform_block_binding", &[&program, &index, &binding]);
+        self.check(ObjectKind::Program, Some(program.0));
+    }
+
+    unsafe fn transform_feedback_varyings(
+        &self,
+        program: glow::Program,
+        varyings: &[&str],
+        buffer_mode: u32,
+    ) {
+        self.record(
+            "transform_feedback_varyings",
+            &[&program, &varyings, &buffer_mode],
+        );
+        self.check(ObjectKind::Program, Some(program.0));
+    }
+
+    unsafe fn create_transform_feedback(&self) -> Result<glow::TransformFeedback, String> {
+        let transform_feedback =
+            NativeTransformFeedback(self.create(ObjectKind::TransformFeedback));
+        self.record("create_transform_feedback", &[&transform_feedback]);
+
+        Ok(transform_feedback)
+    }
+
+    unsafe fn delete_transform_feedback(&self, transform_feedback: glow::TransformFeedback) {
+        self.record("delete_transform_feedback", &[&transform_feedback]);
+        self.delete(ObjectKind::TransformFeedback, transform_feedback.0);
+    }
+
+    unsafe fn bind_transform_feedback(
+        &self,
+        target: u32,
+        transform_feedback: Option<glow::TransformFeedback>,
+    ) {
+        self.record("bind_transform_feedback", &[&target, &transform_feedback]);
+        self.check(
+            ObjectKind::TransformFeedback,
+            transform_feedback.map(|t| t.0),
+        );
+    }
+}
@@ -0,0 +1,267 @@
+use std::{cell::RefCell, rc::Rc};
+
+use super::{
+    context::{Backend, ContextShared},
+    error::check_gl_error,
+    soft,
+    texture::{pixels_size, read_pixels, PixelDestination, Storage},
+    Buffer, Caps, ImageComponentType, ImageFormat, ImageInternalFormat, Rect, RenderbufferError,
+    TextureError,
+};
+
+/// An image that can be attached to a framebuffer, but not sampled.
+///
+/// Renderbuffers with a nonzero number of samples are multisampled. Their
+/// pixels cannot be read directly, so they need to be resolved into a
+/// single-sampled attachment first, see [`Attachment::resolve_into`].
+///
+/// [`Attachment::resolve_into`]: super::Attachment::resolve_into
+pub struct Renderbuffer {
+    ctx: Rc<ContextShared>,
+    storage: Storage<RefCell<soft::Surface>, glow::Renderbuffer>,
+    size: [u32; 2],
+    internal_format: ImageInternalFormat,
+    samples: u32,
+}
+
+impl Renderbuffer {
+    pub(super) fn new(
+        ctx: Rc<ContextShared>,
+        size: [u32; 2],
+        internal_format: ImageInternalFormat,
+        samples: u32,
+    ) -> Result<Self, RenderbufferError> {
+        validate(ctx.caps(), size, internal_format, samples)?;
+
+        if let Backend::Soft(_) = ctx.backend() {
+            let surface = soft::Surface::new(size, internal_format);
+
+            return Ok(Renderbuffer {
+                ctx: ctx.clone(),
+                storage: Storage::Soft(RefCell::new(surface)),
+                size,
+                internal_format,
+                samples,
+            });
+        }
+
+        let gl = ctx.gl();
+
+        let width = size[0]
+            .try_into()
+            .expect("max_renderbuffer_size is out of i32 range");
+        let height = size[1]
+            .try_into()
+            .expect("max_renderbuffer_size is out of i32 range");
+        let num_samples = samples.try_into().expect("max_samples is out of i32 range");
+
+        let id = unsafe { gl.create_renderbuffer() }.map_err(RenderbufferError::ObjectCreation)?;
+
+        unsafe {
+            gl.bind_renderbuffer(glow::RENDERBUFFER, Some(id));
+            gl.renderbuffer_storage_multisample(
+                glow::RENDERBUFFER,
+                num_samples,
+                internal_format.to_gl(),
+                width,
+                height,
+            );
+            gl.bind_renderbuffer(glow::RENDERBUFFER, None);
+        }
+
+        let renderbuffer = Renderbuffer {
+            ctx: ctx.clone(),
+            storage: Storage::Gl(id),
+            size,
+            internal_format,
+            samples,
+        };
+
+        check_gl_error(gl, "after renderbuffer creation").map_err(RenderbufferError::Unexpected)?;
+
+        Ok(renderbuffer)
+    }
+
+    /// # Panics
+    ///
+    /// Panics if this renderbuffer belongs to a software context.
+    pub(super) fn id(&self) -> glow::Renderbuffer {
+        match &self.storage {
+            Storage::Gl(id) => *id,
+            Storage::Soft(_) => panic!("expected an OpenGL renderbuffer"),
+        }
+    }
+
+    /// # Panics
+    ///
+    /// Panics if this renderbuffer belongs to an OpenGL context.
+    pub(super) fn soft(&self) -> &RefCell<soft::Surface> {
+        match &self.storage {
+            Storage::Gl(_) => panic!("expected a software renderbuffer"),
+            Storage::Soft(surface) => surface,
+        }
+    }
+
+    pub(super) fn context(&self) -> &ContextShared {
+        &self.ctx
+    }
+
+    pub fn size(&self) -> [u32; 2] {
+        self.size
+    }
+
+    pub fn internal_format(&self) -> ImageInternalFormat {
+        self.internal_format
+    }
+
+    /// Returns the number of samples per pixel, which is zero if the
+    /// renderbuffer is single-sampled.
+    pub fn samples(&self) -> u32 {
+        self.samples
+    }
+
+    /// Reads the pixels of `rect` into `data`.
+    ///
+    /// See [`Texture2d::read_pixels`](super::Texture2d::read_pixels).
+    pub fn read_pixels(
+        &self,
+        rect: Rect,
+        format: ImageFormat,
+        ty: ImageComponentType,
+        data: &mut [u8],
+    ) -> Result<(), TextureError> {
+        assert_eq!(data.len(), pixels_size(rect, format, ty));
+
+        self.read_pixels_impl(rect, format, ty, PixelDestination::Slice(data))
+    }
+
+    /// Starts reading the pixels of `rect` into `buffer`, starting at byte
+    /// `offset`.
+    ///
+    /// See [`Texture2d::read_pixels_to_buffer`](super::Texture2d::read_pixels_to_buffer).
+    pub fn read_pixels_to_buffer(
+        &self,
+        rect: Rect,
+        format: ImageFormat,
+        ty: ImageComponentType,
+        buffer: &Buffer,
+        offset: usize,
+    ) -> Result<(), TextureError> {
+        assert!(offset + pixels_size(rect, format, ty) <= buffer.len());
+        assert!(self.ctx.ref_eq(buffer.context()));
+
+        self.read_pixels_impl(
+            rect,
+            format,
+            ty,
+            PixelDestination::Buffer { buffer, offset },
+        )
+    }
+
+    fn read_pixels_impl(
+        &self,
+        rect: Rect,
+        format: ImageFormat,
+        ty: ImageComponentType,
+        destination: PixelDestination,
+    ) -> Result<(), TextureError> {
+        assert!(rect.lower_left_corner[0] + rect.size[0] <= self.size[0]);
+        assert!(rect.lower_left_corner[1] + rect.size[1] <= self.size[1]);
+
+        // OpenGL ES 3.0.6: 4.3.1 Reading Pixels
+        // > An `INVALID_OPERATION` error is generated if the value of
+        // > `SAMPLE_BUFFERS` for the read framebuffer is greater than zero.
+        if self.samples > 0 {
+            return Err(TextureError::MultisampledRead);
+        }
+
+        let surface;
+        let source = match &self.storage {
+            Storage::Gl(id) => Storage::Gl(*id),
+            Storage::Soft(cell) => {
+                surface = cell.borrow();
+                Storage::Soft(&*surface)
+            }
+        };
+
+        read_pixels(
+            &self.ctx,
+            self.internal_format,
+            source,
+            |attachment, id| unsafe {
+                self.ctx.gl().framebuffer_renderbuffer(
+                    glow::FRAMEBUFFER,
+                    attachment,
+                    glow::RENDERBUFFER,
+                    id,
+                )
+            },
+            rect,
+            format,
+            ty,
+            destination,
+        )
+    }
+}
+
+impl Drop for Renderbuffer {
+    fn drop(&mut self) {
+        if let Storage::Gl(id) = &self.storage {
+            let gl = self.ctx.gl();
+
+            unsafe {
+                gl.delete_renderbuffer(*id);
+            }
+        }
+    }
+}
+
+fn validate(
+    caps: &Caps,
+    size: [u32; 2],
+    internal_format: ImageInternalFormat,
+    samples: u32,
+) -> Result<(), RenderbufferError> {
+    // OpenGL ES 3.0.6: 4.4.2.1 Renderbuffer Objects
+    // > If either `width` or `height` is negative, or greater than the value of
+    // > `MAX_RENDERBUFFER_SIZE`, the error `INVALID_VALUE` is generated.
+    if size[0] == 0 || size[1] == 0 {
+        return Err(RenderbufferError::Empty);
+    }
+
+    for requested in size {
+        if requested > caps.max_renderbuffer_size {
+            return Err(RenderbufferError::Oversized {
+                requested,
+                max: caps.max_renderbuffer_size,
+            });
+        }
+    }
+
+    // > An `INVALID_ENUM` error is generated if `internalformat` is not a
+    // > color-renderable, depth-renderable, or stencil-renderable format.
+    if !internal_format.is_color_renderable()
+        && !internal_format.is_depth_renderable()
+        && !internal_format.is_stencil_renderable()
+    {
+        return Err(RenderbufferError::UnsupportedFormat(internal_format));
+    }
+
+    // > If `samples` is greater than the maximum number of samples supported
+    // > for `internalformat`, the error `INVALID_OPERATION` is generated.
+    if samples > caps.max_samples {
+        return Err(RenderbufferError::TooManySamples {
+            requested: samples,
+            max: caps.max_samples,
+        });
+    }
+
+    // > If `internalformat` is a signed or unsigned integer format and
+    // > `samples` is greater than zero, the error `INVALID_OPERATION` is
+    // > generated.
+    if internal_format.is_integer() && samples > 0 {
+        return Err(RenderbufferError::UnsupportedMultisample { internal_format });
+    }
+
+    Ok(())
+}
@@ -1,6 +1,4 @@
-use glow::HasContext;
-
-use super::Comparison;
+use super::{gl_api::GlApi, Comparison, ImageInternalFormat};
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum SamplerMagFilter {
@@ -31,6 +29,9 @@ pub struct Sampler2dParams {
     pub min_filter: SamplerMinFilter,
     pub wrap_s: SamplerWrap,
     pub wrap_t: SamplerWrap,
+    /// The wrap mode of the third texture coordinate. This is only used by
+    /// three-dimensional textures.
+    pub wrap_r: SamplerWrap,
 }
 
 impl SamplerMagFilter {
@@ -78,6 +79,7 @@ impl Default for Sampler2dParams {
             min_filter: SamplerMinFilter::NearestMipmapLinear,
             wrap_s: SamplerWrap::Repeat,
             wrap_t: SamplerWrap::Repeat,
+            wrap_r: SamplerWrap::Repeat,
         }
     }
 }
@@ -89,6 +91,7 @@ impl Sampler2dParams {
             min_filter: SamplerMinFilter::LinearMipmapLinear,
             wrap_s: SamplerWrap::Repeat,
             wrap_t: SamplerWrap::Repeat,
+            wrap_r: SamplerWrap::Repeat,
         }
     }
 
@@ -98,21 +101,45 @@ impl Sampler2dParams {
             min_filter: SamplerMinFilter::NearestMipmapNearest,
             wrap_s: SamplerWrap::Repeat,
             wrap_t: SamplerWrap::Repeat,
+            wrap_r: SamplerWrap::Repeat,
         }
     }
 
     pub fn with_wrap(mut self, wrap: SamplerWrap) -> Self {
         self.wrap_s = wrap;
         self.wrap_t = wrap;
+        self.wrap_r = wrap;
+        self
+    }
+
+    /// Returns the parameters with which a texture of format
+    /// `internal_format` is sampled.
+    ///
+    /// Integer textures can not be filtered, so their filters are replaced
+    /// with ones that select a single texel of the base level.
+    pub fn for_internal_format(mut self, internal_format: ImageInternalFormat) -> Self {
+        // OpenGL ES 3.0.6: 3.8.13 Texture Completeness
+        // > [A texture is not complete if] the internal format of the texture
+        // > is integer and either the magnification filter is not `NEAREST`,
+        // > or the minification filter is neither `NEAREST` nor
+        // > `NEAREST_MIPMAP_NEAREST`.
+        if internal_format.is_integer() {
+            self.mag_filter = SamplerMagFilter::Nearest;
+            self.min_filter = match self.min_filter {
+                SamplerMinFilter::Nearest | SamplerMinFilter::Linear => SamplerMinFilter::Nearest,
+                _ => SamplerMinFilter::NearestMipmapNearest,
+            };
+        }
+
         self
     }
 
-    pub(super) fn set_delta(&self, gl: &glow::Context, current: &Sampler2dParams) {
+    pub(super) fn set_delta(&self, gl: &dyn GlApi, target: u32, current: &Sampler2dParams) {
         if self.mag_filter != current.mag_filter {
             let mag_filter = self.mag_filter.to_gl() as i32;
 
             unsafe {
-                gl.tex_parameter_i32(glow::TEXTURE_2D, glow::TEXTURE_MAG_FILTER, mag_filter);
+                gl.tex_parameter_i32(target, glow::TEXTURE_MAG_FILTER, mag_filter);
             }
         }
 
@@ -120,7 +147,7 @@ impl Sampler2dParams {
             let min_filter = self.min_filter.to_gl() as i32;
 
             unsafe {
-                gl.tex_parameter_i32(glow::TEXTURE_2D, glow::TEXTURE_MIN_FILTER, min_filter);
+                gl.tex_parameter_i32(target, glow::TEXTURE_MIN_FILTER, min_filter);
             }
         }
 
@@ -128,7 +155,7 @@ impl Sampler2dParams {
             let wrap_s = self.wrap_s.to_gl() as i32;
 
             unsafe {
-                gl.tex_parameter_i32(glow::TEXTURE_2D, glow::TEXTURE_WRAP_S, wrap_s);
+                gl.tex_parameter_i32(target, glow::TEXTURE_WRAP_S, wrap_s);
             }
         }
 
@@ -136,18 +163,26 @@ impl Sampler2dParams {
             let wrap_t = self.wrap_t.to_gl() as i32;
 
             unsafe {
-                gl.tex_parameter_i32(glow::TEXTURE_2D, glow::TEXTURE_WRAP_T, wrap_t);
+                gl.tex_parameter_i32(target, glow::TEXTURE_WRAP_T, wrap_t);
+            }
+        }
+
+        if self.wrap_r != current.wrap_r {
+            let wrap_r = self.wrap_r.to_gl() as i32;
+
+            unsafe {
+                gl.tex_parameter_i32(target, glow::TEXTURE_WRAP_R, wrap_r);
             }
         }
     }
 }
 
-pub(super) fn set_comparison(gl: &glow::Context, target: u32, comparison: Option<Comparison>) {
+pub(super) fn set_comparison(gl: &dyn GlApi, target: u32, comparison: Option<Comparison>) {
     let mode = comparison.map_or(glow::NONE, |_| glow::COMPARE_REF_TO_TEXTURE) as i32;
     unsafe { gl.tex_parameter_i32(target, glow::TEXTURE_COMPARE_MODE, mode) };
 
     if let Some(comparison) = comparison {
         let comparison = comparison.to_gl() as i32;
-        unsafe { gl.tex_parameter_i32(glow::TEXTURE_2D, glow::TEXTURE_COMPARE_FUNC, comparison) };
+        unsafe { gl.tex_parameter_i32(target, glow::TEXTURE_COMPARE_FUNC, comparison) };
     }
 }
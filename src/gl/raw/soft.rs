@@ -0,0 +1,295 @@
+//! A backend that renders on the CPU.
+//!
+//! Programs are run by evaluating the expressions that the shaders were traced
+//! to, so no GLSL is compiled. This is slow, but it is deterministic and does
+//! not require a driver or a display, which makes it suitable for testing.
+
+mod input;
+mod raster;
+mod sample;
+mod surface;
+
+use std::cell::{Cell, Ref, RefCell, RefMut};
+
+use crate::sl::{eval, program_def::ProgramDef};
+
+use super::{
+    Buffer, ClearValues, DrawError, DrawParams, Framebuffer, Image, ImageComponentType,
+    ImageFormat, ImageInternalFormat, PrimitiveMode, Rect, Sampler, UniformBufferBinding,
+};
+
+pub(super) use surface::Surface;
+
+pub(super) struct Device {
+    default_color: RefCell<Surface>,
+    default_depth_stencil: RefCell<Surface>,
+
+    // The number of samples that have passed the depth and stencil tests so
+    // far, from which occlusion queries are answered.
+    samples_passed: Cell<u64>,
+}
+
+impl Device {
+    pub fn new(size: [u32; 2]) -> Self {
+        Self {
+            default_color: RefCell::new(Surface::new(size, ImageInternalFormat::RgbaU8)),
+            default_depth_stencil: RefCell::new(Surface::new(
+                size,
+                ImageInternalFormat::DepthU24StencilU8,
+            )),
+            samples_passed: Cell::new(0),
+        }
+    }
+
+    pub fn resize_default_framebuffer(&self, size: [u32; 2]) {
+        *self.default_color.borrow_mut() = Surface::new(size, ImageInternalFormat::RgbaU8);
+        *self.default_depth_stencil.borrow_mut() =
+            Surface::new(size, ImageInternalFormat::DepthU24StencilU8);
+    }
+
+    pub fn default_color_mut(&self) -> RefMut<'_, Surface> {
+        self.default_color.borrow_mut()
+    }
+
+    pub fn default_depth_stencil_mut(&self) -> RefMut<'_, Surface> {
+        self.default_depth_stencil.borrow_mut()
+    }
+
+    pub fn samples_passed(&self) -> u64 {
+        self.samples_passed.get()
+    }
+
+    pub fn read_default_framebuffer_rgba_u8(&self, rect: Rect, data: &mut [u8]) {
+        self.default_color.borrow().read_pixels(
+            rect,
+            ImageFormat::Rgba,
+            ImageComponentType::U8,
+            data,
+        );
+    }
+}
+
+/// The mipmap levels of a texture.
+pub(super) struct Texture {
+    levels: RefCell<Vec<Surface>>,
+}
+
+impl Texture {
+    pub fn new(image: &Image, data: &[u8], levels: usize) -> Self {
+        let mut size = image.size;
+        let mut surfaces = Vec::new();
+
+        for _ in 0..levels {
+            surfaces.push(Surface::new(size, image.internal_format));
+            size = size.map(|n| (n / 2).max(1));
+        }
+
+        surfaces[0].write_image([0, 0], image, data);
+
+        Self {
+            levels: RefCell::new(surfaces),
+        }
+    }
+
+    pub fn generate_mipmap(&self) {
+        let mut levels = self.levels.borrow_mut();
+
+        for level in 1..levels.len() {
+            levels[level] = levels[level - 1].downsample();
+        }
+    }
+
+    pub fn set(&self, level: usize, lower_left_corner: [u32; 2], image: &Image, data: &[u8]) {
+        self.levels.borrow_mut()[level].write_image(lower_left_corner, image, data);
+    }
+
+    pub fn levels(&self) -> Ref<'_, [Surface]> {
+        Ref::map(self.levels.borrow(), Vec::as_slice)
+    }
+
+    pub fn level_mut(&self, level: usize) -> RefMut<'_, Surface> {
+        RefMut::map(self.levels.borrow_mut(), |levels| &mut levels[level])
+    }
+}
+
+/// The mipmap levels of a texture with three-dimensional storage, each of
+/// which consists of slices along the third dimension.
+///
+/// The layers of array textures are kept when generating mipmaps, whereas the
+/// slices of three-dimensional textures are halved like the other dimensions.
+pub(super) struct Texture3d {
+    levels: RefCell<Vec<Vec<Surface>>>,
+    layered: bool,
+}
+
+impl Texture3d {
+    pub fn new(images: &[Image], data: &[&[u8]], levels: usize, layered: bool) -> Self {
+        let mut size = images[0].size;
+        let mut depth = images.len();
+        let mut surfaces = Vec::new();
+
+        for _ in 0..levels {
+            surfaces.push(vec![Surface::new(size, images[0].internal_format); depth]);
+            size = size.map(|n| (n / 2).max(1));
+
+            if !layered {
+                depth = (depth / 2).max(1);
+            }
+        }
+
+        for ((slice, image), data) in surfaces[0].iter_mut().zip(images).zip(data) {
+            slice.write_image([0, 0], image, data);
+        }
+
+        Self {
+            levels: RefCell::new(surfaces),
+            layered,
+        }
+    }
+
+    pub fn generate_mipmap(&self) {
+        let mut levels = self.levels.borrow_mut();
+
+        for level in 1..levels.len() {
+            let (previous, next) = levels.split_at_mut(level);
+            let previous = &previous[level - 1];
+
+            next[0] = if self.layered {
+                previous.iter().map(Surface::downsample).collect()
+            } else {
+                (0..next[0].len())
+                    .map(|i| {
+                        let a = previous[2 * i].downsample();
+
+                        match previous.get(2 * i + 1) {
+                            Some(b) => average(&a, &b.downsample()),
+                            None => a,
+                        }
+                    })
+                    .collect()
+            };
+        }
+    }
+
+    pub fn set(&self, level: usize, offset: [u32; 3], image: &Image, data: &[u8]) {
+        self.levels.borrow_mut()[level][offset[2] as usize].write_image(
+            [offset[0], offset[1]],
+            image,
+            data,
+        );
+    }
+
+    pub fn levels(&self) -> Ref<'_, [Vec<Surface>]> {
+        Ref::map(self.levels.borrow(), Vec::as_slice)
+    }
+
+    pub fn slice_mut(&self, level: usize, slice: usize) -> RefMut<'_, Surface> {
+        RefMut::map(self.levels.borrow_mut(), |levels| &mut levels[level][slice])
+    }
+}
+
+fn average(a: &Surface, b: &Surface) -> Surface {
+    let size = a.size();
+    let mut result = Surface::new(size, a.format());
+
+    for y in 0..size[1] {
+        for x in 0..size[0] {
+            let [a, b] = [a, b].map(|surface| surface.get_linear([x, y]));
+
+            result.set_linear([x, y], [0, 1, 2, 3].map(|i| (a[i] + b[i]) / 2.0));
+        }
+    }
+
+    result
+}
+
+/// Draws by running `def` on the CPU.
+///
+/// See [`super::Program::draw`] for the conditions under which this panics.
+pub(super) fn draw(
+    device: &Device,
+    def: &ProgramDef,
+    uniform_buffers: &[UniformBufferBinding],
+    samplers: &[Sampler],
+    vertex_spec: &super::VertexSpec,
+    framebuffer: &Framebuffer,
+    params: &DrawParams,
+) -> Result<(), DrawError> {
+    let uniforms = input::uniforms(def, uniform_buffers);
+    let textures = sample::Textures::new(def, samplers);
+    let mut targets = raster::Targets::new(device, framebuffer);
+
+    targets.clear(params);
+
+    if vertex_spec.index_range.start >= vertex_spec.index_range.end
+        || vertex_spec.num_instances == 0
+    {
+        return Ok(());
+    }
+
+    raster::draw(def, &uniforms, &textures, vertex_spec, &mut targets, params)
+}
+
+/// Captures the vertex shader output of `def` into `buffer`, starting at byte
+/// `offset`.
+///
+/// See [`super::Program::capture`] for the conditions under which this panics.
+pub(super) fn capture(
+    def: &ProgramDef,
+    uniform_buffers: &[UniformBufferBinding],
+    samplers: &[Sampler],
+    vertex_spec: &super::VertexSpec,
+    buffer: &Buffer,
+    offset: usize,
+) -> Result<(), DrawError> {
+    let block_def = def.transform_feedback_block_def.as_ref().unwrap();
+    let uniforms = input::uniforms(def, uniform_buffers);
+    let textures = sample::Textures::new(def, samplers);
+    let exprs: Vec<_> = def
+        .vertex_shader_exprs
+        .iter()
+        .map(|(_, expr)| expr.clone())
+        .collect();
+    let outputs: Vec<_> = block_def
+        .attributes
+        .iter()
+        .map(|attribute| {
+            def.vertex_shader_exprs
+                .iter()
+                .position(|(name, _)| *name == attribute.name)
+                .unwrap()
+        })
+        .collect();
+
+    // Incomplete primitives are not captured.
+    let mut vertex_ids = input::vertex_ids(vertex_spec);
+    let vertices_per_primitive = match vertex_spec.mode {
+        PrimitiveMode::Lines => 2,
+        PrimitiveMode::Triangles => 3,
+        _ => 1,
+    };
+    vertex_ids.truncate(vertex_ids.len() / vertices_per_primitive * vertices_per_primitive);
+
+    let mut data = buffer.soft_data_mut();
+    let mut position = offset;
+
+    for instance_id in 0..vertex_spec.num_instances as u32 {
+        for &vertex_id in &vertex_ids {
+            let mut args = uniforms.clone();
+            args.extend(input::vertex_input(vertex_spec, vertex_id, instance_id));
+
+            let values = eval::eval_with_args(&exprs, args, &textures)?;
+
+            for &i in &outputs {
+                position += input::write_attribute(&values[i], &mut data[position..]);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Clears the images of `framebuffer` to `values`.
+pub(super) fn clear(device: &Device, framebuffer: &Framebuffer, values: &ClearValues) {
+    raster::Targets::new(device, framebuffer).clear_to(values);
+}
@@ -0,0 +1,282 @@
+use std::collections::BTreeMap;
+
+use crate::{
+    gl::raw::{
+        vertex_layout::{VertexAttributeLayout, VertexAttributeType},
+        ElementType, UniformBufferBinding, VertexBufferBinding, VertexSpec,
+    },
+    sl::{
+        dag::{BuiltInType, Type},
+        eval::Constant,
+        program_def::{ProgramDef, VertexInputRate},
+    },
+};
+
+/// Decodes the uniform blocks of `def` from their buffers.
+pub fn uniforms(def: &ProgramDef, bindings: &[UniformBufferBinding]) -> BTreeMap<String, Constant> {
+    def.uniform_block_defs
+        .iter()
+        .zip(bindings)
+        .map(|(block_def, binding)| {
+            let data = binding.buffer.soft_data();
+            let value = read_std140(&block_def.ty, &data[binding.range.clone()], 0);
+
+            (block_def.arg_name.clone(), value)
+        })
+        .collect()
+}
+
+/// Returns the vertex indices that are to be drawn, in order.
+pub fn vertex_ids(vertex_spec: &VertexSpec) -> Vec<u32> {
+    let range = vertex_spec.index_range.clone();
+
+    match &vertex_spec.element_data {
+        Some((buffer, ty)) => {
+            let data = buffer.soft_data();
+            let size = ty.size();
+
+            assert!(range.end * size <= data.len());
+
+            range
+                .map(|i| {
+                    let bytes = &data[i * size..(i + 1) * size];
+
+                    match ty {
+                        ElementType::U16 => u16::from_ne_bytes(bytes.try_into().unwrap()).into(),
+                        ElementType::U32 => u32::from_ne_bytes(bytes.try_into().unwrap()),
+                    }
+                })
+                .collect()
+        }
+        None => range.map(|i| i.try_into().unwrap()).collect(),
+    }
+}
+
+/// Returns the input of a vertex shader invocation.
+pub fn vertex_input(
+    vertex_spec: &VertexSpec,
+    vertex_id: u32,
+    instance_id: u32,
+) -> BTreeMap<String, Constant> {
+    let mut args = BTreeMap::new();
+
+    for VertexBufferBinding {
+        buffer,
+        block_def,
+        input_rate,
+        stride,
+    } in &vertex_spec.vertex_data
+    {
+        let index = match input_rate {
+            VertexInputRate::Vertex => vertex_id,
+            VertexInputRate::Instance => instance_id,
+        };
+        let data = buffer.soft_data();
+        let base = index as usize * stride;
+
+        assert!(base + stride <= data.len());
+
+        for attribute in &block_def.attributes {
+            let layout =
+                VertexAttributeLayout::new(attribute.ty).expect("invalid vertex attribute");
+            let offset = base + attribute.offset;
+
+            let value = if attribute.ty.is_mat() {
+                let columns = (0..layout.locations)
+                    .map(|i| read_attribute(&layout, &data[offset + i * layout.location_size()..]))
+                    .collect();
+
+                Constant::Mat(columns)
+            } else if attribute.ty == BuiltInType::Bool {
+                Constant::Bool(read_attribute(&layout, &data[offset..]) != Constant::U32(0))
+            } else {
+                read_attribute(&layout, &data[offset..])
+            };
+
+            args.insert(attribute.name.clone(), value);
+        }
+    }
+
+    args.insert("gl_VertexID".into(), Constant::I32(vertex_id as i32));
+    args.insert("gl_InstanceID".into(), Constant::I32(instance_id as i32));
+
+    args
+}
+
+fn read_attribute(layout: &VertexAttributeLayout, data: &[u8]) -> Constant {
+    let components: Vec<_> = data
+        .chunks_exact(layout.ty.size())
+        .take(layout.components)
+        .map(|bytes| {
+            let bytes = bytes.try_into().unwrap();
+
+            match layout.ty {
+                VertexAttributeType::F32 => Constant::F32(f32::from_ne_bytes(bytes)),
+                VertexAttributeType::I32 => Constant::I32(i32::from_ne_bytes(bytes)),
+                VertexAttributeType::U32 => Constant::U32(u32::from_ne_bytes(bytes)),
+            }
+        })
+        .collect();
+
+    if components.len() == 1 {
+        components.into_iter().next().unwrap()
+    } else {
+        Constant::Vec(components)
+    }
+}
+
+/// Writes the value of a vertex attribute to `data`, returning the number of
+/// bytes that were written.
+pub fn write_attribute(value: &Constant, data: &mut [u8]) -> usize {
+    let bytes = match value {
+        Constant::F32(x) => x.to_ne_bytes(),
+        Constant::I32(x) => x.to_ne_bytes(),
+        Constant::U32(x) => x.to_ne_bytes(),
+        Constant::Bool(x) => u32::from(*x).to_ne_bytes(),
+        Constant::Vec(components) | Constant::Mat(components) => {
+            return components.iter().fold(0, |size, component| {
+                size + write_attribute(component, &mut data[size..])
+            });
+        }
+        _ => panic!("`{value:?}` is not a vertex attribute"),
+    };
+
+    data[..bytes.len()].copy_from_slice(&bytes);
+
+    bytes.len()
+}
+
+// OpenGL ES 3.0.6: 2.12.6.4 Standard Uniform Block Layout
+fn read_std140(ty: &Type, data: &[u8], offset: usize) -> Constant {
+    match ty {
+        Type::BuiltIn(ty) => {
+            if ty.is_mat() {
+                let n = match ty {
+                    BuiltInType::Mat2 => 2,
+                    BuiltInType::Mat3 => 3,
+                    _ => 4,
+                };
+
+                Constant::Mat(
+                    (0..n)
+                        .map(|i| read_std140_vec(data, offset + i * 16, n, BuiltInType::F32))
+                        .collect(),
+                )
+            } else {
+                let (n, scalar) = built_in_components(*ty);
+
+                if n == 1 {
+                    read_std140_scalar(data, offset, scalar)
+                } else {
+                    read_std140_vec(data, offset, n, scalar)
+                }
+            }
+        }
+        Type::Array(ty) => {
+            let stride = round_up(std140_size(&ty.ty), 16);
+
+            Constant::Array(
+                (0..ty.len)
+                    .map(|i| read_std140(&ty.ty, data, offset + i * stride))
+                    .collect(),
+            )
+        }
+        Type::Struct(ty) => {
+            let mut field_offset = offset;
+
+            Constant::Struct(
+                ty.fields
+                    .iter()
+                    .map(|(_, field_ty)| {
+                        field_offset = round_up(field_offset, std140_align(field_ty));
+
+                        let value = read_std140(field_ty, data, field_offset);
+                        field_offset += std140_size(field_ty);
+
+                        value
+                    })
+                    .collect(),
+            )
+        }
+    }
+}
+
+fn read_std140_vec(data: &[u8], offset: usize, n: usize, scalar: BuiltInType) -> Constant {
+    Constant::Vec(
+        (0..n)
+            .map(|i| read_std140_scalar(data, offset + i * 4, scalar))
+            .collect(),
+    )
+}
+
+fn read_std140_scalar(data: &[u8], offset: usize, ty: BuiltInType) -> Constant {
+    let bytes = data[offset..offset + 4].try_into().unwrap();
+
+    match ty {
+        BuiltInType::F32 => Constant::F32(f32::from_ne_bytes(bytes)),
+        BuiltInType::I32 => Constant::I32(i32::from_ne_bytes(bytes)),
+        BuiltInType::U32 => Constant::U32(u32::from_ne_bytes(bytes)),
+        BuiltInType::Bool => Constant::Bool(u32::from_ne_bytes(bytes) != 0),
+        _ => unreachable!(),
+    }
+}
+
+fn std140_align(ty: &Type) -> usize {
+    match ty {
+        Type::BuiltIn(ty) if ty.is_mat() => 16,
+        Type::BuiltIn(ty) => match built_in_components(*ty).0 {
+            1 => 4,
+            2 => 8,
+            _ => 16,
+        },
+        Type::Array(_) => 16,
+        Type::Struct(ty) => ty
+            .fields
+            .iter()
+            .map(|(_, ty)| round_up(std140_align(ty), 16))
+            .max()
+            .unwrap_or(16),
+    }
+}
+
+fn std140_size(ty: &Type) -> usize {
+    match ty {
+        Type::BuiltIn(BuiltInType::Mat2) => 2 * 16,
+        Type::BuiltIn(BuiltInType::Mat3) => 3 * 16,
+        Type::BuiltIn(BuiltInType::Mat4) => 4 * 16,
+        Type::BuiltIn(ty) => built_in_components(*ty).0 * 4,
+        Type::Array(ty) => ty.len * round_up(std140_size(&ty.ty), 16),
+        Type::Struct(ty) => {
+            let end = ty.fields.iter().fold(0, |offset, (_, ty)| {
+                round_up(offset, std140_align(ty)) + std140_size(ty)
+            });
+
+            round_up(end, std140_align(&Type::Struct(ty.clone())))
+        }
+    }
+}
+
+fn built_in_components(ty: BuiltInType) -> (usize, BuiltInType) {
+    use BuiltInType::*;
+
+    match ty {
+        F32 | I32 | U32 | Bool => (1, ty),
+        Vec2 => (2, F32),
+        IVec2 => (2, I32),
+        UVec2 => (2, U32),
+        BVec2 => (2, Bool),
+        Vec3 => (3, F32),
+        IVec3 => (3, I32),
+        UVec3 => (3, U32),
+        BVec3 => (3, Bool),
+        Vec4 => (4, F32),
+        IVec4 => (4, I32),
+        UVec4 => (4, U32),
+        BVec4 => (4, Bool),
+        Mat2 | Mat3 | Mat4 | Sampler(_) => panic!("`{ty}` has no scalar components"),
+    }
+}
+
+fn round_up(x: usize, align: usize) -> usize {
+    x.div_ceil(align) * align
+}
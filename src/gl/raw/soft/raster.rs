@@ -0,0 +1,1015 @@
+use std::{
+    cell::{Cell, RefMut},
+    collections::{btree_map::Entry, BTreeMap},
+    rc::Rc,
+};
+
+use crate::{
+    gl::raw::{
+        BlendEquation, BlendFunc, Blending, ClearColor, ClearValues, CullFace, DrawError,
+        DrawParams, Framebuffer, ImageInternalFormat, PrimitiveMode, Rect, StencilOp, VertexSpec,
+    },
+    sl::{
+        dag::Expr,
+        eval::{self, Constant, EvalError},
+        program_def::{InterpolationQualifier, ProgramDef},
+    },
+};
+
+use super::{input, sample, surface::is_normalized, Device, Surface};
+
+/// The surfaces that a draw call renders to.
+///
+/// The surfaces stay mutably borrowed for the whole draw call, so sampling
+/// from a texture that is also attached to the framebuffer panics.
+pub struct Targets<'a> {
+    colors: Vec<RefMut<'a, Surface>>,
+    depth_stencil: Option<RefMut<'a, Surface>>,
+    size: [u32; 2],
+    samples_passed: &'a Cell<u64>,
+}
+
+impl<'a> Targets<'a> {
+    pub fn new(device: &'a Device, framebuffer: &'a Framebuffer) -> Self {
+        let (colors, depth_stencil) = match framebuffer {
+            Framebuffer::Default => (
+                vec![device.default_color.borrow_mut()],
+                Some(device.default_depth_stencil.borrow_mut()),
+            ),
+            Framebuffer::Attachments { attachments } => {
+                let mut colors = Vec::new();
+                let mut depth_stencil = None;
+
+                for attachment in attachments {
+                    let surface = attachment.soft_surface_mut();
+
+                    if surface.format().is_color_renderable() {
+                        colors.push(surface);
+                    } else {
+                        depth_stencil = Some(surface);
+                    }
+                }
+
+                (colors, depth_stencil)
+            }
+        };
+
+        // Rendering is restricted to the area that all attachments cover.
+        let size = colors
+            .iter()
+            .chain(&depth_stencil)
+            .map(|surface| surface.size())
+            .reduce(|[x1, y1], [x2, y2]| [x1.min(x2), y1.min(y2)])
+            .unwrap_or([0, 0]);
+
+        Self {
+            colors,
+            depth_stencil,
+            size,
+            samples_passed: &device.samples_passed,
+        }
+    }
+
+    pub fn clear(&mut self, params: &DrawParams) {
+        let bounds = Bounds::new(self.size, params.scissor);
+
+        if let Some(clear_color) = params.clear_color {
+            for target in &mut self.colors {
+                bounds.for_each(|coords| {
+                    let color =
+                        mask_color(params.color_mask, target.get_linear(coords), clear_color);
+
+                    target.set_linear(coords, color);
+                });
+            }
+        }
+
+        if let Some(target) = &mut self.depth_stencil {
+            let clear_depth = params.clear_depth.filter(|_| params.depth_mask);
+            let clear_stencil = params.clear_stencil.filter(|_| has_stencil(target));
+
+            if clear_depth.is_none() && clear_stencil.is_none() {
+                return;
+            }
+
+            bounds.for_each(|coords| {
+                let [mut depth, mut stencil, ..] = target.get(coords);
+
+                if let Some(clear_depth) = clear_depth {
+                    depth = clear_depth;
+                }
+
+                if let Some(clear_stencil) = clear_stencil {
+                    stencil = write_stencil(
+                        params.stencil_mask_front,
+                        stencil as u32,
+                        clear_stencil.into(),
+                    ) as f32;
+                }
+
+                target.set(coords, [depth, stencil, 0.0, 0.0]);
+            });
+        }
+    }
+
+    /// Clears the targets to `values`, ignoring the scissor rectangle and the
+    /// write masks.
+    pub fn clear_to(&mut self, values: &ClearValues) {
+        let bounds = Bounds::new(self.size, None);
+
+        for (target, color) in self.colors.iter_mut().zip(&values.colors) {
+            let Some(color) = *color else {
+                continue;
+            };
+
+            bounds.for_each(|coords| match color {
+                ClearColor::F32(color) => target.set_linear(coords, color),
+                ClearColor::I32(color) => target.set(coords, color.map(|c| c as f32)),
+                ClearColor::U32(color) => target.set(coords, color.map(|c| c as f32)),
+            });
+        }
+
+        if let Some(target) = &mut self.depth_stencil {
+            let clear_stencil = values.stencil.filter(|_| has_stencil(target));
+
+            if values.depth.is_none() && clear_stencil.is_none() {
+                return;
+            }
+
+            bounds.for_each(|coords| {
+                let [mut depth, mut stencil, ..] = target.get(coords);
+
+                if let Some(clear_depth) = values.depth {
+                    depth = clear_depth;
+                }
+
+                if let Some(clear_stencil) = clear_stencil {
+                    stencil = clear_stencil.into();
+                }
+
+                target.set(coords, [depth, stencil, 0.0, 0.0]);
+            });
+        }
+    }
+}
+
+/// Runs the vertex and fragment shaders of `def` and rasterizes the resulting
+/// primitives into `targets`.
+pub fn draw(
+    def: &ProgramDef,
+    uniforms: &BTreeMap<String, Constant>,
+    textures: &dyn eval::Textures,
+    vertex_spec: &VertexSpec,
+    targets: &mut Targets,
+    params: &DrawParams,
+) -> Result<(), DrawError> {
+    let viewport = params.viewport.unwrap_or(Rect {
+        lower_left_corner: [0, 0],
+        size: targets.size,
+    });
+
+    let mut pipeline = Pipeline {
+        def,
+        uniforms,
+        textures,
+        params,
+        bounds: Bounds::new(targets.size, params.scissor),
+        viewport,
+        targets,
+        vertex_exprs: def
+            .vertex_shader_exprs
+            .iter()
+            .map(|(_, expr)| expr.clone())
+            .collect(),
+        fragment_exprs: def
+            .fragment_shader_exprs
+            .iter()
+            .map(|(_, expr)| expr.clone())
+            .collect(),
+    };
+
+    let vertex_ids = input::vertex_ids(vertex_spec);
+
+    for instance_id in 0..vertex_spec.num_instances as u32 {
+        let mut cache = BTreeMap::new();
+        let mut vertices = Vec::with_capacity(vertex_ids.len());
+
+        for &vertex_id in &vertex_ids {
+            let vertex = match cache.entry(vertex_id) {
+                Entry::Occupied(entry) => entry.into_mut(),
+                Entry::Vacant(entry) => {
+                    let vertex = pipeline.process_vertex(vertex_spec, vertex_id, instance_id)?;
+
+                    entry.insert(Rc::new(vertex))
+                }
+            };
+
+            vertices.push(vertex.clone());
+        }
+
+        pipeline.draw_primitives(vertex_spec.mode, &vertices)?;
+    }
+
+    Ok(())
+}
+
+/// A half-open rectangle of pixels.
+#[derive(Debug, Copy, Clone)]
+struct Bounds {
+    min: [u32; 2],
+    max: [u32; 2],
+}
+
+impl Bounds {
+    fn new(size: [u32; 2], scissor: Option<Rect>) -> Self {
+        let mut bounds = Self {
+            min: [0, 0],
+            max: size,
+        };
+
+        if let Some(scissor) = scissor {
+            let start = scissor.lower_left_corner;
+
+            bounds.min = [0, 1].map(|i| start[i].min(size[i]));
+            bounds.max = [0, 1].map(|i| start[i].saturating_add(scissor.size[i]).min(size[i]));
+        }
+
+        bounds
+    }
+
+    fn for_each(&self, mut f: impl FnMut([u32; 2])) {
+        for y in self.min[1]..self.max[1] {
+            for x in self.min[0]..self.max[0] {
+                f([x, y]);
+            }
+        }
+    }
+
+    /// Returns the range of pixels whose centers lie in `[start, end)` along
+    /// axis `i`.
+    fn pixels(&self, i: usize, start: f32, end: f32) -> std::ops::Range<u32> {
+        let clamp = |x: f32| {
+            (x - 0.5)
+                .ceil()
+                .clamp(self.min[i] as f32, self.max[i] as f32) as u32
+        };
+
+        clamp(start)..clamp(end)
+    }
+}
+
+struct Vertex {
+    position: [f32; 4],
+    varyings: Vec<Constant>,
+    point_size: f32,
+}
+
+/// A vertex after clipping, with its position in window coordinates.
+struct WindowVertex {
+    x: f32,
+    y: f32,
+    z: f32,
+    inv_w: f32,
+    varyings: Vec<Constant>,
+}
+
+struct Fragment {
+    coords: [u32; 2],
+    frag_coord: [f32; 4],
+    front_facing: bool,
+    point_coord: [f32; 2],
+    varyings: Vec<Constant>,
+}
+
+struct Pipeline<'a, 'b> {
+    def: &'a ProgramDef,
+    uniforms: &'a BTreeMap<String, Constant>,
+    textures: &'a dyn eval::Textures,
+    params: &'a DrawParams,
+    bounds: Bounds,
+    viewport: Rect,
+    targets: &'a mut Targets<'b>,
+    vertex_exprs: Vec<Rc<Expr>>,
+    fragment_exprs: Vec<Rc<Expr>>,
+}
+
+impl<'a, 'b> Pipeline<'a, 'b> {
+    fn process_vertex(
+        &self,
+        vertex_spec: &VertexSpec,
+        vertex_id: u32,
+        instance_id: u32,
+    ) -> Result<Vertex, DrawError> {
+        let mut args = self.uniforms.clone();
+        args.extend(input::vertex_input(vertex_spec, vertex_id, instance_id));
+
+        let mut outputs = eval::eval_with_args(&self.vertex_exprs, args, self.textures)?;
+
+        let point_size = self
+            .def
+            .vertex_shader_exprs
+            .iter()
+            .position(|(name, _)| name == "gl_PointSize")
+            .map_or(1.0, |i| f32::try_from(outputs[i].clone()).unwrap());
+        let num_varyings = self.def.varying_defs.len();
+        let varyings = outputs.drain(1..1 + num_varyings).collect();
+        let position = outputs.swap_remove(0).try_into().unwrap();
+
+        Ok(Vertex {
+            position,
+            varyings,
+            point_size,
+        })
+    }
+
+    fn draw_primitives(
+        &mut self,
+        mode: PrimitiveMode,
+        vertices: &[Rc<Vertex>],
+    ) -> Result<(), DrawError> {
+        use PrimitiveMode::*;
+
+        let n = vertices.len();
+        let v = |i: usize| &*vertices[i];
+
+        match mode {
+            Points => {
+                for vertex in vertices {
+                    self.draw_point(vertex)?;
+                }
+            }
+            Lines => {
+                for i in (0..n / 2).map(|i| 2 * i) {
+                    self.draw_line([v(i), v(i + 1)])?;
+                }
+            }
+            LineStrip | LineLoop => {
+                for i in 1..n {
+                    self.draw_line([v(i - 1), v(i)])?;
+                }
+
+                if mode == LineLoop && n > 2 {
+                    self.draw_line([v(n - 1), v(0)])?;
+                }
+            }
+            Triangles => {
+                for i in (0..n / 3).map(|i| 3 * i) {
+                    self.draw_triangle([v(i), v(i + 1), v(i + 2)])?;
+                }
+            }
+            TriangleStrip => {
+                for i in 2..n {
+                    // Every other triangle is flipped, so that all triangles
+                    // of the strip have the same winding.
+                    if i % 2 == 0 {
+                        self.draw_triangle([v(i - 2), v(i - 1), v(i)])?;
+                    } else {
+                        self.draw_triangle([v(i - 1), v(i - 2), v(i)])?;
+                    }
+                }
+            }
+            TriangleFan => {
+                for i in 2..n {
+                    self.draw_triangle([v(0), v(i - 1), v(i)])?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn draw_point(&mut self, vertex: &Vertex) -> Result<(), DrawError> {
+        let [x, y, z, w] = vertex.position;
+
+        // Points are discarded if their center lies outside of the clip volume.
+        if [x, y, z].iter().any(|c| c.abs() > w) {
+            return Ok(());
+        }
+
+        let center = self.to_window(vertex.position, vertex.varyings.clone());
+        let size = vertex.point_size.max(1.0);
+
+        for py in self
+            .bounds
+            .pixels(1, center.y - size / 2.0, center.y + size / 2.0)
+        {
+            for px in self
+                .bounds
+                .pixels(0, center.x - size / 2.0, center.x + size / 2.0)
+            {
+                let (fx, fy) = (px as f32 + 0.5, py as f32 + 0.5);
+
+                self.shade(Fragment {
+                    coords: [px, py],
+                    frag_coord: [fx, fy, center.z, center.inv_w],
+                    front_facing: true,
+                    point_coord: [0.5 + (fx - center.x) / size, 0.5 - (fy - center.y) / size],
+                    varyings: center.varyings.clone(),
+                })?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn draw_line(&mut self, vertices: [&Vertex; 2]) -> Result<(), DrawError> {
+        let varyings = self.flatten(&vertices);
+        let mut t = [0.0f32, 1.0f32];
+
+        // Clip the parametric segment against each plane of the clip volume.
+        for plane in CLIP_PLANES {
+            let d0 = plane(vertices[0].position);
+            let d1 = plane(vertices[1].position);
+
+            match (d0 < 0.0, d1 < 0.0) {
+                (true, true) => return Ok(()),
+                (true, false) => t[0] = t[0].max(d0 / (d0 - d1)),
+                (false, true) => t[1] = t[1].min(d0 / (d0 - d1)),
+                (false, false) => (),
+            }
+        }
+
+        if t[0] > t[1] {
+            return Ok(());
+        }
+
+        let [a, b] = t.map(|t| {
+            let position = lerp4(vertices[0].position, vertices[1].position, t);
+            let varyings = interpolate(&[&varyings[0], &varyings[1]], &[1.0 - t, t]);
+
+            self.to_window(position, varyings)
+        });
+
+        let (dx, dy) = (b.x - a.x, b.y - a.y);
+        let major = if dx.abs() >= dy.abs() { 0 } else { 1 };
+        let (start, end) = if major == 0 { (a.x, b.x) } else { (a.y, b.y) };
+        let delta = end - start;
+
+        if delta == 0.0 {
+            return Ok(());
+        }
+
+        for i in self.bounds.pixels(major, start.min(end), start.max(end)) {
+            let t = (i as f32 + 0.5 - start) / delta;
+            let minor = if major == 0 {
+                a.y + t * dy
+            } else {
+                a.x + t * dx
+            };
+            let minor = minor.floor();
+
+            if minor < self.bounds.min[1 - major] as f32
+                || minor >= self.bounds.max[1 - major] as f32
+            {
+                continue;
+            }
+
+            let coords = if major == 0 {
+                [i, minor as u32]
+            } else {
+                [minor as u32, i]
+            };
+            let weights = perspective_weights(&[1.0 - t, t], &[a.inv_w, b.inv_w]);
+
+            self.shade(Fragment {
+                coords,
+                frag_coord: [
+                    coords[0] as f32 + 0.5,
+                    coords[1] as f32 + 0.5,
+                    a.z + t * (b.z - a.z),
+                    a.inv_w + t * (b.inv_w - a.inv_w),
+                ],
+                front_facing: true,
+                point_coord: [0.0, 0.0],
+                varyings: interpolate(&[&a.varyings, &b.varyings], &weights),
+            })?;
+        }
+
+        Ok(())
+    }
+
+    fn draw_triangle(&mut self, vertices: [&Vertex; 3]) -> Result<(), DrawError> {
+        let varyings = self.flatten(&vertices);
+
+        let mut polygon: Vec<_> = vertices
+            .iter()
+            .zip(varyings)
+            .map(|(vertex, varyings)| (vertex.position, varyings))
+            .collect();
+
+        for plane in CLIP_PLANES {
+            polygon = clip_polygon(&polygon, plane);
+
+            if polygon.is_empty() {
+                return Ok(());
+            }
+        }
+
+        let polygon: Vec<_> = polygon
+            .into_iter()
+            .map(|(position, varyings)| self.to_window(position, varyings))
+            .collect();
+
+        // The facing is determined from the signed area in window coordinates,
+        // with counter-clockwise triangles being front-facing.
+        let area: f32 = (0..polygon.len())
+            .map(|i| {
+                let (a, b) = (&polygon[i], &polygon[(i + 1) % polygon.len()]);
+
+                a.x * b.y - b.x * a.y
+            })
+            .sum();
+        let front_facing = area > 0.0;
+
+        match self.params.cull_face {
+            Some(CullFace::Front) if front_facing => return Ok(()),
+            Some(CullFace::Back) if !front_facing => return Ok(()),
+            _ => (),
+        }
+
+        for i in 2..polygon.len() {
+            self.rasterize_triangle([&polygon[0], &polygon[i - 1], &polygon[i]], front_facing)?;
+        }
+
+        Ok(())
+    }
+
+    fn rasterize_triangle(
+        &mut self,
+        vertices: [&WindowVertex; 3],
+        front_facing: bool,
+    ) -> Result<(), DrawError> {
+        let [v0, mut v1, mut v2] = vertices;
+        let mut area = edge(v0, v1, [v2.x, v2.y]);
+
+        if area == 0.0 {
+            return Ok(());
+        }
+
+        // Rasterize in counter-clockwise order, so that the interior is to the
+        // left of every edge.
+        if area < 0.0 {
+            std::mem::swap(&mut v1, &mut v2);
+            area = -area;
+        }
+
+        let vertices = [v0, v1, v2];
+        let min =
+            |f: fn(&WindowVertex) -> f32| vertices.iter().map(|v| f(v)).fold(f32::MAX, f32::min);
+        let max =
+            |f: fn(&WindowVertex) -> f32| vertices.iter().map(|v| f(v)).fold(f32::MIN, f32::max);
+
+        let xs = self.bounds.pixels(0, min(|v| v.x), max(|v| v.x) + 1.0);
+        let ys = self.bounds.pixels(1, min(|v| v.y), max(|v| v.y) + 1.0);
+
+        let edges = [(v1, v2), (v2, v0), (v0, v1)];
+
+        for py in ys {
+            for px in xs.clone() {
+                let p = [px as f32 + 0.5, py as f32 + 0.5];
+                let mut lambda = [0.0; 3];
+                let mut inside = true;
+
+                for (i, (a, b)) in edges.iter().enumerate() {
+                    let e = edge(a, b, p);
+
+                    // Top-left rule: samples on an edge belong to the
+                    // triangle only for top or left edges.
+                    let (dx, dy) = (b.x - a.x, b.y - a.y);
+                    let top_left = (dy == 0.0 && dx < 0.0) || dy < 0.0;
+
+                    if e < 0.0 || (e == 0.0 && !top_left) {
+                        inside = false;
+                        break;
+                    }
+
+                    lambda[i] = e / area;
+                }
+
+                if !inside {
+                    continue;
+                }
+
+                let inv_w = vertices.map(|v| v.inv_w);
+                let weights = perspective_weights(&lambda, &inv_w);
+
+                self.shade(Fragment {
+                    coords: [px, py],
+                    frag_coord: [
+                        p[0],
+                        p[1],
+                        dot3(lambda, vertices.map(|v| v.z)),
+                        dot3(lambda, inv_w),
+                    ],
+                    front_facing,
+                    point_coord: [0.0, 0.0],
+                    varyings: interpolate(&vertices.map(|v| &v.varyings), &weights),
+                })?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns the varyings of `vertices`, with flat varyings taken from the
+    /// provoking vertex, which is the last one.
+    fn flatten(&self, vertices: &[&Vertex]) -> Vec<Vec<Constant>> {
+        let provoking = vertices.last().unwrap();
+
+        vertices
+            .iter()
+            .map(|vertex| {
+                self.def
+                    .varying_defs
+                    .iter()
+                    .enumerate()
+                    .map(|(i, varying_def)| match varying_def.interpolation {
+                        InterpolationQualifier::Smooth => vertex.varyings[i].clone(),
+                        InterpolationQualifier::Flat => provoking.varyings[i].clone(),
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    // OpenGL ES 3.0.6: 2.13.1 Controlling the Viewport
+    fn to_window(&self, [x, y, z, w]: [f32; 4], varyings: Vec<Constant>) -> WindowVertex {
+        let [vx, vy] = self.viewport.lower_left_corner.map(|x| x as f32);
+        let [vw, vh] = self.viewport.size.map(|x| x as f32);
+
+        WindowVertex {
+            x: vx + (x / w + 1.0) * vw / 2.0,
+            y: vy + (y / w + 1.0) * vh / 2.0,
+            z: (z / w + 1.0) / 2.0,
+            inv_w: 1.0 / w,
+            varyings,
+        }
+    }
+
+    fn shade(&mut self, fragment: Fragment) -> Result<(), DrawError> {
+        let mut args = self.uniforms.clone();
+
+        for (varying_def, value) in self.def.varying_defs.iter().zip(fragment.varyings) {
+            args.insert(varying_def.name.clone(), value);
+        }
+
+        args.insert("gl_FragCoord".into(), vec_constant(&fragment.frag_coord));
+        args.insert(
+            "gl_FrontFacing".into(),
+            Constant::Bool(fragment.front_facing),
+        );
+        args.insert("gl_PointCoord".into(), vec_constant(&fragment.point_coord));
+
+        let mut outputs = match eval::eval_with_args(&self.fragment_exprs, args, self.textures) {
+            Ok(outputs) => outputs,
+            Err(EvalError::Discarded) => return Ok(()),
+            Err(err) => return Err(err.into()),
+        };
+
+        let depth = match self.def.fragment_shader_exprs.last() {
+            Some((name, _)) if name == "gl_FragDepth" => {
+                f32::try_from(outputs.pop().unwrap()).unwrap()
+            }
+            _ => fragment.frag_coord[2],
+        };
+
+        if !self.test_depth_stencil(fragment.coords, depth, fragment.front_facing) {
+            return Ok(());
+        }
+
+        let samples_passed = self.targets.samples_passed;
+        samples_passed.set(samples_passed.get() + 1);
+
+        for (target, output) in self.targets.colors.iter_mut().zip(&outputs) {
+            let coords = fragment.coords;
+            let old = target.get_linear(coords);
+            let mut color = color(output);
+
+            // OpenGL ES 3.0.6: 4.1.7 Blending
+            // > Blending applies only if the color buffer has a fixed-point or
+            // > floating-point format.
+            if let Some(blending) = self
+                .params
+                .blending
+                .as_ref()
+                .filter(|_| !target.format().is_integer())
+            {
+                color = blend(blending, target.format(), color, old);
+            }
+
+            target.set_linear(coords, mask_color(self.params.color_mask, old, color));
+        }
+
+        Ok(())
+    }
+
+    /// Performs the stencil and depth tests, updating the depth-stencil target.
+    /// Returns whether the fragment passed.
+    fn test_depth_stencil(&mut self, coords: [u32; 2], depth: f32, front_facing: bool) -> bool {
+        let params = self.params;
+
+        let Some(target) = &mut self.targets.depth_stencil else {
+            return true;
+        };
+
+        let [old_depth, old_stencil, ..] = target.get(coords);
+        let depth = target.quantize([depth, 0.0, 0.0, 0.0])[0];
+        let stencil_enabled = has_stencil(target)
+            && (params.stencil_test_front.is_some() || params.stencil_test_back.is_some());
+
+        let (test, ops, mask) = if front_facing {
+            (
+                params.stencil_test_front,
+                params.stencil_ops_front,
+                params.stencil_mask_front,
+            )
+        } else {
+            (
+                params.stencil_test_back,
+                params.stencil_ops_back,
+                params.stencil_mask_back,
+            )
+        };
+        let test = test.unwrap_or_default();
+        let ops = ops.unwrap_or_default();
+        let reference = test.reference.clamp(0, 0xff) as u32;
+        let old_stencil = old_stencil as u32;
+
+        let update_stencil = |target: &mut Surface, op: StencilOp, depth: f32| {
+            let stencil = stencil_op(op, old_stencil, reference);
+            let stencil = write_stencil(mask, old_stencil, stencil);
+
+            target.set(coords, [depth, stencil as f32, 0.0, 0.0]);
+        };
+
+        if stencil_enabled {
+            let pass = sample::compare(
+                test.comparison,
+                reference & test.mask,
+                old_stencil & test.mask,
+            );
+
+            if !pass {
+                update_stencil(target, ops.stencil_fail, old_depth);
+
+                return false;
+            }
+        }
+
+        if let Some(comparison) = params.depth_test {
+            if !sample::compare(comparison, depth, old_depth) {
+                if stencil_enabled {
+                    update_stencil(target, ops.depth_fail, old_depth);
+                }
+
+                return false;
+            }
+        }
+
+        let new_depth = if params.depth_test.is_some() && params.depth_mask {
+            depth
+        } else {
+            old_depth
+        };
+
+        if stencil_enabled {
+            update_stencil(target, ops.depth_pass, new_depth);
+        } else if new_depth != old_depth {
+            target.set(coords, [new_depth, old_stencil as f32, 0.0, 0.0]);
+        }
+
+        true
+    }
+}
+
+type ClipPlane = fn([f32; 4]) -> f32;
+
+// The clip volume is `-w <= x, y, z <= w`. Each plane returns a distance that
+// is non-negative inside of the volume.
+const CLIP_PLANES: [ClipPlane; 6] = [
+    |[x, _, _, w]| w + x,
+    |[x, _, _, w]| w - x,
+    |[_, y, _, w]| w + y,
+    |[_, y, _, w]| w - y,
+    |[_, _, z, w]| w + z,
+    |[_, _, z, w]| w - z,
+];
+
+// Sutherland-Hodgman clipping of a convex polygon against a single plane.
+fn clip_polygon(
+    polygon: &[([f32; 4], Vec<Constant>)],
+    plane: ClipPlane,
+) -> Vec<([f32; 4], Vec<Constant>)> {
+    let mut result = Vec::new();
+
+    for i in 0..polygon.len() {
+        let (a, a_varyings) = &polygon[i];
+        let (b, b_varyings) = &polygon[(i + 1) % polygon.len()];
+        let (da, db) = (plane(*a), plane(*b));
+
+        if da >= 0.0 {
+            result.push((*a, a_varyings.clone()));
+        }
+
+        if (da >= 0.0) != (db >= 0.0) {
+            let t = da / (da - db);
+
+            result.push((
+                lerp4(*a, *b, t),
+                interpolate(&[a_varyings, b_varyings], &[1.0 - t, t]),
+            ));
+        }
+    }
+
+    result
+}
+
+fn edge(a: &WindowVertex, b: &WindowVertex, p: [f32; 2]) -> f32 {
+    (b.x - a.x) * (p[1] - a.y) - (b.y - a.y) * (p[0] - a.x)
+}
+
+fn lerp4(a: [f32; 4], b: [f32; 4], t: f32) -> [f32; 4] {
+    [0, 1, 2, 3].map(|i| a[i] + (b[i] - a[i]) * t)
+}
+
+fn dot3(a: [f32; 3], b: [f32; 3]) -> f32 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+// OpenGL ES 3.0.6: 3.6.1 Basic Polygon Rasterization
+fn perspective_weights(lambda: &[f32], inv_w: &[f32]) -> Vec<f32> {
+    let weights: Vec<_> = lambda.iter().zip(inv_w).map(|(l, w)| l * w).collect();
+    let sum: f32 = weights.iter().sum();
+
+    weights.into_iter().map(|w| w / sum).collect()
+}
+
+/// Interpolates the varyings of multiple vertices with the given weights.
+fn interpolate(varyings: &[&Vec<Constant>], weights: &[f32]) -> Vec<Constant> {
+    (0..varyings[0].len())
+        .map(|i| {
+            let values: Vec<_> = varyings.iter().map(|v| &v[i]).collect();
+
+            interpolate_value(&values, weights)
+        })
+        .collect()
+}
+
+fn interpolate_value(values: &[&Constant], weights: &[f32]) -> Constant {
+    let composite = |f: fn(Vec<Constant>) -> Constant, n: usize| {
+        f((0..n)
+            .map(|i| {
+                let values: Vec<_> = values
+                    .iter()
+                    .map(|value| match value {
+                        Constant::Vec(xs)
+                        | Constant::Mat(xs)
+                        | Constant::Struct(xs)
+                        | Constant::Array(xs) => &xs[i],
+                        _ => unreachable!(),
+                    })
+                    .collect();
+
+                interpolate_value(&values, weights)
+            })
+            .collect())
+    };
+
+    match values[0] {
+        Constant::F32(_) => Constant::F32(
+            values
+                .iter()
+                .zip(weights)
+                .map(|(value, weight)| f32::try_from((*value).clone()).unwrap() * weight)
+                .sum(),
+        ),
+        Constant::Vec(xs) => composite(Constant::Vec, xs.len()),
+        Constant::Mat(xs) => composite(Constant::Mat, xs.len()),
+        Constant::Struct(xs) => composite(Constant::Struct, xs.len()),
+        Constant::Array(xs) => composite(Constant::Array, xs.len()),
+        // Integer and boolean varyings are always flat.
+        value => value.clone(),
+    }
+}
+
+fn vec_constant(xs: &[f32]) -> Constant {
+    Constant::Vec(xs.iter().copied().map(Constant::F32).collect())
+}
+
+/// Converts a fragment shader output to RGBA.
+fn color(value: &Constant) -> [f32; 4] {
+    let scalar = |value: &Constant| match value {
+        Constant::F32(x) => *x,
+        Constant::I32(x) => *x as f32,
+        Constant::U32(x) => *x as f32,
+        Constant::Bool(x) => f32::from(u8::from(*x)),
+        _ => panic!("expected a scalar, got {value:?}"),
+    };
+
+    let mut result = [0.0, 0.0, 0.0, 1.0];
+
+    match value {
+        Constant::Vec(xs) => {
+            for (x, value) in result.iter_mut().zip(xs) {
+                *x = scalar(value);
+            }
+        }
+        value => result[0] = scalar(value),
+    }
+
+    result
+}
+
+fn mask_color(mask: [bool; 4], old: [f32; 4], new: [f32; 4]) -> [f32; 4] {
+    [0, 1, 2, 3].map(|i| if mask[i] { new[i] } else { old[i] })
+}
+
+// OpenGL ES 3.0.6: 4.1.7 Blending
+fn blend(
+    blending: &Blending,
+    format: ImageInternalFormat,
+    src: [f32; 4],
+    dst: [f32; 4],
+) -> [f32; 4] {
+    let clamp = |color: [f32; 4]| {
+        if is_normalized(format) {
+            color.map(|x| x.clamp(0.0, 1.0))
+        } else {
+            color
+        }
+    };
+
+    let src = clamp(src);
+    let constant = clamp(blending.constant_color);
+
+    let factor = |func: BlendFunc| -> [f32; 4] {
+        use BlendFunc::*;
+
+        match func {
+            Zero => [0.0; 4],
+            One => [1.0; 4],
+            SrcColor => src,
+            OneMinusSrcColor => src.map(|x| 1.0 - x),
+            DstColor => dst,
+            OneMinusDstColor => dst.map(|x| 1.0 - x),
+            SrcAlpha => [src[3]; 4],
+            OneMinusSrcAlpha => [1.0 - src[3]; 4],
+            DstAlpha => [dst[3]; 4],
+            OneMinusDstAlpha => [1.0 - dst[3]; 4],
+            ConstantColor => constant,
+            OneMinusConstantColor => constant.map(|x| 1.0 - x),
+            ConstantAlpha => [constant[3]; 4],
+            OneMinusConstantAlpha => [1.0 - constant[3]; 4],
+            SrcAlphaSaturate => {
+                let f = src[3].min(1.0 - dst[3]);
+
+                [f, f, f, 1.0]
+            }
+        }
+    };
+
+    let src_color = factor(blending.src_func_color);
+    let dst_color = factor(blending.dst_func_color);
+    let src_alpha = factor(blending.src_func_alpha);
+    let dst_alpha = factor(blending.dst_func_alpha);
+
+    [0, 1, 2, 3].map(|i| {
+        let (equation, s, d) = if i < 3 {
+            (blending.color_equation, src_color[i], dst_color[i])
+        } else {
+            (blending.alpha_equation, src_alpha[i], dst_alpha[i])
+        };
+
+        match equation {
+            BlendEquation::Add => src[i] * s + dst[i] * d,
+            BlendEquation::Subtract => src[i] * s - dst[i] * d,
+            BlendEquation::ReverseSubtract => dst[i] * d - src[i] * s,
+            BlendEquation::Min => src[i].min(dst[i]),
+            BlendEquation::Max => src[i].max(dst[i]),
+        }
+    })
+}
+
+fn has_stencil(surface: &Surface) -> bool {
+    surface.format() == ImageInternalFormat::DepthU24StencilU8
+}
+
+// OpenGL ES 3.0.6: 4.1.4 Stencil Test
+fn stencil_op(op: StencilOp, value: u32, reference: u32) -> u32 {
+    use StencilOp::*;
+
+    match op {
+        Keep => value,
+        Zero => 0,
+        Replace => reference,
+        Increment => (value + 1).min(0xff),
+        Decrement => value.saturating_sub(1),
+        Invert => !value & 0xff,
+        IncrementWrap => (value + 1) & 0xff,
+        DecrementWrap => value.wrapping_sub(1) & 0xff,
+    }
+}
+
+fn write_stencil(mask: u32, old: u32, new: u32) -> u32 {
+    ((old & !mask) | (new & mask)) & 0xff
+}
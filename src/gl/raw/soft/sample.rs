@@ -0,0 +1,585 @@
+use std::collections::BTreeMap;
+
+use crate::{
+    gl::raw::{
+        Comparison, ImageFormat, Sampler, Sampler2d, Sampler2dParams, Sampler3d, SamplerMagFilter,
+        SamplerMinFilter, SamplerWrap,
+    },
+    sl::{
+        dag::BuiltInType,
+        eval::{self, Constant, EvalError},
+        program_def::ProgramDef,
+    },
+};
+
+use super::Surface;
+
+type Fetch<'a> = &'a dyn Fn(&Surface, [u32; 2]) -> [f32; 4];
+
+/// The samplers of a draw call, keyed by the name of their uniform.
+pub struct Textures<'a> {
+    samplers: BTreeMap<&'a str, &'a Sampler>,
+}
+
+impl<'a> Textures<'a> {
+    pub fn new(def: &'a ProgramDef, samplers: &'a [Sampler]) -> Self {
+        let samplers = def
+            .uniform_sampler_defs
+            .iter()
+            .zip(samplers)
+            .map(|(sampler_def, sampler)| (sampler_def.name.as_str(), sampler))
+            .collect();
+
+        Self { samplers }
+    }
+}
+
+impl<'a> eval::Textures for Textures<'a> {
+    fn call(
+        &self,
+        sampler: &str,
+        name: &str,
+        args: &[Constant],
+        ty: BuiltInType,
+    ) -> Result<Constant, EvalError> {
+        let sampler = self
+            .samplers
+            .get(sampler)
+            .unwrap_or_else(|| panic!("sampler `{sampler}` is not bound"));
+
+        match sampler {
+            Sampler::Sampler2d(sampler) => call_2d(sampler, name, args, ty),
+            Sampler::Sampler3d(sampler) if sampler.texture.is_cube() => {
+                call_cube(sampler, name, args, ty)
+            }
+            Sampler::Sampler3d(sampler) => call_3d(sampler, name, args, ty),
+        }
+    }
+}
+
+fn call_2d(
+    sampler: &Sampler2d,
+    name: &str,
+    args: &[Constant],
+    ty: BuiltInType,
+) -> Result<Constant, EvalError> {
+    let levels = sampler.texture.soft().levels();
+
+    if name == "textureSize" {
+        let level = level_index(levels.len(), int(&args[0]));
+        let size = levels[level].size();
+
+        return Ok(Constant::Vec(vec![
+            Constant::I32(size[0] as i32),
+            Constant::I32(size[1] as i32),
+        ]));
+    }
+
+    if name == "texelFetch" {
+        let [x, y] = ivec2(&args[0]);
+        let level = int(&args[1]);
+
+        // Out-of-range fetches have undefined results, we return zero.
+        let texel = usize::try_from(level)
+            .ok()
+            .and_then(|level| levels.get(level))
+            .and_then(|surface| fetch_texel(surface, [x, y]))
+            .unwrap_or([0.0; 4]);
+
+        return Ok(to_result(texel, ty));
+    }
+
+    let (coords, lod, offset) = match (name, args) {
+        ("texture", [coords]) => (coords_vec(coords), 0.0, [0, 0]),
+        ("texture", [coords, bias]) => (coords_vec(coords), float(bias), [0, 0]),
+        ("textureLod", [coords, lod]) => (coords_vec(coords), float(lod), [0, 0]),
+        ("textureGrad", [coords, dpdx, dpdy]) => {
+            let size = levels[0].size();
+
+            (coords_vec(coords), grad_lod(&size, dpdx, dpdy), [0, 0])
+        }
+        ("textureOffset", [coords, offset]) => (coords_vec(coords), 0.0, ivec2(offset)),
+        ("textureProj", [coords]) => {
+            let mut coords = coords_vec(coords);
+            let q = coords.pop().unwrap();
+
+            (coords.into_iter().map(|x| x / q).collect(), 0.0, [0, 0])
+        }
+        _ => return Err(EvalError::UnsupportedBuiltIn(name.to_string())),
+    };
+
+    let uv = [coords[0], coords[1]];
+    let params = &sampler
+        .params
+        .for_internal_format(sampler.texture.internal_format());
+    let sample = |fetch: Fetch| {
+        filter(params, levels.len(), lod, |level, linear| {
+            filter_level(
+                &levels[level],
+                [params.wrap_s, params.wrap_t],
+                uv,
+                offset,
+                linear,
+                fetch,
+            )
+        })
+    };
+
+    match sampler.comparison {
+        Some(comparison) => {
+            let reference = coords[2].clamp(0.0, 1.0);
+            let value =
+                sample(&|surface, texel| compare_texel(comparison, reference, surface, texel));
+
+            Ok(Constant::F32(value[0]))
+        }
+        None => Ok(to_result(sample(&color_texel), ty)),
+    }
+}
+
+fn call_3d(
+    sampler: &Sampler3d,
+    name: &str,
+    args: &[Constant],
+    ty: BuiltInType,
+) -> Result<Constant, EvalError> {
+    let levels = sampler.texture.soft().levels();
+    let is_array = sampler.texture.is_array();
+
+    if name == "textureSize" {
+        let slices = &levels[level_index(levels.len(), int(&args[0]))];
+        let size = slices[0].size();
+
+        return Ok(Constant::Vec(vec![
+            Constant::I32(size[0] as i32),
+            Constant::I32(size[1] as i32),
+            Constant::I32(slices.len() as i32),
+        ]));
+    }
+
+    if name == "texelFetch" {
+        let [x, y, z] = ivec3(&args[0]);
+        let level = int(&args[1]);
+
+        // Out-of-range fetches have undefined results, we return zero.
+        let texel = usize::try_from(level)
+            .ok()
+            .and_then(|level| levels.get(level))
+            .zip(usize::try_from(z).ok())
+            .and_then(|(slices, z)| slices.get(z))
+            .and_then(|surface| fetch_texel(surface, [x, y]))
+            .unwrap_or([0.0; 4]);
+
+        return Ok(to_result(texel, ty));
+    }
+
+    let (coords, lod) = match (name, args) {
+        ("texture", [coords]) => (coords_vec(coords), 0.0),
+        ("texture", [coords, bias]) => (coords_vec(coords), float(bias)),
+        ("textureLod", [coords, lod]) => (coords_vec(coords), float(lod)),
+        ("textureGrad", [coords, dpdx, dpdy]) => {
+            let [width, height] = levels[0][0].size();
+            let depth = levels[0].len() as u32;
+
+            (
+                coords_vec(coords),
+                grad_lod(&[width, height, depth], dpdx, dpdy),
+            )
+        }
+        _ => return Err(EvalError::UnsupportedBuiltIn(name.to_string())),
+    };
+
+    let params = &sampler
+        .params
+        .for_internal_format(sampler.texture.internal_format());
+    let uv = [coords[0], coords[1]];
+    let wrap = [params.wrap_s, params.wrap_t];
+
+    // OpenGL ES 3.0.6: 3.8.10 Texture Minification
+    // The layer of array textures is the rounded third coordinate, clamped to
+    // the number of layers.
+    let layer = (coords[2] + 0.5)
+        .floor()
+        .clamp(0.0, (levels[0].len() - 1) as f32) as usize;
+
+    let sample = |fetch: Fetch| {
+        filter(params, levels.len(), lod, |level, linear| {
+            if is_array {
+                filter_level(&levels[level][layer], wrap, uv, [0, 0], linear, fetch)
+            } else {
+                let wrap = [params.wrap_s, params.wrap_t, params.wrap_r];
+                let uvw = [coords[0], coords[1], coords[2]];
+
+                filter_volume(&levels[level], wrap, uvw, linear, fetch)
+            }
+        })
+    };
+
+    match sampler.comparison {
+        Some(comparison) => {
+            let reference = coords[3].clamp(0.0, 1.0);
+            let value =
+                sample(&|surface, texel| compare_texel(comparison, reference, surface, texel));
+
+            Ok(Constant::F32(value[0]))
+        }
+        None => Ok(to_result(sample(&color_texel), ty)),
+    }
+}
+
+fn call_cube(
+    sampler: &Sampler3d,
+    name: &str,
+    args: &[Constant],
+    ty: BuiltInType,
+) -> Result<Constant, EvalError> {
+    let levels = sampler.texture.soft().levels();
+
+    if name == "textureSize" {
+        let size = levels[level_index(levels.len(), int(&args[0]))][0].size();
+
+        return Ok(Constant::Vec(vec![
+            Constant::I32(size[0] as i32),
+            Constant::I32(size[1] as i32),
+        ]));
+    }
+
+    let (coords, lod) = match (name, args) {
+        ("texture", [coords]) => (coords_vec(coords), 0.0),
+        ("texture", [coords, bias]) => (coords_vec(coords), float(bias)),
+        ("textureLod", [coords, lod]) => (coords_vec(coords), float(lod)),
+        _ => return Err(EvalError::UnsupportedBuiltIn(name.to_string())),
+    };
+
+    let params = &sampler
+        .params
+        .for_internal_format(sampler.texture.internal_format());
+    let direction = [coords[0], coords[1], coords[2]];
+    let sample = |fetch: Fetch| {
+        filter(params, levels.len(), lod, |level, linear| {
+            filter_cube(&levels[level], direction, linear, fetch)
+        })
+    };
+
+    match sampler.comparison {
+        Some(comparison) => {
+            let reference = coords[3].clamp(0.0, 1.0);
+            let value =
+                sample(&|surface, texel| compare_texel(comparison, reference, surface, texel));
+
+            Ok(Constant::F32(value[0]))
+        }
+        None => Ok(to_result(sample(&color_texel), ty)),
+    }
+}
+
+/// Returns whether `reference` passes the comparison against `value`.
+pub fn compare<T: PartialOrd>(comparison: Comparison, reference: T, value: T) -> bool {
+    use Comparison::*;
+
+    match comparison {
+        Always => true,
+        Equal => reference == value,
+        Greater => reference > value,
+        GreaterOrEqual => reference >= value,
+        Less => reference < value,
+        LessOrEqual => reference <= value,
+        Never => false,
+        NotEqual => reference != value,
+    }
+}
+
+// OpenGL ES 3.0.6: 3.8.10 Texture Minification
+fn filter(
+    params: &Sampler2dParams,
+    num_levels: usize,
+    lod: f32,
+    sample_level: impl Fn(usize, bool) -> [f32; 4],
+) -> [f32; 4] {
+    use SamplerMinFilter::*;
+
+    let max_level = (num_levels - 1) as f32;
+
+    if lod <= 0.0 {
+        return sample_level(0, params.mag_filter == SamplerMagFilter::Linear);
+    }
+
+    match params.min_filter {
+        Nearest => sample_level(0, false),
+        Linear => sample_level(0, true),
+        NearestMipmapNearest | LinearMipmapNearest => {
+            let level = ((lod + 0.5).ceil() - 1.0).clamp(0.0, max_level);
+
+            sample_level(level as usize, params.min_filter == LinearMipmapNearest)
+        }
+        NearestMipmapLinear | LinearMipmapLinear => {
+            let linear = params.min_filter == LinearMipmapLinear;
+            let lod = lod.min(max_level);
+            let level = lod.floor();
+
+            if level >= max_level {
+                return sample_level(level as usize, linear);
+            }
+
+            let a = sample_level(level as usize, linear);
+            let b = sample_level(level as usize + 1, linear);
+
+            lerp(a, b, lod.fract())
+        }
+    }
+}
+
+fn filter_level(
+    surface: &Surface,
+    wrap: [SamplerWrap; 2],
+    uv: [f32; 2],
+    offset: [i32; 2],
+    linear: bool,
+    fetch: Fetch,
+) -> [f32; 4] {
+    let size = surface.size();
+
+    filter_2d(size, uv, linear, |[x, y]| {
+        let x = wrap_coord(wrap[0], x + offset[0], size[0]);
+        let y = wrap_coord(wrap[1], y + offset[1], size[1]);
+
+        fetch(surface, [x, y])
+    })
+}
+
+/// Filters an image of the given size at `uv`, looking up texels with `texel`.
+fn filter_2d(
+    size: [u32; 2],
+    uv: [f32; 2],
+    linear: bool,
+    texel: impl Fn([i32; 2]) -> [f32; 4],
+) -> [f32; 4] {
+    let u = uv[0] * size[0] as f32;
+    let v = uv[1] * size[1] as f32;
+
+    if linear {
+        let (x, y) = (u - 0.5, v - 0.5);
+        let (x0, y0) = (x.floor() as i32, y.floor() as i32);
+        let (a, b) = (x - x.floor(), y - y.floor());
+
+        let bottom = lerp(texel([x0, y0]), texel([x0 + 1, y0]), a);
+        let top = lerp(texel([x0, y0 + 1]), texel([x0 + 1, y0 + 1]), a);
+
+        lerp(bottom, top, b)
+    } else {
+        texel([u.floor() as i32, v.floor() as i32])
+    }
+}
+
+/// Filters a three-dimensional mipmap level that consists of `slices`.
+fn filter_volume(
+    slices: &[Surface],
+    wrap: [SamplerWrap; 3],
+    uvw: [f32; 3],
+    linear: bool,
+    fetch: Fetch,
+) -> [f32; 4] {
+    let depth = slices.len() as u32;
+    let slice = |z: i32| {
+        let z = wrap_coord(wrap[2], z, depth) as usize;
+
+        filter_level(
+            &slices[z],
+            [wrap[0], wrap[1]],
+            [uvw[0], uvw[1]],
+            [0, 0],
+            linear,
+            fetch,
+        )
+    };
+
+    let w = uvw[2] * depth as f32;
+
+    if linear {
+        let z = w - 0.5;
+        let z0 = z.floor();
+
+        lerp(slice(z0 as i32), slice(z0 as i32 + 1), z - z0)
+    } else {
+        slice(w.floor() as i32)
+    }
+}
+
+/// Filters a mipmap level of a cube map, which consists of six `faces`, in
+/// `direction`.
+///
+/// Filtering is seamless: texels beyond the edge of a face are taken from the
+/// adjacent face, and the wrap modes are ignored.
+fn filter_cube(faces: &[Surface], direction: [f32; 3], linear: bool, fetch: Fetch) -> [f32; 4] {
+    let (face, uv) = cube_face(direction);
+    let size = faces[face].size();
+
+    filter_2d(size, uv, linear, |[x, y]| {
+        if (0..size[0] as i32).contains(&x) && (0..size[1] as i32).contains(&y) {
+            return fetch(&faces[face], [x as u32, y as u32]);
+        }
+
+        // Project the center of the texel onto the adjacent face.
+        let st = [(x, size[0]), (y, size[1])]
+            .map(|(n, size)| 2.0 * (n as f32 + 0.5) / size as f32 - 1.0);
+        let (face, uv) = cube_face(cube_direction(face, st));
+        let [x, y] = [0, 1].map(|i| ((uv[i] * size[i] as f32) as u32).min(size[i] - 1));
+
+        fetch(&faces[face], [x, y])
+    })
+}
+
+/// Returns the face that `direction` points at, and the texture coordinates on
+/// that face.
+fn cube_face([rx, ry, rz]: [f32; 3]) -> (usize, [f32; 2]) {
+    // OpenGL ES 3.0.6: 3.8.10 Cube Map Texture Selection, Table 3.21
+    let (face, sc, tc, ma) = if rx.abs() >= ry.abs() && rx.abs() >= rz.abs() {
+        if rx >= 0.0 {
+            (0, -rz, -ry, rx)
+        } else {
+            (1, rz, -ry, rx)
+        }
+    } else if ry.abs() >= rz.abs() {
+        if ry >= 0.0 {
+            (2, rx, rz, ry)
+        } else {
+            (3, rx, -rz, ry)
+        }
+    } else if rz >= 0.0 {
+        (4, rx, -ry, rz)
+    } else {
+        (5, -rx, -ry, rz)
+    };
+
+    let ma = ma.abs();
+
+    (face, [(sc / ma + 1.0) / 2.0, (tc / ma + 1.0) / 2.0])
+}
+
+/// Returns a direction that points at `[sc, tc]` in `[-1, 1]` on `face`.
+///
+/// This is the inverse of [`cube_face`].
+fn cube_direction(face: usize, [sc, tc]: [f32; 2]) -> [f32; 3] {
+    match face {
+        0 => [1.0, -tc, -sc],
+        1 => [-1.0, -tc, sc],
+        2 => [sc, 1.0, tc],
+        3 => [sc, -1.0, -tc],
+        4 => [sc, -tc, 1.0],
+        5 => [-sc, -tc, -1.0],
+        _ => panic!("cube maps have six faces"),
+    }
+}
+
+fn wrap_coord(wrap: SamplerWrap, x: i32, size: u32) -> u32 {
+    let size = size as i32;
+
+    let x = match wrap {
+        SamplerWrap::ClampToEdge => x.clamp(0, size - 1),
+        SamplerWrap::Repeat => x.rem_euclid(size),
+        SamplerWrap::MirroredRepeat => {
+            let x = x.rem_euclid(2 * size);
+
+            if x < size {
+                x
+            } else {
+                2 * size - 1 - x
+            }
+        }
+    };
+
+    x as u32
+}
+
+fn lerp(a: [f32; 4], b: [f32; 4], t: f32) -> [f32; 4] {
+    [0, 1, 2, 3].map(|i| a[i] + (b[i] - a[i]) * t)
+}
+
+/// Fills in the channels that are missing in the format of `surface`.
+fn expand(surface: &Surface, texel: [f32; 4]) -> [f32; 4] {
+    match surface.format().to_format() {
+        ImageFormat::Rgba | ImageFormat::RgbaInteger => texel,
+        ImageFormat::Rgb | ImageFormat::RgbInteger => [texel[0], texel[1], texel[2], 1.0],
+        ImageFormat::Rg | ImageFormat::RgInteger => [texel[0], texel[1], 0.0, 1.0],
+        ImageFormat::R
+        | ImageFormat::RedInteger
+        | ImageFormat::Depth
+        | ImageFormat::DepthStencil => [texel[0], 0.0, 0.0, 1.0],
+    }
+}
+
+fn to_result(texel: [f32; 4], ty: BuiltInType) -> Constant {
+    let component = |x: f32| match ty {
+        BuiltInType::IVec4 => Constant::I32(x as i32),
+        BuiltInType::UVec4 => Constant::U32(x as u32),
+        _ => Constant::F32(x),
+    };
+
+    Constant::Vec(texel.into_iter().map(component).collect())
+}
+
+fn level_index(num_levels: usize, level: i32) -> usize {
+    usize::try_from(level).unwrap_or(0).min(num_levels - 1)
+}
+
+fn color_texel(surface: &Surface, texel: [u32; 2]) -> [f32; 4] {
+    expand(surface, surface.get_linear(texel))
+}
+
+fn compare_texel(
+    comparison: Comparison,
+    reference: f32,
+    surface: &Surface,
+    texel: [u32; 2],
+) -> [f32; 4] {
+    let depth = surface.get(texel)[0];
+
+    [f32::from(u8::from(compare(comparison, reference, depth))); 4]
+}
+
+/// Returns the texel at `[x, y]` of `surface`, or `None` if it is out of
+/// range.
+fn fetch_texel(surface: &Surface, [x, y]: [i32; 2]) -> Option<[f32; 4]> {
+    let size = surface.size();
+
+    ((0..size[0] as i32).contains(&x) && (0..size[1] as i32).contains(&y))
+        .then(|| color_texel(surface, [x as u32, y as u32]))
+}
+
+/// Returns the level of detail for the derivatives `dpdx` and `dpdy` of
+/// texture coordinates, which are scaled by the base level `size`.
+fn grad_lod(size: &[u32], dpdx: &Constant, dpdy: &Constant) -> f32 {
+    let [dpdx, dpdy] = [dpdx, dpdy].map(|d| {
+        coords_vec(d)
+            .iter()
+            .zip(size)
+            .map(|(d, &n)| (d * n as f32).powi(2))
+            .sum::<f32>()
+            .sqrt()
+    });
+
+    dpdx.max(dpdy).log2()
+}
+
+fn coords_vec(value: &Constant) -> Vec<f32> {
+    match value {
+        Constant::Vec(components) => components.iter().map(float).collect(),
+        _ => panic!("expected texture coordinates, got {value:?}"),
+    }
+}
+
+fn float(value: &Constant) -> f32 {
+    f32::try_from(value.clone()).unwrap()
+}
+
+fn int(value: &Constant) -> i32 {
+    i32::try_from(value.clone()).unwrap()
+}
+
+fn ivec2(value: &Constant) -> [i32; 2] {
+    value.clone().try_into().unwrap()
+}
+
+fn ivec3(value: &Constant) -> [i32; 3] {
+    value.clone().try_into().unwrap()
+}
@@ -0,0 +1,445 @@
+use crate::{
+    gl::raw::{Image, ImageComponentType, ImageFormat, ImageInternalFormat, Rect},
+    sl::eval::{f16_to_f32, f32_to_f16},
+};
+
+/// A two-dimensional image that is stored on the CPU.
+///
+/// Every texel has four components. Color texels store their channels in
+/// order, leaving missing channels at zero. Depth texels store the depth in
+/// the first component and the stencil value in the second component.
+///
+/// Values are quantized to the precision of the internal format when they are
+/// written. Texels of sRGB formats are stored in their encoded form, and texels
+/// of integer formats are stored as integral values, which are exact up to
+/// `2^24`.
+#[derive(Debug, Clone)]
+pub struct Surface {
+    size: [u32; 2],
+    format: ImageInternalFormat,
+    texels: Vec<[f32; 4]>,
+}
+
+impl Surface {
+    pub fn new(size: [u32; 2], format: ImageInternalFormat) -> Self {
+        Self {
+            size,
+            format,
+            texels: vec![[0.0; 4]; size[0] as usize * size[1] as usize],
+        }
+    }
+
+    pub fn size(&self) -> [u32; 2] {
+        self.size
+    }
+
+    pub fn format(&self) -> ImageInternalFormat {
+        self.format
+    }
+
+    pub fn get(&self, [x, y]: [u32; 2]) -> [f32; 4] {
+        self.texels[self.index([x, y])]
+    }
+
+    pub fn set(&mut self, [x, y]: [u32; 2], texel: [f32; 4]) {
+        let index = self.index([x, y]);
+
+        self.texels[index] = quantize(self.format, texel);
+    }
+
+    /// Returns `texel` as it would be stored in this surface.
+    pub fn quantize(&self, texel: [f32; 4]) -> [f32; 4] {
+        quantize(self.format, texel)
+    }
+
+    /// Writes the tightly packed pixel data of `image` into the rectangle
+    /// starting at `lower_left_corner`.
+    pub fn write_image(&mut self, lower_left_corner: [u32; 2], image: &Image, data: &[u8]) {
+        assert_eq!(image.internal_format, self.format);
+        assert!(lower_left_corner[0] + image.size[0] <= self.size[0]);
+        assert!(lower_left_corner[1] + image.size[1] <= self.size[1]);
+
+        let num_components = image.internal_format.to_format().size();
+        let pixel_size = num_components * image.ty.size_of();
+        let normalized = !image.internal_format.is_integer();
+
+        for (i, pixel) in data.chunks_exact(pixel_size).enumerate() {
+            let x = lower_left_corner[0] + i as u32 % image.size[0];
+            let y = lower_left_corner[1] + i as u32 / image.size[0];
+
+            let mut texel = [0.0; 4];
+
+            if image.ty == ImageComponentType::U24U8 {
+                let value = u32::from_ne_bytes(pixel.try_into().unwrap());
+
+                texel[0] = (value >> 8) as f32 / 0xff_ffff as f32;
+                texel[1] = (value & 0xff) as f32;
+            } else {
+                let components = pixel.chunks_exact(image.ty.size_of());
+
+                for (x, bytes) in texel.iter_mut().zip(components) {
+                    *x = decode_component(image.ty, normalized, bytes);
+                }
+            }
+
+            self.set([x, y], texel);
+        }
+    }
+
+    /// Returns the next smaller mipmap level of this surface.
+    pub fn downsample(&self) -> Self {
+        let size = self.size.map(|n| (n / 2).max(1));
+        let mut result = Self::new(size, self.format);
+
+        for y in 0..size[1] {
+            for x in 0..size[0] {
+                let mut sum = [0.0; 4];
+                let mut count = 0.0;
+
+                for (dx, dy) in [(0, 0), (1, 0), (0, 1), (1, 1)] {
+                    let source = [2 * x + dx, 2 * y + dy];
+
+                    if source[0] < self.size[0] && source[1] < self.size[1] {
+                        let texel = self.get_linear(source);
+
+                        for (sum, x) in sum.iter_mut().zip(texel) {
+                            *sum += x;
+                        }
+
+                        count += 1.0;
+                    }
+                }
+
+                result.set_linear([x, y], sum.map(|x| x / count));
+            }
+        }
+
+        result
+    }
+
+    /// Returns a texel with sRGB color channels decoded to linear space.
+    pub fn get_linear(&self, [x, y]: [u32; 2]) -> [f32; 4] {
+        let texel = self.get([x, y]);
+
+        if is_srgb(self.format) {
+            [
+                srgb_to_linear(texel[0]),
+                srgb_to_linear(texel[1]),
+                srgb_to_linear(texel[2]),
+                texel[3],
+            ]
+        } else {
+            texel
+        }
+    }
+
+    /// Sets a texel given in linear space, encoding the color channels to
+    /// sRGB if needed.
+    pub fn set_linear(&mut self, [x, y]: [u32; 2], texel: [f32; 4]) {
+        let texel = if is_srgb(self.format) {
+            [
+                linear_to_srgb(texel[0]),
+                linear_to_srgb(texel[1]),
+                linear_to_srgb(texel[2]),
+                texel[3],
+            ]
+        } else {
+            texel
+        };
+
+        self.set([x, y], texel);
+    }
+
+    /// Copies `source_rect` of `source` into `destination_rect`, scaling the
+    /// pixels with nearest or linear filtering.
+    ///
+    /// Color channels are converted through linear space, and missing alpha
+    /// channels are filled with one. Linear filtering does not sample outside
+    /// of `source_rect`.
+    pub fn blit(
+        &mut self,
+        source: &Surface,
+        source_rect: Rect,
+        destination_rect: Rect,
+        linear: bool,
+    ) {
+        assert!(source_rect.lower_left_corner[0] + source_rect.size[0] <= source.size[0]);
+        assert!(source_rect.lower_left_corner[1] + source_rect.size[1] <= source.size[1]);
+        assert!(destination_rect.lower_left_corner[0] + destination_rect.size[0] <= self.size[0]);
+        assert!(destination_rect.lower_left_corner[1] + destination_rect.size[1] <= self.size[1]);
+
+        if source_rect.size.contains(&0) || destination_rect.size.contains(&0) {
+            return;
+        }
+
+        let is_color = !matches!(
+            self.format.to_format(),
+            ImageFormat::Depth | ImageFormat::DepthStencil
+        );
+        let fill_alpha = is_color && source.format.to_format().size() < 4;
+
+        let texel = |x: f32, y: f32| {
+            let [x, y] = [0, 1].map(|i| {
+                let min = source_rect.lower_left_corner[i];
+                let max = min + source_rect.size[i] - 1;
+
+                ([x, y][i].floor().max(0.0) as u32).clamp(min, max)
+            });
+
+            let mut texel = if is_color {
+                source.get_linear([x, y])
+            } else {
+                source.get([x, y])
+            };
+
+            if fill_alpha {
+                texel[3] = 1.0;
+            }
+
+            texel
+        };
+        let lerp =
+            |a: [f32; 4], b: [f32; 4], t: f32| [0, 1, 2, 3].map(|i| a[i] + (b[i] - a[i]) * t);
+
+        for y in 0..destination_rect.size[1] {
+            for x in 0..destination_rect.size[0] {
+                // The center of the destination pixel in source coordinates.
+                let u = source_rect.lower_left_corner[0] as f32
+                    + (x as f32 + 0.5) * source_rect.size[0] as f32
+                        / destination_rect.size[0] as f32;
+                let v = source_rect.lower_left_corner[1] as f32
+                    + (y as f32 + 0.5) * source_rect.size[1] as f32
+                        / destination_rect.size[1] as f32;
+
+                let value = if linear {
+                    let (x, y) = (u - 0.5, v - 0.5);
+                    let (a, b) = (x - x.floor(), y - y.floor());
+
+                    let bottom = lerp(texel(x, y), texel(x + 1.0, y), a);
+                    let top = lerp(texel(x, y + 1.0), texel(x + 1.0, y + 1.0), a);
+
+                    lerp(bottom, top, b)
+                } else {
+                    texel(u, v)
+                };
+
+                let coords = [
+                    destination_rect.lower_left_corner[0] + x,
+                    destination_rect.lower_left_corner[1] + y,
+                ];
+
+                if is_color {
+                    self.set_linear(coords, value);
+                } else {
+                    self.set(coords, value);
+                }
+            }
+        }
+    }
+
+    /// Reads the pixels of `rect` into `data`, tightly packed with the given
+    /// format and component type.
+    ///
+    /// As in OpenGL, missing color channels are read as zero, except for
+    /// alpha, which is read as one.
+    pub fn read_pixels(
+        &self,
+        rect: Rect,
+        format: ImageFormat,
+        ty: ImageComponentType,
+        data: &mut [u8],
+    ) {
+        assert!(rect.lower_left_corner[0] + rect.size[0] <= self.size[0]);
+        assert!(rect.lower_left_corner[1] + rect.size[1] <= self.size[1]);
+
+        let rows = (0..rect.size[1]).map(|y| y + rect.lower_left_corner[1]);
+        let texels =
+            rows.flat_map(|y| (0..rect.size[0]).map(move |x| [x + rect.lower_left_corner[0], y]));
+        let pixel_size = format.size() * ty.size_of();
+        let is_color = !matches!(format, ImageFormat::Depth | ImageFormat::DepthStencil);
+        let normalized = !self.format.is_integer();
+
+        for (pixel, coords) in data.chunks_exact_mut(pixel_size).zip(texels) {
+            let mut texel = self.get(coords);
+
+            if is_color && self.format.to_format().size() < 4 {
+                texel[3] = 1.0;
+            }
+
+            for (bytes, value) in pixel.chunks_exact_mut(ty.size_of()).zip(texel) {
+                encode_component(ty, normalized, value, bytes);
+            }
+        }
+    }
+
+    fn index(&self, [x, y]: [u32; 2]) -> usize {
+        assert!(x < self.size[0] && y < self.size[1]);
+
+        y as usize * self.size[0] as usize + x as usize
+    }
+}
+
+pub fn is_srgb(format: ImageInternalFormat) -> bool {
+    use ImageInternalFormat::*;
+
+    matches!(format, RgbaU8Gamma | RgbU8Gamma)
+}
+
+/// Returns whether the color channels of `format` are clamped to `[0, 1]` or
+/// `[-1, 1]`.
+pub fn is_normalized(format: ImageInternalFormat) -> bool {
+    use ImageInternalFormat::*;
+
+    matches!(
+        format,
+        RgbaU8
+            | RgbaU8Gamma
+            | RgbaI8Snorm
+            | RgbU8
+            | RgbU8Gamma
+            | RgbI8Snorm
+            | RgU8
+            | RgI8Snorm
+            | RU8
+            | RI8Snorm
+    )
+}
+
+pub fn srgb_to_linear(x: f32) -> f32 {
+    if x <= 0.04045 {
+        x / 12.92
+    } else {
+        ((x + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+pub fn linear_to_srgb(x: f32) -> f32 {
+    if x <= 0.0031308 {
+        x * 12.92
+    } else {
+        1.055 * x.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+/// Decodes a component of pixel data. Integer components are converted to
+/// normalized fixed-point values if `normalized` is true.
+fn decode_component(ty: ImageComponentType, normalized: bool, bytes: &[u8]) -> f32 {
+    use ImageComponentType::*;
+
+    let (value, max) = match ty {
+        U8 => (f64::from(bytes[0]), f64::from(u8::MAX)),
+        I8 => (f64::from(bytes[0] as i8), f64::from(i8::MAX)),
+        U16 => (
+            f64::from(u16::from_ne_bytes(bytes.try_into().unwrap())),
+            f64::from(u16::MAX),
+        ),
+        I16 => (
+            f64::from(i16::from_ne_bytes(bytes.try_into().unwrap())),
+            f64::from(i16::MAX),
+        ),
+        U32 => (
+            f64::from(u32::from_ne_bytes(bytes.try_into().unwrap())),
+            f64::from(u32::MAX),
+        ),
+        I32 => (
+            f64::from(i32::from_ne_bytes(bytes.try_into().unwrap())),
+            f64::from(i32::MAX),
+        ),
+        F16 => return f16_to_f32(u16::from_ne_bytes(bytes.try_into().unwrap())),
+        F32 => return f32::from_ne_bytes(bytes.try_into().unwrap()),
+        U24U8 => unreachable!(),
+    };
+
+    if normalized {
+        (value / max).max(-1.0) as f32
+    } else {
+        value as f32
+    }
+}
+
+/// Encodes a component of pixel data. Integer components are converted from
+/// normalized fixed-point values if `normalized` is true.
+fn encode_component(ty: ImageComponentType, normalized: bool, value: f32, bytes: &mut [u8]) {
+    use ImageComponentType::*;
+
+    let value = f64::from(value);
+    let integer = |min: f64, max: f64| {
+        if normalized {
+            (value.clamp(min.max(-1.0), 1.0) * max).round()
+        } else {
+            value.round().clamp(min, max)
+        }
+    };
+
+    match ty {
+        U8 => bytes[0] = integer(0.0, u8::MAX.into()) as u8,
+        I8 => bytes[0] = integer(i8::MIN.into(), i8::MAX.into()) as i8 as u8,
+        U16 => bytes.copy_from_slice(&(integer(0.0, u16::MAX.into()) as u16).to_ne_bytes()),
+        I16 => {
+            bytes.copy_from_slice(&(integer(i16::MIN.into(), i16::MAX.into()) as i16).to_ne_bytes())
+        }
+        U32 => bytes.copy_from_slice(&(integer(0.0, u32::MAX.into()) as u32).to_ne_bytes()),
+        I32 => {
+            bytes.copy_from_slice(&(integer(i32::MIN.into(), i32::MAX.into()) as i32).to_ne_bytes())
+        }
+        F16 => bytes.copy_from_slice(&f32_to_f16(value as f32).to_ne_bytes()),
+        F32 => bytes.copy_from_slice(&(value as f32).to_ne_bytes()),
+        U24U8 => unreachable!(),
+    }
+}
+
+fn quantize(format: ImageInternalFormat, texel: [f32; 4]) -> [f32; 4] {
+    use ImageInternalFormat::*;
+
+    let num_components = format.to_format().size();
+    let unorm = |bits: i32| {
+        let max = ((1u32 << bits) - 1) as f32;
+
+        move |x: f32| (x.clamp(0.0, 1.0) * max).round() / max
+    };
+    let snorm = |x: f32| (x.clamp(-1.0, 1.0) * 127.0).round() / 127.0;
+    let half = |x: f32| f16_to_f32(f32_to_f16(x));
+    let uint = |bits: i32| {
+        let max = ((1u64 << bits) - 1) as f32;
+
+        move |x: f32| x.round().clamp(0.0, max)
+    };
+    let sint = |bits: i32| {
+        let max = ((1u64 << (bits - 1)) - 1) as f32;
+
+        move |x: f32| x.round().clamp(-max - 1.0, max)
+    };
+
+    match format {
+        DepthU16 => [unorm(16)(texel[0]), 0.0, 0.0, 0.0],
+        DepthF32 => [texel[0].clamp(0.0, 1.0), 0.0, 0.0, 0.0],
+        DepthU24StencilU8 => [
+            unorm(24)(texel[0]),
+            texel[1].clamp(0.0, 255.0).round(),
+            0.0,
+            0.0,
+        ],
+        _ => {
+            let f: &dyn Fn(f32) -> f32 = match format {
+                RgbaU8 | RgbaU8Gamma | RgbU8 | RgbU8Gamma | RgU8 | RU8 => &unorm(8),
+                RgbaI8Snorm | RgbI8Snorm | RgI8Snorm | RI8Snorm => &snorm,
+                RgbaF16 | RgbF16 | RgF16 | RF16 => &half,
+                RgbaU8Int | RgbU8Int | RgU8Int | RU8Int => &uint(8),
+                RgbaI8Int | RgbI8Int | RgI8Int | RI8Int => &sint(8),
+                RgbaU16Int | RgbU16Int | RgU16Int | RU16Int => &uint(16),
+                RgbaI16Int | RgbI16Int | RgI16Int | RI16Int => &sint(16),
+                RgbaU32Int | RgbU32Int | RgU32Int | RU32Int => &uint(32),
+                RgbaI32Int | RgbI32Int | RgI32Int | RI32Int => &sint(32),
+                _ => &|x| x,
+            };
+
+            let mut result = [0.0; 4];
+
+            for (x, value) in result.iter_mut().zip(texel).take(num_components) {
+                *x = f(value);
+            }
+
+            result
+        }
+    }
+}
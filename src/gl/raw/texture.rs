@@ -1,26 +1,61 @@
 use std::{cell::Cell, rc::Rc};
 
-use glow::HasContext;
-
-use crate::gl::{raw::error::check_gl_error, TextureError};
+use crate::gl::{raw::error::check_gl_error, FramebufferError, TextureError};
 
 use super::{
-    context::ContextShared, sampler_params::set_comparison, Caps, Comparison, Image,
-    ImageInternalFormat, Sampler2dParams,
+    context::{Backend, ContextShared},
+    gl_api::GlApi,
+    sampler_params::set_comparison,
+    soft, Buffer, Caps, Comparison, Framebuffer, Image, ImageComponentType, ImageFormat,
+    ImageInternalFormat, Rect, Sampler2dParams,
 };
 
+pub(super) enum Storage<T, Id = glow::Texture> {
+    Gl(Id),
+    Soft(T),
+}
+
 pub struct Texture2d {
     ctx: Rc<ContextShared>,
-    id: glow::Texture,
+    storage: Storage<soft::Texture>,
     size: [u32; 2],
     internal_format: ImageInternalFormat,
     levels: usize,
     params: Cell<Sampler2dParams>,
 }
 
+/// A texture with three-dimensional storage.
+///
+/// This is either a two-dimensional array texture, whose layers are sampled
+/// separately, a cube map, whose six layers are its faces, or a
+/// three-dimensional texture.
+pub struct Texture3d {
+    ctx: Rc<ContextShared>,
+    storage: Storage<soft::Texture3d>,
+    target: u32,
+    size: [u32; 3],
+    internal_format: ImageInternalFormat,
+    levels: usize,
+    params: Cell<Sampler2dParams>,
+}
+
+/// A face of a cube map.
+///
+/// The faces are ordered like the layers that they are stored in.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum CubeFace {
+    PositiveX,
+    NegativeX,
+    PositiveY,
+    NegativeY,
+    PositiveZ,
+    NegativeZ,
+}
+
 #[derive(Clone)]
 pub enum Sampler {
     Sampler2d(Sampler2d),
+    Sampler3d(Sampler3d),
 }
 
 #[derive(Clone)]
@@ -30,6 +65,18 @@ pub struct Sampler2d {
     pub comparison: Option<Comparison>,
 }
 
+#[derive(Clone)]
+pub struct Sampler3d {
+    pub texture: Rc<Texture3d>,
+    pub params: Sampler2dParams,
+    pub comparison: Option<Comparison>,
+}
+
+pub(super) enum PixelDestination<'a> {
+    Slice(&'a mut [u8]),
+    Buffer { buffer: &'a Buffer, offset: usize },
+}
+
 struct ImageData<'a> {
     image: &'a Image<'a>,
     buffer: Vec<u8>,
@@ -82,8 +129,31 @@ impl Texture2d {
             .try_into()
             .expect("max_texture_size is out of i32 range");
 
-        let mut data = ImageData::new(&image);
-        let slice = data.as_slice()?;
+        // Mipmap levels that `image` does not provide data for are left empty
+        // here.
+        let images: Vec<_> = image.levels().collect();
+        let mut data: Vec<_> = images.iter().map(ImageData::new).collect();
+        let slices = data
+            .iter_mut()
+            .map(ImageData::as_slice)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        if let Backend::Soft(_) = ctx.backend() {
+            let texture = soft::Texture::new(&image, slices[0], levels as usize);
+
+            for (level, (image, slice)) in images.iter().zip(&slices).enumerate().skip(1) {
+                texture.set(level, [0, 0], image, slice);
+            }
+
+            return Ok(Texture2d {
+                ctx: ctx.clone(),
+                storage: Storage::Soft(texture),
+                size: image.size,
+                internal_format: image.internal_format,
+                levels: levels as usize,
+                params: Default::default(),
+            });
+        }
 
         let gl = ctx.gl();
 
@@ -112,24 +182,18 @@ impl Texture2d {
                 height,
             )
         };
-        unsafe {
-            gl.tex_sub_image_2d(
-                glow::TEXTURE_2D,
-                0,
-                0,
-                0,
-                width,
-                height,
-                image.internal_format.to_format().to_gl(),
-                image.ty.to_gl(),
-                glow::PixelUnpackData::Slice(slice),
-            )
-        };
+
+        for (level, (image, slice)) in images.iter().zip(slices).enumerate() {
+            let level = level.try_into().unwrap();
+
+            upload_slice(gl, glow::TEXTURE_2D, level, [0, 0, 0], image, slice);
+        }
+
         unsafe { gl.bind_texture(glow::TEXTURE_2D, None) };
 
         let texture = Texture2d {
             ctx: ctx.clone(),
-            id,
+            storage: Storage::Gl(id),
             size: image.size,
             internal_format: image.internal_format,
             levels: levels as usize,
@@ -145,8 +209,11 @@ impl Texture2d {
 
     pub(super) fn new(ctx: Rc<ContextShared>, image: Image) -> Result<Self, TextureError> {
         validate_size(image.size, ctx.caps())?;
+        validate_format(image.internal_format, ctx.caps())?;
 
-        Self::new_with_levels(ctx, image, 1)
+        let levels = 1 + image.mipmap_data.len();
+
+        Self::new_with_levels(ctx, image, levels)
     }
 
     pub(super) fn new_with_mipmap(
@@ -154,6 +221,8 @@ impl Texture2d {
         image: Image,
     ) -> Result<Self, TextureError> {
         validate_size(image.size, ctx.caps())?;
+        validate_format(image.internal_format, ctx.caps())?;
+        validate_mipmap(image.internal_format)?;
 
         // OpenGL ES 3.0.6: 3.8.4 Immutable-Format Texture Images
         // > An INVALID_OPERATION error is generated if `levels` is greater than
@@ -161,27 +230,58 @@ impl Texture2d {
         let levels = (image.size[0].max(image.size[1]) as f64).log2() as usize + 1;
 
         let texture = Self::new_with_levels(ctx.clone(), image, levels)?;
-        let gl = ctx.gl();
 
-        unsafe {
-            gl.bind_texture(glow::TEXTURE_2D, Some(texture.id));
-            gl.generate_mipmap(glow::TEXTURE_2D);
-            gl.bind_texture(glow::TEXTURE_2D, None);
-        }
+        match &texture.storage {
+            Storage::Gl(id) => {
+                let gl = ctx.gl();
 
-        check_gl_error(gl, "after new texture with mipmaps").map_err(TextureError::Unexpected)?;
+                unsafe {
+                    gl.bind_texture(glow::TEXTURE_2D, Some(*id));
+                    gl.generate_mipmap(glow::TEXTURE_2D);
+                    gl.bind_texture(glow::TEXTURE_2D, None);
+                }
+
+                check_gl_error(gl, "after new texture with mipmaps")
+                    .map_err(TextureError::Unexpected)?;
+            }
+            Storage::Soft(texture) => texture.generate_mipmap(),
+        }
 
         Ok(texture)
     }
 
+    /// # Panics
+    ///
+    /// Panics if this texture belongs to a software context.
     pub(super) fn id(&self) -> glow::Texture {
-        self.id
+        match &self.storage {
+            Storage::Gl(id) => *id,
+            Storage::Soft(_) => panic!("expected an OpenGL texture"),
+        }
+    }
+
+    pub(super) fn context(&self) -> &ContextShared {
+        &self.ctx
+    }
+
+    /// # Panics
+    ///
+    /// Panics if this texture belongs to an OpenGL context.
+    pub(super) fn soft(&self) -> &soft::Texture {
+        match &self.storage {
+            Storage::Gl(_) => panic!("expected a software texture"),
+            Storage::Soft(texture) => texture,
+        }
     }
 
     pub fn size(&self) -> [u32; 2] {
         self.size
     }
 
+    pub fn levels(&self) -> usize {
+        self.levels
+    }
+
     pub fn internal_format(&self) -> ImageInternalFormat {
         self.internal_format
     }
@@ -198,28 +298,23 @@ impl Texture2d {
         let mut data = ImageData::new(&image);
         let slice = data.as_slice()?;
 
+        let id = match &self.storage {
+            Storage::Gl(id) => *id,
+            Storage::Soft(texture) => {
+                texture.set(level, lower_left_corner, &image, slice);
+
+                return Ok(());
+            }
+        };
+
         let gl = self.ctx.gl();
 
         let level = level.try_into().unwrap();
         let x = lower_left_corner[0].try_into().unwrap();
         let y = lower_left_corner[1].try_into().unwrap();
-        let width = image.size[0].try_into().unwrap();
-        let height = image.size[1].try_into().unwrap();
 
-        unsafe { gl.bind_texture(glow::TEXTURE_2D, Some(self.id)) };
-        unsafe {
-            gl.tex_sub_image_2d(
-                glow::TEXTURE_2D,
-                level,
-                x,
-                y,
-                width,
-                height,
-                image.internal_format.to_format().to_gl(),
-                image.ty.to_gl(),
-                glow::PixelUnpackData::Slice(slice),
-            )
-        };
+        unsafe { gl.bind_texture(glow::TEXTURE_2D, Some(id)) };
+        upload_slice(gl, glow::TEXTURE_2D, level, [x, y, 0], &image, slice);
         unsafe { gl.bind_texture(glow::TEXTURE_2D, None) };
 
         // This might be triggered if `rect` is outside of the texture image
@@ -229,11 +324,129 @@ impl Texture2d {
         Ok(())
     }
 
+    /// Copies `rect` of the first color image of `framebuffer` into mipmap
+    /// level `level`, starting at `lower_left_corner`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the framebuffer has no color image, if `level` does not
+    /// exist, if a rectangle is not contained in its image, or if the
+    /// framebuffer belongs to a different context.
+    pub fn copy_from(
+        &self,
+        level: usize,
+        lower_left_corner: [u32; 2],
+        framebuffer: &Framebuffer,
+        rect: Rect,
+    ) -> Result<(), FramebufferError> {
+        framebuffer.copy_into_texture_2d(rect, self, level, lower_left_corner)
+    }
+
+    /// Reads the pixels of `rect` in mipmap level `level` into `data`, tightly
+    /// packed with the given format and component type.
+    ///
+    /// Rows are written from bottom to top.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `level` does not exist, if `rect` is not contained in the
+    /// level, or if `data` does not have the size of `rect`.
+    pub fn read_pixels(
+        &self,
+        level: usize,
+        rect: Rect,
+        format: ImageFormat,
+        ty: ImageComponentType,
+        data: &mut [u8],
+    ) -> Result<(), TextureError> {
+        assert_eq!(data.len(), pixels_size(rect, format, ty));
+
+        self.read_pixels_impl(level, rect, format, ty, PixelDestination::Slice(data))
+    }
+
+    /// Starts reading the pixels of `rect` in mipmap level `level` into
+    /// `buffer`, starting at byte `offset`.
+    ///
+    /// With OpenGL, this returns without waiting for the GPU. The pixels are
+    /// packed as in [`Self::read_pixels`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `level` does not exist, if `rect` is not contained in the
+    /// level, if the pixels do not fit into `buffer`, or if `buffer` belongs to
+    /// a different context.
+    pub fn read_pixels_to_buffer(
+        &self,
+        level: usize,
+        rect: Rect,
+        format: ImageFormat,
+        ty: ImageComponentType,
+        buffer: &Buffer,
+        offset: usize,
+    ) -> Result<(), TextureError> {
+        assert!(offset + pixels_size(rect, format, ty) <= buffer.len());
+        assert!(self.ctx.ref_eq(buffer.context()));
+
+        self.read_pixels_impl(
+            level,
+            rect,
+            format,
+            ty,
+            PixelDestination::Buffer { buffer, offset },
+        )
+    }
+
+    fn read_pixels_impl(
+        &self,
+        level: usize,
+        rect: Rect,
+        format: ImageFormat,
+        ty: ImageComponentType,
+        destination: PixelDestination,
+    ) -> Result<(), TextureError> {
+        assert!(level < self.levels);
+
+        let level_size = self.size.map(|n| (n >> level).max(1));
+
+        assert!(rect.lower_left_corner[0] + rect.size[0] <= level_size[0]);
+        assert!(rect.lower_left_corner[1] + rect.size[1] <= level_size[1]);
+
+        let levels;
+        let source = match &self.storage {
+            Storage::Gl(id) => Storage::Gl(*id),
+            Storage::Soft(texture) => {
+                levels = texture.levels();
+                Storage::Soft(&levels[level])
+            }
+        };
+
+        let level = level.try_into().unwrap();
+
+        read_pixels(
+            &self.ctx,
+            self.internal_format,
+            source,
+            |attachment, id| unsafe {
+                self.ctx.gl().framebuffer_texture_2d(
+                    glow::FRAMEBUFFER,
+                    attachment,
+                    glow::TEXTURE_2D,
+                    id,
+                    level,
+                )
+            },
+            rect,
+            format,
+            ty,
+            destination,
+        )
+    }
+
     pub(super) fn set_params(&self, new: Sampler2dParams, comparison: Option<Comparison>) {
-        let gl = &self.ctx.gl();
+        let gl = self.ctx.gl();
 
         let current = self.params.get();
-        new.set_delta(gl, &current);
+        new.set_delta(gl, glow::TEXTURE_2D, &current);
         self.params.set(new);
 
         // FIXME: Check that comparison can be applied to the texture.
@@ -246,20 +459,468 @@ impl Texture2d {
 
 impl Drop for Texture2d {
     fn drop(&mut self) {
-        let gl = self.ctx.gl();
+        if let Storage::Gl(id) = &self.storage {
+            let gl = self.ctx.gl();
+
+            unsafe {
+                gl.delete_texture(*id);
+            }
+        }
+    }
+}
+
+impl Texture3d {
+    fn new_with_levels(
+        ctx: Rc<ContextShared>,
+        target: u32,
+        images: &[Image],
+        levels: usize,
+    ) -> Result<Self, TextureError> {
+        // OpenGL ES 3.0.6: 3.8.4 Immutable-Format Texture Images
+        assert!(levels > 0);
+
+        let size = [images[0].size[0], images[0].size[1], images.len() as u32];
+        let internal_format = images[0].internal_format;
+
+        let levels = levels.try_into().expect("levels is out of i32 range");
+        let [width, height, depth] =
+            size.map(|n| i32::try_from(n).expect("max_3d_texture_size is out of i32 range"));
+
+        // The levels of each slice, in the order in which they are uploaded.
+        let level_images: Vec<_> = images
+            .iter()
+            .enumerate()
+            .flat_map(|(z, image)| {
+                image
+                    .levels()
+                    .enumerate()
+                    .map(move |(level, image)| (z, level, image))
+            })
+            .collect();
+        let mut data: Vec<_> = level_images
+            .iter()
+            .map(|(_, _, image)| ImageData::new(image))
+            .collect();
+        let slices = data
+            .iter_mut()
+            .map(ImageData::as_slice)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        if let Backend::Soft(_) = ctx.backend() {
+            let layered = target != glow::TEXTURE_3D;
+            let base_slices: Vec<_> = level_images
+                .iter()
+                .zip(&slices)
+                .filter(|((_, level, _), _)| *level == 0)
+                .map(|(_, slice)| *slice)
+                .collect();
+            let texture = soft::Texture3d::new(images, &base_slices, levels as usize, layered);
 
+            for ((z, level, image), slice) in level_images.iter().zip(&slices) {
+                if *level > 0 {
+                    texture.set(*level, [0, 0, *z as u32], image, slice);
+                }
+            }
+
+            return Ok(Texture3d {
+                ctx: ctx.clone(),
+                storage: Storage::Soft(texture),
+                target,
+                size,
+                internal_format,
+                levels: levels as usize,
+                params: Default::default(),
+            });
+        }
+
+        let gl = ctx.gl();
+
+        // See the comment in `Texture2d::new_with_levels`.
         unsafe {
-            gl.delete_texture(self.id);
+            gl.pixel_store_i32(glow::UNPACK_ALIGNMENT, 1);
+        }
+
+        let id = unsafe { gl.create_texture() }.map_err(TextureError::ObjectCreation)?;
+
+        unsafe { gl.bind_texture(target, Some(id)) };
+
+        // Cube maps have two-dimensional storage, which consists of six faces.
+        if target == glow::TEXTURE_CUBE_MAP {
+            unsafe { gl.tex_storage_2d(target, levels, internal_format.to_gl(), width, height) };
+        } else {
+            unsafe {
+                gl.tex_storage_3d(
+                    target,
+                    levels,
+                    internal_format.to_gl(),
+                    width,
+                    height,
+                    depth,
+                )
+            };
+        }
+
+        // Each image is uploaded as a separate slice, so that we do not need to
+        // concatenate them first.
+        for ((z, level, image), slice) in level_images.iter().zip(slices) {
+            let level = (*level).try_into().unwrap();
+            let z = (*z).try_into().unwrap();
+
+            upload_slice(gl, target, level, [0, 0, z], image, slice);
+        }
+
+        unsafe { gl.bind_texture(target, None) };
+
+        let texture = Texture3d {
+            ctx: ctx.clone(),
+            storage: Storage::Gl(id),
+            target,
+            size,
+            internal_format,
+            levels: levels as usize,
+            params: Default::default(),
+        };
+
+        // Check for errors *after* passing ownership of the texture to
+        // `shared` so that it will be cleaned up if there is an error.
+        check_gl_error(gl, "after new 3D texture").map_err(TextureError::Unexpected)?;
+
+        Ok(texture)
+    }
+
+    pub(super) fn new(
+        ctx: Rc<ContextShared>,
+        target: u32,
+        images: &[Image],
+    ) -> Result<Self, TextureError> {
+        validate_images(target, images, ctx.caps())?;
+
+        let levels = 1 + images[0].mipmap_data.len();
+
+        Self::new_with_levels(ctx, target, images, levels)
+    }
+
+    pub(super) fn new_with_mipmap(
+        ctx: Rc<ContextShared>,
+        target: u32,
+        images: &[Image],
+    ) -> Result<Self, TextureError> {
+        let size = validate_images(target, images, ctx.caps())?;
+        validate_mipmap(images[0].internal_format)?;
+
+        // The layers of array textures and the faces of cube maps do not
+        // shrink in smaller levels.
+        let max_size = if target == glow::TEXTURE_3D {
+            size[0].max(size[1]).max(size[2])
+        } else {
+            size[0].max(size[1])
+        };
+        let levels = (max_size as f64).log2() as usize + 1;
+
+        let texture = Self::new_with_levels(ctx.clone(), target, images, levels)?;
+
+        match &texture.storage {
+            Storage::Gl(id) => {
+                let gl = ctx.gl();
+
+                unsafe {
+                    gl.bind_texture(target, Some(*id));
+                    gl.generate_mipmap(target);
+                    gl.bind_texture(target, None);
+                }
+
+                check_gl_error(gl, "after new 3D texture with mipmaps")
+                    .map_err(TextureError::Unexpected)?;
+            }
+            Storage::Soft(texture) => texture.generate_mipmap(),
+        }
+
+        Ok(texture)
+    }
+
+    /// # Panics
+    ///
+    /// Panics if this texture belongs to a software context.
+    pub(super) fn id(&self) -> glow::Texture {
+        match &self.storage {
+            Storage::Gl(id) => *id,
+            Storage::Soft(_) => panic!("expected an OpenGL texture"),
+        }
+    }
+
+    pub(super) fn context(&self) -> &ContextShared {
+        &self.ctx
+    }
+
+    /// # Panics
+    ///
+    /// Panics if this texture belongs to an OpenGL context.
+    pub(super) fn soft(&self) -> &soft::Texture3d {
+        match &self.storage {
+            Storage::Gl(_) => panic!("expected a software texture"),
+            Storage::Soft(texture) => texture,
+        }
+    }
+
+    /// Returns whether this is a two-dimensional array texture.
+    pub fn is_array(&self) -> bool {
+        self.target == glow::TEXTURE_2D_ARRAY
+    }
+
+    /// Returns whether this is a cube map.
+    pub fn is_cube(&self) -> bool {
+        self.target == glow::TEXTURE_CUBE_MAP
+    }
+
+    /// Returns the width, the height, and the number of layers or the depth
+    /// of the texture. Cube maps have six layers.
+    pub fn size(&self) -> [u32; 3] {
+        self.size
+    }
+
+    pub fn internal_format(&self) -> ImageInternalFormat {
+        self.internal_format
+    }
+
+    pub fn levels(&self) -> usize {
+        self.levels
+    }
+
+    /// Writes `image` into the slice `offset[2]` of mipmap level `level`,
+    /// starting at `[offset[0], offset[1]]`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `level` or the slice does not exist, or if the format of
+    /// `image` does not match the texture.
+    pub fn set(&self, level: usize, offset: [u32; 3], image: Image) -> Result<(), TextureError> {
+        assert!(level < self.levels);
+        assert_eq!(self.internal_format, image.internal_format);
+
+        assert!(offset[2] < self.level_depth(level));
+
+        let mut data = ImageData::new(&image);
+        let slice = data.as_slice()?;
+
+        let id = match &self.storage {
+            Storage::Gl(id) => *id,
+            Storage::Soft(texture) => {
+                texture.set(level, offset, &image, slice);
+
+                return Ok(());
+            }
+        };
+
+        let gl = self.ctx.gl();
+
+        let level = level.try_into().unwrap();
+        let offset = offset.map(|n| n.try_into().unwrap());
+
+        unsafe { gl.bind_texture(self.target, Some(id)) };
+        upload_slice(gl, self.target, level, offset, &image, slice);
+        unsafe { gl.bind_texture(self.target, None) };
+
+        // This might be triggered if `image` is outside of the texture image
+        // bounds.
+        check_gl_error(gl, "after 3D texture set").map_err(TextureError::Unexpected)?;
+
+        Ok(())
+    }
+
+    /// Reads the pixels of `rect` in layer `layer` of mipmap level `level`
+    /// into `data`.
+    ///
+    /// See [`Texture2d::read_pixels`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `level` or `layer` does not exist, if `rect` is not contained
+    /// in the level, or if `data` does not have the size of `rect`.
+    pub fn read_pixels(
+        &self,
+        level: usize,
+        layer: u32,
+        rect: Rect,
+        format: ImageFormat,
+        ty: ImageComponentType,
+        data: &mut [u8],
+    ) -> Result<(), TextureError> {
+        assert_eq!(data.len(), pixels_size(rect, format, ty));
+
+        self.read_pixels_impl(
+            level,
+            layer,
+            rect,
+            format,
+            ty,
+            PixelDestination::Slice(data),
+        )
+    }
+
+    /// Starts reading the pixels of `rect` in layer `layer` of mipmap level
+    /// `level` into `buffer`, starting at byte `offset`.
+    ///
+    /// See [`Texture2d::read_pixels_to_buffer`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `level` or `layer` does not exist, if `rect` is not contained
+    /// in the level, if the pixels do not fit into `buffer`, or if `buffer`
+    /// belongs to a different context.
+    #[allow(clippy::too_many_arguments)]
+    pub fn read_pixels_to_buffer(
+        &self,
+        level: usize,
+        layer: u32,
+        rect: Rect,
+        format: ImageFormat,
+        ty: ImageComponentType,
+        buffer: &Buffer,
+        offset: usize,
+    ) -> Result<(), TextureError> {
+        assert!(offset + pixels_size(rect, format, ty) <= buffer.len());
+        assert!(self.ctx.ref_eq(buffer.context()));
+
+        self.read_pixels_impl(
+            level,
+            layer,
+            rect,
+            format,
+            ty,
+            PixelDestination::Buffer { buffer, offset },
+        )
+    }
+
+    fn read_pixels_impl(
+        &self,
+        level: usize,
+        layer: u32,
+        rect: Rect,
+        format: ImageFormat,
+        ty: ImageComponentType,
+        destination: PixelDestination,
+    ) -> Result<(), TextureError> {
+        assert!(level < self.levels);
+        assert!(layer < self.level_depth(level));
+
+        let level_size = [self.size[0], self.size[1]].map(|n| (n >> level).max(1));
+
+        assert!(rect.lower_left_corner[0] + rect.size[0] <= level_size[0]);
+        assert!(rect.lower_left_corner[1] + rect.size[1] <= level_size[1]);
+
+        let levels;
+        let source = match &self.storage {
+            Storage::Gl(id) => Storage::Gl(*id),
+            Storage::Soft(texture) => {
+                levels = texture.levels();
+                Storage::Soft(&levels[level][layer as usize])
+            }
+        };
+
+        let level = level.try_into().unwrap();
+
+        read_pixels(
+            &self.ctx,
+            self.internal_format,
+            source,
+            |attachment, id| self.framebuffer_texture(attachment, id, level, layer),
+            rect,
+            format,
+            ty,
+            destination,
+        )
+    }
+
+    /// Attaches layer `layer` of mipmap level `level` to `attachment` of the
+    /// bound framebuffer, or detaches it if `texture` is `None`.
+    ///
+    /// The layers of cube maps are attached as faces.
+    pub(super) fn framebuffer_texture(
+        &self,
+        attachment: u32,
+        texture: Option<glow::Texture>,
+        level: i32,
+        layer: u32,
+    ) {
+        let gl = self.ctx.gl();
+
+        if self.is_cube() {
+            unsafe {
+                gl.framebuffer_texture_2d(
+                    glow::FRAMEBUFFER,
+                    attachment,
+                    glow::TEXTURE_CUBE_MAP_POSITIVE_X + layer,
+                    texture,
+                    level,
+                )
+            };
+        } else {
+            let layer = layer.try_into().expect("layer is out of i32 range");
+
+            unsafe {
+                gl.framebuffer_texture_layer(glow::FRAMEBUFFER, attachment, texture, level, layer)
+            };
+        }
+    }
+
+    /// Returns the number of slices in mipmap level `level`.
+    pub(super) fn level_depth(&self, level: usize) -> u32 {
+        if self.target == glow::TEXTURE_3D {
+            (self.size[2] >> level).max(1)
+        } else {
+            self.size[2]
+        }
+    }
+
+    pub(super) fn set_params(&self, new: Sampler2dParams, comparison: Option<Comparison>) {
+        let gl = self.ctx.gl();
+
+        let current = self.params.get();
+        new.set_delta(gl, self.target, &current);
+        self.params.set(new);
+
+        set_comparison(gl, self.target, comparison);
+
+        #[cfg(debug_assertions)]
+        check_gl_error(gl, "after texture params").unwrap();
+    }
+}
+
+impl Drop for Texture3d {
+    fn drop(&mut self) {
+        if let Storage::Gl(id) = &self.storage {
+            let gl = self.ctx.gl();
+
+            unsafe {
+                gl.delete_texture(*id);
+            }
         }
     }
 }
 
+impl CubeFace {
+    pub const ALL: [CubeFace; 6] = [
+        CubeFace::PositiveX,
+        CubeFace::NegativeX,
+        CubeFace::PositiveY,
+        CubeFace::NegativeY,
+        CubeFace::PositiveZ,
+        CubeFace::NegativeZ,
+    ];
+
+    /// Returns the layer that the face is stored in.
+    pub const fn layer(self) -> u32 {
+        self as u32
+    }
+}
+
 impl Sampler {
     pub(super) fn context(&self) -> &ContextShared {
         use Sampler::*;
 
         match self {
             Sampler2d(texture) => &texture.texture.ctx,
+            Sampler3d(texture) => &texture.texture.ctx,
         }
     }
 
@@ -271,12 +932,26 @@ impl Sampler {
                 comparison,
             }) => {
                 let gl = texture.ctx.gl();
-                let id = texture.id;
+                let id = texture.id();
 
                 unsafe {
                     gl.bind_texture(glow::TEXTURE_2D, Some(id));
                 }
 
+                texture.set_params(*params, *comparison);
+            }
+            Sampler::Sampler3d(Sampler3d {
+                texture,
+                params,
+                comparison,
+            }) => {
+                let gl = texture.ctx.gl();
+                let id = texture.id();
+
+                unsafe {
+                    gl.bind_texture(texture.target, Some(id));
+                }
+
                 texture.set_params(*params, *comparison);
             }
         }
@@ -293,10 +968,246 @@ impl Sampler {
                     gl.bind_texture(glow::TEXTURE_2D, None);
                 }
             }
+            Sampler3d(sampler) => {
+                let gl = sampler.texture.ctx.gl();
+
+                unsafe {
+                    gl.bind_texture(sampler.texture.target, None);
+                }
+            }
+        }
+    }
+}
+
+/// Reads pixels from `source`, which is a mipmap level of a texture, a layer of
+/// such a level, or a renderbuffer.
+///
+/// `attach` attaches the source to the bound framebuffer, or detaches it if it
+/// is given `None`.
+#[allow(clippy::too_many_arguments)]
+pub(super) fn read_pixels<Id>(
+    ctx: &ContextShared,
+    internal_format: ImageInternalFormat,
+    source: Storage<&soft::Surface, Id>,
+    attach: impl Fn(u32, Option<Id>),
+    rect: Rect,
+    format: ImageFormat,
+    ty: ImageComponentType,
+    destination: PixelDestination,
+) -> Result<(), TextureError> {
+    if !internal_format.is_readable_as(format, ty) {
+        return Err(TextureError::UnsupportedRead {
+            internal_format,
+            format,
+            ty,
+        });
+    }
+
+    let id = match source {
+        Storage::Gl(id) => id,
+        Storage::Soft(surface) => {
+            match destination {
+                PixelDestination::Slice(data) => surface.read_pixels(rect, format, ty, data),
+                PixelDestination::Buffer { buffer, offset } => {
+                    let size = pixels_size(rect, format, ty);
+                    let mut data = buffer.soft_data_mut();
+
+                    surface.read_pixels(rect, format, ty, &mut data[offset..offset + size]);
+                }
+            }
+
+            return Ok(());
+        }
+    };
+
+    let gl = ctx.gl();
+
+    let attachment = match format {
+        ImageFormat::Depth if internal_format.is_stencil_renderable() => {
+            glow::DEPTH_STENCIL_ATTACHMENT
+        }
+        ImageFormat::Depth => glow::DEPTH_ATTACHMENT,
+        _ => glow::COLOR_ATTACHMENT0,
+    };
+
+    let x = rect.lower_left_corner[0].try_into().unwrap();
+    let y = rect.lower_left_corner[1].try_into().unwrap();
+    let width = rect.size[0].try_into().unwrap();
+    let height = rect.size[1].try_into().unwrap();
+
+    // Pixels are read by temporarily attaching the source to the context's
+    // framebuffer object.
+    unsafe { gl.bind_framebuffer(glow::FRAMEBUFFER, Some(ctx.draw_fbo())) };
+    attach(attachment, Some(id));
+
+    if attachment == glow::COLOR_ATTACHMENT0 {
+        unsafe { gl.read_buffer(glow::COLOR_ATTACHMENT0) };
+    }
+
+    let (pixels, pack_buffer) = match destination {
+        PixelDestination::Slice(data) => (glow::PixelPackData::Slice(data), None),
+        PixelDestination::Buffer { buffer, offset } => (
+            glow::PixelPackData::BufferOffset(offset.try_into().unwrap()),
+            Some(buffer.id()),
+        ),
+    };
+
+    if pack_buffer.is_some() {
+        unsafe { gl.bind_buffer(glow::PIXEL_PACK_BUFFER, pack_buffer) };
+    }
+
+    // See the comment on `UNPACK_ALIGNMENT` in `Texture2d::new_with_levels`.
+    unsafe {
+        gl.pixel_store_i32(glow::PACK_ALIGNMENT, 1);
+        gl.read_pixels(x, y, width, height, format.to_gl(), ty.to_gl(), pixels);
+    }
+
+    if pack_buffer.is_some() {
+        unsafe { gl.bind_buffer(glow::PIXEL_PACK_BUFFER, None) };
+    }
+
+    attach(attachment, None);
+    unsafe { gl.bind_framebuffer(glow::FRAMEBUFFER, None) };
+
+    // Reading depth is not supported by OpenGL ES, so this may fail.
+    check_gl_error(gl, "after reading pixels").map_err(TextureError::Unexpected)?;
+
+    Ok(())
+}
+
+/// Uploads `image` into the slice `offset[2]` of the bound texture.
+///
+/// The slices of cube maps are their faces, which are uploaded separately.
+/// Two-dimensional textures have only the slice zero.
+fn upload_slice(
+    gl: &dyn GlApi,
+    target: u32,
+    level: i32,
+    [x, y, z]: [i32; 3],
+    image: &Image,
+    data: &[u8],
+) {
+    let width = image.size[0].try_into().unwrap();
+    let height = image.size[1].try_into().unwrap();
+    let target_2d = match target {
+        glow::TEXTURE_2D => {
+            assert_eq!(z, 0);
+
+            Some(target)
+        }
+        glow::TEXTURE_CUBE_MAP => {
+            Some(glow::TEXTURE_CUBE_MAP_POSITIVE_X + u32::try_from(z).unwrap())
+        }
+        _ => None,
+    };
+
+    // Compressed images have no pixel format and component type, since their
+    // data is given in blocks.
+    if image.internal_format.is_compressed() {
+        let format = image.internal_format.to_gl();
+        let pixels = glow::CompressedPixelUnpackData::Slice(data);
+
+        if let Some(target) = target_2d {
+            unsafe {
+                gl.compressed_tex_sub_image_2d(target, level, x, y, width, height, format, pixels)
+            };
+        } else {
+            unsafe {
+                gl.compressed_tex_sub_image_3d(
+                    target, level, x, y, z, width, height, 1, format, pixels,
+                )
+            };
         }
+
+        return;
+    }
+
+    let format = image.internal_format.to_format().to_gl();
+    let ty = image.ty.to_gl();
+    let pixels = glow::PixelUnpackData::Slice(data);
+
+    if let Some(target) = target_2d {
+        unsafe { gl.tex_sub_image_2d(target, level, x, y, width, height, format, ty, pixels) };
+    } else {
+        unsafe {
+            gl.tex_sub_image_3d(target, level, x, y, z, width, height, 1, format, ty, pixels)
+        };
     }
 }
 
+pub(super) fn pixels_size(rect: Rect, format: ImageFormat, ty: ImageComponentType) -> usize {
+    rect.size[0] as usize * rect.size[1] as usize * format.size() * ty.size_of()
+}
+
+/// Checks that `images` can be the slices of a texture with the given target,
+/// and returns the size of the texture.
+///
+/// # Panics
+///
+/// Panics if the images differ in size, format, or number of mipmap levels, if
+/// the faces of a cube map are not six squares, or if the slices of a
+/// three-dimensional texture have mipmap data.
+fn validate_images(target: u32, images: &[Image], caps: &Caps) -> Result<[u32; 3], TextureError> {
+    let Some(first) = images.first() else {
+        return Err(TextureError::Empty);
+    };
+
+    for image in images {
+        assert_eq!(image.size, first.size);
+        assert_eq!(image.internal_format, first.internal_format);
+        assert_eq!(image.mipmap_data.len(), first.mipmap_data.len());
+    }
+
+    validate_format(first.internal_format, caps)?;
+
+    // The slices of three-dimensional textures are not kept in smaller mipmap
+    // levels, so they can not provide their own mipmap data. None of the
+    // compressed formats support three-dimensional textures either.
+    if target == glow::TEXTURE_3D {
+        assert!(first.mipmap_data.is_empty());
+
+        if first.internal_format.is_compressed() {
+            return Err(TextureError::UnsupportedFormat(first.internal_format));
+        }
+    }
+
+    let size = [first.size[0], first.size[1], images.len() as u32];
+    let max = match target {
+        glow::TEXTURE_3D => [caps.max_3d_texture_size; 3],
+        glow::TEXTURE_CUBE_MAP => {
+            // OpenGL ES 3.0.6: 3.8.4 Immutable-Format Texture Images
+            // > If `target` is `TEXTURE_CUBE_MAP`, `width` and `height` must
+            // > be equal.
+            assert_eq!(images.len(), 6);
+            assert_eq!(first.size[0], first.size[1]);
+
+            [
+                caps.max_cube_map_texture_size,
+                caps.max_cube_map_texture_size,
+                6,
+            ]
+        }
+        _ => [
+            caps.max_texture_size,
+            caps.max_texture_size,
+            caps.max_array_texture_layers,
+        ],
+    };
+
+    // OpenGL ES 3.0.6: 3.8.4 Immutable-Format Texture Images
+    if size.contains(&0) {
+        return Err(TextureError::Empty);
+    }
+
+    for (requested, max) in size.into_iter().zip(max) {
+        if requested > max {
+            return Err(TextureError::Oversized { requested, max });
+        }
+    }
+
+    Ok(size)
+}
+
 fn validate_size(size: [u32; 2], caps: &Caps) -> Result<(), TextureError> {
     // OpenGL ES 3.0.6: 3.8.4 Immutable-Format Texture Images
     // > If [...] `width`, `height` [...] is less than 1, the error
@@ -321,3 +1232,24 @@ fn validate_size(size: [u32; 2], caps: &Caps) -> Result<(), TextureError> {
 
     Ok(())
 }
+
+fn validate_mipmap(internal_format: ImageInternalFormat) -> Result<(), TextureError> {
+    // OpenGL ES 3.0.6: 3.8.10 Texture Minification
+    // > If the levelbase array was not specified with an unsized internal
+    // > format from table 3.3 or a sized internal format that is both
+    // > color-renderable and texture-filterable according to table 3.13, an
+    // > INVALID_OPERATION error is generated.
+    if internal_format.is_integer() || internal_format.is_compressed() {
+        return Err(TextureError::MipmapUnsupported(internal_format));
+    }
+
+    Ok(())
+}
+
+fn validate_format(internal_format: ImageInternalFormat, caps: &Caps) -> Result<(), TextureError> {
+    if !caps.supports_format(internal_format) {
+        return Err(TextureError::UnsupportedFormat(internal_format));
+    }
+
+    Ok(())
+}
@@ -1,7 +1,5 @@
 use std::{ops::Range, rc::Rc};
 
-use glow::HasContext;
-
 use crate::{
     gl::raw::{
         error::check_gl_error,
@@ -0,0 +1,71 @@
+use std::{marker::PhantomData, rc::Rc};
+
+use crate::sl::{self, ColorSample};
+
+use super::{raw, ColorAttachment, DepthAttachment};
+
+/// A color image that can be rendered to, but not sampled.
+///
+/// Multisampled renderbuffers are resolved into a single-sampled texture with
+/// [`ColorAttachment::resolve_into`].
+pub struct ColorRenderbuffer<S = sl::Vec4> {
+    raw: Rc<raw::Renderbuffer>,
+    _phantom: PhantomData<S>,
+}
+
+/// A depth image that can be rendered to, but not sampled.
+///
+/// Multisampled renderbuffers are resolved into a single-sampled texture with
+/// [`DepthAttachment::resolve_into`].
+pub struct DepthRenderbuffer {
+    raw: Rc<raw::Renderbuffer>,
+}
+
+impl<S> ColorRenderbuffer<S> {
+    pub(super) fn from_raw(raw: raw::Renderbuffer) -> Self {
+        Self {
+            raw: Rc::new(raw),
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<S: ColorSample> ColorRenderbuffer<S> {
+    pub fn as_color_attachment(&self) -> ColorAttachment<S> {
+        ColorAttachment::from_raw(raw::Attachment::Renderbuffer {
+            renderbuffer: self.raw.clone(),
+        })
+    }
+
+    pub fn size(&self) -> [u32; 2] {
+        self.raw.size()
+    }
+
+    /// Returns the number of samples per pixel, which is zero if the
+    /// renderbuffer is single-sampled.
+    pub fn samples(&self) -> u32 {
+        self.raw.samples()
+    }
+}
+
+impl DepthRenderbuffer {
+    pub(super) fn from_raw(raw: raw::Renderbuffer) -> Self {
+        Self { raw: Rc::new(raw) }
+    }
+
+    pub fn as_depth_attachment(&self) -> DepthAttachment {
+        DepthAttachment::from_raw(raw::Attachment::Renderbuffer {
+            renderbuffer: self.raw.clone(),
+        })
+    }
+
+    pub fn size(&self) -> [u32; 2] {
+        self.raw.size()
+    }
+
+    /// Returns the number of samples per pixel, which is zero if the
+    /// renderbuffer is single-sampled.
+    pub fn samples(&self) -> u32 {
+        self.raw.samples()
+    }
+}
@@ -3,8 +3,9 @@ use std::{marker::PhantomData, rc::Rc};
 use crate::sl::{self, ColorSample};
 
 use super::{
-    raw::{self, Sampler2dParams},
-    ColorAttachment, ColorImage, Comparison, DepthAttachment, DepthImage, TextureError,
+    raw::{self, CubeFace, Sampler2dParams},
+    ColorAttachment, ColorImage, Comparison, DepthAttachment, DepthImage, Framebuffer,
+    FramebufferError, PixelPackBuffer, Rect, TextureError,
 };
 
 pub struct ColorTexture2d<S = sl::Vec4> {
@@ -27,6 +28,57 @@ pub struct ComparisonSampler2d {
     raw: raw::Sampler2d,
 }
 
+pub struct ColorTexture2dArray<S = sl::Vec4> {
+    raw: Rc<raw::Texture3d>,
+    _phantom: PhantomData<S>,
+}
+
+pub struct DepthTexture2dArray {
+    raw: Rc<raw::Texture3d>,
+}
+
+pub struct ColorTexture3d<S = sl::Vec4> {
+    raw: Rc<raw::Texture3d>,
+    _phantom: PhantomData<S>,
+}
+
+pub struct ColorTextureCube<S = sl::Vec4> {
+    raw: Rc<raw::Texture3d>,
+    _phantom: PhantomData<S>,
+}
+
+pub struct DepthTextureCube {
+    raw: Rc<raw::Texture3d>,
+}
+
+#[derive(Clone)]
+pub struct ColorSampler2dArray<S = sl::Vec4> {
+    raw: raw::Sampler3d,
+    _phantom: PhantomData<S>,
+}
+
+#[derive(Clone)]
+pub struct ComparisonSampler2dArray {
+    raw: raw::Sampler3d,
+}
+
+#[derive(Clone)]
+pub struct ColorSampler3d<S = sl::Vec4> {
+    raw: raw::Sampler3d,
+    _phantom: PhantomData<S>,
+}
+
+#[derive(Clone)]
+pub struct ColorSamplerCube<S = sl::Vec4> {
+    raw: raw::Sampler3d,
+    _phantom: PhantomData<S>,
+}
+
+#[derive(Clone)]
+pub struct ComparisonSamplerCube {
+    raw: raw::Sampler3d,
+}
+
 impl<S> ColorTexture2d<S> {
     pub(super) fn from_raw(raw: raw::Texture2d) -> Self {
         // FIXME: This should validate against `S`.
@@ -50,11 +102,15 @@ impl<S: ColorSample> ColorTexture2d<S> {
         })
     }
 
+    /// Returns a sampler for the texture.
+    ///
+    /// Integer textures are sampled without filtering, regardless of the
+    /// filters in `params`. See [`Sampler2dParams::for_internal_format`].
     pub fn as_color_sampler(&self, params: Sampler2dParams) -> ColorSampler2d<S> {
         // FIXME: Check texture completeness.
         ColorSampler2d::from_raw(raw::Sampler2d {
             texture: self.raw.clone(),
-            params,
+            params: params.for_internal_format(self.raw.internal_format()),
             comparison: None,
         })
     }
@@ -71,6 +127,70 @@ impl<S: ColorSample> ColorTexture2d<S> {
     ) -> Result<(), TextureError> {
         self.raw.set(level, lower_left_corner, image.raw())
     }
+
+    /// Copies `rect` of the color attachment of `framebuffer` into mipmap
+    /// level `level`, starting at `lower_left_corner`.
+    ///
+    /// The framebuffer must not be multisampled, see [`Context::blit`] for
+    /// resolving multisampled framebuffers.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `level` does not exist, if a rectangle is not contained in its
+    /// image, or if the framebuffer belongs to a different context.
+    ///
+    /// [`Context::blit`]: super::Context::blit
+    pub fn copy_from(
+        &self,
+        level: usize,
+        lower_left_corner: [u32; 2],
+        framebuffer: &Framebuffer<S>,
+        rect: Rect,
+    ) -> Result<(), FramebufferError> {
+        self.raw
+            .copy_from(level, lower_left_corner, &framebuffer.raw(), rect)
+    }
+
+    /// Reads RGBA pixels with 8-bit components from the base level.
+    ///
+    /// See [`ColorAttachment::read_rgba_u8`].
+    pub fn read_rgba_u8(&self, rect: Rect) -> Result<Vec<u8>, TextureError> {
+        self.as_color_attachment().read_rgba_u8(rect)
+    }
+
+    /// Reads RGBA pixels with 32-bit floating-point components from the base
+    /// level.
+    ///
+    /// See [`ColorAttachment::read_rgba_f32`].
+    pub fn read_rgba_f32(&self, rect: Rect) -> Result<Vec<f32>, TextureError> {
+        self.as_color_attachment().read_rgba_f32(rect)
+    }
+
+    /// Starts reading RGBA pixels with 8-bit components from the base level
+    /// into `buffer`.
+    ///
+    /// See [`ColorAttachment::read_rgba_u8_to_buffer`].
+    pub fn read_rgba_u8_to_buffer(
+        &self,
+        rect: Rect,
+        buffer: &PixelPackBuffer<u8>,
+    ) -> Result<(), TextureError> {
+        self.as_color_attachment()
+            .read_rgba_u8_to_buffer(rect, buffer)
+    }
+
+    /// Starts reading RGBA pixels with 32-bit floating-point components from
+    /// the base level into `buffer`.
+    ///
+    /// See [`ColorAttachment::read_rgba_f32_to_buffer`].
+    pub fn read_rgba_f32_to_buffer(
+        &self,
+        rect: Rect,
+        buffer: &PixelPackBuffer<f32>,
+    ) -> Result<(), TextureError> {
+        self.as_color_attachment()
+            .read_rgba_f32_to_buffer(rect, buffer)
+    }
 }
 
 impl DepthTexture2d {
@@ -127,6 +247,24 @@ impl DepthTexture2d {
     ) -> Result<(), TextureError> {
         self.raw.set(level, lower_left_corner, image.raw())
     }
+
+    /// Reads depth values from the base level.
+    ///
+    /// See [`DepthAttachment::read_f32`].
+    pub fn read_f32(&self, rect: Rect) -> Result<Vec<f32>, TextureError> {
+        self.as_depth_attachment().read_f32(rect)
+    }
+
+    /// Starts reading depth values from the base level into `buffer`.
+    ///
+    /// See [`DepthAttachment::read_f32_to_buffer`].
+    pub fn read_f32_to_buffer(
+        &self,
+        rect: Rect,
+        buffer: &PixelPackBuffer<f32>,
+    ) -> Result<(), TextureError> {
+        self.as_depth_attachment().read_f32_to_buffer(rect, buffer)
+    }
 }
 
 impl<S> ColorSampler2d<S> {
@@ -151,3 +289,342 @@ impl ComparisonSampler2d {
         &self.raw
     }
 }
+
+impl<S> ColorTexture2dArray<S> {
+    pub(super) fn from_raw(raw: raw::Texture3d) -> Self {
+        // FIXME: This should validate against `S`.
+
+        Self {
+            raw: Rc::new(raw),
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<S: ColorSample> ColorTexture2dArray<S> {
+    /// Returns layer `layer` of the texture as an attachment.
+    pub fn as_color_attachment(&self, layer: u32) -> ColorAttachment<S> {
+        self.as_color_attachment_with_level(layer, 0)
+    }
+
+    pub fn as_color_attachment_with_level(&self, layer: u32, level: u32) -> ColorAttachment<S> {
+        ColorAttachment::from_raw(raw::Attachment::Texture3d {
+            texture: self.raw.clone(),
+            layer,
+            level,
+        })
+    }
+
+    pub fn as_color_sampler(&self, params: Sampler2dParams) -> ColorSampler2dArray<S> {
+        // FIXME: Check texture completeness.
+        ColorSampler2dArray::from_raw(raw::Sampler3d {
+            texture: self.raw.clone(),
+            params: params.for_internal_format(self.raw.internal_format()),
+            comparison: None,
+        })
+    }
+
+    /// Returns the width, the height and the number of layers.
+    pub fn size(&self) -> [u32; 3] {
+        self.raw.size()
+    }
+
+    pub fn set(
+        &self,
+        level: usize,
+        layer: u32,
+        lower_left_corner: [u32; 2],
+        image: ColorImage<S>,
+    ) -> Result<(), TextureError> {
+        let [x, y] = lower_left_corner;
+
+        self.raw.set(level, [x, y, layer], image.raw())
+    }
+}
+
+impl DepthTexture2dArray {
+    pub(super) fn from_raw(raw: raw::Texture3d) -> Self {
+        // FIXME: This should validate against depth.
+
+        Self { raw: Rc::new(raw) }
+    }
+
+    /// Returns layer `layer` of the texture as an attachment.
+    pub fn as_depth_attachment(&self, layer: u32) -> DepthAttachment {
+        self.as_depth_attachment_with_level(layer, 0)
+    }
+
+    pub fn as_depth_attachment_with_level(&self, layer: u32, level: u32) -> DepthAttachment {
+        DepthAttachment::from_raw(raw::Attachment::Texture3d {
+            texture: self.raw.clone(),
+            layer,
+            level,
+        })
+    }
+
+    pub fn as_color_sampler(&self, params: Sampler2dParams) -> ColorSampler2dArray<sl::F32> {
+        // FIXME: Check texture completeness.
+        ColorSampler2dArray::from_raw(raw::Sampler3d {
+            texture: self.raw.clone(),
+            params,
+            comparison: None,
+        })
+    }
+
+    pub fn as_comparison_sampler(
+        &self,
+        params: Sampler2dParams,
+        comparison: Comparison,
+    ) -> ComparisonSampler2dArray {
+        // FIXME: Check texture completeness.
+        ComparisonSampler2dArray::from_raw(raw::Sampler3d {
+            texture: self.raw.clone(),
+            params,
+            comparison: Some(comparison),
+        })
+    }
+
+    /// Returns the width, the height and the number of layers.
+    pub fn size(&self) -> [u32; 3] {
+        self.raw.size()
+    }
+
+    pub fn set(
+        &self,
+        level: usize,
+        layer: u32,
+        lower_left_corner: [u32; 2],
+        image: DepthImage,
+    ) -> Result<(), TextureError> {
+        let [x, y] = lower_left_corner;
+
+        self.raw.set(level, [x, y, layer], image.raw())
+    }
+}
+
+impl<S> ColorTexture3d<S> {
+    pub(super) fn from_raw(raw: raw::Texture3d) -> Self {
+        // FIXME: This should validate against `S`.
+
+        Self {
+            raw: Rc::new(raw),
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<S: ColorSample> ColorTexture3d<S> {
+    /// Returns slice `layer` of the texture as an attachment.
+    pub fn as_color_attachment(&self, layer: u32) -> ColorAttachment<S> {
+        self.as_color_attachment_with_level(layer, 0)
+    }
+
+    /// Returns slice `layer` of mipmap level `level` as an attachment.
+    ///
+    /// The slices of a mipmap level are numbered up to the depth of that
+    /// level.
+    pub fn as_color_attachment_with_level(&self, layer: u32, level: u32) -> ColorAttachment<S> {
+        ColorAttachment::from_raw(raw::Attachment::Texture3d {
+            texture: self.raw.clone(),
+            layer,
+            level,
+        })
+    }
+
+    pub fn as_color_sampler(&self, params: Sampler2dParams) -> ColorSampler3d<S> {
+        // FIXME: Check texture completeness.
+        ColorSampler3d::from_raw(raw::Sampler3d {
+            texture: self.raw.clone(),
+            params: params.for_internal_format(self.raw.internal_format()),
+            comparison: None,
+        })
+    }
+
+    pub fn size(&self) -> [u32; 3] {
+        self.raw.size()
+    }
+
+    /// Writes `image` into the slice `offset[2]` of mipmap level `level`,
+    /// starting at `[offset[0], offset[1]]`.
+    pub fn set(
+        &self,
+        level: usize,
+        offset: [u32; 3],
+        image: ColorImage<S>,
+    ) -> Result<(), TextureError> {
+        self.raw.set(level, offset, image.raw())
+    }
+}
+
+impl<S> ColorSampler2dArray<S> {
+    fn from_raw(raw: raw::Sampler3d) -> Self {
+        Self {
+            raw,
+            _phantom: PhantomData,
+        }
+    }
+
+    pub fn raw(&self) -> &raw::Sampler3d {
+        &self.raw
+    }
+}
+
+impl ComparisonSampler2dArray {
+    fn from_raw(raw: raw::Sampler3d) -> Self {
+        Self { raw }
+    }
+
+    pub fn raw(&self) -> &raw::Sampler3d {
+        &self.raw
+    }
+}
+
+impl<S> ColorSampler3d<S> {
+    fn from_raw(raw: raw::Sampler3d) -> Self {
+        Self {
+            raw,
+            _phantom: PhantomData,
+        }
+    }
+
+    pub fn raw(&self) -> &raw::Sampler3d {
+        &self.raw
+    }
+}
+
+impl<S> ColorTextureCube<S> {
+    pub(super) fn from_raw(raw: raw::Texture3d) -> Self {
+        // FIXME: This should validate against `S`.
+
+        Self {
+            raw: Rc::new(raw),
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<S: ColorSample> ColorTextureCube<S> {
+    pub fn as_color_attachment(&self, face: CubeFace) -> ColorAttachment<S> {
+        self.as_color_attachment_with_level(face, 0)
+    }
+
+    pub fn as_color_attachment_with_level(&self, face: CubeFace, level: u32) -> ColorAttachment<S> {
+        ColorAttachment::from_raw(raw::Attachment::Texture3d {
+            texture: self.raw.clone(),
+            layer: face.layer(),
+            level,
+        })
+    }
+
+    pub fn as_color_sampler(&self, params: Sampler2dParams) -> ColorSamplerCube<S> {
+        // FIXME: Check texture completeness.
+        ColorSamplerCube::from_raw(raw::Sampler3d {
+            texture: self.raw.clone(),
+            params: params.for_internal_format(self.raw.internal_format()),
+            comparison: None,
+        })
+    }
+
+    /// Returns the width and the height of each face.
+    pub fn size(&self) -> [u32; 2] {
+        let [width, height, _] = self.raw.size();
+
+        [width, height]
+    }
+
+    pub fn set(
+        &self,
+        face: CubeFace,
+        level: usize,
+        lower_left_corner: [u32; 2],
+        image: ColorImage<S>,
+    ) -> Result<(), TextureError> {
+        let [x, y] = lower_left_corner;
+
+        self.raw.set(level, [x, y, face.layer()], image.raw())
+    }
+}
+
+impl DepthTextureCube {
+    pub(super) fn from_raw(raw: raw::Texture3d) -> Self {
+        // FIXME: This should validate against depth.
+
+        Self { raw: Rc::new(raw) }
+    }
+
+    pub fn as_depth_attachment(&self, face: CubeFace) -> DepthAttachment {
+        self.as_depth_attachment_with_level(face, 0)
+    }
+
+    pub fn as_depth_attachment_with_level(&self, face: CubeFace, level: u32) -> DepthAttachment {
+        DepthAttachment::from_raw(raw::Attachment::Texture3d {
+            texture: self.raw.clone(),
+            layer: face.layer(),
+            level,
+        })
+    }
+
+    pub fn as_color_sampler(&self, params: Sampler2dParams) -> ColorSamplerCube<sl::F32> {
+        // FIXME: Check texture completeness.
+        ColorSamplerCube::from_raw(raw::Sampler3d {
+            texture: self.raw.clone(),
+            params,
+            comparison: None,
+        })
+    }
+
+    pub fn as_comparison_sampler(
+        &self,
+        params: Sampler2dParams,
+        comparison: Comparison,
+    ) -> ComparisonSamplerCube {
+        // FIXME: Check texture completeness.
+        ComparisonSamplerCube::from_raw(raw::Sampler3d {
+            texture: self.raw.clone(),
+            params,
+            comparison: Some(comparison),
+        })
+    }
+
+    /// Returns the width and the height of each face.
+    pub fn size(&self) -> [u32; 2] {
+        let [width, height, _] = self.raw.size();
+
+        [width, height]
+    }
+
+    pub fn set(
+        &self,
+        face: CubeFace,
+        level: usize,
+        lower_left_corner: [u32; 2],
+        image: DepthImage,
+    ) -> Result<(), TextureError> {
+        let [x, y] = lower_left_corner;
+
+        self.raw.set(level, [x, y, face.layer()], image.raw())
+    }
+}
+
+impl<S> ColorSamplerCube<S> {
+    fn from_raw(raw: raw::Sampler3d) -> Self {
+        Self {
+            raw,
+            _phantom: PhantomData,
+        }
+    }
+
+    pub fn raw(&self) -> &raw::Sampler3d {
+        &self.raw
+    }
+}
+
+impl ComparisonSamplerCube {
+    fn from_raw(raw: raw::Sampler3d) -> Self {
+        Self { raw }
+    }
+
+    pub fn raw(&self) -> &raw::Sampler3d {
+        &self.raw
+    }
+}
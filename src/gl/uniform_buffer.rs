@@ -1,25 +1,33 @@
-use std::{marker::PhantomData, mem::size_of, rc::Rc};
+use std::{marker::PhantomData, mem::size_of, ops::Range, rc::Rc};
 
 use crevice::std140::{AsStd140, Std140};
 
 use crate::{Block, Gl};
 
-use super::{raw, BufferError, BufferUsage};
+use super::{raw, BufferError, BufferUsage, Caps};
 
 /// Stores a uniform block in a buffer on the GPU.
 ///
+/// A `UniformBuffer<[B]>` stores multiple blocks of type `B`, each of which
+/// can be bound separately. This allows using one buffer for the uniforms of
+/// many draw calls.
+///
 /// Instances of `UniformBuffer` can be created with
-/// [`Context::create_uniform_buffer`](crate::gl::Context::create_uniform_buffer).
-pub struct UniformBuffer<B> {
-    pub(super) raw: Rc<raw::Buffer>,
+/// [`Context::create_uniform_buffer`](crate::gl::Context::create_uniform_buffer)
+/// and
+/// [`Context::create_uniform_buffer_slice`](crate::gl::Context::create_uniform_buffer_slice).
+pub struct UniformBuffer<B: ?Sized> {
+    raw: Rc<raw::Buffer>,
+    // The distance between the starts of consecutive blocks, in bytes.
+    stride: usize,
     _phantom: PhantomData<B>,
 }
 
 #[derive(Clone)]
 pub struct UniformBufferBinding<B> {
     raw: Rc<raw::Buffer>,
+    range: Range<usize>,
     _phantom: PhantomData<B>,
-    // TODO: Uniform buffer slicing.
 }
 
 impl<B: Block<Gl>> UniformBuffer<B> {
@@ -36,6 +44,7 @@ impl<B: Block<Gl>> UniformBuffer<B> {
 
         Ok(Self {
             raw: Rc::new(raw),
+            stride: bytes.len(),
             _phantom: PhantomData,
         })
     }
@@ -52,20 +61,163 @@ impl<B: Block<Gl>> UniformBuffer<B> {
         self.raw.set(bytes);
     }
 
+    /// Reads the block back from the GPU.
+    ///
+    /// See [`raw::Buffer::get_sub_data`].
+    pub fn read(&self) -> Result<B::Gl, BufferError> {
+        let mut bytes = vec![0; size_of::<<B::Gl as AsStd140>::Output>()];
+
+        self.raw.get_sub_data(0, &mut bytes)?;
+
+        let data: <B::Gl as AsStd140>::Output = bytemuck::pod_read_unaligned(&bytes);
+
+        Ok(B::Gl::from_std140(data))
+    }
+
     pub fn as_binding(&self) -> UniformBufferBinding<B::Sl> {
-        UniformBufferBinding {
-            raw: self.raw.clone(),
+        UniformBufferBinding::from_raw(self.raw.clone(), 0..self.stride)
+    }
+}
+
+impl<B: Block<Gl>> UniformBuffer<[B]> {
+    pub(super) fn new_slice(
+        ctx: &raw::Context,
+        data: &[B::Gl],
+        usage: BufferUsage,
+    ) -> Result<Self, BufferError> {
+        let stride = slice_stride::<B>(ctx.caps());
+        let bytes = slice_to_bytes::<B>(data, stride);
+
+        let raw = ctx.create_buffer(&bytes, glow::UNIFORM_BUFFER, usage)?;
+
+        Ok(Self {
+            raw: Rc::new(raw),
+            stride,
             _phantom: PhantomData,
+        })
+    }
+
+    pub fn usage(&self) -> BufferUsage {
+        self.raw.usage()
+    }
+
+    /// Returns the number of blocks in the buffer.
+    pub fn len(&self) -> usize {
+        self.raw.len() / self.stride
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn set(&self, data: &[B::Gl]) {
+        self.raw.set(&slice_to_bytes::<B>(data, self.stride));
+    }
+
+    /// Replaces the blocks starting at index `offset` with `data`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range is out of bounds.
+    pub fn set_range(&self, offset: usize, data: &[B::Gl]) {
+        assert!(offset + data.len() <= self.len());
+
+        self.raw.set_sub_data(
+            offset * self.stride,
+            &slice_to_bytes::<B>(data, self.stride),
+        );
+    }
+
+    /// Grows the buffer so that it can hold at least `len` blocks.
+    ///
+    /// See [`VertexBuffer::grow`](super::VertexBuffer::grow).
+    pub fn grow(&self, len: usize) {
+        if len > self.len() {
+            self.raw.orphan(len * self.stride);
         }
     }
+
+    /// Reads all blocks back from the GPU.
+    ///
+    /// See [`UniformBuffer::read`].
+    pub fn read(&self) -> Result<Vec<B::Gl>, BufferError> {
+        let mut bytes = vec![0; self.raw.len()];
+
+        self.raw.get_sub_data(0, &mut bytes)?;
+
+        Ok(bytes
+            .chunks_exact(self.stride)
+            .map(|chunk| {
+                let size = size_of::<<B::Gl as AsStd140>::Output>();
+                let data: <B::Gl as AsStd140>::Output =
+                    bytemuck::pod_read_unaligned(&chunk[..size]);
+
+                B::Gl::from_std140(data)
+            })
+            .collect())
+    }
+
+    /// Returns a binding of the block at `index`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn as_binding(&self, index: usize) -> UniformBufferBinding<B::Sl> {
+        assert!(index < self.len());
+
+        let start = index * self.stride;
+
+        let end = start + padded_size::<<B::Gl as AsStd140>::Output>();
+
+        UniformBufferBinding::from_raw(self.raw.clone(), start..end)
+    }
 }
 
 impl<B> UniformBufferBinding<B> {
-    pub(super) fn raw(&self) -> &raw::Buffer {
-        &self.raw
+    pub(super) fn from_raw(raw: Rc<raw::Buffer>, range: Range<usize>) -> Self {
+        Self {
+            raw,
+            range,
+            _phantom: PhantomData,
+        }
+    }
+
+    pub(super) fn raw(&self) -> raw::UniformBufferBinding {
+        raw::UniformBufferBinding {
+            buffer: self.raw.clone(),
+            range: self.range.clone(),
+        }
     }
 }
 
+// Blocks in a slice start at multiples of `UNIFORM_BUFFER_OFFSET_ALIGNMENT`,
+// so that each of them can be bound with `glBindBufferRange`.
+fn slice_stride<B: Block<Gl>>(caps: &Caps) -> usize {
+    let size = padded_size::<<B::Gl as AsStd140>::Output>();
+    let alignment = caps.uniform_buffer_offset_alignment as usize;
+
+    size.next_multiple_of(alignment)
+}
+
+pub(super) fn slice_to_bytes<B: Block<Gl>>(data: &[B::Gl], stride: usize) -> Vec<u8> {
+    let mut bytes = vec![0; data.len() * stride];
+
+    for (block, chunk) in data.iter().zip(bytes.chunks_exact_mut(stride)) {
+        let block = block.as_std140();
+        let block_bytes = block.as_bytes();
+
+        chunk[..block_bytes.len()].copy_from_slice(block_bytes);
+    }
+
+    bytes
+}
+
+pub(super) fn padded_size<B: Std140>() -> usize {
+    const MIN_ALIGNMENT: usize = 16;
+
+    size_of::<B>().next_multiple_of(MIN_ALIGNMENT)
+}
+
 fn to_bytes<'a, B: Std140>(data: &'a B, buffer: &'a mut Vec<u8>) -> &'a [u8] {
     // FIXME: This is a workaround for cases like an uniform buffer that
     // contains only a `Vec2`. In this case, `crevice` gives us a type that is
@@ -0,0 +1,159 @@
+use std::{cell::RefCell, collections::VecDeque, rc::Rc};
+
+use crevice::std140::AsStd140;
+
+use crate::{Block, Gl};
+
+use super::{
+    raw,
+    uniform_buffer::{padded_size, slice_to_bytes},
+    BufferError, BufferUsage, FenceError, UniformBufferBinding,
+};
+
+/// Allocates transient uniform blocks from one large buffer on the GPU.
+///
+/// Each call to [`Self::push`] writes a block into the next free range of the
+/// buffer and returns a binding for it, so that per-draw uniforms do not need
+/// a separate buffer each. Calling [`Self::end_frame`] after submitting the
+/// draws of a frame guards the frame's blocks with a fence. Their memory is
+/// reused once the GPU has completed the frame.
+///
+/// A binding must not be drawn with once a later frame has reused its memory,
+/// since it would then see the blocks of that frame. Memory is only reused
+/// after the fence of its frame has been signaled, so bindings are safe to
+/// use for the draws of the frame in which they were pushed. The bindings keep
+/// the buffer alive, so using one too late gives wrong uniforms, but is not
+/// undefined behavior.
+///
+/// Instances of `UniformRing` can be created with
+/// [`Context::create_uniform_ring`](crate::gl::Context::create_uniform_ring).
+pub struct UniformRing {
+    ctx: Rc<raw::Context>,
+    raw: Rc<raw::Buffer>,
+    alignment: usize,
+    state: RefCell<State>,
+}
+
+struct State {
+    // The offset at which the next block is written.
+    head: usize,
+
+    // The offset of the oldest block that may still be used by the GPU.
+    tail: usize,
+
+    // The offset of the first block of the current frame.
+    frame_start: usize,
+
+    // The fences of frames that have been ended, together with the offset at
+    // which each frame ends.
+    frames: VecDeque<(raw::Fence, usize)>,
+}
+
+impl UniformRing {
+    pub(super) fn new(ctx: Rc<raw::Context>, capacity: usize) -> Result<Self, BufferError> {
+        let raw = ctx.create_buffer(
+            &vec![0; capacity],
+            glow::UNIFORM_BUFFER,
+            BufferUsage::StreamDraw,
+        )?;
+        let alignment = ctx.caps().uniform_buffer_offset_alignment as usize;
+
+        Ok(Self {
+            ctx,
+            raw: Rc::new(raw),
+            alignment,
+            state: RefCell::new(State {
+                head: 0,
+                tail: 0,
+                frame_start: 0,
+                frames: VecDeque::new(),
+            }),
+        })
+    }
+
+    /// Returns the size of the buffer in bytes.
+    pub fn capacity(&self) -> usize {
+        self.raw.len()
+    }
+
+    /// Writes `data` into the buffer and returns a binding for it.
+    ///
+    /// Returns [`BufferError::Full`] if the GPU is still using all of the
+    /// memory that would be needed. In that case, the ring is too small for
+    /// the number of frames that are in flight.
+    pub fn push<B: Block<Gl>>(
+        &self,
+        data: B::Gl,
+    ) -> Result<UniformBufferBinding<B::Sl>, BufferError> {
+        let size = padded_size::<<B::Gl as AsStd140>::Output>();
+        let offset = self.alloc(size.next_multiple_of(self.alignment))?;
+
+        self.raw
+            .set_sub_data(offset, &slice_to_bytes::<B>(&[data], size));
+
+        Ok(UniformBufferBinding::from_raw(
+            self.raw.clone(),
+            offset..offset + size,
+        ))
+    }
+
+    /// Ends the current frame.
+    ///
+    /// This should be called once all draws that use the blocks of the frame
+    /// have been submitted.
+    pub fn end_frame(&self) -> Result<(), FenceError> {
+        let mut state = self.state.borrow_mut();
+
+        if state.head == state.frame_start {
+            return Ok(());
+        }
+
+        let fence = self.ctx.create_fence()?;
+        let end = state.head;
+
+        state.frames.push_back((fence, end));
+        state.frame_start = end;
+
+        Ok(())
+    }
+
+    fn alloc(&self, size: usize) -> Result<usize, BufferError> {
+        let mut state = self.state.borrow_mut();
+
+        while let Some((fence, end)) = state.frames.front() {
+            if !fence.is_signaled() {
+                break;
+            }
+
+            state.tail = *end;
+            state.frames.pop_front();
+        }
+
+        if state.frames.is_empty() && state.head == state.frame_start {
+            // Nothing is in use, so we can start over at the beginning.
+            state.head = 0;
+            state.tail = 0;
+            state.frame_start = 0;
+        }
+
+        // The head never catches up with the tail, so that `head == tail`
+        // unambiguously means that the ring is empty.
+        let offset = if state.head >= state.tail {
+            if state.head + size <= self.capacity() {
+                state.head
+            } else if size < state.tail {
+                0
+            } else {
+                return Err(BufferError::Full { requested: size });
+            }
+        } else if state.head + size < state.tail {
+            state.head
+        } else {
+            return Err(BufferError::Full { requested: size });
+        };
+
+        state.head = offset + size;
+
+        Ok(offset)
+    }
+}
@@ -1,8 +1,8 @@
-use std::{marker::PhantomData, mem::size_of, rc::Rc};
+use std::{marker::PhantomData, mem::size_of, ops::Range, rc::Rc};
 
 use crate::{sl::program_def::VertexInputRate, Block, Gl, Sl};
 
-use super::{raw, BufferUsage, PrimitiveMode, VertexSpec};
+use super::{raw, BufferError, BufferUsage, PrimitiveMode, VertexSpec};
 
 /// Stores vertex blocks in a buffer on the GPU.
 ///
@@ -52,6 +52,60 @@ where
         self.raw.set(bytemuck::cast_slice(data));
     }
 
+    /// Replaces the vertices starting at index `offset` with `data`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range is out of bounds.
+    pub fn set_range(&self, offset: usize, data: &[B]) {
+        assert!(offset + data.len() <= self.len());
+
+        self.raw
+            .set_sub_data(offset * size_of::<B>(), bytemuck::cast_slice(data));
+    }
+
+    /// Grows the buffer so that it can hold at least `len` vertices.
+    ///
+    /// If the buffer is shorter than `len`, it is reallocated with `len`
+    /// vertices, and its previous contents are discarded. The previous storage
+    /// is orphaned, so draws that still use it do not stall. Otherwise,
+    /// nothing happens.
+    ///
+    /// Together with [`Self::set_range`], this allows streaming data of
+    /// varying length without reallocating the buffer every frame.
+    pub fn grow(&self, len: usize) {
+        if len > self.len() {
+            self.raw.orphan(len * size_of::<B>());
+        }
+    }
+
+    /// Reads all vertices back from the GPU.
+    ///
+    /// See [`raw::Buffer::get_sub_data`].
+    pub fn read(&self) -> Result<Vec<B>, BufferError> {
+        self.read_range(0..self.len())
+    }
+
+    /// Reads the vertices in `range` back from the GPU.
+    ///
+    /// See [`Self::read`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `range` is out of bounds.
+    pub fn read_range(&self, range: Range<usize>) -> Result<Vec<B>, BufferError> {
+        assert!(range.start <= range.end && range.end <= self.len());
+
+        let mut data = vec![B::zeroed(); range.len()];
+
+        self.raw.get_sub_data(
+            range.start * size_of::<B>(),
+            bytemuck::cast_slice_mut(&mut data),
+        )?;
+
+        Ok(data)
+    }
+
     pub fn as_binding(&self) -> VertexBufferBinding<B::Sl> {
         VertexBufferBinding {
             raw: self.raw.clone(),
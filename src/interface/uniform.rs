@@ -20,6 +20,21 @@ pub trait UniformDom: Copy {
     /// A two-dimensional comparison sampler field.
     type ComparisonSampler2d: Uniform<Self>;
 
+    /// A two-dimensional array color sampler field.
+    type ColorSampler2dArray<S: sl::ColorSample>: Uniform<Self>;
+
+    /// A two-dimensional array comparison sampler field.
+    type ComparisonSampler2dArray: Uniform<Self>;
+
+    /// A three-dimensional color sampler field.
+    type ColorSampler3d<S: sl::ColorSample>: Uniform<Self>;
+
+    /// A cube map color sampler field.
+    type ColorSamplerCube<S: sl::ColorSample>: Uniform<Self>;
+
+    /// A cube map comparison sampler field.
+    type ComparisonSamplerCube: Uniform<Self>;
+
     /// A nested uniform interface field.
     type Uniform<U: Uniform<Sl>>: Uniform<Self>;
 
@@ -32,6 +47,11 @@ impl UniformDom for Gl {
     type Block<B: Block<Sl, Sl = B>> = gl::UniformBufferBinding<B>;
     type ColorSampler2d<S: sl::ColorSample> = gl::ColorSampler2d<S>;
     type ComparisonSampler2d = gl::ComparisonSampler2d;
+    type ColorSampler2dArray<S: sl::ColorSample> = gl::ColorSampler2dArray<S>;
+    type ComparisonSampler2dArray = gl::ComparisonSampler2dArray;
+    type ColorSampler3d<S: sl::ColorSample> = gl::ColorSampler3d<S>;
+    type ColorSamplerCube<S: sl::ColorSample> = gl::ColorSamplerCube<S>;
+    type ComparisonSamplerCube = gl::ComparisonSamplerCube;
     type Uniform<R: Uniform<Sl>> = R::Gl;
     type Array<U: Uniform<Sl>, const N: usize> = [U::Gl; N];
 }
@@ -41,6 +61,11 @@ impl UniformDom for Sl {
     type Block<B: Block<Sl, Sl = B>> = B;
     type ColorSampler2d<S: sl::ColorSample> = sl::ColorSampler2d<S>;
     type ComparisonSampler2d = sl::ComparisonSampler2d;
+    type ColorSampler2dArray<S: sl::ColorSample> = sl::ColorSampler2dArray<S>;
+    type ComparisonSampler2dArray = sl::ComparisonSampler2dArray;
+    type ColorSampler3d<S: sl::ColorSample> = sl::ColorSampler3d<S>;
+    type ColorSamplerCube<S: sl::ColorSample> = sl::ColorSamplerCube<S>;
+    type ComparisonSamplerCube = sl::ComparisonSamplerCube;
     type Uniform<R: Uniform<Sl>> = R;
     type Array<U: Uniform<Sl>, const N: usize> = [U; N];
 }
@@ -167,6 +192,116 @@ unsafe impl Uniform<Sl> for sl::ComparisonSampler2d {
     }
 }
 
+unsafe impl<S: sl::ColorSample> Uniform<Gl> for gl::ColorSampler2dArray<S> {
+    type Gl = gl::ColorSampler2dArray<S>;
+    type Sl = sl::ColorSampler2dArray<S>;
+
+    fn visit<'a>(&'a self, path: &str, visitor: &mut impl UniformVisitor<'a, Gl>) {
+        visitor.accept_color_sampler_2d_array(path, self);
+    }
+}
+
+unsafe impl<S: sl::ColorSample> Uniform<Sl> for sl::ColorSampler2dArray<S> {
+    type Gl = gl::ColorSampler2dArray<S>;
+    type Sl = Self;
+
+    fn visit<'a>(&'a self, path: &str, visitor: &mut impl UniformVisitor<'a, Sl>) {
+        visitor.accept_color_sampler_2d_array(path, self)
+    }
+
+    fn shader_input(path: &str) -> Self {
+        <Self as sl::Object>::from_arg(path)
+    }
+}
+
+unsafe impl Uniform<Gl> for gl::ComparisonSampler2dArray {
+    type Gl = gl::ComparisonSampler2dArray;
+    type Sl = sl::ComparisonSampler2dArray;
+
+    fn visit<'a>(&'a self, path: &str, visitor: &mut impl UniformVisitor<'a, Gl>) {
+        visitor.accept_comparison_sampler_2d_array(path, self);
+    }
+}
+
+unsafe impl Uniform<Sl> for sl::ComparisonSampler2dArray {
+    type Gl = gl::ComparisonSampler2dArray;
+    type Sl = sl::ComparisonSampler2dArray;
+
+    fn visit<'a>(&'a self, path: &str, visitor: &mut impl UniformVisitor<'a, Sl>) {
+        visitor.accept_comparison_sampler_2d_array(path, self)
+    }
+
+    fn shader_input(path: &str) -> Self {
+        <Self as sl::Object>::from_arg(path)
+    }
+}
+
+unsafe impl<S: sl::ColorSample> Uniform<Gl> for gl::ColorSampler3d<S> {
+    type Gl = gl::ColorSampler3d<S>;
+    type Sl = sl::ColorSampler3d<S>;
+
+    fn visit<'a>(&'a self, path: &str, visitor: &mut impl UniformVisitor<'a, Gl>) {
+        visitor.accept_color_sampler_3d(path, self);
+    }
+}
+
+unsafe impl<S: sl::ColorSample> Uniform<Sl> for sl::ColorSampler3d<S> {
+    type Gl = gl::ColorSampler3d<S>;
+    type Sl = Self;
+
+    fn visit<'a>(&'a self, path: &str, visitor: &mut impl UniformVisitor<'a, Sl>) {
+        visitor.accept_color_sampler_3d(path, self)
+    }
+
+    fn shader_input(path: &str) -> Self {
+        <Self as sl::Object>::from_arg(path)
+    }
+}
+
+unsafe impl<S: sl::ColorSample> Uniform<Gl> for gl::ColorSamplerCube<S> {
+    type Gl = gl::ColorSamplerCube<S>;
+    type Sl = sl::ColorSamplerCube<S>;
+
+    fn visit<'a>(&'a self, path: &str, visitor: &mut impl UniformVisitor<'a, Gl>) {
+        visitor.accept_color_sampler_cube(path, self);
+    }
+}
+
+unsafe impl<S: sl::ColorSample> Uniform<Sl> for sl::ColorSamplerCube<S> {
+    type Gl = gl::ColorSamplerCube<S>;
+    type Sl = Self;
+
+    fn visit<'a>(&'a self, path: &str, visitor: &mut impl UniformVisitor<'a, Sl>) {
+        visitor.accept_color_sampler_cube(path, self)
+    }
+
+    fn shader_input(path: &str) -> Self {
+        <Self as sl::Object>::from_arg(path)
+    }
+}
+
+unsafe impl Uniform<Gl> for gl::ComparisonSamplerCube {
+    type Gl = gl::ComparisonSamplerCube;
+    type Sl = sl::ComparisonSamplerCube;
+
+    fn visit<'a>(&'a self, path: &str, visitor: &mut impl UniformVisitor<'a, Gl>) {
+        visitor.accept_comparison_sampler_cube(path, self);
+    }
+}
+
+unsafe impl Uniform<Sl> for sl::ComparisonSamplerCube {
+    type Gl = gl::ComparisonSamplerCube;
+    type Sl = sl::ComparisonSamplerCube;
+
+    fn visit<'a>(&'a self, path: &str, visitor: &mut impl UniformVisitor<'a, Sl>) {
+        visitor.accept_comparison_sampler_cube(path, self)
+    }
+
+    fn shader_input(path: &str) -> Self {
+        <Self as sl::Object>::from_arg(path)
+    }
+}
+
 unsafe impl<U, V, D> Uniform<D> for (U, V)
 where
     U: Uniform<D>,
@@ -220,6 +355,27 @@ pub trait UniformVisitor<'a, D: UniformDom> {
         sampler: &'a D::ColorSampler2d<S>,
     );
     fn accept_comparison_sampler_2d(&mut self, path: &str, sampler: &'a D::ComparisonSampler2d);
+    fn accept_color_sampler_2d_array<S: sl::ColorSample>(
+        &mut self,
+        path: &str,
+        sampler: &'a D::ColorSampler2dArray<S>,
+    );
+    fn accept_comparison_sampler_2d_array(
+        &mut self,
+        path: &str,
+        sampler: &'a D::ComparisonSampler2dArray,
+    );
+    fn accept_color_sampler_3d<S: sl::ColorSample>(
+        &mut self,
+        path: &str,
+        sampler: &'a D::ColorSampler3d<S>,
+    );
+    fn accept_color_sampler_cube<S: sl::ColorSample>(
+        &mut self,
+        path: &str,
+        sampler: &'a D::ColorSamplerCube<S>,
+    );
+    fn accept_comparison_sampler_cube(&mut self, path: &str, sampler: &'a D::ComparisonSamplerCube);
 }
 
 /// Non-empty uniform data.
@@ -231,6 +387,16 @@ impl<S: sl::ColorSample> UniformNonUnit for sl::ColorSampler2d<S> {}
 
 impl UniformNonUnit for sl::ComparisonSampler2d {}
 
+impl<S: sl::ColorSample> UniformNonUnit for sl::ColorSampler2dArray<S> {}
+
+impl UniformNonUnit for sl::ComparisonSampler2dArray {}
+
+impl<S: sl::ColorSample> UniformNonUnit for sl::ColorSampler3d<S> {}
+
+impl<S: sl::ColorSample> UniformNonUnit for sl::ColorSamplerCube<S> {}
+
+impl UniformNonUnit for sl::ComparisonSamplerCube {}
+
 impl<U, V> UniformNonUnit for (U, V)
 where
     U: Uniform<Sl>,
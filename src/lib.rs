@@ -56,7 +56,7 @@ pub mod internal {
         interface::{FragmentVisitor, UniformVisitor, VertexField, VertexVisitor},
         sl::{
             dag::{Expr, StructType, Type},
-            primitives::{field, simplify_struct_literal, value_arg},
+            primitives::{call_func_def, field, simplify_struct_literal, value_arg},
             unique_struct_type,
         },
     };
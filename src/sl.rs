@@ -18,6 +18,7 @@ pub(crate) mod codegen;
 pub(crate) mod primitives;
 
 pub mod dag;
+pub mod eval;
 pub mod program_def;
 pub mod transpile;
 
@@ -31,8 +32,11 @@ pub use {
     array::{array, Array},
     interpolant::Interpolant,
     mat::{mat2, mat3, mat4, Mat2, Mat3, Mat4},
-    primitives::{all, and, any, branch, branches, or},
-    sampler::{ColorSample, ColorSampler2d, ComparisonSampler2d, Sample},
+    primitives::{all, and, any, branch, branches, fold_range, fold_range_until, or},
+    sampler::{
+        ColorSample, ColorSampler2d, ColorSampler2dArray, ColorSampler3d, ColorSamplerCube,
+        ComparisonSampler2d, ComparisonSampler2dArray, ComparisonSamplerCube, Sample,
+    },
     scalar::{Bool, F32, I32, U32},
     sig::{
         Const, Derivatives, FromFsInput, FromVsInput, FsFunc, FsInput, FsSig, FullFsOutput,
@@ -44,7 +48,7 @@ pub use {
     },
 };
 
-pub use posh_derive::{Const, Interpolant, Value};
+pub use posh_derive::{function, Const, Interpolant, Value};
 
 use self::dag::BinaryOp;
 
@@ -1,29 +1,36 @@
+mod func_registry;
 mod scope_form;
 mod simplified_expr;
 mod struct_registry;
 mod var_form;
 
+pub mod wgsl;
+
 use std::{
+    collections::BTreeSet,
     fmt::{self, Display, Formatter, Write},
     rc::Rc,
 };
 
-use crate::sl::dag::ArrayType;
+use crate::sl::dag::{ArrayType, BuiltInType};
 
 use super::{
     dag::{Expr, Type},
-    program_def::{UniformBlockDef, UniformSamplerDef},
+    program_def::{PrecisionQualifier, UniformBlockDef, UniformSamplerDef},
+    transpile::{DefaultPrecision, ShaderTarget},
 };
 
 use self::{
+    func_registry::FuncRegistry,
     scope_form::{Scope, ScopeForm, VarInit},
-    simplified_expr::VarId,
+    simplified_expr::{ExprKey, SimplifiedExpr, VarId},
     struct_registry::StructRegistry,
     var_form::VarForm,
 };
 
 #[derive(Debug, Clone)]
 struct WriteFuncContext<'a> {
+    target: ShaderTarget,
     struct_registry: &'a StructRegistry,
     scope_form: &'a ScopeForm<'a>,
     depth: usize,
@@ -40,6 +47,22 @@ impl<'a> WriteFuncContext<'a> {
     fn indent(&self) -> Indent {
         Indent(self.depth)
     }
+
+    fn precision(&self, precision: Option<PrecisionQualifier>, ty: &Type) -> PrecisionPrefix {
+        PrecisionPrefix(precision.filter(|_| self.target.is_embedded() && has_precision(ty)))
+    }
+}
+
+struct PrecisionPrefix(Option<PrecisionQualifier>);
+
+impl Display for PrecisionPrefix {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        if let Some(precision) = self.0 {
+            write!(f, "{} ", precision.to_glsl())?;
+        }
+
+        Ok(())
+    }
 }
 
 struct Indent(usize);
@@ -56,59 +79,112 @@ impl Display for Indent {
 
 pub fn write_shader_stage(
     f: &mut impl Write,
+    target: ShaderTarget,
+    default_precision: DefaultPrecision,
     block_defs: &[UniformBlockDef],
     sampler_defs: &[UniformSamplerDef],
-    attributes: impl Iterator<Item = (String, String, Type)>,
-    outputs: &[(&str, Rc<Expr>)],
+    attributes: impl Iterator<Item = (String, Option<PrecisionQualifier>, String, Type)>,
+    outputs: &[(String, Rc<Expr>)],
 ) -> fmt::Result {
     let roots: Vec<_> = outputs.iter().map(|(_, root)| root.clone()).collect();
     let struct_registry = StructRegistry::new(&roots, block_defs.iter().map(|def| &def.ty));
-    let var_form = VarForm::new(&struct_registry, &roots);
+    let func_registry = FuncRegistry::new(&roots);
+    let var_form = VarForm::new(&struct_registry, &func_registry, &roots);
     let scope_form = ScopeForm::new(&var_form);
 
     let write_context = WriteFuncContext {
+        target,
         struct_registry: &struct_registry,
         scope_form: &scope_form,
         depth: 1,
     };
 
-    // TODO: Specify appropriate GLSL version depending on the target.
-    writeln!(f, "#version 300 es")?;
-    writeln!(f)?;
+    writeln!(f, "{}", target.version_directive())?;
+
+    if !target.has_packing_built_ins() && uses_packing_built_ins(&roots, &func_registry) {
+        writeln!(f, "#extension GL_ARB_shading_language_packing : require")?;
+    }
 
-    // TODO: Make precision configurable.
-    writeln!(f, "precision highp float;")?;
-    writeln!(f, "precision highp int;")?;
-    writeln!(f, "precision highp sampler2DShadow;")?;
-    writeln!(f, "precision highp sampler2D;")?;
     writeln!(f)?;
 
-    write_struct_defs(f, &struct_registry)?;
+    if target.is_embedded() {
+        let DefaultPrecision {
+            float,
+            int,
+            sampler,
+        } = default_precision;
+
+        writeln!(f, "precision {} float;", float.to_glsl())?;
+        writeln!(f, "precision {} int;", int.to_glsl())?;
+        writeln!(f, "precision {} sampler2DShadow;", sampler.to_glsl())?;
+        writeln!(f, "precision {} sampler2D;", sampler.to_glsl())?;
+        writeln!(f, "precision {} isampler2D;", sampler.to_glsl())?;
+        writeln!(f, "precision {} usampler2D;", sampler.to_glsl())?;
+        writeln!(f, "precision {} sampler2DArrayShadow;", sampler.to_glsl())?;
+        writeln!(f, "precision {} sampler2DArray;", sampler.to_glsl())?;
+        writeln!(f, "precision {} isampler2DArray;", sampler.to_glsl())?;
+        writeln!(f, "precision {} usampler2DArray;", sampler.to_glsl())?;
+        writeln!(f, "precision {} sampler3D;", sampler.to_glsl())?;
+        writeln!(f, "precision {} isampler3D;", sampler.to_glsl())?;
+        writeln!(f, "precision {} usampler3D;", sampler.to_glsl())?;
+        writeln!(f, "precision {} samplerCubeShadow;", sampler.to_glsl())?;
+        writeln!(f, "precision {} samplerCube;", sampler.to_glsl())?;
+        writeln!(f, "precision {} isamplerCube;", sampler.to_glsl())?;
+        writeln!(f, "precision {} usamplerCube;", sampler.to_glsl())?;
+        writeln!(f)?;
+    }
+
+    write_struct_defs(f, target, &struct_registry)?;
 
     writeln!(f)?;
 
     for sampler_def in sampler_defs {
-        writeln!(f, "uniform {} {};", sampler_def.ty, sampler_def.name)?;
+        if target.has_explicit_bindings() {
+            write!(f, "layout(binding = {}) ", sampler_def.texture_unit)?;
+        }
+
+        let precision =
+            PrecisionPrefix(Some(default_precision.sampler).filter(|_| target.is_embedded()));
+
+        writeln!(
+            f,
+            "uniform {precision}{} {};",
+            sampler_def.ty, sampler_def.name
+        )?;
     }
 
     for block_def in block_defs {
         let ty_name = type_name(&struct_registry, &block_def.ty);
 
-        writeln!(f, "layout(std140) uniform {} {{", block_def.block_name)?;
-        writeln!(f, "    {} {};", ty_name, block_def.arg_name)?;
+        if target.has_explicit_bindings() {
+            writeln!(
+                f,
+                "layout(std140, binding = {}) uniform {} {{",
+                block_def.location, block_def.block_name
+            )?;
+        } else {
+            writeln!(f, "layout(std140) uniform {} {{", block_def.block_name)?;
+        }
+
+        let precision = uniform_precision(target, &block_def.ty);
+
+        writeln!(f, "    {precision}{} {};", ty_name, block_def.arg_name)?;
         writeln!(f, "}};")?;
     }
 
     writeln!(f)?;
 
-    for (kind, name, ty) in attributes {
+    for (kind, precision, name, ty) in attributes {
+        let precision = write_context.precision(precision, &ty);
         let ty_name = type_name(&struct_registry, &ty);
 
-        writeln!(f, "{kind} {ty_name} {name};")?;
+        writeln!(f, "{kind} {precision}{ty_name} {name};")?;
     }
 
     writeln!(f)?;
 
+    write_func_defs(f, target, &struct_registry, &func_registry)?;
+
     writeln!(f, "void main() {{")?;
     write_scope(f, write_context, scope_form.root_scope())?;
     for ((name, _), simplified_expr) in outputs.iter().zip(var_form.simplified_roots()) {
@@ -119,6 +195,85 @@ pub fn write_shader_stage(
     Ok(())
 }
 
+const PACKING_BUILT_INS: &[&str] = &[
+    "packHalf2x16",
+    "unpackHalf2x16",
+    "packUnorm2x16",
+    "unpackUnorm2x16",
+    "packSnorm2x16",
+    "unpackSnorm2x16",
+];
+
+/// Returns true if `roots` or the functions that they call use any of the
+/// built-in functions for packing floating-point values into integers.
+fn uses_packing_built_ins(roots: &[Rc<Expr>], func_registry: &FuncRegistry) -> bool {
+    let mut visited = BTreeSet::new();
+    let mut stack: Vec<_> = roots
+        .iter()
+        .cloned()
+        .chain(func_registry.defs().map(|(_, def)| def.result.clone()))
+        .collect();
+
+    while let Some(expr) = stack.pop() {
+        if !visited.insert(ExprKey::from(&expr)) {
+            continue;
+        }
+
+        if let Expr::CallBuiltIn { name, .. } = &*expr {
+            if PACKING_BUILT_INS.contains(&name.as_str()) {
+                return true;
+            }
+        }
+
+        expr.successors(|succ| stack.push(succ.clone()));
+    }
+
+    false
+}
+
+fn write_func_defs(
+    f: &mut impl Write,
+    target: ShaderTarget,
+    struct_registry: &StructRegistry,
+    func_registry: &FuncRegistry,
+) -> fmt::Result {
+    for (name, def) in func_registry.defs() {
+        let roots = [def.result.clone()];
+        let var_form = VarForm::new(struct_registry, func_registry, &roots);
+        let scope_form = ScopeForm::new(&var_form);
+
+        let write_context = WriteFuncContext {
+            target,
+            struct_registry,
+            scope_form: &scope_form,
+            depth: 1,
+        };
+
+        let result_ty_name = type_name(struct_registry, &def.result.ty());
+        let params = def
+            .params
+            .iter()
+            .map(|(param_name, param_ty)| {
+                format!("{} {param_name}", type_name(struct_registry, param_ty))
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        writeln!(f, "{result_ty_name} {name}({params}) {{")?;
+
+        if write_scope(f, write_context, scope_form.root_scope())? {
+            let result = var_form.simplified_roots()[0];
+
+            writeln!(f, "    return {result};")?;
+        }
+
+        writeln!(f, "}}")?;
+        writeln!(f)?;
+    }
+
+    Ok(())
+}
+
 fn write_var(
     f: &mut impl Write,
     ctx: WriteFuncContext,
@@ -131,9 +286,15 @@ fn write_var(
 
     match var_init {
         Expr(expr) => {
-            let ty_name = type_name(ctx.struct_registry, &expr.ty());
+            let ty = expr.ty();
+            let precision = match expr {
+                SimplifiedExpr::Precision { precision, .. } => Some(*precision),
+                _ => None,
+            };
+            let precision = ctx.precision(precision, &ty);
+            let ty_name = type_name(ctx.struct_registry, &ty);
 
-            writeln!(f, "{indent}{ty_name} {var_id} = {expr};")?;
+            writeln!(f, "{indent}{precision}{ty_name} {var_id} = {expr};")?;
 
             Ok(true)
         }
@@ -200,6 +361,52 @@ fn write_var(
 
             Ok(false)
         }
+        Loop {
+            id,
+            start,
+            end,
+            init,
+            body_id,
+            stop,
+            ty,
+        } => {
+            let ty_name = type_name(ctx.struct_registry, ty);
+            let body_scope = ctx.scope_form.scope(*body_id);
+            let acc = id.acc_name();
+            let i = id.index_name();
+
+            writeln!(f, "{indent}{ty_name} {acc} = {init};")?;
+            writeln!(f, "{indent}for (uint {i} = {start}; {i} < {end}; {i}++) {{")?;
+
+            {
+                let ctx = ctx.nest();
+                let indent = ctx.indent();
+
+                let next = body_scope.result.unwrap();
+
+                if write_scope(f, ctx, body_scope)? {
+                    if let Some(stop) = stop {
+                        // The stop condition must be evaluated before the
+                        // accumulator is updated, since it may refer to the
+                        // accumulator's current value.
+                        let stop_name = id.stop_name();
+
+                        writeln!(f, "{indent}bool {stop_name} = {stop};")?;
+                        writeln!(f, "{indent}{acc} = {next};")?;
+                        writeln!(f, "{indent}if ({stop_name}) {{")?;
+                        writeln!(f, "{indent}    break;")?;
+                        writeln!(f, "{indent}}}")?;
+                    } else {
+                        writeln!(f, "{indent}{acc} = {next};")?;
+                    }
+                }
+            }
+
+            writeln!(f, "{indent}}}")?;
+            writeln!(f, "{indent}{ty_name} {var_id} = {acc};")?;
+
+            Ok(true)
+        }
     }
 }
 
@@ -217,14 +424,27 @@ fn write_scope(
     Ok(true)
 }
 
-fn write_struct_defs(f: &mut impl Write, struct_reg: &StructRegistry) -> fmt::Result {
+fn write_struct_defs(
+    f: &mut impl Write,
+    target: ShaderTarget,
+    struct_reg: &StructRegistry,
+) -> fmt::Result {
     for (name, ty) in struct_reg.defs() {
+        // The structs of uniform blocks are the only ones that are shared
+        // between the shader stages.
+        let is_uniform = struct_reg.is_in_extra_types(ty);
+
         writeln!(f, "struct {name} {{")?;
 
         for (field_name, field_ty) in ty.fields.iter() {
+            let precision = if is_uniform {
+                uniform_precision(target, field_ty)
+            } else {
+                PrecisionPrefix(None)
+            };
             let field_ty_name = type_name(struct_reg, field_ty);
 
-            writeln!(f, "    {field_ty_name} {field_name};")?;
+            writeln!(f, "    {precision}{field_ty_name} {field_name};")?;
         }
 
         writeln!(f, "}};")?;
@@ -233,6 +453,29 @@ fn write_struct_defs(f: &mut impl Write, struct_reg: &StructRegistry) -> fmt::Re
     Ok(())
 }
 
+// Uniforms that are shared by the vertex and the fragment shader need to be
+// declared with the same precision in both stages, but the stages have
+// different default precisions. Thus, the members of uniform blocks are always
+// declared with an explicit `highp` qualifier.
+fn uniform_precision(target: ShaderTarget, ty: &Type) -> PrecisionPrefix {
+    PrecisionPrefix(
+        Some(PrecisionQualifier::High).filter(|_| target.is_embedded() && has_precision(ty)),
+    )
+}
+
+// Precision qualifiers can only be given for floating-point and integer types
+// and arrays thereof.
+fn has_precision(ty: &Type) -> bool {
+    use BuiltInType::*;
+
+    match ty {
+        Type::BuiltIn(Bool | BVec2 | BVec3 | BVec4) => false,
+        Type::BuiltIn(ty) => ty.is_transparent(),
+        Type::Struct(_) => false,
+        Type::Array(ArrayType { ty, .. }) => has_precision(ty),
+    }
+}
+
 fn type_name(struct_reg: &StructRegistry, ty: &Type) -> String {
     use Type::*;
 
@@ -0,0 +1,82 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    rc::Rc,
+};
+
+use crate::sl::dag::{Expr, FuncDef};
+
+use super::simplified_expr::ExprKey;
+
+type FuncId = usize;
+
+#[derive(Debug, Clone)]
+pub struct FuncRegistry {
+    defs: Vec<FuncDef>,
+    ids: BTreeMap<ExprKey, FuncId>,
+}
+
+impl FuncRegistry {
+    pub fn new(roots: &[Rc<Expr>]) -> Self {
+        let mut visited = BTreeSet::new();
+        let mut visited_funcs = BTreeSet::new();
+        let mut defs = Vec::new();
+
+        for expr in roots {
+            collect_funcs_in_expr(expr, &mut visited, &mut visited_funcs, &mut defs);
+        }
+
+        let ids = defs
+            .iter()
+            .enumerate()
+            .map(|(id, def)| (ExprKey::from(&def.result), id))
+            .collect();
+
+        Self { defs, ids }
+    }
+
+    pub fn name(&self, def: &FuncDef) -> String {
+        func_name(def.name, self.ids[&(&def.result).into()])
+    }
+
+    /// Returns the function definitions, ordered such that every function only
+    /// calls functions that come before it.
+    pub fn defs(&self) -> impl Iterator<Item = (String, &FuncDef)> + '_ {
+        self.defs
+            .iter()
+            .enumerate()
+            .map(|(id, def)| (func_name(def.name, id), def))
+    }
+}
+
+fn func_name(name: &str, id: FuncId) -> String {
+    format!("{name}_Posh{id}")
+}
+
+fn collect_funcs_in_expr(
+    expr: &Rc<Expr>,
+    visited: &mut BTreeSet<ExprKey>,
+    visited_funcs: &mut BTreeSet<ExprKey>,
+    defs: &mut Vec<FuncDef>,
+) {
+    if visited.contains(&expr.into()) {
+        return;
+    }
+
+    visited.insert(expr.into());
+
+    expr.successors(|succ| collect_funcs_in_expr(succ, visited, visited_funcs, defs));
+
+    if let Expr::CallFuncDef { def, .. } = &**expr {
+        // Function definitions are cached, so all call sites of a function
+        // share the same body.
+        if !visited_funcs.insert((&def.result).into()) {
+            return;
+        }
+
+        // Visit the body before adding the definition, so that functions
+        // called in the body are defined first.
+        collect_funcs_in_expr(&def.result, visited, visited_funcs, defs);
+
+        defs.push(def.clone());
+    }
+}
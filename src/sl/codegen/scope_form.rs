@@ -3,7 +3,7 @@ use std::collections::{BTreeMap, BTreeSet};
 use crate::sl::dag::Type;
 
 use super::{
-    simplified_expr::{SimplifiedExpr, VarId},
+    simplified_expr::{LoopId, SimplifiedExpr, VarId},
     var_form::VarForm,
 };
 
@@ -19,6 +19,15 @@ pub enum VarInit<'a> {
         ty: &'a Type,
     },
     Discard,
+    Loop {
+        id: LoopId,
+        start: &'a SimplifiedExpr,
+        end: &'a SimplifiedExpr,
+        init: &'a SimplifiedExpr,
+        body_id: ScopeId,
+        stop: Option<&'a SimplifiedExpr>,
+        ty: &'a Type,
+    },
 }
 
 #[derive(Debug, Clone, Default)]
@@ -95,6 +104,42 @@ impl<'a> ScopeForm<'a> {
                         ty,
                     }
                 }
+                Loop {
+                    id,
+                    start,
+                    end,
+                    init,
+                    next,
+                    stop,
+                    ty,
+                } => {
+                    scope_form.insert_deps(parent_id, start);
+                    scope_form.insert_deps(parent_id, end);
+                    scope_form.insert_deps(parent_id, init);
+
+                    let stop = stop.as_deref();
+                    let body_exprs: Vec<_> = std::iter::once(&**next).chain(stop).collect();
+
+                    let body_id = scope_form.add(
+                        Scope {
+                            parent_id: Some(parent_id),
+                            depth: scope_form.scopes[&parent_id].depth + 1,
+                            vars: BTreeMap::new(),
+                            result: Some(next),
+                        },
+                        &body_exprs,
+                    );
+
+                    VarInit::Loop {
+                        id: *id,
+                        start,
+                        end,
+                        init,
+                        body_id,
+                        stop,
+                        ty,
+                    }
+                }
                 Arg { .. }
                 | ScalarLiteral { .. }
                 | Unary { .. }
@@ -102,7 +147,8 @@ impl<'a> ScopeForm<'a> {
                 | CallFunc { .. }
                 | Subscript { .. }
                 | Field { .. }
-                | Var { .. } => {
+                | Var { .. }
+                | Precision { .. } => {
                     scope_form.insert_deps(parent_id, var_expr);
 
                     VarInit::Expr(var_expr)
@@ -178,12 +224,19 @@ fn unscoped_successors(expr: &SimplifiedExpr, f: &mut impl FnMut(VarId)) {
         Branch { cond, .. } => {
             unscoped_successors(cond, f);
         }
+        Loop {
+            start, end, init, ..
+        } => {
+            unscoped_successors(start, f);
+            unscoped_successors(end, f);
+            unscoped_successors(init, f);
+        }
         Arg { .. } | ScalarLiteral { .. } => (),
         Binary { left, right, .. } => {
             unscoped_successors(left, f);
             unscoped_successors(right, f);
         }
-        Unary { arg, .. } => {
+        Unary { arg, .. } | Precision { arg, .. } => {
             unscoped_successors(arg, f);
         }
         CallFunc { args, .. } => {
@@ -3,7 +3,10 @@ use std::{
     rc::Rc,
 };
 
-use crate::sl::dag::{BinaryOp, BuiltInType, Expr, Type, UnaryOp};
+use crate::sl::{
+    dag::{BinaryOp, BuiltInType, Expr, Type, UnaryOp},
+    program_def::PrecisionQualifier,
+};
 
 #[derive(Debug, Copy, Clone, Hash, PartialEq, Eq, PartialOrd, Ord)]
 pub struct ExprKey(*const Expr);
@@ -17,6 +20,23 @@ impl<'a> From<&'a Rc<Expr>> for ExprKey {
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct VarId(pub usize);
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct LoopId(pub usize);
+
+impl LoopId {
+    pub fn acc_name(&self) -> String {
+        format!("{self}_acc")
+    }
+
+    pub fn index_name(&self) -> String {
+        format!("{self}_i")
+    }
+
+    pub fn stop_name(&self) -> String {
+        format!("{self}_stop")
+    }
+}
+
 #[derive(Debug, Clone)]
 pub enum SimplifiedExpr {
     Arg {
@@ -66,6 +86,20 @@ pub enum SimplifiedExpr {
     Discard {
         ty: Type,
     },
+    Loop {
+        id: LoopId,
+        start: Box<SimplifiedExpr>,
+        end: Box<SimplifiedExpr>,
+        init: Box<SimplifiedExpr>,
+        next: Box<SimplifiedExpr>,
+        stop: Option<Box<SimplifiedExpr>>,
+        ty: Type,
+    },
+    Precision {
+        arg: Box<SimplifiedExpr>,
+        precision: PrecisionQualifier,
+        ty: Type,
+    },
 }
 
 impl SimplifiedExpr {
@@ -83,6 +117,8 @@ impl SimplifiedExpr {
             Subscript { ty, .. } => ty.clone(),
             Var { ty, .. } => ty.clone(),
             Discard { ty, .. } => ty.clone(),
+            Loop { ty, .. } => ty.clone(),
+            Precision { ty, .. } => ty.clone(),
         }
     }
 }
@@ -108,6 +144,12 @@ impl Display for VarId {
     }
 }
 
+impl Display for LoopId {
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        write!(f, "loop_{}", self.0)
+    }
+}
+
 impl Display for SimplifiedExpr {
     fn fmt(&self, f: &mut Formatter) -> Result {
         use SimplifiedExpr::*;
@@ -138,6 +180,9 @@ impl Display for SimplifiedExpr {
             Subscript { base, index, .. } => write!(f, "{base}[{index}]"),
             Var { id, .. } => write!(f, "{id}"),
             Discard { .. } => write!(f, "discard"),
+            Loop { id, .. } => write!(f, "{}", id.acc_name()),
+            // The precision qualifier is written in the variable declaration.
+            Precision { arg, .. } => write!(f, "{arg}"),
         }
     }
 }
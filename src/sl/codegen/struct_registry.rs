@@ -22,6 +22,7 @@ impl<'a> From<&'a Rc<StructType>> for StructKey {
 pub struct StructRegistry {
     defs: Vec<Rc<StructType>>,
     ids: BTreeMap<StructKey, StructId>,
+    extra_structs: BTreeSet<StructKey>,
 }
 
 impl StructRegistry {
@@ -36,6 +37,8 @@ impl StructRegistry {
             collect_structs_in_type(ty, &mut structs, &mut structs_insertion_order);
         }
 
+        let extra_structs = structs.keys().copied().collect();
+
         {
             let mut visited = BTreeSet::new();
 
@@ -56,13 +59,23 @@ impl StructRegistry {
             .map(|(id, ty)| (StructKey::from(ty), id))
             .collect();
 
-        Self { defs, ids }
+        Self {
+            defs,
+            ids,
+            extra_structs,
+        }
     }
 
     pub fn name(&self, ty: &Rc<StructType>) -> String {
         struct_name(&ty.name, self.ids[&ty.into()])
     }
 
+    /// Returns true if `ty` occurs in one of the `extra_types` that the
+    /// registry was created with.
+    pub fn is_in_extra_types(&self, ty: &StructType) -> bool {
+        self.extra_structs.contains(&StructKey(ty as *const _))
+    }
+
     pub fn defs(&self) -> impl Iterator<Item = (String, &StructType)> + '_ {
         self.defs
             .iter()
@@ -123,6 +136,14 @@ fn collect_structs_in_expr(
     expr.successors(|succ| {
         collect_structs_in_expr(succ, visited, structs, structs_insertion_order)
     });
+
+    if let Expr::CallFuncDef { def, .. } = &**expr {
+        for (_, param_ty) in &def.params {
+            collect_structs_in_type(param_ty, structs, structs_insertion_order);
+        }
+
+        collect_structs_in_expr(&def.result, visited, structs, structs_insertion_order);
+    }
 }
 
 fn visit(
@@ -6,7 +6,8 @@ use std::{
 use crate::sl::dag::{Expr, Type};
 
 use super::{
-    simplified_expr::{ExprKey, SimplifiedExpr, VarId},
+    func_registry::FuncRegistry,
+    simplified_expr::{ExprKey, LoopId, SimplifiedExpr, VarId},
     struct_registry::StructRegistry,
 };
 
@@ -14,11 +15,16 @@ use super::{
 pub struct VarForm {
     var_exprs: Vec<SimplifiedExpr>,
     simplified_exprs: BTreeMap<ExprKey, SimplifiedExpr>,
+    loop_ids: BTreeMap<ExprKey, LoopId>,
     roots: Vec<ExprKey>,
 }
 
 impl VarForm {
-    pub fn new(struct_registry: &StructRegistry, roots: &[Rc<Expr>]) -> Self {
+    pub fn new(
+        struct_registry: &StructRegistry,
+        func_registry: &FuncRegistry,
+        roots: &[Rc<Expr>],
+    ) -> Self {
         let mut var_form = Self {
             roots: roots.iter().map(ExprKey::from).collect(),
             ..Self::default()
@@ -27,11 +33,41 @@ impl VarForm {
         let topo = topological_ordering(roots);
         let usages = count_usages(&topo);
 
+        // The placeholder arguments of loops need to be known before visiting
+        // the loop bodies, since they are referenced by name in the bodies.
+        for expr in &topo {
+            if let Expr::Loop { acc, index, .. } = &**expr {
+                let id = LoopId(var_form.loop_ids.len());
+
+                var_form.loop_ids.insert(expr.into(), id);
+                var_form.simplified_exprs.insert(
+                    acc.into(),
+                    SimplifiedExpr::Arg {
+                        name: id.acc_name(),
+                        ty: acc.ty(),
+                    },
+                );
+                var_form.simplified_exprs.insert(
+                    index.into(),
+                    SimplifiedExpr::Arg {
+                        name: id.index_name(),
+                        ty: index.ty(),
+                    },
+                );
+            }
+        }
+
         for expr in &topo {
             let key = ExprKey::from(expr);
+
+            if var_form.simplified_exprs.contains_key(&key) {
+                // Loop placeholder arguments have already been mapped.
+                continue;
+            }
+
             let count = usages.get(&key).copied().unwrap_or(0);
 
-            let simplified_expr = var_form.map_expr(struct_registry, (**expr).clone());
+            let simplified_expr = var_form.map_expr(struct_registry, func_registry, expr);
 
             if var_form.should_have_var(count, expr) && Self::can_have_var(expr) {
                 let var_id = VarId(var_form.var_exprs.len());
@@ -66,10 +102,15 @@ impl VarForm {
             .collect()
     }
 
-    fn map_expr(&self, struct_registry: &StructRegistry, expr: Expr) -> SimplifiedExpr {
+    fn map_expr(
+        &self,
+        struct_registry: &StructRegistry,
+        func_registry: &FuncRegistry,
+        expr: &Rc<Expr>,
+    ) -> SimplifiedExpr {
         let map_succ = |succ: Rc<Expr>| self.simplified_exprs[&ExprKey::from(&succ)].clone();
 
-        match expr {
+        match (**expr).clone() {
             Expr::Arg { name, ty } => SimplifiedExpr::Arg { name, ty },
             Expr::ScalarLiteral { value, ty } => SimplifiedExpr::ScalarLiteral { value, ty },
             Expr::StructLiteral { args, ty } => SimplifiedExpr::CallFunc {
@@ -98,14 +139,11 @@ impl VarForm {
                 right: Box::new(map_succ(right)),
                 ty,
             },
-            Expr::CallFuncDef { def, args } => {
-                // TODO: Resolve function name through a prebuilt registry.
-                SimplifiedExpr::CallFunc {
-                    name: def.name.to_string(),
-                    args: args.into_iter().map(map_succ).collect(),
-                    ty: def.result.ty(),
-                }
-            }
+            Expr::CallFuncDef { def, args } => SimplifiedExpr::CallFunc {
+                name: func_registry.name(&def),
+                args: args.into_iter().map(map_succ).collect(),
+                ty: def.result.ty(),
+            },
             Expr::CallBuiltIn { name, args, ty } => SimplifiedExpr::CallFunc {
                 name,
                 args: args.into_iter().map(map_succ).collect(),
@@ -128,6 +166,28 @@ impl VarForm {
                 ty,
             },
             Expr::Discard { ty } => SimplifiedExpr::Discard { ty },
+            Expr::Loop {
+                start,
+                end,
+                init,
+                next,
+                stop,
+                ty,
+                ..
+            } => SimplifiedExpr::Loop {
+                id: self.loop_ids[&ExprKey::from(expr)],
+                start: Box::new(map_succ(start)),
+                end: Box::new(map_succ(end)),
+                init: Box::new(map_succ(init)),
+                next: Box::new(map_succ(next)),
+                stop: stop.map(|stop| Box::new(map_succ(stop))),
+                ty,
+            },
+            Expr::Precision { arg, precision, ty } => SimplifiedExpr::Precision {
+                arg: Box::new(map_succ(arg)),
+                precision,
+                ty,
+            },
         }
     }
 
@@ -137,6 +197,8 @@ impl VarForm {
         match expr {
             Branch { .. } => true,
             Discard { .. } => true,
+            Loop { .. } => true,
+            Precision { .. } => true,
             ArrayLiteral { .. } => true,
             Arg { .. } | ScalarLiteral { .. } => false,
             Field { base, .. } => {
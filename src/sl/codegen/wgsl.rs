@@ -0,0 +1,1413 @@
+use std::{
+    collections::BTreeSet,
+    fmt::{self, Display, Formatter, Write},
+    rc::Rc,
+};
+
+use crate::sl::{
+    dag::{ArrayType, BinaryOp, BuiltInType, Expr, SamplerType, Type, UnaryOp},
+    program_def::{InterpolationQualifier, ProgramDef, WgslProgramDef},
+    transpile::WgslError,
+};
+
+use super::{
+    func_registry::FuncRegistry,
+    scope_form::{Scope, ScopeForm, VarInit},
+    simplified_expr::{ExprKey, SimplifiedExpr, VarId},
+    struct_registry::StructRegistry,
+    var_form::VarForm,
+    Indent,
+};
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Stage {
+    Vertex,
+    Fragment,
+}
+
+#[derive(Debug, Clone)]
+struct WriteFuncContext<'a> {
+    struct_registry: &'a StructRegistry,
+    scope_form: &'a ScopeForm<'a>,
+    stage: Stage,
+    depth: usize,
+}
+
+impl<'a> WriteFuncContext<'a> {
+    fn nest(&self) -> Self {
+        Self {
+            depth: self.depth + 1,
+            ..self.clone()
+        }
+    }
+
+    fn indent(&self) -> Indent {
+        Indent(self.depth)
+    }
+
+    fn expr(&self, expr: &'a SimplifiedExpr) -> WgslExpr<'a> {
+        WgslExpr {
+            expr,
+            struct_registry: self.struct_registry,
+            stage: self.stage,
+        }
+    }
+
+    fn type_name(&self, ty: &Type) -> String {
+        type_name(self.struct_registry, ty)
+    }
+}
+
+/// Checks that the expressions of `program_def` can be expressed in WGSL.
+pub fn check(program_def: &ProgramDef) -> Result<(), WgslError> {
+    let mut visited = BTreeSet::new();
+
+    for (name, expr) in &program_def.vertex_shader_exprs {
+        if name == "gl_PointSize" {
+            return Err(WgslError::Unsupported("setting the point size".to_string()));
+        }
+
+        check_expr(expr, Stage::Vertex, &mut visited)?;
+    }
+
+    // Function definitions may be shared between stages, but the checks
+    // depend on the stage, so we visit the fragment shader separately.
+    let mut visited = BTreeSet::new();
+
+    for (_, expr) in &program_def.fragment_shader_exprs {
+        check_expr(expr, Stage::Fragment, &mut visited)?;
+    }
+
+    Ok(())
+}
+
+fn check_expr(
+    expr: &Rc<Expr>,
+    stage: Stage,
+    visited: &mut BTreeSet<ExprKey>,
+) -> Result<(), WgslError> {
+    if !visited.insert(expr.into()) {
+        return Ok(());
+    }
+
+    let mut result = Ok(());
+
+    expr.successors(|succ| {
+        if result.is_ok() {
+            result = check_expr(succ, stage, visited);
+        }
+    });
+
+    result?;
+
+    match &**expr {
+        Expr::Arg { name, .. } if name == "gl_PointCoord" => Err(WgslError::Unsupported(
+            "reading the point coordinate".to_string(),
+        )),
+        Expr::CallFuncDef { def, .. } => {
+            if def
+                .params
+                .iter()
+                .any(|(_, ty)| matches!(ty, Type::BuiltIn(BuiltInType::Sampler(_))))
+            {
+                return Err(WgslError::Unsupported(format!(
+                    "passing samplers to function `{}`",
+                    def.name
+                )));
+            }
+
+            check_expr(&def.result, stage, visited)
+        }
+        Expr::CallBuiltIn { name, args, .. } => check_built_in(name, args, stage),
+        _ => Ok(()),
+    }
+}
+
+fn check_built_in(name: &str, args: &[Rc<Expr>], stage: Stage) -> Result<(), WgslError> {
+    let unsupported = || Err(WgslError::UnsupportedBuiltIn(name.to_string()));
+
+    if name == "inverse" {
+        return unsupported();
+    }
+
+    let Some(Type::BuiltIn(BuiltInType::Sampler(sampler))) = args.first().map(|arg| arg.ty())
+    else {
+        return Ok(());
+    };
+
+    use SamplerType::*;
+
+    match (sampler, name, args.len()) {
+        (_, "textureSize", _) => Ok(()),
+        (
+            ColorSampler2d | ColorSampler2dArray | ColorSampler3d | ColorSamplerCube,
+            "texture",
+            3,
+        ) if stage == Stage::Vertex => unsupported(),
+        (ColorSampler2d | ColorSampler2dArray | ColorSampler3d | ColorSamplerCube, _, _) => Ok(()),
+        (
+            IColorSampler2d | UColorSampler2d | IColorSampler2dArray | UColorSampler2dArray
+            | IColorSampler3d | UColorSampler3d,
+            "texelFetch",
+            _,
+        ) => Ok(()),
+        (ComparisonSampler2d | ComparisonSampler2dArray | ComparisonSamplerCube, "texture", 2) => {
+            Ok(())
+        }
+        (ComparisonSampler2d, "textureOffset" | "textureProj", _) => Ok(()),
+        _ => unsupported(),
+    }
+}
+
+/// Writes a WGSL module that contains both stages of `program_def`.
+///
+/// The bindings and locations are taken from `wgsl_def`. The program must
+/// have passed [`check`].
+pub fn write_module(
+    f: &mut impl Write,
+    program_def: &ProgramDef,
+    wgsl_def: &WgslProgramDef,
+) -> fmt::Result {
+    let vertex_roots: Vec<_> = program_def
+        .vertex_shader_exprs
+        .iter()
+        .map(|(_, root)| root.clone())
+        .collect();
+    let fragment_roots: Vec<_> = program_def
+        .fragment_shader_exprs
+        .iter()
+        .map(|(_, root)| root.clone())
+        .collect();
+    let all_roots: Vec<_> = vertex_roots
+        .iter()
+        .chain(&fragment_roots)
+        .cloned()
+        .collect();
+
+    let struct_registry = StructRegistry::new(
+        &all_roots,
+        program_def.uniform_block_defs.iter().map(|def| &def.ty),
+    );
+    let func_registry = FuncRegistry::new(&all_roots);
+
+    // GLSL allows sampling textures with implicit derivatives in non-uniform
+    // control flow, so we need to turn off the corresponding WGSL error.
+    writeln!(f, "diagnostic(off, derivative_uniformity);")?;
+    writeln!(f)?;
+
+    write_struct_defs(f, &struct_registry)?;
+    write_bindings(f, &struct_registry, wgsl_def)?;
+    write_private_vars(f, &struct_registry, wgsl_def)?;
+    write_func_defs(f, &struct_registry, &func_registry)?;
+    write_vertex_entry_point(
+        f,
+        &struct_registry,
+        &func_registry,
+        program_def,
+        wgsl_def,
+        &vertex_roots,
+    )?;
+    writeln!(f)?;
+    write_fragment_entry_point(
+        f,
+        &struct_registry,
+        &func_registry,
+        program_def,
+        wgsl_def,
+        &fragment_roots,
+    )?;
+
+    Ok(())
+}
+
+fn write_struct_defs(f: &mut impl Write, struct_registry: &StructRegistry) -> fmt::Result {
+    for (name, ty) in struct_registry.defs() {
+        writeln!(f, "struct {name} {{")?;
+
+        for (field_name, field_ty) in ty.fields.iter() {
+            let field_ty_name = type_name(struct_registry, field_ty);
+
+            writeln!(f, "    {field_name}: {field_ty_name},")?;
+        }
+
+        writeln!(f, "}}")?;
+        writeln!(f)?;
+    }
+
+    Ok(())
+}
+
+fn write_bindings(
+    f: &mut impl Write,
+    struct_registry: &StructRegistry,
+    wgsl_def: &WgslProgramDef,
+) -> fmt::Result {
+    for block_def in &wgsl_def.uniform_block_defs {
+        let ty_name = type_name(struct_registry, &block_def.ty);
+
+        writeln!(
+            f,
+            "{} var<uniform> {}: {ty_name};",
+            block_def.binding, block_def.name
+        )?;
+    }
+
+    for sampler_def in &wgsl_def.uniform_sampler_defs {
+        let ty_name = built_in_type_name(BuiltInType::Sampler(sampler_def.ty));
+
+        writeln!(
+            f,
+            "{} var {}: {ty_name};",
+            sampler_def.texture_binding,
+            texture_name(&sampler_def.name)
+        )?;
+
+        if let Some(sampler_binding) = sampler_def.sampler_binding {
+            let sampler_ty_name = match sampler_def.ty {
+                SamplerType::ComparisonSampler2d
+                | SamplerType::ComparisonSampler2dArray
+                | SamplerType::ComparisonSamplerCube => "sampler_comparison",
+                _ => "sampler",
+            };
+
+            writeln!(
+                f,
+                "{sampler_binding} var {}: {sampler_ty_name};",
+                sampler_name(&sampler_def.name)
+            )?;
+        }
+    }
+
+    writeln!(f)
+}
+
+// Shader inputs are stored in private variables, so that they can be referred
+// to by name in the same way as in GLSL.
+fn write_private_vars(
+    f: &mut impl Write,
+    struct_registry: &StructRegistry,
+    wgsl_def: &WgslProgramDef,
+) -> fmt::Result {
+    writeln!(f, "var<private> gl_VertexID: i32;")?;
+    writeln!(f, "var<private> gl_InstanceID: i32;")?;
+
+    for attribute_def in wgsl_def
+        .vertex_block_defs
+        .iter()
+        .flat_map(|block_def| &block_def.attributes)
+    {
+        let ty_name = built_in_type_name(attribute_def.ty);
+
+        writeln!(f, "var<private> {}: {ty_name};", attribute_def.name)?;
+    }
+
+    writeln!(f, "var<private> gl_FragCoord: vec4<f32>;")?;
+    writeln!(f, "var<private> gl_FrontFacing: bool;")?;
+
+    for varying_def in &wgsl_def.varying_defs {
+        let ty_name = type_name(struct_registry, &varying_def.ty);
+
+        writeln!(f, "var<private> {}: {ty_name};", varying_def.name)?;
+    }
+
+    writeln!(f)
+}
+
+fn write_func_defs(
+    f: &mut impl Write,
+    struct_registry: &StructRegistry,
+    func_registry: &FuncRegistry,
+) -> fmt::Result {
+    for (name, def) in func_registry.defs() {
+        let roots = [def.result.clone()];
+        let var_form = VarForm::new(struct_registry, func_registry, &roots);
+        let scope_form = ScopeForm::new(&var_form);
+
+        // Function bodies are shared by both stages. Since samplers cannot be
+        // passed to functions, the stage only matters for fragment-only
+        // built-ins such as derivatives.
+        let write_context = WriteFuncContext {
+            struct_registry,
+            scope_form: &scope_form,
+            stage: Stage::Fragment,
+            depth: 1,
+        };
+
+        let result_ty_name = type_name(struct_registry, &def.result.ty());
+        let params = def
+            .params
+            .iter()
+            .map(|(param_name, param_ty)| {
+                format!("{param_name}: {}", type_name(struct_registry, param_ty))
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        writeln!(f, "fn {name}({params}) -> {result_ty_name} {{")?;
+
+        if write_scope(f, write_context.clone(), scope_form.root_scope())? {
+            let result = write_context.expr(var_form.simplified_roots()[0]);
+
+            writeln!(f, "    return {result};")?;
+        } else {
+            // The invocation has been discarded, so the result is irrelevant.
+            writeln!(f, "    return {result_ty_name}();")?;
+        }
+
+        writeln!(f, "}}")?;
+        writeln!(f)?;
+    }
+
+    Ok(())
+}
+
+fn write_vertex_entry_point(
+    f: &mut impl Write,
+    struct_registry: &StructRegistry,
+    func_registry: &FuncRegistry,
+    program_def: &ProgramDef,
+    wgsl_def: &WgslProgramDef,
+    roots: &[Rc<Expr>],
+) -> fmt::Result {
+    let attribute_defs = || {
+        wgsl_def
+            .vertex_block_defs
+            .iter()
+            .flat_map(|block_def| &block_def.attributes)
+    };
+
+    writeln!(f, "struct VertexInput_Posh {{")?;
+    writeln!(f, "    @builtin(vertex_index) vertex_index: u32,")?;
+    writeln!(f, "    @builtin(instance_index) instance_index: u32,")?;
+
+    for attribute_def in attribute_defs() {
+        let ty = attribute_input_type(attribute_def.ty);
+
+        for (i, name) in columns(&attribute_def.name, attribute_def.ty) {
+            let location = attribute_def.location + i;
+
+            writeln!(f, "    @location({location}) {name}: {ty},")?;
+        }
+    }
+
+    writeln!(f, "}}")?;
+    writeln!(f)?;
+
+    writeln!(f, "struct VertexOutput_Posh {{")?;
+    writeln!(f, "    @builtin(position) gl_Position: vec4<f32>,")?;
+    write_varying_fields(f, struct_registry, wgsl_def)?;
+    writeln!(f, "}}")?;
+    writeln!(f)?;
+
+    let var_form = VarForm::new(struct_registry, func_registry, roots);
+    let scope_form = ScopeForm::new(&var_form);
+    let write_context = WriteFuncContext {
+        struct_registry,
+        scope_form: &scope_form,
+        stage: Stage::Vertex,
+        depth: 1,
+    };
+
+    writeln!(f, "@vertex")?;
+    writeln!(
+        f,
+        "fn {}(input: VertexInput_Posh) -> VertexOutput_Posh {{",
+        wgsl_def.vertex_entry_point
+    )?;
+    writeln!(f, "    gl_VertexID = i32(input.vertex_index);")?;
+    writeln!(f, "    gl_InstanceID = i32(input.instance_index);")?;
+
+    for attribute_def in attribute_defs() {
+        let name = &attribute_def.name;
+
+        match attribute_def.ty {
+            BuiltInType::Bool => writeln!(f, "    {name} = input.{name} != 0u;")?,
+            ty if ty.is_mat() => {
+                let args = columns(name, ty)
+                    .map(|(_, column)| format!("input.{column}"))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+
+                writeln!(f, "    {name} = {}({args});", built_in_type_name(ty))?;
+            }
+            _ => writeln!(f, "    {name} = input.{name};")?,
+        }
+    }
+
+    writeln!(f)?;
+
+    if !write_scope(f, write_context.clone(), scope_form.root_scope())? {
+        writeln!(f, "    return VertexOutput_Posh();")?;
+        writeln!(f, "}}")?;
+
+        return Ok(());
+    }
+
+    writeln!(f, "    var output: VertexOutput_Posh;")?;
+
+    let outputs = program_def.vertex_shader_exprs.iter().map(|(name, _)| name);
+
+    for (name, root) in outputs.zip(var_form.simplified_roots()) {
+        let root = write_context.expr(root);
+
+        match root.expr.ty() {
+            Type::BuiltIn(ty) if ty.is_mat() => {
+                for (i, column) in columns(name, ty) {
+                    writeln!(f, "    output.{column} = {root}[{i}];")?;
+                }
+            }
+            _ => writeln!(f, "    output.{name} = {root};")?,
+        }
+    }
+
+    // Clip-space depth ranges from `-w` to `w` in OpenGL, but from zero to `w`
+    // in WebGPU. Remapping it gives the same window-space depth as in OpenGL.
+    writeln!(
+        f,
+        "    output.gl_Position.z = (output.gl_Position.z + output.gl_Position.w) * 0.5;"
+    )?;
+    writeln!(f, "    return output;")?;
+    writeln!(f, "}}")
+}
+
+fn write_fragment_entry_point(
+    f: &mut impl Write,
+    struct_registry: &StructRegistry,
+    func_registry: &FuncRegistry,
+    program_def: &ProgramDef,
+    wgsl_def: &WgslProgramDef,
+    roots: &[Rc<Expr>],
+) -> fmt::Result {
+    writeln!(f, "struct FragmentInput_Posh {{")?;
+    writeln!(f, "    @builtin(position) frag_coord: vec4<f32>,")?;
+    writeln!(f, "    @builtin(front_facing) front_facing: bool,")?;
+    write_varying_fields(f, struct_registry, wgsl_def)?;
+    writeln!(f, "}}")?;
+    writeln!(f)?;
+
+    let has_outputs = !program_def.fragment_shader_exprs.is_empty();
+
+    if has_outputs {
+        writeln!(f, "struct FragmentOutput_Posh {{")?;
+
+        for (location, (name, expr)) in program_def.fragment_shader_exprs.iter().enumerate() {
+            let ty_name = type_name(struct_registry, &expr.ty());
+
+            if name == "gl_FragDepth" {
+                writeln!(f, "    @builtin(frag_depth) {name}: {ty_name},")?;
+            } else {
+                writeln!(f, "    @location({location}) {name}: {ty_name},")?;
+            }
+        }
+
+        writeln!(f, "}}")?;
+        writeln!(f)?;
+    }
+
+    let var_form = VarForm::new(struct_registry, func_registry, roots);
+    let scope_form = ScopeForm::new(&var_form);
+    let write_context = WriteFuncContext {
+        struct_registry,
+        scope_form: &scope_form,
+        stage: Stage::Fragment,
+        depth: 1,
+    };
+
+    writeln!(f, "@fragment")?;
+
+    if has_outputs {
+        writeln!(
+            f,
+            "fn {}(input: FragmentInput_Posh) -> FragmentOutput_Posh {{",
+            wgsl_def.fragment_entry_point
+        )?;
+    } else {
+        writeln!(
+            f,
+            "fn {}(input: FragmentInput_Posh) {{",
+            wgsl_def.fragment_entry_point
+        )?;
+    }
+
+    writeln!(f, "    gl_FragCoord = input.frag_coord;")?;
+    writeln!(f, "    gl_FrontFacing = input.front_facing;")?;
+
+    for varying_def in &wgsl_def.varying_defs {
+        let name = &varying_def.name;
+
+        match &varying_def.ty {
+            Type::BuiltIn(ty) if ty.is_mat() => {
+                let args = columns(name, *ty)
+                    .map(|(_, column)| format!("input.{column}"))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+
+                writeln!(f, "    {name} = {}({args});", built_in_type_name(*ty))?;
+            }
+            _ => writeln!(f, "    {name} = input.{name};")?,
+        }
+    }
+
+    writeln!(f)?;
+
+    let completed = write_scope(f, write_context.clone(), scope_form.root_scope())?;
+
+    if !has_outputs {
+        return writeln!(f, "}}");
+    }
+
+    if !completed {
+        writeln!(f, "    return FragmentOutput_Posh();")?;
+        return writeln!(f, "}}");
+    }
+
+    writeln!(f, "    var output: FragmentOutput_Posh;")?;
+
+    let outputs = program_def
+        .fragment_shader_exprs
+        .iter()
+        .map(|(name, _)| name);
+
+    for (name, root) in outputs.zip(var_form.simplified_roots()) {
+        writeln!(f, "    output.{name} = {};", write_context.expr(root))?;
+    }
+
+    writeln!(f, "    return output;")?;
+    writeln!(f, "}}")
+}
+
+fn write_varying_fields(
+    f: &mut impl Write,
+    struct_registry: &StructRegistry,
+    wgsl_def: &WgslProgramDef,
+) -> fmt::Result {
+    for varying_def in &wgsl_def.varying_defs {
+        let location = varying_def.location;
+
+        let interpolation = match varying_def.interpolation {
+            InterpolationQualifier::Smooth => "",
+            InterpolationQualifier::Flat => " @interpolate(flat)",
+        };
+
+        match &varying_def.ty {
+            Type::BuiltIn(ty) if ty.is_mat() => {
+                let column_ty = attribute_input_type(*ty);
+
+                for (i, column) in columns(&varying_def.name, *ty) {
+                    let location = location + i;
+
+                    writeln!(
+                        f,
+                        "    @location({location}){interpolation} {column}: {column_ty},"
+                    )?;
+                }
+            }
+            ty => {
+                let ty_name = type_name(struct_registry, ty);
+
+                writeln!(
+                    f,
+                    "    @location({location}){interpolation} {}: {ty_name},",
+                    varying_def.name
+                )?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn write_var(
+    f: &mut impl Write,
+    ctx: WriteFuncContext,
+    var_id: VarId,
+    var_init: &VarInit,
+) -> Result<bool, fmt::Error> {
+    use VarInit::*;
+
+    let indent = ctx.indent();
+
+    match var_init {
+        Expr(expr) => {
+            let ty = expr.ty();
+            let ty_name = ctx.type_name(&ty);
+
+            // Arrays are stored in variables so that they can be indexed
+            // dynamically.
+            let keyword = match ty {
+                Type::Array(_) => "var",
+                _ => "let",
+            };
+
+            writeln!(
+                f,
+                "{indent}{keyword} {var_id}: {ty_name} = {};",
+                ctx.expr(expr)
+            )?;
+
+            Ok(true)
+        }
+        Branch {
+            cond,
+            yes_id,
+            no_id,
+            ty,
+        } => {
+            let ty_name = ctx.type_name(ty);
+            let yes_scope = ctx.scope_form.scope(*yes_id);
+            let no_scope = ctx.scope_form.scope(*no_id);
+            let cond = ctx.expr(cond);
+
+            if yes_scope.vars.is_empty() && no_scope.vars.is_empty() && ty.built_in_type().is_some()
+            {
+                let yes_result = ctx.expr(yes_scope.result.unwrap());
+                let no_result = ctx.expr(no_scope.result.unwrap());
+
+                writeln!(
+                    f,
+                    "{indent}let {var_id}: {ty_name} = select({no_result}, {yes_result}, {cond});"
+                )?;
+
+                return Ok(true);
+            }
+
+            writeln!(f, "{indent}var {var_id}: {ty_name};")?;
+            writeln!(f, "{indent}if ({cond}) {{")?;
+
+            {
+                let ctx = ctx.nest();
+                let indent = ctx.indent();
+
+                let result = ctx.expr(yes_scope.result.unwrap());
+
+                if write_scope(f, ctx.clone(), yes_scope)? {
+                    writeln!(f, "{indent}{var_id} = {result};")?;
+                }
+            }
+
+            writeln!(f, "{indent}}} else {{")?;
+
+            {
+                let ctx = ctx.nest();
+                let indent = ctx.indent();
+
+                let result = ctx.expr(no_scope.result.unwrap());
+
+                if write_scope(f, ctx.clone(), no_scope)? {
+                    writeln!(f, "{indent}{var_id} = {result};")?;
+                }
+            }
+
+            writeln!(f, "{indent}}}")?;
+
+            Ok(true)
+        }
+        Discard => {
+            writeln!(f, "{indent}discard;")?;
+
+            Ok(false)
+        }
+        Loop {
+            id,
+            start,
+            end,
+            init,
+            body_id,
+            stop,
+            ty,
+        } => {
+            let ty_name = ctx.type_name(ty);
+            let body_scope = ctx.scope_form.scope(*body_id);
+            let acc = id.acc_name();
+            let i = id.index_name();
+            let start = ctx.expr(start);
+            let end = ctx.expr(end);
+
+            writeln!(f, "{indent}var {acc}: {ty_name} = {};", ctx.expr(init))?;
+            writeln!(
+                f,
+                "{indent}for (var {i}: u32 = {start}; {i} < {end}; {i}++) {{"
+            )?;
+
+            {
+                let ctx = ctx.nest();
+                let indent = ctx.indent();
+
+                let next = ctx.expr(body_scope.result.unwrap());
+
+                if write_scope(f, ctx.clone(), body_scope)? {
+                    if let Some(stop) = stop {
+                        // As in GLSL, the stop condition must be evaluated
+                        // before the accumulator is updated.
+                        let stop_name = id.stop_name();
+
+                        writeln!(f, "{indent}let {stop_name}: bool = {};", ctx.expr(stop))?;
+                        writeln!(f, "{indent}{acc} = {next};")?;
+                        writeln!(f, "{indent}if ({stop_name}) {{")?;
+                        writeln!(f, "{indent}    break;")?;
+                        writeln!(f, "{indent}}}")?;
+                    } else {
+                        writeln!(f, "{indent}{acc} = {next};")?;
+                    }
+                }
+            }
+
+            writeln!(f, "{indent}}}")?;
+            writeln!(f, "{indent}let {var_id}: {ty_name} = {acc};")?;
+
+            Ok(true)
+        }
+    }
+}
+
+fn write_scope(
+    f: &mut impl Write,
+    ctx: WriteFuncContext,
+    scope: &Scope,
+) -> Result<bool, fmt::Error> {
+    for (var_id, var_init) in &scope.vars {
+        if !write_var(f, ctx.clone(), *var_id, var_init)? {
+            return Ok(false);
+        }
+    }
+
+    Ok(true)
+}
+
+/// A simplified expression that is displayed in WGSL syntax.
+#[derive(Clone, Copy)]
+struct WgslExpr<'a> {
+    expr: &'a SimplifiedExpr,
+    struct_registry: &'a StructRegistry,
+    stage: Stage,
+}
+
+impl<'a> WgslExpr<'a> {
+    fn wrap(&self, expr: &'a SimplifiedExpr) -> Self {
+        Self { expr, ..*self }
+    }
+
+    fn type_name(&self, ty: &Type) -> String {
+        type_name(self.struct_registry, ty)
+    }
+
+    fn write_call(&self, f: &mut Formatter, name: &str, args: &[SimplifiedExpr]) -> fmt::Result {
+        let args = args
+            .iter()
+            .map(|arg| self.wrap(arg).to_string())
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        write!(f, "{name}({args})")
+    }
+
+    fn write_binary(
+        &self,
+        f: &mut Formatter,
+        left: &'a SimplifiedExpr,
+        op: BinaryOp,
+        right: &'a SimplifiedExpr,
+        ty: &Type,
+    ) -> fmt::Result {
+        use BinaryOp::*;
+
+        let left_ty = left.ty();
+        let right_ty = right.ty();
+        let left = self.wrap(left);
+        let right = self.wrap(right);
+
+        match (op, &left_ty) {
+            (Eq, _) => return f.write_str(&eq(&left.to_string(), &right.to_string(), &left_ty)),
+            (Ne, _) => {
+                let eq = eq(&left.to_string(), &right.to_string(), &left_ty);
+
+                return write!(f, "!{eq}");
+            }
+            (Add | Sub | Div, Type::BuiltIn(mat)) if mat.is_mat() => {
+                // WGSL only supports adding and subtracting matrices of the
+                // same type and does not support division of matrices, so we
+                // need to operate on the columns.
+                if op != Div && left_ty == right_ty {
+                    return write!(f, "({left} {op} {right})");
+                }
+
+                let columns = (0..mat_size(*mat))
+                    .map(|i| {
+                        if left_ty == right_ty {
+                            format!("{left}[{i}] {op} {right}[{i}]")
+                        } else {
+                            format!("{left}[{i}] {op} {right}")
+                        }
+                    })
+                    .collect::<Vec<_>>()
+                    .join(", ");
+
+                return write!(f, "{}({columns})", built_in_type_name(*mat));
+            }
+            _ => (),
+        }
+
+        let ty_name = self.type_name(ty);
+
+        match op {
+            Shl | Shr => {
+                // WGSL requires the shift amount to be unsigned and of the same
+                // shape as the shifted value.
+                let amount_ty = match left_ty {
+                    Type::BuiltIn(ty) => unsigned(ty),
+                    _ => unreachable!(),
+                };
+
+                if right_ty == Type::BuiltIn(amount_ty) {
+                    write!(f, "({left} {op} {right})")
+                } else {
+                    let amount_ty_name = built_in_type_name(amount_ty);
+
+                    write!(f, "({left} {op} {amount_ty_name}({right}))")
+                }
+            }
+            BitAnd | BitOr | BitXor if left_ty != right_ty => {
+                // WGSL does not support mixing vectors and scalars in bitwise
+                // operations.
+                if left_ty == *ty {
+                    write!(f, "({left} {op} {ty_name}({right}))")
+                } else {
+                    write!(f, "({ty_name}({left}) {op} {right})")
+                }
+            }
+            _ => write!(f, "({left} {op} {right})"),
+        }
+    }
+
+    fn write_constructor(
+        &self,
+        f: &mut Formatter,
+        ty: &Type,
+        args: &'a [SimplifiedExpr],
+    ) -> fmt::Result {
+        let ty_name = self.type_name(ty);
+
+        if let (Type::BuiltIn(ty), [arg]) = (ty, args) {
+            let arg_ty = arg.ty().built_in_type().unwrap();
+
+            if ty.is_mat() && !arg_ty.is_mat() {
+                // GLSL constructs a diagonal matrix from a single scalar.
+                let n = mat_size(*ty);
+                let arg = self.wrap(arg);
+                let scalars = (0..n * n)
+                    .map(|i| {
+                        if i / n == i % n {
+                            arg.to_string()
+                        } else {
+                            "0.0".to_string()
+                        }
+                    })
+                    .collect::<Vec<_>>()
+                    .join(", ");
+
+                return write!(f, "{ty_name}({scalars})");
+            }
+
+            if let (Some(n), Some(m)) = (vec_size(*ty), vec_size(arg_ty)) {
+                if m > n {
+                    // GLSL allows dropping components in constructors.
+                    return write!(f, "{ty_name}({}.{})", self.wrap(arg), &"xyzw"[..n]);
+                }
+            }
+        }
+
+        self.write_call(f, &ty_name, args)
+    }
+
+    fn write_built_in(
+        &self,
+        f: &mut Formatter,
+        name: &str,
+        args: &'a [SimplifiedExpr],
+        ty: &Type,
+    ) -> fmt::Result {
+        if let Some(SimplifiedExpr::Arg {
+            name: sampler,
+            ty: Type::BuiltIn(BuiltInType::Sampler(sampler_ty)),
+        }) = args.first()
+        {
+            return self.write_texture_call(f, name, sampler, *sampler_ty, &args[1..]);
+        }
+
+        let arg = |i: usize| self.wrap(&args[i]);
+
+        match name {
+            "atan" if args.len() == 2 => self.write_call(f, "atan2", args),
+            "inversesqrt" => self.write_call(f, "inverseSqrt", args),
+            "dFdx" => self.write_call(f, "dpdx", args),
+            "dFdy" => self.write_call(f, "dpdy", args),
+            "roundEven" => self.write_call(f, "round", args),
+            "packHalf2x16" => self.write_call(f, "pack2x16float", args),
+            "packSnorm2x16" => self.write_call(f, "pack2x16snorm", args),
+            "packUnorm2x16" => self.write_call(f, "pack2x16unorm", args),
+            "unpackHalf2x16" => self.write_call(f, "unpack2x16float", args),
+            "unpackSnorm2x16" => self.write_call(f, "unpack2x16snorm", args),
+            "unpackUnorm2x16" => self.write_call(f, "unpack2x16unorm", args),
+            "equal" => write!(f, "({} == {})", arg(0), arg(1)),
+            "notEqual" => write!(f, "({} != {})", arg(0), arg(1)),
+            "lessThan" => write!(f, "({} < {})", arg(0), arg(1)),
+            "lessThanEqual" => write!(f, "({} <= {})", arg(0), arg(1)),
+            "greaterThan" => write!(f, "({} > {})", arg(0), arg(1)),
+            "greaterThanEqual" => write!(f, "({} >= {})", arg(0), arg(1)),
+            "not" => write!(f, "!({})", arg(0)),
+            "mod" => {
+                let (x, y) = (arg(0), arg(1));
+
+                write!(f, "({x} - {y} * floor({x} / {y}))")
+            }
+            "mix" if args[2].ty().built_in_type().is_some_and(is_bool) => {
+                write!(f, "select({}, {}, {})", arg(0), arg(1), arg(2))
+            }
+            "floatBitsToInt" | "floatBitsToUint" | "intBitsToFloat" | "uintBitsToFloat" => {
+                write!(f, "bitcast<{}>({})", self.type_name(ty), arg(0))
+            }
+            "isnan" => write!(f, "({0} != {0})", arg(0)),
+            "isinf" => {
+                let x_ty_name = self.type_name(&args[0].ty());
+
+                write!(f, "(abs({}) > {x_ty_name}({:?}))", arg(0), f32::MAX)
+            }
+            "outerProduct" | "matrixCompMult" => {
+                let mat = ty.built_in_type().unwrap();
+                let columns = (0..mat_size(mat))
+                    .map(|i| {
+                        if name == "outerProduct" {
+                            format!("{} * {}[{i}]", arg(0), arg(1))
+                        } else {
+                            format!("{}[{i}] * {}[{i}]", arg(0), arg(1))
+                        }
+                    })
+                    .collect::<Vec<_>>()
+                    .join(", ");
+
+                write!(f, "{}({columns})", built_in_type_name(mat))
+            }
+            _ => self.write_call(f, name, args),
+        }
+    }
+
+    fn write_texture_call(
+        &self,
+        f: &mut Formatter,
+        name: &str,
+        sampler: &str,
+        sampler_ty: SamplerType,
+        args: &'a [SimplifiedExpr],
+    ) -> fmt::Result {
+        let texture = texture_name(sampler);
+        let sampler = sampler_name(sampler);
+        let args: Vec<_> = args.iter().map(|arg| self.wrap(arg).to_string()).collect();
+        let vertex = self.stage == Stage::Vertex;
+        let dim = sampler_dim(sampler_ty);
+
+        if name == "textureSize" {
+            return match dim {
+                SamplerDim::D2 | SamplerDim::Cube => {
+                    write!(f, "vec2<i32>(textureDimensions({texture}, {}))", args[0])
+                }
+                SamplerDim::D2Array => write!(
+                    f,
+                    "vec3<i32>(vec3<u32>(textureDimensions({texture}, {}), \
+                     textureNumLayers({texture})))",
+                    args[0]
+                ),
+                SamplerDim::D3 => write!(f, "vec3<i32>(textureDimensions({texture}, {}))", args[0]),
+            };
+        }
+
+        if name == "texelFetch" {
+            return match dim {
+                SamplerDim::D2Array => write!(
+                    f,
+                    "textureLoad({texture}, {0}.xy, {0}.z, {1})",
+                    args[0], args[1]
+                ),
+                SamplerDim::D2 | SamplerDim::D3 | SamplerDim::Cube => {
+                    write!(f, "textureLoad({texture}, {}, {})", args[0], args[1])
+                }
+            };
+        }
+
+        if sampler_ty == SamplerType::ComparisonSampler2dArray {
+            // The coordinates are given as `vec4(tex_coords, layer, reference)`.
+            let coords = &args[0];
+            let function = if vertex {
+                "textureSampleCompareLevel"
+            } else {
+                "textureSampleCompare"
+            };
+
+            return write!(
+                f,
+                "{function}({texture}, {sampler}, {coords}.xy, {}, {coords}.w)",
+                array_layer(&format!("{coords}.z"))
+            );
+        }
+
+        if sampler_ty == SamplerType::ComparisonSamplerCube {
+            // The coordinates are given as `vec4(direction, reference)`.
+            let coords = &args[0];
+            let function = if vertex {
+                "textureSampleCompareLevel"
+            } else {
+                "textureSampleCompare"
+            };
+
+            return write!(
+                f,
+                "{function}({texture}, {sampler}, {coords}.xyz, {coords}.w)"
+            );
+        }
+
+        if sampler_ty == SamplerType::ComparisonSampler2d {
+            // The coordinates are given as `vec3(tex_coords, reference)`, or as
+            // `vec4(tex_coords, reference, q)` for projection.
+            let coords = &args[0];
+            let (tex_coords, reference) = if name == "textureProj" {
+                (
+                    format!("({coords}.xy / {coords}.w)"),
+                    format!("({coords}.z / {coords}.w)"),
+                )
+            } else {
+                (format!("{coords}.xy"), format!("{coords}.z"))
+            };
+            let offset = match name {
+                "textureOffset" => format!(", {}", args[1]),
+                _ => String::new(),
+            };
+
+            // Vertex shaders can only sample the base level.
+            let function = if vertex {
+                "textureSampleCompareLevel"
+            } else {
+                "textureSampleCompare"
+            };
+
+            return write!(
+                f,
+                "{function}({texture}, {sampler}, {tex_coords}, {reference}{offset})"
+            );
+        }
+
+        // WGSL takes the layer of array textures as a separate argument.
+        let tex_coords = &match dim {
+            SamplerDim::D2Array => {
+                format!("{}.xy, {}", args[0], array_layer(&format!("{}.z", args[0])))
+            }
+            SamplerDim::D2 | SamplerDim::D3 | SamplerDim::Cube => args[0].clone(),
+        };
+
+        match (name, vertex) {
+            ("texture", true) => write!(
+                f,
+                "textureSampleLevel({texture}, {sampler}, {tex_coords}, 0.0)"
+            ),
+            ("texture", false) if args.len() == 2 => write!(
+                f,
+                "textureSampleBias({texture}, {sampler}, {tex_coords}, {})",
+                args[1]
+            ),
+            ("texture", false) => write!(f, "textureSample({texture}, {sampler}, {tex_coords})"),
+            ("textureLod", _) => write!(
+                f,
+                "textureSampleLevel({texture}, {sampler}, {tex_coords}, {})",
+                args[1]
+            ),
+            ("textureGrad", _) => write!(
+                f,
+                "textureSampleGrad({texture}, {sampler}, {tex_coords}, {}, {})",
+                args[1], args[2]
+            ),
+            ("textureOffset", true) => write!(
+                f,
+                "textureSampleLevel({texture}, {sampler}, {tex_coords}, 0.0, {})",
+                args[1]
+            ),
+            ("textureOffset", false) => write!(
+                f,
+                "textureSample({texture}, {sampler}, {tex_coords}, {})",
+                args[1]
+            ),
+            ("textureProj", true) => write!(
+                f,
+                "textureSampleLevel({texture}, {sampler}, {tex_coords}.xy / {tex_coords}.z, 0.0)"
+            ),
+            ("textureProj", false) => write!(
+                f,
+                "textureSample({texture}, {sampler}, {tex_coords}.xy / {tex_coords}.z)"
+            ),
+            _ => unreachable!("unexpected texture function `{name}`"),
+        }
+    }
+}
+
+impl<'a> Display for WgslExpr<'a> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        use SimplifiedExpr::*;
+
+        match self.expr {
+            Arg { name, .. } => f.write_str(name),
+            ScalarLiteral { value, .. } => f.write_str(value),
+            Unary { op, arg, ty } => match ty {
+                Type::BuiltIn(mat) if mat.is_mat() && *op == UnaryOp::Neg => {
+                    // WGSL does not support negating matrices.
+                    let arg = self.wrap(arg);
+                    let columns = (0..mat_size(*mat))
+                        .map(|i| format!("-{arg}[{i}]"))
+                        .collect::<Vec<_>>()
+                        .join(", ");
+
+                    write!(f, "{}({columns})", built_in_type_name(*mat))
+                }
+                _ => write!(f, "({op}{})", self.wrap(arg)),
+            },
+            Binary {
+                left,
+                op,
+                right,
+                ty,
+            } => self.write_binary(f, left, *op, right, ty),
+            CallFunc { name, args, ty } => {
+                let is_constructor = match ty {
+                    Type::BuiltIn(ty) => *name == ty.to_string(),
+                    Type::Array(ty) => *name == ty.to_string(),
+                    Type::Struct(_) => false,
+                };
+
+                if is_constructor {
+                    self.write_constructor(f, ty, args)
+                } else if name.contains("_Posh") {
+                    // Struct literals and user-defined functions are named by
+                    // the registries, so they are the same in WGSL.
+                    self.write_call(f, name, args)
+                } else {
+                    self.write_built_in(f, name, args, ty)
+                }
+            }
+            Field { base, name, .. } => {
+                let base_expr = self.wrap(base);
+
+                match base.ty() {
+                    Type::BuiltIn(ty) if ty.is_mat() => {
+                        let index = match *name {
+                            "x_axis" => 0,
+                            "y_axis" => 1,
+                            "z_axis" => 2,
+                            "w_axis" => 3,
+                            _ => unreachable!(),
+                        };
+
+                        write!(f, "{base_expr}[{index}]")
+                    }
+                    _ => write!(f, "{base_expr}.{name}"),
+                }
+            }
+            Branch { cond, yes, no, .. } => write!(
+                f,
+                "select({}, {}, {})",
+                self.wrap(no),
+                self.wrap(yes),
+                self.wrap(cond)
+            ),
+            Subscript { base, index, .. } => {
+                write!(f, "{}[{}]", self.wrap(base), self.wrap(index))
+            }
+            Var { id, .. } => write!(f, "{id}"),
+            Discard { .. } => unreachable!("discard is not an expression in WGSL"),
+            Loop { id, .. } => write!(f, "{}", id.acc_name()),
+            // WGSL has no precision qualifiers.
+            Precision { arg, .. } => write!(f, "{}", self.wrap(arg)),
+        }
+    }
+}
+
+/// Returns a WGSL expression that compares `left` and `right` of type `ty` for
+/// equality.
+///
+/// Unlike GLSL, WGSL compares vectors component-wise and does not support
+/// comparing matrices, structs or arrays.
+fn eq(left: &str, right: &str, ty: &Type) -> String {
+    let all = |parts: Vec<String>| format!("({})", parts.join(" && "));
+
+    match ty {
+        Type::BuiltIn(ty) if ty.is_mat() => all((0..mat_size(*ty))
+            .map(|i| format!("all({left}[{i}] == {right}[{i}])"))
+            .collect()),
+        Type::BuiltIn(ty) if vec_size(*ty).is_some() => format!("all({left} == {right})"),
+        Type::BuiltIn(_) => format!("({left} == {right})"),
+        Type::Struct(ty) => all(ty
+            .fields
+            .iter()
+            .map(|(name, field_ty)| {
+                eq(
+                    &format!("{left}.{name}"),
+                    &format!("{right}.{name}"),
+                    field_ty,
+                )
+            })
+            .collect()),
+        Type::Array(ArrayType { ty, len }) => all((0..*len)
+            .map(|i| eq(&format!("{left}[{i}]"), &format!("{right}[{i}]"), ty))
+            .collect()),
+    }
+}
+
+fn texture_name(sampler: &str) -> String {
+    format!("{sampler}_texture")
+}
+
+fn sampler_name(sampler: &str) -> String {
+    format!("{sampler}_sampler")
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum SamplerDim {
+    D2,
+    D2Array,
+    D3,
+    Cube,
+}
+
+fn sampler_dim(ty: SamplerType) -> SamplerDim {
+    use SamplerType::*;
+
+    match ty {
+        ColorSampler2d | IColorSampler2d | UColorSampler2d | ComparisonSampler2d => SamplerDim::D2,
+        ColorSampler2dArray
+        | IColorSampler2dArray
+        | UColorSampler2dArray
+        | ComparisonSampler2dArray => SamplerDim::D2Array,
+        ColorSampler3d | IColorSampler3d | UColorSampler3d => SamplerDim::D3,
+        ColorSamplerCube | IColorSamplerCube | UColorSamplerCube | ComparisonSamplerCube => {
+            SamplerDim::Cube
+        }
+    }
+}
+
+// GLSL selects the layer of array textures by rounding the coordinate.
+fn array_layer(coord: &str) -> String {
+    format!("i32(floor({coord} + 0.5))")
+}
+
+/// Returns the names of the inputs that make up a vertex attribute or
+/// varying, which are split into their columns for matrices.
+fn columns(name: &str, ty: BuiltInType) -> impl Iterator<Item = (u32, String)> + '_ {
+    let n = if ty.is_mat() { mat_size(ty) } else { 1 };
+
+    (0..n).map(move |i| {
+        if ty.is_mat() {
+            (i as u32, format!("{name}_col{i}"))
+        } else {
+            (0, name.to_string())
+        }
+    })
+}
+
+/// Returns the type of a single location of a vertex attribute.
+fn attribute_input_type(ty: BuiltInType) -> &'static str {
+    use BuiltInType::*;
+
+    match ty {
+        Bool => "u32",
+        Mat2 => "vec2<f32>",
+        Mat3 => "vec3<f32>",
+        Mat4 => "vec4<f32>",
+        ty => built_in_type_name(ty),
+    }
+}
+
+fn type_name(struct_registry: &StructRegistry, ty: &Type) -> String {
+    use Type::*;
+
+    match ty {
+        BuiltIn(ty) => built_in_type_name(*ty).to_string(),
+        Struct(ty) => struct_registry.name(ty),
+        Array(ArrayType { ty, len }) => format!("array<{}, {len}>", type_name(struct_registry, ty)),
+    }
+}
+
+fn built_in_type_name(ty: BuiltInType) -> &'static str {
+    use BuiltInType::*;
+
+    match ty {
+        F32 => "f32",
+        I32 => "i32",
+        U32 => "u32",
+        Bool => "bool",
+        Vec2 => "vec2<f32>",
+        IVec2 => "vec2<i32>",
+        UVec2 => "vec2<u32>",
+        BVec2 => "vec2<bool>",
+        Vec3 => "vec3<f32>",
+        IVec3 => "vec3<i32>",
+        UVec3 => "vec3<u32>",
+        BVec3 => "vec3<bool>",
+        Vec4 => "vec4<f32>",
+        IVec4 => "vec4<i32>",
+        UVec4 => "vec4<u32>",
+        BVec4 => "vec4<bool>",
+        Mat2 => "mat2x2<f32>",
+        Mat3 => "mat3x3<f32>",
+        Mat4 => "mat4x4<f32>",
+        Sampler(SamplerType::ColorSampler2d) => "texture_2d<f32>",
+        Sampler(SamplerType::IColorSampler2d) => "texture_2d<i32>",
+        Sampler(SamplerType::UColorSampler2d) => "texture_2d<u32>",
+        Sampler(SamplerType::ComparisonSampler2d) => "texture_depth_2d",
+        Sampler(SamplerType::ColorSampler2dArray) => "texture_2d_array<f32>",
+        Sampler(SamplerType::IColorSampler2dArray) => "texture_2d_array<i32>",
+        Sampler(SamplerType::UColorSampler2dArray) => "texture_2d_array<u32>",
+        Sampler(SamplerType::ComparisonSampler2dArray) => "texture_depth_2d_array",
+        Sampler(SamplerType::ColorSampler3d) => "texture_3d<f32>",
+        Sampler(SamplerType::IColorSampler3d) => "texture_3d<i32>",
+        Sampler(SamplerType::UColorSampler3d) => "texture_3d<u32>",
+        Sampler(SamplerType::ColorSamplerCube) => "texture_cube<f32>",
+        Sampler(SamplerType::IColorSamplerCube) => "texture_cube<i32>",
+        Sampler(SamplerType::UColorSamplerCube) => "texture_cube<u32>",
+        Sampler(SamplerType::ComparisonSamplerCube) => "texture_depth_cube",
+    }
+}
+
+fn vec_size(ty: BuiltInType) -> Option<usize> {
+    use BuiltInType::*;
+
+    match ty {
+        Vec2 | IVec2 | UVec2 | BVec2 => Some(2),
+        Vec3 | IVec3 | UVec3 | BVec3 => Some(3),
+        Vec4 | IVec4 | UVec4 | BVec4 => Some(4),
+        _ => None,
+    }
+}
+
+fn mat_size(ty: BuiltInType) -> usize {
+    use BuiltInType::*;
+
+    match ty {
+        Mat2 => 2,
+        Mat3 => 3,
+        Mat4 => 4,
+        _ => panic!("expected matrix type, got `{ty}`"),
+    }
+}
+
+fn is_bool(ty: BuiltInType) -> bool {
+    use BuiltInType::*;
+
+    matches!(ty, Bool | BVec2 | BVec3 | BVec4)
+}
+
+/// Returns the unsigned type with the same shape as `ty`.
+fn unsigned(ty: BuiltInType) -> BuiltInType {
+    use BuiltInType::*;
+
+    match vec_size(ty) {
+        None => U32,
+        Some(2) => UVec2,
+        Some(3) => UVec3,
+        Some(_) => UVec4,
+    }
+}
@@ -84,6 +84,25 @@ impl Display for Expr {
             Subscript { base, index, .. } => write!(f, "{base}[{index}]"),
             Branch { cond, yes, no, .. } => write!(f, "({cond} ? {yes} : {no})"),
             Discard { .. } => write!(f, "discard"),
+            Loop {
+                start,
+                end,
+                init,
+                acc,
+                index,
+                next,
+                stop,
+                ..
+            } => {
+                write!(f, "fold({start}..{end}, {init}, |{acc}, {index}| {next}")?;
+
+                if let Some(stop) = stop {
+                    write!(f, " until {stop}")?;
+                }
+
+                f.write_char(')')
+            }
+            Precision { arg, precision, .. } => write!(f, "{}({arg})", precision.to_glsl()),
         }
     }
 }
@@ -97,6 +116,17 @@ impl Display for SamplerType {
             IColorSampler2d => "isampler2D",
             UColorSampler2d => "usampler2D",
             ComparisonSampler2d => "sampler2DShadow",
+            ColorSampler2dArray => "sampler2DArray",
+            IColorSampler2dArray => "isampler2DArray",
+            UColorSampler2dArray => "usampler2DArray",
+            ComparisonSampler2dArray => "sampler2DArrayShadow",
+            ColorSampler3d => "sampler3D",
+            IColorSampler3d => "isampler3D",
+            UColorSampler3d => "usampler3D",
+            ColorSamplerCube => "samplerCube",
+            IColorSamplerCube => "isamplerCube",
+            UColorSamplerCube => "usamplerCube",
+            ComparisonSamplerCube => "samplerCubeShadow",
         };
 
         f.write_str(s)
@@ -1,5 +1,7 @@
 use std::rc::Rc;
 
+use crate::sl::program_def::PrecisionQualifier;
+
 use super::{ArrayType, BuiltInType, StructType, Type};
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
@@ -95,6 +97,21 @@ pub enum Expr {
     Discard {
         ty: Type,
     },
+    Loop {
+        start: Rc<Expr>,
+        end: Rc<Expr>,
+        init: Rc<Expr>,
+        acc: Rc<Expr>,
+        index: Rc<Expr>,
+        next: Rc<Expr>,
+        stop: Option<Rc<Expr>>,
+        ty: Type,
+    },
+    Precision {
+        arg: Rc<Expr>,
+        precision: PrecisionQualifier,
+        ty: Type,
+    },
 }
 
 impl Expr {
@@ -114,6 +131,8 @@ impl Expr {
             Subscript { ty, .. } => ty.clone(),
             Branch { ty, .. } => ty.clone(),
             Discard { ty, .. } => ty.clone(),
+            Loop { ty, .. } => ty.clone(),
+            Precision { ty, .. } => ty.clone(),
         }
     }
 
@@ -139,9 +158,9 @@ impl Expr {
                 f(left);
                 f(right);
             }
-            CallFuncDef { def, args, .. } => {
-                f(&def.result);
-
+            CallFuncDef { args, .. } => {
+                // The function definition's body is not a successor, since it
+                // is generated separately from the call site.
                 for arg in args {
                     f(arg);
                 }
@@ -164,6 +183,28 @@ impl Expr {
                 f(no);
             }
             Discard { .. } => {}
+            Loop {
+                start,
+                end,
+                init,
+                next,
+                stop,
+                ..
+            } => {
+                // The accumulator and index arguments are placeholders that
+                // are bound by the loop itself, so they are not successors.
+                f(start);
+                f(end);
+                f(init);
+                f(next);
+
+                if let Some(stop) = stop {
+                    f(stop);
+                }
+            }
+            Precision { arg, .. } => {
+                f(arg);
+            }
         }
     }
 }
@@ -6,6 +6,17 @@ pub enum SamplerType {
     IColorSampler2d,
     UColorSampler2d,
     ComparisonSampler2d,
+    ColorSampler2dArray,
+    IColorSampler2dArray,
+    UColorSampler2dArray,
+    ComparisonSampler2dArray,
+    ColorSampler3d,
+    IColorSampler3d,
+    UColorSampler3d,
+    ColorSamplerCube,
+    IColorSamplerCube,
+    UColorSamplerCube,
+    ComparisonSamplerCube,
 }
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
@@ -0,0 +1,411 @@
+//! Evaluation of shading language expressions on the CPU.
+//!
+//! This makes it possible to test shader code without a GPU. Inputs can be
+//! given either by converting host values with [`ToSl`], or by binding
+//! arguments by name with [`Evaluator::with_arg`].
+//!
+//! Computing derivatives is not supported. Sampling textures is only
+//! supported when evaluating programs in the software backend of
+//! [`gl::Context`](crate::gl::Context).
+
+mod built_in;
+mod constant;
+
+use std::{collections::BTreeMap, rc::Rc};
+
+use thiserror::Error;
+
+use crate::ToSl;
+
+use super::{
+    dag::{BinaryOp, BuiltInType, Expr, Type},
+    Bool, Derivatives, FsInput, Object, Value, Vec2, Vec4, VsInput,
+};
+
+pub use constant::Constant;
+
+pub(crate) use built_in::{f16_to_f32, f32_to_f16};
+
+/// An error that occurred while evaluating an expression.
+#[derive(Debug, Clone, Error)]
+pub enum EvalError {
+    #[error("the fragment was discarded")]
+    Discarded,
+
+    #[error("argument `{0}` is not bound")]
+    UnboundArg(String),
+
+    #[error("argument `{name}` is bound to a value of type `{actual}`, but expected `{expected}`")]
+    ArgTypeMismatch {
+        name: String,
+        expected: Type,
+        actual: Type,
+    },
+
+    #[error("built-in function `{0}` is not supported")]
+    UnsupportedBuiltIn(String),
+
+    #[error("index {index} is out of bounds for length {len}")]
+    IndexOutOfBounds { index: String, len: usize },
+
+    #[error("integer division by zero")]
+    DivisionByZero,
+}
+
+/// Evaluates expressions on the CPU.
+#[derive(Debug, Clone, Default)]
+pub struct Evaluator {
+    args: BTreeMap<String, Rc<Expr>>,
+}
+
+impl Evaluator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Binds the argument called `name` to `value`.
+    ///
+    /// This is needed for evaluating expressions that refer to shader inputs
+    /// by name, e.g. `gl_FragCoord` or `uniforms`.
+    pub fn with_arg(mut self, name: impl Into<String>, value: impl ToSl) -> Self {
+        self.args.insert(name.into(), value.to_sl().expr());
+        self
+    }
+
+    pub fn eval<V: Value>(&self, value: V) -> Result<Constant, EvalError> {
+        State::new(&self.args, None).eval(&value.expr())
+    }
+}
+
+/// Evaluates `value` on the CPU.
+///
+/// This is a shorthand for [`Evaluator::eval`] without any argument bindings.
+pub fn eval<V: Value>(value: V) -> Result<Constant, EvalError> {
+    Evaluator::new().eval(value)
+}
+
+/// Texture lookups for evaluating the sampling built-in functions.
+pub(crate) trait Textures {
+    /// Calls the sampling built-in function `name` on the sampler that is
+    /// bound to the uniform `sampler`.
+    fn call(
+        &self,
+        sampler: &str,
+        name: &str,
+        args: &[Constant],
+        ty: BuiltInType,
+    ) -> Result<Constant, EvalError>;
+}
+
+/// Evaluates `exprs` with the given argument values.
+///
+/// Subexpressions that are shared between `exprs` are evaluated only once.
+pub(crate) fn eval_with_args(
+    exprs: &[Rc<Expr>],
+    args: BTreeMap<String, Constant>,
+    textures: &dyn Textures,
+) -> Result<Vec<Constant>, EvalError> {
+    let no_args = BTreeMap::new();
+    let mut state = State::new(&no_args, Some(textures));
+    state.frames[0].args = args;
+
+    state.eval_all(exprs)
+}
+
+/// Returns input for calling a vertex shader whose output is to be evaluated
+/// on the CPU.
+pub fn vs_input<V>(vertex: V, vertex_id: u32, instance_id: u32) -> VsInput<V> {
+    VsInput {
+        vertex,
+        vertex_id: vertex_id.to_sl(),
+        instance_id: instance_id.to_sl(),
+        _private: (),
+    }
+}
+
+/// Returns input for calling a fragment shader whose output is to be
+/// evaluated on the CPU.
+pub fn fs_input<W>(
+    interp: W,
+    fragment_coord: impl ToSl<Output = Vec4>,
+    front_facing: impl ToSl<Output = Bool>,
+    point_coord: impl ToSl<Output = Vec2>,
+) -> FsInput<W> {
+    FsInput {
+        interp,
+        fragment_coord: fragment_coord.to_sl(),
+        front_facing: front_facing.to_sl(),
+        point_coord: point_coord.to_sl(),
+        derivatives: Derivatives(()),
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+struct ExprKey(*const Expr);
+
+impl<'a> From<&'a Rc<Expr>> for ExprKey {
+    fn from(value: &'a Rc<Expr>) -> Self {
+        ExprKey(&**value as *const _)
+    }
+}
+
+#[derive(Default)]
+struct Frame {
+    values: BTreeMap<ExprKey, Constant>,
+    args: BTreeMap<String, Constant>,
+}
+
+// Loop bodies and function definitions are evaluated in their own frames, so
+// that values depending on the loop iteration or on function arguments are
+// not cached across iterations or calls.
+struct State<'a> {
+    args: &'a BTreeMap<String, Rc<Expr>>,
+    textures: Option<&'a dyn Textures>,
+    frames: Vec<Frame>,
+}
+
+impl<'a> State<'a> {
+    fn new(args: &'a BTreeMap<String, Rc<Expr>>, textures: Option<&'a dyn Textures>) -> Self {
+        Self {
+            args,
+            textures,
+            frames: vec![Frame::default()],
+        }
+    }
+
+    fn eval(&mut self, expr: &Rc<Expr>) -> Result<Constant, EvalError> {
+        let key = ExprKey::from(expr);
+
+        if let Some(value) = self.frames.iter().rev().find_map(|f| f.values.get(&key)) {
+            return Ok(value.clone());
+        }
+
+        let value = self.eval_uncached(expr)?;
+
+        self.frames
+            .last_mut()
+            .unwrap()
+            .values
+            .insert(key, value.clone());
+
+        Ok(value)
+    }
+
+    fn eval_in_frame(&mut self, frame: Frame, expr: &Rc<Expr>) -> Result<Constant, EvalError> {
+        self.frames.push(frame);
+        let result = self.eval(expr);
+        self.frames.pop();
+
+        result
+    }
+
+    fn eval_uncached(&mut self, expr: &Rc<Expr>) -> Result<Constant, EvalError> {
+        use Expr::*;
+
+        match &**expr {
+            Arg { name, ty } => self.eval_arg(name, ty),
+            ScalarLiteral { value, ty } => Ok(parse_scalar_literal(value, *ty)),
+            StructLiteral { args, .. } => Ok(Constant::Struct(self.eval_all(args)?)),
+            ArrayLiteral { args, .. } => Ok(Constant::Array(self.eval_all(args)?)),
+            Unary { op, arg, ty } => {
+                let arg = self.eval(arg)?;
+
+                built_in::unary(*op, &arg, built_in_type(ty))
+            }
+            Binary {
+                left,
+                op,
+                right,
+                ty,
+            } => {
+                let left = self.eval(left)?;
+
+                // Logical operators short-circuit, which matters if the right
+                // operand discards.
+                match (op, &left) {
+                    (BinaryOp::And, Constant::Bool(false)) => return Ok(Constant::Bool(false)),
+                    (BinaryOp::Or, Constant::Bool(true)) => return Ok(Constant::Bool(true)),
+                    _ => (),
+                }
+
+                let right = self.eval(right)?;
+
+                built_in::binary(&left, *op, &right, built_in_type(ty))
+            }
+            CallFuncDef { def, args } => {
+                let args = self.eval_all(args)?;
+                let frame = Frame {
+                    values: BTreeMap::new(),
+                    args: def
+                        .params
+                        .iter()
+                        .map(|(name, _)| name.to_string())
+                        .zip(args)
+                        .collect(),
+                };
+
+                self.eval_in_frame(frame, &def.result)
+            }
+            CallBuiltIn { name, args, ty } => {
+                let args = self.eval_all(args)?;
+
+                match (args.first(), self.textures) {
+                    (Some(Constant::Sampler(sampler)), Some(textures)) => {
+                        textures.call(sampler, name, &args[1..], built_in_type(ty))
+                    }
+                    (Some(Constant::Sampler(_)), None) => {
+                        Err(EvalError::UnsupportedBuiltIn(name.clone()))
+                    }
+                    _ => built_in::call(name, &args, built_in_type(ty)),
+                }
+            }
+            Field { base, name, .. } => {
+                let index = match base.ty() {
+                    Type::Struct(ty) => ty
+                        .fields
+                        .iter()
+                        .position(|(field_name, _)| field_name == name),
+                    Type::BuiltIn(_) => ["x", "y", "z", "w"]
+                        .iter()
+                        .chain(&["x_axis", "y_axis", "z_axis", "w_axis"])
+                        .position(|field_name| field_name == name)
+                        .map(|index| index % 4),
+                    Type::Array(_) => None,
+                }
+                .unwrap_or_else(|| panic!("invalid field `{name}` of {base}"));
+
+                Ok(elements(self.eval(base)?)[index].clone())
+            }
+            Subscript { base, index, .. } => {
+                let base = elements(self.eval(base)?);
+                let index = self.eval(index)?;
+
+                index
+                    .as_index()
+                    .and_then(|i| base.get(i).cloned())
+                    .ok_or_else(|| EvalError::IndexOutOfBounds {
+                        index: format!("{index:?}"),
+                        len: base.len(),
+                    })
+            }
+            Branch { cond, yes, no, .. } => {
+                if self.eval(cond)?.as_bool() {
+                    self.eval(yes)
+                } else {
+                    self.eval(no)
+                }
+            }
+            Discard { .. } => Err(EvalError::Discarded),
+            // Values are always evaluated with full precision.
+            Precision { arg, .. } => self.eval(arg),
+            Loop {
+                start,
+                end,
+                init,
+                acc,
+                index,
+                next,
+                stop,
+                ..
+            } => {
+                let start = u32::try_from(self.eval(start)?).unwrap();
+                let end = u32::try_from(self.eval(end)?).unwrap();
+                let mut value = self.eval(init)?;
+
+                for i in start..end {
+                    self.frames.push(Frame::default());
+
+                    let frame = self.frames.last_mut().unwrap();
+                    frame.values.insert(acc.into(), value);
+                    frame.values.insert(index.into(), Constant::U32(i));
+
+                    let result = self.eval(next).and_then(|next| {
+                        let stop = match stop {
+                            Some(stop) => self.eval(stop)?.as_bool(),
+                            None => false,
+                        };
+
+                        Ok((next, stop))
+                    });
+
+                    self.frames.pop();
+
+                    let (next, stop) = result?;
+                    value = next;
+
+                    if stop {
+                        break;
+                    }
+                }
+
+                Ok(value)
+            }
+        }
+    }
+
+    fn eval_all(&mut self, exprs: &[Rc<Expr>]) -> Result<Vec<Constant>, EvalError> {
+        exprs.iter().map(|expr| self.eval(expr)).collect()
+    }
+
+    fn eval_arg(&mut self, name: &str, ty: &Type) -> Result<Constant, EvalError> {
+        if let Some(value) = self.frames.iter().rev().find_map(|f| f.args.get(name)) {
+            return Ok(value.clone());
+        }
+
+        if let Type::BuiltIn(BuiltInType::Sampler(_)) = ty {
+            return Ok(Constant::Sampler(name.to_string()));
+        }
+
+        let expr = self
+            .args
+            .get(name)
+            .ok_or_else(|| EvalError::UnboundArg(name.to_string()))?;
+
+        if expr.ty() != *ty {
+            return Err(EvalError::ArgTypeMismatch {
+                name: name.to_string(),
+                expected: ty.clone(),
+                actual: expr.ty(),
+            });
+        }
+
+        // Bound arguments are evaluated independently of the current frames.
+        let value = State::new(self.args, self.textures).eval(expr)?;
+
+        self.frames[0].args.insert(name.to_string(), value.clone());
+
+        Ok(value)
+    }
+}
+
+fn built_in_type(ty: &Type) -> BuiltInType {
+    ty.built_in_type()
+        .unwrap_or_else(|| panic!("expected built-in type, got `{ty}`"))
+}
+
+fn elements(value: Constant) -> Vec<Constant> {
+    match value {
+        Constant::Vec(elements)
+        | Constant::Mat(elements)
+        | Constant::Struct(elements)
+        | Constant::Array(elements) => elements,
+        _ => panic!("expected a composite value, got {value:?}"),
+    }
+}
+
+fn parse_scalar_literal(value: &str, ty: BuiltInType) -> Constant {
+    use BuiltInType::*;
+
+    let parsed = match ty {
+        F32 => value.parse().ok().map(Constant::F32),
+        I32 => value.parse().ok().map(Constant::I32),
+        U32 => value
+            .strip_suffix('u')
+            .and_then(|value| value.parse().ok())
+            .map(Constant::U32),
+        Bool => value.parse().ok().map(Constant::Bool),
+        _ => None,
+    };
+
+    parsed.unwrap_or_else(|| panic!("invalid scalar literal `{value}` of type `{ty}`"))
+}
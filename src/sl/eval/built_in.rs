@@ -0,0 +1,612 @@
+use crate::sl::dag::{BinaryOp, BuiltInType, UnaryOp};
+
+use super::{
+    constant::{component_type, shape, Constant, Shape},
+    EvalError,
+};
+
+pub fn unary(op: UnaryOp, arg: &Constant, ty: BuiltInType) -> Result<Constant, EvalError> {
+    use Constant::*;
+
+    cwise(ty, &[arg], |args| {
+        Ok(match (op, &args[0]) {
+            (UnaryOp::Neg, F32(x)) => F32(-x),
+            (UnaryOp::Neg, I32(x)) => I32(x.wrapping_neg()),
+            (UnaryOp::Neg, U32(x)) => U32(x.wrapping_neg()),
+            (UnaryOp::Not, Bool(x)) => Bool(!x),
+            (UnaryOp::BitNot, I32(x)) => I32(!x),
+            (UnaryOp::BitNot, U32(x)) => U32(!x),
+            (op, arg) => panic!("invalid operand for `{op}`: {arg:?}"),
+        })
+    })
+}
+
+pub fn binary(
+    left: &Constant,
+    op: BinaryOp,
+    right: &Constant,
+    ty: BuiltInType,
+) -> Result<Constant, EvalError> {
+    use BinaryOp::*;
+    use Constant::*;
+
+    match op {
+        Eq => Ok(Bool(left == right)),
+        Ne => Ok(Bool(left != right)),
+        And => Ok(Bool(left.as_bool() && right.as_bool())),
+        Or => Ok(Bool(left.as_bool() || right.as_bool())),
+        Lt | Le | Ge | Gt => {
+            let ordering = match (left, right) {
+                (F32(x), F32(y)) => x.partial_cmp(y),
+                (I32(x), I32(y)) => x.partial_cmp(y),
+                (U32(x), U32(y)) => x.partial_cmp(y),
+                _ => panic!("invalid operands for `{op}`: {left:?}, {right:?}"),
+            };
+
+            Ok(Bool(ordering.is_some_and(|ordering| match op {
+                Lt => ordering.is_lt(),
+                Le => ordering.is_le(),
+                Ge => ordering.is_ge(),
+                Gt => ordering.is_gt(),
+                _ => unreachable!(),
+            })))
+        }
+        Mul => match (left, right) {
+            (Mat(_), Mat(_)) => Ok(mat_from_columns(mat_mul(&columns(left), &columns(right)))),
+            (Mat(_), Vec(_)) => Ok(vec_f32(mat_mul_vec(&columns(left), &floats(right)))),
+            (Vec(_), Mat(_)) => Ok(vec_f32(
+                columns(right)
+                    .iter()
+                    .map(|column| dot(&floats(left), column))
+                    .collect(),
+            )),
+            _ => cwise(ty, &[left, right], |args| arith(op, &args[0], &args[1])),
+        },
+        Add | Sub | Div | Shl | Shr | BitAnd | BitOr | BitXor | Rem => {
+            cwise(ty, &[left, right], |args| arith(op, &args[0], &args[1]))
+        }
+    }
+}
+
+pub fn call(name: &str, args: &[Constant], ty: BuiltInType) -> Result<Constant, EvalError> {
+    use Constant::*;
+
+    if name == format!("{ty}") {
+        return Ok(construct(ty, args));
+    }
+
+    let args_ref: std::vec::Vec<_> = args.iter().collect();
+    let args_ref = &args_ref[..];
+
+    match name {
+        "sin" => cwise_f32(ty, args_ref, |x| x[0].sin()),
+        "cos" => cwise_f32(ty, args_ref, |x| x[0].cos()),
+        "tan" => cwise_f32(ty, args_ref, |x| x[0].tan()),
+        "asin" => cwise_f32(ty, args_ref, |x| x[0].asin()),
+        "acos" => cwise_f32(ty, args_ref, |x| x[0].acos()),
+        "atan" if args.len() == 1 => cwise_f32(ty, args_ref, |x| x[0].atan()),
+        "atan" => cwise_f32(ty, args_ref, |x| x[0].atan2(x[1])),
+        "sinh" => cwise_f32(ty, args_ref, |x| x[0].sinh()),
+        "cosh" => cwise_f32(ty, args_ref, |x| x[0].cosh()),
+        "tanh" => cwise_f32(ty, args_ref, |x| x[0].tanh()),
+        "asinh" => cwise_f32(ty, args_ref, |x| x[0].asinh()),
+        "acosh" => cwise_f32(ty, args_ref, |x| x[0].acosh()),
+        "atanh" => cwise_f32(ty, args_ref, |x| x[0].atanh()),
+        "pow" => cwise_f32(ty, args_ref, |x| x[0].powf(x[1])),
+        "exp" => cwise_f32(ty, args_ref, |x| x[0].exp()),
+        "log" => cwise_f32(ty, args_ref, |x| x[0].ln()),
+        "exp2" => cwise_f32(ty, args_ref, |x| x[0].exp2()),
+        "log2" => cwise_f32(ty, args_ref, |x| x[0].log2()),
+        "sqrt" => cwise_f32(ty, args_ref, |x| x[0].sqrt()),
+        "inversesqrt" => cwise_f32(ty, args_ref, |x| 1.0 / x[0].sqrt()),
+        "floor" => cwise_f32(ty, args_ref, |x| x[0].floor()),
+        "ceil" => cwise_f32(ty, args_ref, |x| x[0].ceil()),
+        "fract" => cwise_f32(ty, args_ref, |x| x[0] - x[0].floor()),
+        "round" => cwise_f32(ty, args_ref, |x| x[0].round()),
+        "roundEven" => cwise_f32(ty, args_ref, |x| round_even(x[0])),
+        "trunc" => cwise_f32(ty, args_ref, |x| x[0].trunc()),
+        "mod" => cwise_f32(ty, args_ref, |x| x[0] - x[1] * (x[0] / x[1]).floor()),
+        "step" => cwise_f32(ty, args_ref, |x| if x[1] < x[0] { 0.0 } else { 1.0 }),
+        "smoothstep" => cwise_f32(ty, args_ref, |x| {
+            let t = ((x[2] - x[0]) / (x[1] - x[0])).clamp(0.0, 1.0);
+
+            t * t * (3.0 - 2.0 * t)
+        }),
+        "abs" => cwise(ty, args_ref, |x| {
+            Ok(match &x[0] {
+                F32(x) => F32(x.abs()),
+                I32(x) => I32(x.wrapping_abs()),
+                x => panic!("invalid argument for `abs`: {x:?}"),
+            })
+        }),
+        "sign" => cwise(ty, args_ref, |x| {
+            Ok(match &x[0] {
+                F32(x) if *x > 0.0 => F32(1.0),
+                F32(x) if *x < 0.0 => F32(-1.0),
+                F32(x) => F32(*x),
+                I32(x) => I32(x.signum()),
+                x => panic!("invalid argument for `sign`: {x:?}"),
+            })
+        }),
+        "min" => cwise(ty, args_ref, |x| Ok(min(&x[0], &x[1]))),
+        "max" => cwise(ty, args_ref, |x| Ok(max(&x[0], &x[1]))),
+        "clamp" => cwise(ty, args_ref, |x| Ok(min(&max(&x[0], &x[1]), &x[2]))),
+        "mix" => cwise(ty, args_ref, |x| {
+            Ok(match &x[2] {
+                Bool(a) => {
+                    if *a {
+                        x[1].clone()
+                    } else {
+                        x[0].clone()
+                    }
+                }
+                F32(a) => F32(x[0].as_f32() * (1.0 - a) + x[1].as_f32() * a),
+                a => panic!("invalid argument for `mix`: {a:?}"),
+            })
+        }),
+        "isnan" => cwise(ty, args_ref, |x| Ok(Bool(x[0].as_f32().is_nan()))),
+        "isinf" => cwise(ty, args_ref, |x| Ok(Bool(x[0].as_f32().is_infinite()))),
+        "floatBitsToInt" => cwise(ty, args_ref, |x| Ok(I32(x[0].as_f32().to_bits() as i32))),
+        "floatBitsToUint" => cwise(ty, args_ref, |x| Ok(U32(x[0].as_f32().to_bits()))),
+        "intBitsToFloat" => cwise(ty, args_ref, |x| match &x[0] {
+            I32(x) => Ok(F32(f32::from_bits(*x as u32))),
+            x => panic!("invalid argument for `intBitsToFloat`: {x:?}"),
+        }),
+        "uintBitsToFloat" => cwise(ty, args_ref, |x| match &x[0] {
+            U32(x) => Ok(F32(f32::from_bits(*x))),
+            x => panic!("invalid argument for `uintBitsToFloat`: {x:?}"),
+        }),
+        "length" => Ok(F32(length(&floats(&args[0])))),
+        "distance" => Ok(F32(length(&sub(&floats(&args[0]), &floats(&args[1]))))),
+        "dot" => Ok(F32(dot(&floats(&args[0]), &floats(&args[1])))),
+        "normalize" => {
+            let x = floats(&args[0]);
+            let len = length(&x);
+
+            Ok(from_floats(ty, x.iter().map(|x| x / len).collect()))
+        }
+        "cross" => {
+            let (x, y) = (floats(&args[0]), floats(&args[1]));
+
+            Ok(vec_f32(vec![
+                x[1] * y[2] - y[1] * x[2],
+                x[2] * y[0] - y[2] * x[0],
+                x[0] * y[1] - y[0] * x[1],
+            ]))
+        }
+        "faceforward" => {
+            let (n, i, n_ref) = (floats(&args[0]), floats(&args[1]), floats(&args[2]));
+            let sign = if dot(&n_ref, &i) < 0.0 { 1.0 } else { -1.0 };
+
+            Ok(from_floats(ty, scale(&n, sign)))
+        }
+        "reflect" => {
+            let (i, n) = (floats(&args[0]), floats(&args[1]));
+
+            Ok(from_floats(ty, sub(&i, &scale(&n, 2.0 * dot(&n, &i)))))
+        }
+        "refract" => {
+            let (i, n, eta) = (floats(&args[0]), floats(&args[1]), args[2].as_f32());
+            let d = dot(&n, &i);
+            let k = 1.0 - eta * eta * (1.0 - d * d);
+
+            let result = if k < 0.0 {
+                vec![0.0; i.len()]
+            } else {
+                sub(&scale(&i, eta), &scale(&n, eta * d + k.sqrt()))
+            };
+
+            Ok(from_floats(ty, result))
+        }
+        "lessThan" => binary_cwise(args, BinaryOp::Lt, ty),
+        "lessThanEqual" => binary_cwise(args, BinaryOp::Le, ty),
+        "greaterThan" => binary_cwise(args, BinaryOp::Gt, ty),
+        "greaterThanEqual" => binary_cwise(args, BinaryOp::Ge, ty),
+        "equal" => binary_cwise(args, BinaryOp::Eq, ty),
+        "notEqual" => binary_cwise(args, BinaryOp::Ne, ty),
+        "any" => Ok(Bool(args[0].components().iter().any(Constant::as_bool))),
+        "all" => Ok(Bool(args[0].components().iter().all(Constant::as_bool))),
+        "not" => cwise(ty, args_ref, |x| Ok(Bool(!x[0].as_bool()))),
+        "matrixCompMult" => cwise_f32(ty, args_ref, |x| x[0] * x[1]),
+        "outerProduct" => {
+            let (c, r) = (floats(&args[0]), floats(&args[1]));
+
+            Ok(mat_from_columns(r.iter().map(|r| scale(&c, *r)).collect()))
+        }
+        "transpose" => {
+            let m = columns(&args[0]);
+
+            Ok(mat_from_columns(
+                (0..m.len())
+                    .map(|i| m.iter().map(|column| column[i]).collect())
+                    .collect(),
+            ))
+        }
+        "determinant" => Ok(F32(determinant(columns(&args[0])))),
+        "inverse" => Ok(mat_from_columns(inverse(columns(&args[0])))),
+        "packHalf2x16" => Ok(U32(pack_2x16(&args[0], f32_to_f16))),
+        "packUnorm2x16" => Ok(U32(pack_2x16(&args[0], |x| {
+            (x.clamp(0.0, 1.0) * 65535.0).round() as u16
+        }))),
+        "packSnorm2x16" => Ok(U32(pack_2x16(&args[0], |x| {
+            (x.clamp(-1.0, 1.0) * 32767.0).round() as i16 as u16
+        }))),
+        "unpackHalf2x16" => Ok(unpack_2x16(&args[0], f16_to_f32)),
+        "unpackUnorm2x16" => Ok(unpack_2x16(&args[0], |x| x as f32 / 65535.0)),
+        "unpackSnorm2x16" => Ok(unpack_2x16(&args[0], |x| {
+            (x as i16 as f32 / 32767.0).clamp(-1.0, 1.0)
+        })),
+        _ => Err(EvalError::UnsupportedBuiltIn(name.to_string())),
+    }
+}
+
+// Follows the rules for constructors given in the GLSL ES 3.00 specification,
+// section 5.4.
+fn construct(ty: BuiltInType, args: &[Constant]) -> Constant {
+    let component_ty = component_type(ty);
+
+    match (shape(ty), args) {
+        (Shape::Scalar, [arg, ..]) => arg.components()[0].convert(component_ty),
+        (Shape::Vec(n), [arg]) if shape_of(arg) == Shape::Scalar => {
+            Constant::Vec(vec![arg.convert(component_ty); n])
+        }
+        (Shape::Mat(n), [arg]) if shape_of(arg) == Shape::Scalar => {
+            let x = arg.convert(component_ty).as_f32();
+
+            mat_from_columns(
+                (0..n)
+                    .map(|i| (0..n).map(|j| if i == j { x } else { 0.0 }).collect())
+                    .collect(),
+            )
+        }
+        (Shape::Mat(n), [Constant::Mat(_)]) => {
+            let m = columns(&args[0]);
+
+            mat_from_columns(
+                (0..n)
+                    .map(|i| {
+                        (0..n)
+                            .map(|j| match m.get(i).and_then(|column| column.get(j)) {
+                                Some(x) => *x,
+                                None if i == j => 1.0,
+                                None => 0.0,
+                            })
+                            .collect()
+                    })
+                    .collect(),
+            )
+        }
+        (Shape::Vec(_) | Shape::Mat(_), _) => {
+            let components = args
+                .iter()
+                .flat_map(Constant::components)
+                .map(|x| x.convert(component_ty))
+                .collect();
+
+            Constant::from_components(ty, components)
+        }
+        _ => panic!("invalid arguments for constructing `{ty}`: {args:?}"),
+    }
+}
+
+fn shape_of(value: &Constant) -> Shape {
+    match value {
+        Constant::Vec(components) => Shape::Vec(components.len()),
+        Constant::Mat(columns) => Shape::Mat(columns.len()),
+        _ => Shape::Scalar,
+    }
+}
+
+fn arith(op: BinaryOp, x: &Constant, y: &Constant) -> Result<Constant, EvalError> {
+    use BinaryOp::*;
+    use Constant::*;
+
+    Ok(match (x, y) {
+        (F32(x), F32(y)) => F32(match op {
+            Add => x + y,
+            Sub => x - y,
+            Mul => x * y,
+            Div => x / y,
+            _ => panic!("invalid operator for `F32`: `{op}`"),
+        }),
+        (I32(x), I32(y)) => I32(match op {
+            Add => x.wrapping_add(*y),
+            Sub => x.wrapping_sub(*y),
+            Mul => x.wrapping_mul(*y),
+            Div => x.checked_div(*y).ok_or(EvalError::DivisionByZero)?,
+            Rem => x.checked_rem(*y).ok_or(EvalError::DivisionByZero)?,
+            Shl => x.wrapping_shl(*y as u32),
+            Shr => x.wrapping_shr(*y as u32),
+            BitAnd => x & y,
+            BitOr => x | y,
+            BitXor => x ^ y,
+            _ => panic!("invalid operator for `I32`: `{op}`"),
+        }),
+        (U32(x), U32(y)) => U32(match op {
+            Add => x.wrapping_add(*y),
+            Sub => x.wrapping_sub(*y),
+            Mul => x.wrapping_mul(*y),
+            Div => x.checked_div(*y).ok_or(EvalError::DivisionByZero)?,
+            Rem => x.checked_rem(*y).ok_or(EvalError::DivisionByZero)?,
+            Shl => x.wrapping_shl(*y),
+            Shr => x.wrapping_shr(*y),
+            BitAnd => x & y,
+            BitOr => x | y,
+            BitXor => x ^ y,
+            _ => panic!("invalid operator for `U32`: `{op}`"),
+        }),
+        _ => panic!("invalid operands for `{op}`: {x:?}, {y:?}"),
+    })
+}
+
+fn binary_cwise(args: &[Constant], op: BinaryOp, ty: BuiltInType) -> Result<Constant, EvalError> {
+    cwise(ty, &[&args[0], &args[1]], |x| {
+        binary(&x[0], op, &x[1], BuiltInType::Bool)
+    })
+}
+
+// Applies `f` component-wise, broadcasting scalar arguments.
+fn cwise(
+    ty: BuiltInType,
+    args: &[&Constant],
+    f: impl Fn(&[Constant]) -> Result<Constant, EvalError>,
+) -> Result<Constant, EvalError> {
+    let args: Vec<_> = args.iter().map(|arg| arg.components()).collect();
+    let n = args.iter().map(Vec::len).max().unwrap_or(1);
+
+    let components = (0..n)
+        .map(|i| {
+            let xs: Vec<_> = args
+                .iter()
+                .map(|arg| if arg.len() == 1 { &arg[0] } else { &arg[i] })
+                .cloned()
+                .collect();
+
+            f(&xs)
+        })
+        .collect::<Result<_, _>>()?;
+
+    Ok(Constant::from_components(ty, components))
+}
+
+fn cwise_f32(
+    ty: BuiltInType,
+    args: &[&Constant],
+    f: impl Fn(&[f32]) -> f32,
+) -> Result<Constant, EvalError> {
+    cwise(ty, args, |xs| {
+        let xs: Vec<_> = xs.iter().map(Constant::as_f32).collect();
+
+        Ok(Constant::F32(f(&xs)))
+    })
+}
+
+fn min(x: &Constant, y: &Constant) -> Constant {
+    use Constant::*;
+
+    match (x, y) {
+        (F32(x), F32(y)) => F32(if y < x { *y } else { *x }),
+        (I32(x), I32(y)) => I32(*x.min(y)),
+        (U32(x), U32(y)) => U32(*x.min(y)),
+        _ => panic!("invalid arguments for `min`: {x:?}, {y:?}"),
+    }
+}
+
+fn max(x: &Constant, y: &Constant) -> Constant {
+    use Constant::*;
+
+    match (x, y) {
+        (F32(x), F32(y)) => F32(if x < y { *y } else { *x }),
+        (I32(x), I32(y)) => I32(*x.max(y)),
+        (U32(x), U32(y)) => U32(*x.max(y)),
+        _ => panic!("invalid arguments for `max`: {x:?}, {y:?}"),
+    }
+}
+
+fn round_even(x: f32) -> f32 {
+    if (x - x.trunc()).abs() == 0.5 {
+        2.0 * (x / 2.0).round()
+    } else {
+        x.round()
+    }
+}
+
+fn floats(value: &Constant) -> Vec<f32> {
+    value.components().iter().map(Constant::as_f32).collect()
+}
+
+fn from_floats(ty: BuiltInType, xs: Vec<f32>) -> Constant {
+    Constant::from_components(ty, xs.into_iter().map(Constant::F32).collect())
+}
+
+fn vec_f32(xs: Vec<f32>) -> Constant {
+    Constant::Vec(xs.into_iter().map(Constant::F32).collect())
+}
+
+fn columns(value: &Constant) -> Vec<Vec<f32>> {
+    match value {
+        Constant::Mat(columns) => columns.iter().map(floats).collect(),
+        _ => panic!("expected a matrix, got {value:?}"),
+    }
+}
+
+fn mat_from_columns(columns: Vec<Vec<f32>>) -> Constant {
+    Constant::Mat(columns.into_iter().map(vec_f32).collect())
+}
+
+fn dot(x: &[f32], y: &[f32]) -> f32 {
+    x.iter().zip(y).map(|(x, y)| x * y).sum()
+}
+
+fn length(x: &[f32]) -> f32 {
+    dot(x, x).sqrt()
+}
+
+fn sub(x: &[f32], y: &[f32]) -> Vec<f32> {
+    x.iter().zip(y).map(|(x, y)| x - y).collect()
+}
+
+fn scale(x: &[f32], s: f32) -> Vec<f32> {
+    x.iter().map(|x| x * s).collect()
+}
+
+fn mat_mul_vec(m: &[Vec<f32>], v: &[f32]) -> Vec<f32> {
+    (0..m[0].len())
+        .map(|row| m.iter().zip(v).map(|(column, v)| column[row] * v).sum())
+        .collect()
+}
+
+fn mat_mul(x: &[Vec<f32>], y: &[Vec<f32>]) -> Vec<Vec<f32>> {
+    y.iter().map(|column| mat_mul_vec(x, column)).collect()
+}
+
+fn determinant(mut m: Vec<Vec<f32>>) -> f32 {
+    let n = m.len();
+    let mut det = 1.0;
+
+    for i in 0..n {
+        let pivot = (i..n)
+            .max_by(|&a, &b| m[a][i].abs().total_cmp(&m[b][i].abs()))
+            .unwrap();
+
+        if m[pivot][i] == 0.0 {
+            return 0.0;
+        }
+
+        if pivot != i {
+            m.swap(pivot, i);
+            det = -det;
+        }
+
+        det *= m[i][i];
+
+        for j in i + 1..n {
+            let factor = m[j][i] / m[i][i];
+
+            let (upper, lower) = m.split_at_mut(j);
+
+            for (x, y) in lower[0][i..n].iter_mut().zip(&upper[i][i..n]) {
+                *x -= factor * y;
+            }
+        }
+    }
+
+    det
+}
+
+// Gauss-Jordan elimination. Since inverting commutes with transposing, we can
+// treat the columns as rows.
+fn inverse(mut m: Vec<Vec<f32>>) -> Vec<Vec<f32>> {
+    let n = m.len();
+    let mut inv: Vec<Vec<f32>> = (0..n)
+        .map(|i| (0..n).map(|j| if i == j { 1.0 } else { 0.0 }).collect())
+        .collect();
+
+    for i in 0..n {
+        let pivot = (i..n)
+            .max_by(|&a, &b| m[a][i].abs().total_cmp(&m[b][i].abs()))
+            .unwrap();
+
+        m.swap(pivot, i);
+        inv.swap(pivot, i);
+
+        let factor = 1.0 / m[i][i];
+
+        for k in 0..n {
+            m[i][k] *= factor;
+            inv[i][k] *= factor;
+        }
+
+        for j in 0..n {
+            if j != i {
+                let factor = m[j][i];
+
+                for k in 0..n {
+                    m[j][k] -= factor * m[i][k];
+                    inv[j][k] -= factor * inv[i][k];
+                }
+            }
+        }
+    }
+
+    inv
+}
+
+fn pack_2x16(value: &Constant, f: impl Fn(f32) -> u16) -> u32 {
+    let xs = floats(value);
+
+    u32::from(f(xs[0])) | (u32::from(f(xs[1])) << 16)
+}
+
+fn unpack_2x16(value: &Constant, f: impl Fn(u16) -> f32) -> Constant {
+    let x = match value {
+        Constant::U32(x) => *x,
+        _ => panic!("expected `U32`, got {value:?}"),
+    };
+
+    vec_f32(vec![f(x as u16), f((x >> 16) as u16)])
+}
+
+pub fn f32_to_f16(x: f32) -> u16 {
+    let bits = x.to_bits();
+    let sign = ((bits >> 16) & 0x8000) as u16;
+    let exp = ((bits >> 23) & 0xff) as i32;
+    let mant = bits & 0x7f_ffff;
+
+    if exp == 0xff {
+        // Infinity or NaN.
+        return sign | 0x7c00 | if mant != 0 { 0x200 } else { 0 };
+    }
+
+    let exp = exp - 127 + 15;
+
+    if exp >= 0x1f {
+        // Overflow to infinity.
+        return sign | 0x7c00;
+    }
+
+    let (half, shift) = if exp <= 0 {
+        // Subnormal or zero.
+        if exp < -10 {
+            return sign;
+        }
+
+        let shift = (14 - exp) as u32;
+
+        ((mant | 0x80_0000) >> shift, shift)
+    } else {
+        (((exp as u32) << 10) | (mant >> 13), 13)
+    };
+
+    // Round to nearest, ties to even. Note that rounding may carry over into
+    // the exponent, which gives the correct result.
+    let mant = if exp <= 0 { mant | 0x80_0000 } else { mant };
+    let rem = mant & ((1 << shift) - 1);
+    let halfway = 1 << (shift - 1);
+    let half = if rem > halfway || (rem == halfway && half & 1 != 0) {
+        half + 1
+    } else {
+        half
+    };
+
+    sign | half as u16
+}
+
+pub fn f16_to_f32(x: u16) -> f32 {
+    let sign = u32::from(x & 0x8000) << 16;
+    let exp = u32::from((x >> 10) & 0x1f);
+    let mant = u32::from(x & 0x3ff);
+
+    let bits = match exp {
+        0 => {
+            // Subnormal or zero.
+            let value = mant as f32 * (-24.0f32).exp2();
+
+            return if sign != 0 { -value } else { value };
+        }
+        0x1f => sign | 0x7f80_0000 | (mant << 13),
+        _ => sign | ((exp + 127 - 15) << 23) | (mant << 13),
+    };
+
+    f32::from_bits(bits)
+}
@@ -0,0 +1,255 @@
+use crate::sl::dag::BuiltInType;
+
+/// A concrete value computed by evaluating an expression on the CPU.
+///
+/// Vectors hold their components, and matrices hold their columns as vectors.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Constant {
+    F32(f32),
+    I32(i32),
+    U32(u32),
+    Bool(bool),
+    Vec(Vec<Constant>),
+    Mat(Vec<Constant>),
+    Struct(Vec<Constant>),
+    Array(Vec<Constant>),
+
+    /// A sampler, identified by the name of the uniform that it refers to.
+    Sampler(String),
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub(super) enum Shape {
+    Scalar,
+    Vec(usize),
+    Mat(usize),
+    Sampler,
+}
+
+impl Constant {
+    /// Returns the scalar components of a scalar, vector or matrix.
+    ///
+    /// Matrix components are returned in column-major order.
+    pub(super) fn components(&self) -> Vec<Constant> {
+        use Constant::*;
+
+        match self {
+            F32(_) | I32(_) | U32(_) | Bool(_) => vec![self.clone()],
+            Vec(components) => components.clone(),
+            Mat(columns) => columns.iter().flat_map(Constant::components).collect(),
+            Struct(_) | Array(_) | Sampler(_) => panic!("expected a scalar, vector or matrix"),
+        }
+    }
+
+    /// Builds a value of type `ty` from its scalar components.
+    pub(super) fn from_components(ty: BuiltInType, mut components: Vec<Constant>) -> Self {
+        match shape(ty) {
+            Shape::Scalar => components.swap_remove(0),
+            Shape::Vec(_) => Constant::Vec(components),
+            Shape::Mat(n) => Constant::Mat(
+                components
+                    .chunks(n)
+                    .map(|column| Constant::Vec(column.to_vec()))
+                    .collect(),
+            ),
+            Shape::Sampler => panic!("samplers cannot be constructed from components"),
+        }
+    }
+
+    pub(super) fn as_f32(&self) -> f32 {
+        match self {
+            Constant::F32(value) => *value,
+            _ => panic!("expected `F32`, got {self:?}"),
+        }
+    }
+
+    pub(super) fn as_bool(&self) -> bool {
+        match self {
+            Constant::Bool(value) => *value,
+            _ => panic!("expected `Bool`, got {self:?}"),
+        }
+    }
+
+    /// Returns an index value as `usize`.
+    pub(super) fn as_index(&self) -> Option<usize> {
+        match self {
+            Constant::I32(value) => usize::try_from(*value).ok(),
+            Constant::U32(value) => usize::try_from(*value).ok(),
+            _ => panic!("expected `I32` or `U32`, got {self:?}"),
+        }
+    }
+
+    /// Converts a scalar to the scalar type `ty`, following the conversion
+    /// rules of GLSL constructors.
+    pub(super) fn convert(&self, ty: BuiltInType) -> Constant {
+        use Constant::*;
+
+        match (ty, self) {
+            (BuiltInType::F32, F32(x)) => F32(*x),
+            (BuiltInType::F32, I32(x)) => F32(*x as f32),
+            (BuiltInType::F32, U32(x)) => F32(*x as f32),
+            (BuiltInType::F32, Bool(x)) => F32(u8::from(*x) as f32),
+            (BuiltInType::I32, F32(x)) => I32(*x as i32),
+            (BuiltInType::I32, I32(x)) => I32(*x),
+            (BuiltInType::I32, U32(x)) => I32(*x as i32),
+            (BuiltInType::I32, Bool(x)) => I32(i32::from(*x)),
+            (BuiltInType::U32, F32(x)) => U32(*x as u32),
+            (BuiltInType::U32, I32(x)) => U32(*x as u32),
+            (BuiltInType::U32, U32(x)) => U32(*x),
+            (BuiltInType::U32, Bool(x)) => U32(u32::from(*x)),
+            (BuiltInType::Bool, F32(x)) => Bool(*x != 0.0),
+            (BuiltInType::Bool, I32(x)) => Bool(*x != 0),
+            (BuiltInType::Bool, U32(x)) => Bool(*x != 0),
+            (BuiltInType::Bool, Bool(x)) => Bool(*x),
+            _ => panic!("cannot convert {self:?} to `{ty}`"),
+        }
+    }
+}
+
+pub(super) fn shape(ty: BuiltInType) -> Shape {
+    use BuiltInType::*;
+
+    match ty {
+        F32 | I32 | U32 | Bool => Shape::Scalar,
+        Vec2 | IVec2 | UVec2 | BVec2 => Shape::Vec(2),
+        Vec3 | IVec3 | UVec3 | BVec3 => Shape::Vec(3),
+        Vec4 | IVec4 | UVec4 | BVec4 => Shape::Vec(4),
+        Mat2 => Shape::Mat(2),
+        Mat3 => Shape::Mat(3),
+        Mat4 => Shape::Mat(4),
+        Sampler(_) => Shape::Sampler,
+    }
+}
+
+/// Returns the scalar type of the components of `ty`.
+pub(super) fn component_type(ty: BuiltInType) -> BuiltInType {
+    use BuiltInType::*;
+
+    match ty {
+        F32 | Vec2 | Vec3 | Vec4 | Mat2 | Mat3 | Mat4 => F32,
+        I32 | IVec2 | IVec3 | IVec4 => I32,
+        U32 | UVec2 | UVec3 | UVec4 => U32,
+        Bool | BVec2 | BVec3 | BVec4 => Bool,
+        Sampler(_) => panic!("samplers do not have components"),
+    }
+}
+
+macro_rules! impl_scalar_conversions {
+    ($variant:ident, $physical:ty) => {
+        impl From<$physical> for Constant {
+            fn from(value: $physical) -> Self {
+                Constant::$variant(value)
+            }
+        }
+
+        impl TryFrom<Constant> for $physical {
+            type Error = Constant;
+
+            fn try_from(value: Constant) -> Result<Self, Self::Error> {
+                match value {
+                    Constant::$variant(value) => Ok(value),
+                    value => Err(value),
+                }
+            }
+        }
+
+        impl<const N: usize> TryFrom<Constant> for [$physical; N] {
+            type Error = Constant;
+
+            fn try_from(value: Constant) -> Result<Self, Self::Error> {
+                let components = match &value {
+                    Constant::Vec(components) if components.len() == N => components,
+                    _ => return Err(value),
+                };
+
+                let mut result = [<$physical>::default(); N];
+
+                for (x, component) in result.iter_mut().zip(components) {
+                    *x = match component {
+                        Constant::$variant(component) => *component,
+                        _ => return Err(value),
+                    };
+                }
+
+                Ok(result)
+            }
+        }
+    };
+}
+
+impl_scalar_conversions!(F32, f32);
+impl_scalar_conversions!(I32, i32);
+impl_scalar_conversions!(U32, u32);
+impl_scalar_conversions!(Bool, bool);
+
+#[cfg(feature = "glam")]
+macro_rules! impl_glam_vec_conversions {
+    ($vec:ident, $physical:ty, $n:literal) => {
+        impl TryFrom<Constant> for glam::$vec {
+            type Error = Constant;
+
+            fn try_from(value: Constant) -> Result<Self, Self::Error> {
+                <[$physical; $n]>::try_from(value).map(Self::from)
+            }
+        }
+    };
+}
+
+#[cfg(feature = "glam")]
+impl_glam_vec_conversions!(Vec2, f32, 2);
+#[cfg(feature = "glam")]
+impl_glam_vec_conversions!(Vec3, f32, 3);
+#[cfg(feature = "glam")]
+impl_glam_vec_conversions!(Vec4, f32, 4);
+#[cfg(feature = "glam")]
+impl_glam_vec_conversions!(IVec2, i32, 2);
+#[cfg(feature = "glam")]
+impl_glam_vec_conversions!(IVec3, i32, 3);
+#[cfg(feature = "glam")]
+impl_glam_vec_conversions!(IVec4, i32, 4);
+#[cfg(feature = "glam")]
+impl_glam_vec_conversions!(UVec2, u32, 2);
+#[cfg(feature = "glam")]
+impl_glam_vec_conversions!(UVec3, u32, 3);
+#[cfg(feature = "glam")]
+impl_glam_vec_conversions!(UVec4, u32, 4);
+#[cfg(feature = "glam")]
+impl_glam_vec_conversions!(BVec2, bool, 2);
+#[cfg(feature = "glam")]
+impl_glam_vec_conversions!(BVec3, bool, 3);
+#[cfg(feature = "glam")]
+impl_glam_vec_conversions!(BVec4, bool, 4);
+
+#[cfg(feature = "glam")]
+macro_rules! impl_glam_mat_conversions {
+    ($mat:ident, $n:literal) => {
+        impl TryFrom<Constant> for glam::$mat {
+            type Error = Constant;
+
+            fn try_from(value: Constant) -> Result<Self, Self::Error> {
+                let columns = match &value {
+                    Constant::Mat(columns) if columns.len() == $n => columns,
+                    _ => return Err(value),
+                };
+
+                let mut result = [[0.0; $n]; $n];
+
+                for (x, column) in result.iter_mut().zip(columns) {
+                    *x = match <[f32; $n]>::try_from(column.clone()) {
+                        Ok(column) => column,
+                        Err(_) => return Err(value),
+                    };
+                }
+
+                Ok(Self::from_cols_array_2d(&result))
+            }
+        }
+    };
+}
+
+#[cfg(feature = "glam")]
+impl_glam_mat_conversions!(Mat2, 2);
+#[cfg(feature = "glam")]
+impl_glam_mat_conversions!(Mat3, 3);
+#[cfg(feature = "glam")]
+impl_glam_mat_conversions!(Mat4, 4);
@@ -1,5 +1,5 @@
 macro_rules! impl_gen_type {
-    ($ty:ident, $tyb:ident) => {
+    ($ty:ident, $tyb:ident, $tyi:ident, $tyu:ident) => {
         impl $ty {
             pub fn length(self) -> super::F32 {
                 super::primitives::built_in_1("length", self)
@@ -73,6 +73,10 @@ macro_rules! impl_gen_type {
                 super::primitives::built_in_1("sqrt", self)
             }
 
+            pub fn inverse_sqrt(self) -> Self {
+                super::primitives::built_in_1("inversesqrt", self)
+            }
+
             pub fn abs(self) -> Self {
                 super::primitives::built_in_1("abs", self)
             }
@@ -89,6 +93,16 @@ macro_rules! impl_gen_type {
                 super::primitives::built_in_1("round", self)
             }
 
+            /// Rounds to the nearest integer, rounding halfway cases to the
+            /// nearest even integer.
+            pub fn round_even(self) -> Self {
+                super::primitives::built_in_1("roundEven", self)
+            }
+
+            pub fn trunc(self) -> Self {
+                super::primitives::built_in_1("trunc", self)
+            }
+
             pub fn ceil(self) -> Self {
                 super::primitives::built_in_1("ceil", self)
             }
@@ -207,6 +221,72 @@ macro_rules! impl_gen_type {
                 super::primitives::built_in_3("smoothstep", edge0.to_sl(), edge1.to_sl(), self)
             }
 
+            pub fn is_nan(self) -> $tyb {
+                super::primitives::built_in_1("isnan", self)
+            }
+
+            pub fn is_infinite(self) -> $tyb {
+                super::primitives::built_in_1("isinf", self)
+            }
+
+            /// Returns the reflection direction of the incident vector `self`
+            /// at a surface with normal `normal`.
+            ///
+            /// `normal` should be normalized.
+            pub fn reflect(self, normal: impl crate::ToSl<Output = Self>) -> Self {
+                super::primitives::built_in_2("reflect", self, normal.to_sl())
+            }
+
+            /// Returns the refraction vector of the incident vector `self` at a
+            /// surface with normal `normal`, given the ratio of indices of
+            /// refraction `eta`.
+            ///
+            /// Both `self` and `normal` should be normalized.
+            pub fn refract(
+                self,
+                normal: impl crate::ToSl<Output = Self>,
+                eta: impl crate::ToSl<Output = F32>,
+            ) -> Self {
+                super::primitives::built_in_3("refract", self, normal.to_sl(), eta.to_sl())
+            }
+
+            /// Returns `self` if `incident.dot(reference) < 0`, and `-self`
+            /// otherwise.
+            pub fn face_forward(
+                self,
+                incident: impl crate::ToSl<Output = Self>,
+                reference: impl crate::ToSl<Output = Self>,
+            ) -> Self {
+                super::primitives::built_in_3(
+                    "faceforward",
+                    self,
+                    incident.to_sl(),
+                    reference.to_sl(),
+                )
+            }
+
+            /// Returns the raw bits of the floating-point representation.
+            pub fn to_bits(self) -> $tyu {
+                super::primitives::built_in_1("floatBitsToUint", self)
+            }
+
+            /// Returns the raw bits of the floating-point representation as
+            /// signed integers.
+            pub fn to_bits_signed(self) -> $tyi {
+                super::primitives::built_in_1("floatBitsToInt", self)
+            }
+
+            /// Reinterprets raw bits as floating-point representation.
+            pub fn from_bits(bits: impl crate::ToSl<Output = $tyu>) -> Self {
+                super::primitives::built_in_1("uintBitsToFloat", bits.to_sl())
+            }
+
+            /// Reinterprets raw bits given as signed integers as floating-point
+            /// representation.
+            pub fn from_bits_signed(bits: impl crate::ToSl<Output = $tyi>) -> Self {
+                super::primitives::built_in_1("intBitsToFloat", bits.to_sl())
+            }
+
             pub fn dfdx(self, _: super::Derivatives) -> Self {
                 super::primitives::built_in_1("dFdx", self)
             }
@@ -221,3 +301,70 @@ macro_rules! impl_gen_type {
         }
     };
 }
+
+macro_rules! impl_gen_integral_type {
+    ($ty:ident) => {
+        impl $ty {
+            pub fn min(self, y: impl crate::ToSl<Output = Self>) -> Self {
+                super::primitives::built_in_2("min", self, y.to_sl())
+            }
+
+            pub fn max(self, y: impl crate::ToSl<Output = Self>) -> Self {
+                super::primitives::built_in_2("max", self, y.to_sl())
+            }
+
+            pub fn clamp(
+                self,
+                min: impl crate::ToSl<Output = Self>,
+                max: impl crate::ToSl<Output = Self>,
+            ) -> Self {
+                super::primitives::built_in_3("clamp", self, min.to_sl(), max.to_sl())
+            }
+        }
+    };
+}
+
+macro_rules! impl_gen_signed_type {
+    ($ty:ident) => {
+        impl_gen_integral_type!($ty);
+
+        impl $ty {
+            pub fn abs(self) -> Self {
+                super::primitives::built_in_1("abs", self)
+            }
+
+            pub fn signum(self) -> Self {
+                super::primitives::built_in_1("sign", self)
+            }
+        }
+    };
+}
+
+macro_rules! impl_precision {
+    ($ty:ident) => {
+        impl $ty {
+            /// Marks this value to be computed with low precision.
+            ///
+            /// This overrides the default precision of the shader. Shader
+            /// outputs that are assigned a marked value are declared with its
+            /// precision. This only has an effect when targeting GLSL ES.
+            pub fn lowp(self) -> Self {
+                super::primitives::precision(self, super::program_def::PrecisionQualifier::Low)
+            }
+
+            /// Marks this value to be computed with medium precision.
+            ///
+            /// See [`Self::lowp`].
+            pub fn mediump(self) -> Self {
+                super::primitives::precision(self, super::program_def::PrecisionQualifier::Medium)
+            }
+
+            /// Marks this value to be computed with high precision.
+            ///
+            /// See [`Self::lowp`].
+            pub fn highp(self) -> Self {
+                super::primitives::precision(self, super::program_def::PrecisionQualifier::High)
+            }
+        }
+    };
+}
@@ -252,6 +252,10 @@ impl_mat!(Mat2, Vec2, (x_axis, y_axis), (X, Y));
 impl_mat!(Mat3, Vec3, (x_axis, y_axis, z_axis), (X, Y, Z));
 impl_mat!(Mat4, Vec4, (x_axis, y_axis, z_axis, w_axis), (X, Y, Z, W));
 
+impl_precision!(Mat2);
+impl_precision!(Mat3);
+impl_precision!(Mat4);
+
 #[cfg(feature = "glam")]
 impl ToSl for glam::Mat2 {
     type Output = Mat2;
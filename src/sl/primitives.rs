@@ -4,7 +4,8 @@ use crate::ToSl;
 
 use super::{
     dag::{BinaryOp, Expr, FuncDef, StructType, Type, UnaryOp},
-    Bool, Object, Value,
+    program_def::PrecisionQualifier,
+    Bool, Object, Value, U32,
 };
 
 pub fn and(left: impl ToSl<Output = Bool>, right: impl ToSl<Output = Bool>) -> Bool {
@@ -61,6 +62,72 @@ where
     }
 }
 
+/// Folds `f` over the range `start..end`, starting with the value `init`.
+///
+/// Unlike a Rust loop in shader code, which is unrolled when the shader is
+/// traced, this is emitted as a loop in the generated shader. The bounds of
+/// the range need not be constant, so they can e.g. come from a uniform.
+pub fn fold_range<V: Value>(
+    start: impl ToSl<Output = U32>,
+    end: impl ToSl<Output = U32>,
+    init: impl ToSl<Output = V>,
+    f: impl FnOnce(V, U32) -> V,
+) -> V {
+    fold_range_impl(start, end, init, |acc, index| (f(acc, index), None))
+}
+
+/// Folds `f` over the range `start..end`, starting with the value `init`, and
+/// stops early once `f` signals to do so.
+///
+/// The closure `f` returns the next value of the accumulator together with a
+/// condition. If the condition is true, the loop exits with the next value of
+/// the accumulator.
+///
+/// See also [`fold_range`].
+pub fn fold_range_until<V: Value>(
+    start: impl ToSl<Output = U32>,
+    end: impl ToSl<Output = U32>,
+    init: impl ToSl<Output = V>,
+    f: impl FnOnce(V, U32) -> (V, Bool),
+) -> V {
+    fold_range_impl(start, end, init, |acc, index| {
+        let (next, stop) = f(acc, index);
+
+        (next, Some(stop))
+    })
+}
+
+fn fold_range_impl<V: Value>(
+    start: impl ToSl<Output = U32>,
+    end: impl ToSl<Output = U32>,
+    init: impl ToSl<Output = V>,
+    f: impl FnOnce(V, U32) -> (V, Option<Bool>),
+) -> V {
+    let ty = V::ty();
+    let start = start.to_sl().expr();
+    let end = end.to_sl().expr();
+    let init = init.to_sl().expr();
+
+    // The loop body is traced once, with placeholder arguments standing in for
+    // the accumulator and the loop index.
+    let acc = V::from_arg("loop_acc");
+    let index = U32::from_arg("loop_index");
+    let (next, stop) = f(acc, index);
+
+    let expr = Expr::Loop {
+        start,
+        end,
+        init,
+        acc: acc.expr(),
+        index: index.expr(),
+        next: next.expr(),
+        stop: stop.map(|stop| stop.expr()),
+        ty,
+    };
+
+    V::from_expr(expr)
+}
+
 pub(crate) fn cast<U, V>(u: impl ToSl<Output = U>) -> V
 where
     U: Value,
@@ -106,6 +173,15 @@ where
     R::from_expr(expr)
 }
 
+pub(crate) fn precision<V: Value>(value: V, precision: PrecisionQualifier) -> V {
+    let ty = V::ty();
+    let arg = value.expr();
+
+    let expr = Expr::Precision { ty, arg, precision };
+
+    V::from_expr(expr)
+}
+
 pub(crate) fn built_in_1<U, R>(name: &str, u: U) -> R
 where
     U: Object,
@@ -151,6 +227,23 @@ where
     R::from_expr(expr)
 }
 
+pub(crate) fn built_in_4<U, V, W, X, R>(name: &str, u: U, v: V, w: W, x: X) -> R
+where
+    U: Object,
+    V: Object,
+    W: Object,
+    X: Object,
+    R: Value,
+{
+    let ty = R::ty();
+    let name = name.to_string();
+    let args = vec![u.expr(), v.expr(), w.expr(), x.expr()];
+
+    let expr = Expr::CallBuiltIn { ty, name, args };
+
+    R::from_expr(expr)
+}
+
 #[doc(hidden)]
 pub fn field<R: Value>(base: Rc<Expr>, name: &'static str) -> R {
     let ty = R::ty();
@@ -161,7 +254,36 @@ pub fn field<R: Value>(base: Rc<Expr>, name: &'static str) -> R {
 }
 
 #[doc(hidden)]
-pub fn call_func_def<R: Value>(def: FuncDef, args: Vec<Rc<Expr>>) -> R {
+pub fn call_func_def<M: 'static, R: Value>(
+    name: &'static str,
+    params: impl FnOnce() -> Vec<(&'static str, Type)>,
+    body: impl FnOnce() -> R,
+    args: Vec<Rc<Expr>>,
+) -> R {
+    use std::{any::TypeId, cell::RefCell, collections::BTreeMap};
+
+    std::thread_local! {
+        static MAP: RefCell<BTreeMap<TypeId, FuncDef>> = const { RefCell::new(BTreeMap::new()) };
+    }
+
+    let key = TypeId::of::<M>();
+
+    let def = if let Some(def) = MAP.with(|map| map.borrow().get(&key).cloned()) {
+        def
+    } else {
+        // We must not borrow `MAP` while tracing the body, since the body may
+        // also call functions.
+        let def = FuncDef {
+            name,
+            params: params(),
+            result: body().expr(),
+        };
+
+        MAP.with(|map| map.borrow_mut().insert(key, def.clone()));
+
+        def
+    };
+
     assert!(def.params.len() == args.len());
 
     let expr = Expr::CallFuncDef { def, args };
@@ -1,9 +1,14 @@
-//! Definitions for generated GLSL source code.
+//! Definitions for generated GLSL and WGSL source code.
 //!
 //! This is exposed only in order to make the internally generated source code
 //! more transparent. It is typically not necessary to use this module.
 
-use super::dag::{BuiltInType, SamplerType, Type};
+use std::{
+    fmt::{self, Display, Formatter},
+    rc::Rc,
+};
+
+use super::dag::{BuiltInType, Expr, SamplerType, Type};
 
 /// Uniform input definition.
 #[derive(Debug, Clone)]
@@ -61,6 +66,42 @@ impl InterpolationQualifier {
     }
 }
 
+/// Precision qualifier of a value in GLSL ES.
+///
+/// Precision qualifiers only have an effect when targeting GLSL ES. They are
+/// omitted in the source code generated for other targets.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum PrecisionQualifier {
+    Low,
+    Medium,
+    High,
+}
+
+impl PrecisionQualifier {
+    pub fn to_glsl(self) -> &'static str {
+        use PrecisionQualifier::*;
+
+        match self {
+            Low => "lowp",
+            Medium => "mediump",
+            High => "highp",
+        }
+    }
+}
+
+/// Definition of a value that is passed from the vertex shader to the fragment
+/// shader.
+#[derive(Debug, Clone)]
+pub struct VaryingDef {
+    pub name: String,
+    pub ty: Type,
+    pub interpolation: InterpolationQualifier,
+
+    /// The precision with which the value is declared. If this is `None`, the
+    /// default precision of the program is used.
+    pub precision: Option<PrecisionQualifier>,
+}
+
 /// VsInterface input definition.
 #[derive(Debug, Clone)]
 pub struct VertexBlockDef {
@@ -88,4 +129,122 @@ pub struct ProgramDef {
 
     /// FsInterface shader source code.
     pub fragment_shader_source: String,
+
+    /// Values that are passed from the vertex shader to the fragment shader.
+    pub varying_defs: Vec<VaryingDef>,
+
+    /// The layout in which the vertex shader output is captured into a vertex
+    /// buffer with transform feedback, if the program captures its output.
+    ///
+    /// Each attribute names the varying that is written to it.
+    pub transform_feedback_block_def: Option<VertexBlockDef>,
+
+    /// Expressions computed by the vertex shader, keyed by the name of the
+    /// output that they are assigned to.
+    ///
+    /// This is the input from which `vertex_shader_source` is generated. It
+    /// is used for evaluating programs on the CPU.
+    pub vertex_shader_exprs: Vec<(String, Rc<Expr>)>,
+
+    /// Expressions computed by the fragment shader, keyed by the name of the
+    /// output that they are assigned to.
+    ///
+    /// Color outputs come first, ordered by their location.
+    pub fragment_shader_exprs: Vec<(String, Rc<Expr>)>,
+}
+
+/// The group and binding index of a resource in a WGSL module.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct WgslBinding {
+    pub group: u32,
+    pub binding: u32,
+}
+
+impl Display for WgslBinding {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "@group({}) @binding({})", self.group, self.binding)
+    }
+}
+
+/// Uniform input definition of a WGSL program.
+#[derive(Debug, Clone)]
+pub struct WgslUniformBlockDef {
+    /// The name of the uniform variable.
+    pub name: String,
+
+    /// The type of the uniform variable.
+    pub ty: Type,
+
+    pub binding: WgslBinding,
+}
+
+/// Sampler input definition of a WGSL program.
+///
+/// WGSL separates textures from samplers, so every sampler is split into a
+/// texture variable named `{name}_texture` and a sampler variable named
+/// `{name}_sampler`. Integer textures cannot be filtered and have no sampler.
+#[derive(Debug, Clone)]
+pub struct WgslSamplerDef {
+    pub name: String,
+    pub ty: SamplerType,
+    pub texture_binding: WgslBinding,
+    pub sampler_binding: Option<WgslBinding>,
+}
+
+/// Vertex attribute definition of a WGSL program.
+///
+/// Matrices are split into one input per column, taking up consecutive
+/// locations starting at `location`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WgslVertexAttributeDef {
+    pub name: String,
+    pub ty: BuiltInType,
+    pub offset: usize,
+    pub location: u32,
+}
+
+/// Vertex input definition of a WGSL program, corresponding to one vertex
+/// buffer.
+#[derive(Debug, Clone)]
+pub struct WgslVertexBlockDef {
+    pub attributes: Vec<WgslVertexAttributeDef>,
+}
+
+/// Definition of a value that is passed from the vertex shader to the fragment
+/// shader in a WGSL program.
+#[derive(Debug, Clone)]
+pub struct WgslVaryingDef {
+    pub name: String,
+    pub ty: Type,
+    pub interpolation: InterpolationQualifier,
+    pub location: u32,
+}
+
+/// Type-erased definition of a WGSL program.
+///
+/// Both shader stages are contained in a single module. See
+/// [`crate::sl::transpile::transpile_to_wgsl_program_def`] for how to
+/// construct a [`WgslProgramDef`].
+#[derive(Debug, Clone, Default)]
+pub struct WgslProgramDef {
+    /// Uniforms that the program needs.
+    pub uniform_block_defs: Vec<WgslUniformBlockDef>,
+
+    /// Samplers that the program needs.
+    pub uniform_sampler_defs: Vec<WgslSamplerDef>,
+
+    /// Vertex buffers that the program needs.
+    pub vertex_block_defs: Vec<WgslVertexBlockDef>,
+
+    /// Values that are passed from the vertex shader to the fragment shader.
+    pub varying_defs: Vec<WgslVaryingDef>,
+
+    /// The name of the vertex shader entry point.
+    pub vertex_entry_point: &'static str,
+
+    /// The name of the fragment shader entry point.
+    pub fragment_entry_point: &'static str,
+
+    /// WGSL module source code.
+    pub source: String,
 }
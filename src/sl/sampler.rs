@@ -1,4 +1,4 @@
-use std::rc::Rc;
+use std::{marker::PhantomData, rc::Rc};
 
 use sealed::sealed;
 
@@ -6,7 +6,8 @@ use crate::{Block, Sl, ToSl};
 
 use super::{
     dag::{BuiltInType, Expr, SamplerType, Trace, Type},
-    primitives::built_in_2,
+    ivec2,
+    primitives::{built_in_2, built_in_3, built_in_4},
     IVec2, IVec3, IVec4, Interpolant, Object, UVec2, UVec3, UVec4, Value, Vec2, Vec3, Vec4, F32,
     I32, U32,
 };
@@ -17,6 +18,12 @@ use super::{
 pub trait Sample {
     const SAMPLER_TYPE: SamplerType;
 
+    const SAMPLER_2D_ARRAY_TYPE: SamplerType;
+
+    const SAMPLER_3D_TYPE: SamplerType;
+
+    const SAMPLER_CUBE_TYPE: SamplerType;
+
     type Vec4: Value;
 
     type Gl;
@@ -33,6 +40,12 @@ macro_rules! impl_color_sample {
         impl Sample for $sample {
             const SAMPLER_TYPE: SamplerType = SamplerType::$ty;
 
+            const SAMPLER_2D_ARRAY_TYPE: SamplerType = array_sampler_type(SamplerType::$ty);
+
+            const SAMPLER_3D_TYPE: SamplerType = volume_sampler_type(SamplerType::$ty);
+
+            const SAMPLER_CUBE_TYPE: SamplerType = cube_sampler_type(SamplerType::$ty);
+
             type Vec4 = $vec4;
 
             type Gl = <$sample as Block<Sl>>::Gl;
@@ -60,6 +73,33 @@ impl_color_sample!(Vec4, ColorSampler2d, |v: Vec4| v);
 impl_color_sample!(IVec4, IColorSampler2d, |v: IVec4| v);
 impl_color_sample!(UVec4, UColorSampler2d, |v: UVec4| v);
 
+const fn array_sampler_type(ty: SamplerType) -> SamplerType {
+    match ty {
+        SamplerType::ColorSampler2d => SamplerType::ColorSampler2dArray,
+        SamplerType::IColorSampler2d => SamplerType::IColorSampler2dArray,
+        SamplerType::UColorSampler2d => SamplerType::UColorSampler2dArray,
+        _ => panic!("expected a two-dimensional color sampler type"),
+    }
+}
+
+const fn volume_sampler_type(ty: SamplerType) -> SamplerType {
+    match ty {
+        SamplerType::ColorSampler2d => SamplerType::ColorSampler3d,
+        SamplerType::IColorSampler2d => SamplerType::IColorSampler3d,
+        SamplerType::UColorSampler2d => SamplerType::UColorSampler3d,
+        _ => panic!("expected a two-dimensional color sampler type"),
+    }
+}
+
+const fn cube_sampler_type(ty: SamplerType) -> SamplerType {
+    match ty {
+        SamplerType::ColorSampler2d => SamplerType::ColorSamplerCube,
+        SamplerType::IColorSampler2d => SamplerType::IColorSamplerCube,
+        SamplerType::UColorSampler2d => SamplerType::UColorSamplerCube,
+        _ => panic!("expected a two-dimensional color sampler type"),
+    }
+}
+
 #[derive(Debug, Copy, Clone)]
 pub enum ColorSampler2dInternal<S> {
     Sampler(Trace),
@@ -75,6 +115,42 @@ pub struct ComparisonSampler2d {
     trace: Trace,
 }
 
+/// An array of two-dimensional images which can be sampled.
+///
+/// The third texture coordinate selects the layer.
+#[derive(Debug, Copy, Clone)]
+pub struct ColorSampler2dArray<S = Vec4> {
+    trace: Trace,
+    _phantom: PhantomData<S>,
+}
+
+/// An array of two-dimensional depth images which can be sampled with
+/// comparison.
+#[derive(Debug, Copy, Clone)]
+pub struct ComparisonSampler2dArray {
+    trace: Trace,
+}
+
+/// A three-dimensional object which can be sampled.
+#[derive(Debug, Copy, Clone)]
+pub struct ColorSampler3d<S = Vec4> {
+    trace: Trace,
+    _phantom: PhantomData<S>,
+}
+
+/// A cube map which can be sampled in a direction.
+#[derive(Debug, Copy, Clone)]
+pub struct ColorSamplerCube<S = Vec4> {
+    trace: Trace,
+    _phantom: PhantomData<S>,
+}
+
+/// A depth cube map which can be sampled in a direction with comparison.
+#[derive(Debug, Copy, Clone)]
+pub struct ComparisonSamplerCube {
+    trace: Trace,
+}
+
 impl<S: ColorSample> Object for ColorSampler2d<S> {
     fn ty() -> Type {
         Type::BuiltIn(BuiltInType::Sampler(S::SAMPLER_TYPE))
@@ -103,9 +179,54 @@ impl<S: ColorSample> ColorSampler2d<S> {
     }
 
     pub fn sample(self, tex_coords: Vec2) -> S {
-        let sample = built_in_2("texture", self, tex_coords);
+        self.sample_with(|sampler| built_in_2("texture", sampler, tex_coords))
+    }
 
-        S::from_vec4(sample)
+    /// Samples at an explicit level of detail.
+    ///
+    /// Unlike [`Self::sample`], this can be used in vertex shaders.
+    pub fn sample_lod(self, tex_coords: Vec2, lod: impl ToSl<Output = F32>) -> S {
+        self.sample_with(|sampler| built_in_3("textureLod", sampler, tex_coords, lod.to_sl()))
+    }
+
+    /// Samples with a bias added to the implicitly computed level of detail.
+    pub fn sample_bias(self, tex_coords: Vec2, bias: impl ToSl<Output = F32>) -> S {
+        self.sample_with(|sampler| built_in_3("texture", sampler, tex_coords, bias.to_sl()))
+    }
+
+    /// Samples with explicitly given derivatives of the texture coordinates.
+    pub fn sample_grad(self, tex_coords: Vec2, dpdx: Vec2, dpdy: Vec2) -> S {
+        self.sample_with(|sampler| built_in_4("textureGrad", sampler, tex_coords, dpdx, dpdy))
+    }
+
+    /// Samples with a constant offset applied to the texel coordinates.
+    ///
+    /// The offset must lie within the range given by the implementation's
+    /// `MIN_PROGRAM_TEXEL_OFFSET` and `MAX_PROGRAM_TEXEL_OFFSET`, which is at
+    /// least `[-8, 7]`.
+    pub fn sample_offset(self, tex_coords: Vec2, offset: [i32; 2]) -> S {
+        self.sample_with(|sampler| {
+            built_in_3("textureOffset", sampler, tex_coords, texel_offset(offset))
+        })
+    }
+
+    /// Samples with projection, i.e. the texture coordinates are divided by
+    /// the last component of `tex_coords`.
+    pub fn sample_proj(self, tex_coords: Vec3) -> S {
+        self.sample_with(|sampler| built_in_2("textureProj", sampler, tex_coords))
+    }
+
+    /// Looks up a single texel at integer coordinates of the given mipmap
+    /// level, without any filtering.
+    pub fn texel_fetch(self, coords: UVec2, level: impl ToSl<Output = U32>) -> S {
+        self.sample_with(|sampler| {
+            built_in_3(
+                "texelFetch",
+                sampler,
+                coords.as_ivec2(),
+                level.to_sl().as_i32(),
+            )
+        })
     }
 
     pub fn size(self, level: impl ToSl<Output = U32>) -> UVec2 {
@@ -114,7 +235,14 @@ impl<S: ColorSample> ColorSampler2d<S> {
         size.as_uvec2()
     }
 
-    // TODO: Various sampling functions.
+    fn sample_with(self, f: impl FnOnce(Self) -> S::Vec4) -> S {
+        use ColorSampler2dInternal::*;
+
+        match self.0 {
+            Sampler(_) => S::from_vec4(f(self)),
+            Constant(sample) => sample,
+        }
+    }
 }
 
 impl Object for ComparisonSampler2d {
@@ -141,9 +269,339 @@ impl ComparisonSampler2d {
         built_in_2("texture", self, tex_coords.extend(reference_depth))
     }
 
+    /// Compares at an explicit level of detail.
+    pub fn sample_compare_lod(
+        self,
+        tex_coords: Vec2,
+        reference_depth: F32,
+        lod: impl ToSl<Output = F32>,
+    ) -> F32 {
+        built_in_3(
+            "textureLod",
+            self,
+            tex_coords.extend(reference_depth),
+            lod.to_sl(),
+        )
+    }
+
+    /// Compares with a bias added to the implicitly computed level of detail.
+    pub fn sample_compare_bias(
+        self,
+        tex_coords: Vec2,
+        reference_depth: F32,
+        bias: impl ToSl<Output = F32>,
+    ) -> F32 {
+        built_in_3(
+            "texture",
+            self,
+            tex_coords.extend(reference_depth),
+            bias.to_sl(),
+        )
+    }
+
+    /// Compares with explicitly given derivatives of the texture coordinates.
+    pub fn sample_compare_grad(
+        self,
+        tex_coords: Vec2,
+        reference_depth: F32,
+        dpdx: Vec2,
+        dpdy: Vec2,
+    ) -> F32 {
+        built_in_4(
+            "textureGrad",
+            self,
+            tex_coords.extend(reference_depth),
+            dpdx,
+            dpdy,
+        )
+    }
+
+    /// Compares with a constant offset applied to the texel coordinates.
+    ///
+    /// See [`ColorSampler2d::sample_offset`] for the valid range of offsets.
+    pub fn sample_compare_offset(
+        self,
+        tex_coords: Vec2,
+        reference_depth: F32,
+        offset: [i32; 2],
+    ) -> F32 {
+        built_in_3(
+            "textureOffset",
+            self,
+            tex_coords.extend(reference_depth),
+            texel_offset(offset),
+        )
+    }
+
+    /// Compares with projection, i.e. the texture coordinates and the
+    /// reference depth are divided by `q`.
+    pub fn sample_compare_proj(
+        self,
+        tex_coords: Vec2,
+        reference_depth: F32,
+        q: impl ToSl<Output = F32>,
+    ) -> F32 {
+        built_in_2(
+            "textureProj",
+            self,
+            tex_coords.extend(reference_depth).extend(q.to_sl()),
+        )
+    }
+
+    pub fn size(self, level: impl ToSl<Output = U32>) -> UVec2 {
+        let size: IVec2 = built_in_2("textureSize", self, level.to_sl().as_i32());
+
+        size.as_uvec2()
+    }
+}
+
+impl<S: ColorSample> Object for ColorSampler2dArray<S> {
+    fn ty() -> Type {
+        Type::BuiltIn(BuiltInType::Sampler(S::SAMPLER_2D_ARRAY_TYPE))
+    }
+
+    fn expr(&self) -> Rc<Expr> {
+        self.trace.expr()
+    }
+
+    fn from_arg(name: &str) -> Self {
+        Self {
+            trace: Trace::new(Expr::Arg {
+                ty: Self::ty(),
+                name: name.into(),
+            }),
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<S: ColorSample> ColorSampler2dArray<S> {
+    /// Samples the layer given by the rounded third component of
+    /// `tex_coords`.
+    pub fn sample(self, tex_coords: Vec3) -> S {
+        S::from_vec4(built_in_2("texture", self, tex_coords))
+    }
+
+    /// Samples at an explicit level of detail.
+    ///
+    /// See [`ColorSampler2d::sample_lod`].
+    pub fn sample_lod(self, tex_coords: Vec3, lod: impl ToSl<Output = F32>) -> S {
+        S::from_vec4(built_in_3("textureLod", self, tex_coords, lod.to_sl()))
+    }
+
+    /// Samples with a bias added to the implicitly computed level of detail.
+    pub fn sample_bias(self, tex_coords: Vec3, bias: impl ToSl<Output = F32>) -> S {
+        S::from_vec4(built_in_3("texture", self, tex_coords, bias.to_sl()))
+    }
+
+    /// Samples with explicitly given derivatives of the two-dimensional
+    /// texture coordinates.
+    pub fn sample_grad(self, tex_coords: Vec3, dpdx: Vec2, dpdy: Vec2) -> S {
+        S::from_vec4(built_in_4("textureGrad", self, tex_coords, dpdx, dpdy))
+    }
+
+    /// Looks up a single texel at integer coordinates of the given mipmap
+    /// level, without any filtering. The third component of `coords` is the
+    /// layer.
+    pub fn texel_fetch(self, coords: UVec3, level: impl ToSl<Output = U32>) -> S {
+        S::from_vec4(built_in_3(
+            "texelFetch",
+            self,
+            coords.as_ivec3(),
+            level.to_sl().as_i32(),
+        ))
+    }
+
+    /// Returns the width, the height and the number of layers.
+    pub fn size(self, level: impl ToSl<Output = U32>) -> UVec3 {
+        let size: IVec3 = built_in_2("textureSize", self, level.to_sl().as_i32());
+
+        size.as_uvec3()
+    }
+}
+
+impl Object for ComparisonSampler2dArray {
+    fn ty() -> Type {
+        Type::BuiltIn(BuiltInType::Sampler(SamplerType::ComparisonSampler2dArray))
+    }
+
+    fn expr(&self) -> Rc<Expr> {
+        self.trace.expr()
+    }
+
+    fn from_arg(name: &str) -> Self {
+        Self {
+            trace: Trace::new(Expr::Arg {
+                ty: Self::ty(),
+                name: name.into(),
+            }),
+        }
+    }
+}
+
+impl ComparisonSampler2dArray {
+    /// Compares against the layer given by the rounded third component of
+    /// `tex_coords`.
+    pub fn sample_compare(self, tex_coords: Vec3, reference_depth: F32) -> F32 {
+        built_in_2("texture", self, tex_coords.extend(reference_depth))
+    }
+
+    /// Returns the width, the height and the number of layers.
+    pub fn size(self, level: impl ToSl<Output = U32>) -> UVec3 {
+        let size: IVec3 = built_in_2("textureSize", self, level.to_sl().as_i32());
+
+        size.as_uvec3()
+    }
+}
+
+impl<S: ColorSample> Object for ColorSampler3d<S> {
+    fn ty() -> Type {
+        Type::BuiltIn(BuiltInType::Sampler(S::SAMPLER_3D_TYPE))
+    }
+
+    fn expr(&self) -> Rc<Expr> {
+        self.trace.expr()
+    }
+
+    fn from_arg(name: &str) -> Self {
+        Self {
+            trace: Trace::new(Expr::Arg {
+                ty: Self::ty(),
+                name: name.into(),
+            }),
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<S: ColorSample> ColorSampler3d<S> {
+    pub fn sample(self, tex_coords: Vec3) -> S {
+        S::from_vec4(built_in_2("texture", self, tex_coords))
+    }
+
+    /// Samples at an explicit level of detail.
+    ///
+    /// See [`ColorSampler2d::sample_lod`].
+    pub fn sample_lod(self, tex_coords: Vec3, lod: impl ToSl<Output = F32>) -> S {
+        S::from_vec4(built_in_3("textureLod", self, tex_coords, lod.to_sl()))
+    }
+
+    /// Samples with a bias added to the implicitly computed level of detail.
+    pub fn sample_bias(self, tex_coords: Vec3, bias: impl ToSl<Output = F32>) -> S {
+        S::from_vec4(built_in_3("texture", self, tex_coords, bias.to_sl()))
+    }
+
+    /// Samples with explicitly given derivatives of the texture coordinates.
+    pub fn sample_grad(self, tex_coords: Vec3, dpdx: Vec3, dpdy: Vec3) -> S {
+        S::from_vec4(built_in_4("textureGrad", self, tex_coords, dpdx, dpdy))
+    }
+
+    /// Looks up a single texel at integer coordinates of the given mipmap
+    /// level, without any filtering.
+    pub fn texel_fetch(self, coords: UVec3, level: impl ToSl<Output = U32>) -> S {
+        S::from_vec4(built_in_3(
+            "texelFetch",
+            self,
+            coords.as_ivec3(),
+            level.to_sl().as_i32(),
+        ))
+    }
+
+    pub fn size(self, level: impl ToSl<Output = U32>) -> UVec3 {
+        let size: IVec3 = built_in_2("textureSize", self, level.to_sl().as_i32());
+
+        size.as_uvec3()
+    }
+}
+
+impl<S: ColorSample> Object for ColorSamplerCube<S> {
+    fn ty() -> Type {
+        Type::BuiltIn(BuiltInType::Sampler(S::SAMPLER_CUBE_TYPE))
+    }
+
+    fn expr(&self) -> Rc<Expr> {
+        self.trace.expr()
+    }
+
+    fn from_arg(name: &str) -> Self {
+        Self {
+            trace: Trace::new(Expr::Arg {
+                ty: Self::ty(),
+                name: name.into(),
+            }),
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<S: ColorSample> ColorSamplerCube<S> {
+    /// Samples the face that `direction` points at.
+    ///
+    /// The length of `direction` does not matter. Filtering takes texels from
+    /// adjacent faces near the edges of a face.
+    pub fn sample(self, direction: Vec3) -> S {
+        S::from_vec4(built_in_2("texture", self, direction))
+    }
+
+    /// Samples at an explicit level of detail.
+    ///
+    /// See [`ColorSampler2d::sample_lod`].
+    pub fn sample_lod(self, direction: Vec3, lod: impl ToSl<Output = F32>) -> S {
+        S::from_vec4(built_in_3("textureLod", self, direction, lod.to_sl()))
+    }
+
+    /// Samples with a bias added to the implicitly computed level of detail.
+    pub fn sample_bias(self, direction: Vec3, bias: impl ToSl<Output = F32>) -> S {
+        S::from_vec4(built_in_3("texture", self, direction, bias.to_sl()))
+    }
+
+    /// Returns the width and the height of each face.
     pub fn size(self, level: impl ToSl<Output = U32>) -> UVec2 {
         let size: IVec2 = built_in_2("textureSize", self, level.to_sl().as_i32());
 
         size.as_uvec2()
     }
 }
+
+impl Object for ComparisonSamplerCube {
+    fn ty() -> Type {
+        Type::BuiltIn(BuiltInType::Sampler(SamplerType::ComparisonSamplerCube))
+    }
+
+    fn expr(&self) -> Rc<Expr> {
+        self.trace.expr()
+    }
+
+    fn from_arg(name: &str) -> Self {
+        Self {
+            trace: Trace::new(Expr::Arg {
+                ty: Self::ty(),
+                name: name.into(),
+            }),
+        }
+    }
+}
+
+impl ComparisonSamplerCube {
+    /// Compares against the face that `direction` points at.
+    ///
+    /// For omnidirectional shadows, `direction` is the vector from the light
+    /// to the fragment.
+    pub fn sample_compare(self, direction: Vec3, reference_depth: F32) -> F32 {
+        built_in_2("texture", self, direction.extend(reference_depth))
+    }
+
+    /// Returns the width and the height of each face.
+    pub fn size(self, level: impl ToSl<Output = U32>) -> UVec2 {
+        let size: IVec2 = built_in_2("textureSize", self, level.to_sl().as_i32());
+
+        size.as_uvec2()
+    }
+}
+
+// GLSL ES 3.00 requires texel offsets to be constant expressions. We construct
+// a fresh literal for every call, so that codegen never moves the offset into
+// a variable.
+fn texel_offset([x, y]: [i32; 2]) -> IVec2 {
+    ivec2(x, y)
+}
@@ -9,8 +9,8 @@ use crate::ToSl;
 use super::{
     branches,
     dag::{BinaryOp, Expr, Trace, Type, UnaryOp},
-    primitives::{binary, cast, unary, value_arg},
-    FsInput, Object, Value, ValueNonArray,
+    primitives::{binary, built_in_1, cast, unary, value_arg},
+    FsInput, Object, Value, ValueNonArray, Vec2,
 };
 
 // Maps from logical scalar type to physical scalar type.
@@ -277,7 +277,14 @@ impl_numeric_ops!(U32);
 impl_integral_ops!(I32);
 impl_integral_ops!(U32);
 
-impl_gen_type!(F32, Bool);
+impl_gen_type!(F32, Bool, I32, U32);
+
+impl_precision!(F32);
+impl_precision!(I32);
+impl_precision!(U32);
+
+impl_gen_signed_type!(I32);
+impl_gen_integral_type!(U32);
 
 impl F32 {
     pub const ZERO: Self = F32(Trace::c(|| 0.0.to_sl().expr()));
@@ -326,6 +333,27 @@ impl U32 {
     pub fn as_bool(self) -> Bool {
         cast(self)
     }
+
+    /// Unpacks two 16-bit floating-point numbers.
+    ///
+    /// This is the inverse of [`Vec2::pack_half_2x16`].
+    pub fn unpack_half_2x16(self) -> Vec2 {
+        built_in_1("unpackHalf2x16", self)
+    }
+
+    /// Unpacks two 16-bit unsigned normalized integers.
+    ///
+    /// This is the inverse of [`Vec2::pack_unorm_2x16`].
+    pub fn unpack_unorm_2x16(self) -> Vec2 {
+        built_in_1("unpackUnorm2x16", self)
+    }
+
+    /// Unpacks two 16-bit signed normalized integers.
+    ///
+    /// This is the inverse of [`Vec2::pack_snorm_2x16`].
+    pub fn unpack_snorm_2x16(self) -> Vec2 {
+        built_in_1("unpackSnorm2x16", self)
+    }
 }
 
 impl Bool {
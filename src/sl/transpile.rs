@@ -5,6 +5,8 @@
 
 use std::{iter::once, rc::Rc};
 
+use thiserror::Error;
+
 use crate::{
     interface::{FragmentVisitor, UniformUnion, UniformVisitor, VertexVisitor},
     Block, FsInterface, Sl, VsInterface,
@@ -12,20 +14,111 @@ use crate::{
 
 use super::{
     codegen,
-    dag::{Expr, SamplerType, Trace, Type},
+    dag::{BuiltInType, Expr, SamplerType, Trace, Type},
     primitives::value_arg,
-    program_def::{ProgramDef, UniformBlockDef, UniformSamplerDef, VertexBlockDef},
+    program_def::{
+        PrecisionQualifier, ProgramDef, UniformBlockDef, UniformSamplerDef, VaryingDef,
+        VertexBlockDef, WgslBinding, WgslProgramDef, WgslSamplerDef, WgslUniformBlockDef,
+        WgslVaryingDef, WgslVertexAttributeDef, WgslVertexBlockDef,
+    },
     sig::{FromFsInput, FromVsInput, VsFunc, VsSig},
-    ColorSample, ColorSampler2d, ComparisonSampler2d, Derivatives, FsFunc, FsInput, FsSig,
-    Interpolant, IntoFullFsOutput, IntoFullVsOutput, Object, VsInput, I32,
+    ColorSample, ColorSampler2d, ColorSampler2dArray, ColorSampler3d, ColorSamplerCube,
+    ComparisonSampler2d, ComparisonSampler2dArray, ComparisonSamplerCube, Derivatives, FsFunc,
+    FsInput, FsSig, Interpolant, IntoFullFsOutput, IntoFullVsOutput, Object, VsInput, I32,
 };
 
+/// The GLSL dialect that shader source code is generated for.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Hash)]
+pub enum ShaderTarget {
+    /// GLSL ES 3.00, for OpenGL ES 3.0 and WebGL 2.
+    #[default]
+    GlslEs300,
+
+    /// GLSL 3.30 for OpenGL 3.3 core profiles.
+    Glsl330,
+
+    /// GLSL 4.10 for OpenGL 4.1 core profiles.
+    Glsl410,
+
+    /// GLSL 4.50 for OpenGL 4.5 core profiles.
+    Glsl450,
+}
+
+impl ShaderTarget {
+    /// Returns the `#version` directive of this target.
+    pub fn version_directive(self) -> &'static str {
+        use ShaderTarget::*;
+
+        match self {
+            GlslEs300 => "#version 300 es",
+            Glsl330 => "#version 330 core",
+            Glsl410 => "#version 410 core",
+            Glsl450 => "#version 450 core",
+        }
+    }
+
+    /// Returns true if this target requires precision qualifiers.
+    pub fn is_embedded(self) -> bool {
+        self == ShaderTarget::GlslEs300
+    }
+
+    /// Returns true if this target supports specifying texture units and
+    /// uniform block bindings with `layout(binding = ...)` qualifiers.
+    pub fn has_explicit_bindings(self) -> bool {
+        self == ShaderTarget::Glsl450
+    }
+
+    /// Returns true if this target has all of the built-in functions for
+    /// packing floating-point values into integers, such as `packHalf2x16`.
+    ///
+    /// Other targets require `GL_ARB_shading_language_packing` for them.
+    pub fn has_packing_built_ins(self) -> bool {
+        matches!(self, ShaderTarget::GlslEs300 | ShaderTarget::Glsl450)
+    }
+}
+
+/// The default precision qualifiers of a fragment shader.
+///
+/// These apply to all values in the fragment shader whose precision is not
+/// given explicitly, e.g. with [`Vec4::mediump`](crate::sl::Vec4::mediump).
+/// Vertex shaders always use `highp` by default for floating-point and integer
+/// values.
+///
+/// Uniforms are declared with the same precision in both shaders, since GLSL ES
+/// requires this for linking: samplers use the default sampler precision, and
+/// the members of uniform blocks are `highp`.
+///
+/// Precision qualifiers only have an effect when targeting GLSL ES.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct DefaultPrecision {
+    /// The default precision of floating-point values.
+    pub float: PrecisionQualifier,
+
+    /// The default precision of signed and unsigned integer values.
+    pub int: PrecisionQualifier,
+
+    /// The default precision of samplers.
+    pub sampler: PrecisionQualifier,
+}
+
+impl Default for DefaultPrecision {
+    fn default() -> Self {
+        Self {
+            float: PrecisionQualifier::High,
+            int: PrecisionQualifier::High,
+            sampler: PrecisionQualifier::High,
+        }
+    }
+}
+
 /// Transpiles a vertex shader and a fragment shader to GLSL source code.
 ///
 /// This is used internally by `posh` in order to create
 /// [`Program`](crate::gl::Program)s. It is exposed for the purpose of
 /// inspecting generated shader source code.
 pub fn transpile_to_program_def<U, VSig, VFunc, FSig, FFunc>(
+    target: ShaderTarget,
+    default_precision: DefaultPrecision,
     vertex_shader: VFunc,
     fragment_shader: FFunc,
 ) -> ProgramDef
@@ -37,6 +130,8 @@ where
     FFunc: FsFunc<FSig>,
 {
     transpile_to_program_def_with_consts::<U, VSig, VFunc, FSig, FFunc>(
+        target,
+        default_precision,
         &(),
         vertex_shader,
         fragment_shader,
@@ -48,6 +143,8 @@ where
 ///
 /// See also [`transpile_to_program_def`].
 pub fn transpile_to_program_def_with_consts<U, VSig, VFunc, FSig, FFunc>(
+    target: ShaderTarget,
+    default_precision: DefaultPrecision,
     consts: &VSig::C,
     vertex_shader: VFunc,
     fragment_shader: FFunc,
@@ -70,7 +167,7 @@ where
         (visitor.block_defs, visitor.sampler_defs)
     };
 
-    let (vertex_block_defs, varying_outputs, vertex_shader_source) = {
+    let (vertex_block_defs, varying_outputs, vertex_shader_exprs, vertex_shader_source) = {
         let input = || VsInput {
             vertex: <VSig as VsSig>::V::shader_input("vertex_input"),
             vertex_id: value_arg::<I32>("gl_VertexID").as_u32(),
@@ -96,6 +193,7 @@ where
             .map(|attribute_def| {
                 (
                     "in".to_string(),
+                    None,
                     attribute_def.name.clone(),
                     Type::BuiltIn(attribute_def.ty),
                 )
@@ -105,38 +203,46 @@ where
                 varying_outputs.iter().map(|(name, interp, expr)| {
                     let kind = format!("{} out", interp.to_glsl());
 
-                    (kind, name.clone(), expr.ty())
+                    (kind, precision(expr), name.clone(), expr.ty())
                 }),
             );
-        let exprs = once(("gl_Position", output.clip_pos.expr()))
+        let exprs: Vec<_> = once(("gl_Position".to_string(), output.clip_pos.expr()))
             .chain(
                 varying_outputs
                     .iter()
-                    .map(|(name, _, expr)| (name.as_str(), expr.clone())),
+                    .map(|(name, _, expr)| (name.clone(), expr.clone())),
             )
             .chain(
                 output
                     .point_size
-                    .map(|value| ("gl_PointSize", value.expr())),
-            );
+                    .map(|value| ("gl_PointSize".to_string(), value.expr())),
+            )
+            .collect();
 
         let mut source = String::new();
+        // Samplers are declared with the same precision in both stages, so
+        // that their declarations match.
         codegen::write_shader_stage(
             &mut source,
+            target,
+            DefaultPrecision {
+                sampler: default_precision.sampler,
+                ..DefaultPrecision::default()
+            },
             &uniform_block_defs,
             &uniform_sampler_defs,
             attributes,
-            &exprs.collect::<Vec<_>>(),
+            &exprs,
         )
         .unwrap();
 
-        (vertex_block_defs, varying_outputs, source)
+        (vertex_block_defs, varying_outputs, exprs, source)
     };
 
     // TODO: Remove hardcoded path names.
     let uniforms = U::shader_input("uniforms");
 
-    let fragment_shader_source = {
+    let (fragment_shader_exprs, fragment_shader_source) = {
         let input = FsInput {
             interp: <VSig as VsSig>::W::shader_input("vertex_output"),
             fragment_coord: value_arg("gl_FragCoord"),
@@ -157,47 +263,304 @@ where
             .map(|(name, interp, expr)| {
                 let kind = format!("{} in", interp.to_glsl());
 
-                (kind, name.clone(), expr.ty())
+                (kind, precision(expr), name.clone(), expr.ty())
             })
             .chain(visitor.outputs.iter().enumerate().map(|(i, (name, expr))| {
                 (
                     format!("layout(location = {i}) out"),
+                    precision(expr),
                     name.clone(),
                     expr.ty(),
                 )
             }));
 
-        let exprs = visitor
+        let exprs: Vec<_> = visitor
             .outputs
             .iter()
-            .map(|(name, expr)| (name.as_str(), expr.clone()))
+            .cloned()
             .chain(
                 output
                     .fragment_depth
-                    .map(|value| ("gl_FragDepth", value.expr())),
-            );
+                    .map(|value| ("gl_FragDepth".to_string(), value.expr())),
+            )
+            .collect();
 
         let mut source = String::new();
         codegen::write_shader_stage(
             &mut source,
+            target,
+            default_precision,
             &uniform_block_defs,
             &uniform_sampler_defs,
             attributes,
-            &exprs.collect::<Vec<_>>(),
+            &exprs,
         )
         .unwrap();
 
-        source
+        (exprs, source)
     };
 
     Trace::clear_cache();
 
+    let varying_defs = varying_outputs
+        .into_iter()
+        .map(|(name, interpolation, expr)| VaryingDef {
+            name,
+            ty: expr.ty(),
+            interpolation,
+            precision: precision(&expr),
+        })
+        .collect();
+
     ProgramDef {
         uniform_block_defs,
         uniform_sampler_defs,
         vertex_block_defs,
         vertex_shader_source,
         fragment_shader_source,
+        varying_defs,
+        transform_feedback_block_def: None,
+        vertex_shader_exprs,
+        fragment_shader_exprs,
+    }
+}
+
+/// Transpiles a vertex shader whose output is captured with transform feedback
+/// to GLSL source code.
+///
+/// The output of the vertex shader is a block, whose varyings are recorded in
+/// [`ProgramDef::transform_feedback_block_def`] with the layout of the vertex
+/// buffer that they are written to. Primitives are discarded before
+/// rasterization, so the program's fragment shader does nothing.
+///
+/// This is used internally by `posh` in order to create
+/// [`TransformFeedbackProgram`](crate::gl::TransformFeedbackProgram)s.
+pub fn transpile_to_transform_feedback_program_def<U, VSig, VFunc>(
+    target: ShaderTarget,
+    default_precision: DefaultPrecision,
+    vertex_shader: VFunc,
+) -> ProgramDef
+where
+    U: UniformUnion<VSig::U, ()>,
+    VSig: VsSig<C = ()>,
+    VSig::W: Block<Sl>,
+    VFunc: VsFunc<VSig>,
+{
+    let mut program_def = transpile_to_program_def::<U, VSig, VFunc, fn(VSig::W), _>(
+        target,
+        default_precision,
+        vertex_shader,
+        discard_fragment::<VSig::W>,
+    );
+
+    // TODO: Remove hardcoded path names.
+    program_def.transform_feedback_block_def = Some(VertexBlockDef {
+        attributes: <VSig::W as Block<Sl>>::vertex_attribute_defs("vertex_output"),
+    });
+
+    program_def
+}
+
+fn discard_fragment<W: Interpolant>(_: W) {}
+
+/// An error that occurred while transpiling to WGSL.
+#[derive(Debug, Clone, Error)]
+pub enum WgslError {
+    #[error("built-in function `{0}` is not supported in WGSL")]
+    UnsupportedBuiltIn(String),
+
+    #[error("{0} is not supported in WGSL")]
+    Unsupported(String),
+}
+
+/// Transpiles a vertex shader and a fragment shader to a WGSL module.
+///
+/// This allows using `posh` shaders outside of OpenGL, e.g. with `wgpu`. Both
+/// stages are contained in one module. All resources are bound in group 0,
+/// starting with the uniform blocks, followed by the textures and samplers.
+/// Vertex attributes use the same locations as in GLSL.
+///
+/// Uniform blocks must follow WGSL's layout rules for the uniform address
+/// space, which are stricter than those of `std140` in some cases.
+///
+/// Note that in WGSL, the origin of `fragment_coord` is the upper-left corner
+/// of the framebuffer instead of the lower-left corner. Clip-space depth is
+/// remapped from OpenGL's range of `-w` to `w` to WebGPU's range of zero to
+/// `w`, so that window-space depth is the same as with OpenGL.
+pub fn transpile_to_wgsl_program_def<U, VSig, VFunc, FSig, FFunc>(
+    vertex_shader: VFunc,
+    fragment_shader: FFunc,
+) -> Result<WgslProgramDef, WgslError>
+where
+    U: UniformUnion<VSig::U, FSig::U>,
+    VSig: VsSig<C = ()>,
+    VFunc: VsFunc<VSig>,
+    FSig: FsSig<C = (), W = VSig::W>,
+    FFunc: FsFunc<FSig>,
+{
+    transpile_to_wgsl_program_def_with_consts::<U, VSig, VFunc, FSig, FFunc>(
+        &(),
+        vertex_shader,
+        fragment_shader,
+    )
+}
+
+/// Transpiles a vertex shader and a fragment shader with constant input to a
+/// WGSL module.
+///
+/// See also [`transpile_to_wgsl_program_def`].
+pub fn transpile_to_wgsl_program_def_with_consts<U, VSig, VFunc, FSig, FFunc>(
+    consts: &VSig::C,
+    vertex_shader: VFunc,
+    fragment_shader: FFunc,
+) -> Result<WgslProgramDef, WgslError>
+where
+    U: UniformUnion<VSig::U, FSig::U>,
+    VSig: VsSig,
+    VFunc: VsFunc<VSig>,
+    FSig: FsSig<C = VSig::C, W = VSig::W>,
+    FFunc: FsFunc<FSig>,
+{
+    let program_def = transpile_to_program_def_with_consts::<U, VSig, VFunc, FSig, FFunc>(
+        ShaderTarget::default(),
+        DefaultPrecision::default(),
+        consts,
+        vertex_shader,
+        fragment_shader,
+    );
+
+    codegen::wgsl::check(&program_def)?;
+
+    let mut next_binding = 0;
+    let mut binding = || {
+        let binding = WgslBinding {
+            group: 0,
+            binding: next_binding,
+        };
+        next_binding += 1;
+
+        binding
+    };
+
+    let uniform_block_defs = program_def
+        .uniform_block_defs
+        .iter()
+        .map(|block_def| WgslUniformBlockDef {
+            name: block_def.arg_name.clone(),
+            ty: block_def.ty.clone(),
+            binding: binding(),
+        })
+        .collect();
+
+    let uniform_sampler_defs = program_def
+        .uniform_sampler_defs
+        .iter()
+        .map(|sampler_def| {
+            let texture_binding = binding();
+            let sampler_binding = match sampler_def.ty {
+                // Integer textures can only be loaded from, so they do not
+                // need a sampler.
+                SamplerType::IColorSampler2d
+                | SamplerType::UColorSampler2d
+                | SamplerType::IColorSampler2dArray
+                | SamplerType::UColorSampler2dArray
+                | SamplerType::IColorSampler3d
+                | SamplerType::UColorSampler3d
+                | SamplerType::IColorSamplerCube
+                | SamplerType::UColorSamplerCube => None,
+                SamplerType::ColorSampler2d
+                | SamplerType::ComparisonSampler2d
+                | SamplerType::ColorSampler2dArray
+                | SamplerType::ComparisonSampler2dArray
+                | SamplerType::ColorSampler3d
+                | SamplerType::ColorSamplerCube
+                | SamplerType::ComparisonSamplerCube => Some(binding()),
+            };
+
+            WgslSamplerDef {
+                name: sampler_def.name.clone(),
+                ty: sampler_def.ty,
+                texture_binding,
+                sampler_binding,
+            }
+        })
+        .collect();
+
+    let mut location = 0;
+
+    let vertex_block_defs = program_def
+        .vertex_block_defs
+        .iter()
+        .map(|block_def| WgslVertexBlockDef {
+            attributes: block_def
+                .attributes
+                .iter()
+                .map(|attribute_def| {
+                    let def = WgslVertexAttributeDef {
+                        name: attribute_def.name.clone(),
+                        ty: attribute_def.ty,
+                        offset: attribute_def.offset,
+                        location,
+                    };
+                    location += num_locations(&Type::BuiltIn(attribute_def.ty));
+
+                    def
+                })
+                .collect(),
+        })
+        .collect();
+
+    let mut location = 0;
+
+    let varying_defs = program_def
+        .varying_defs
+        .iter()
+        .map(|varying_def| {
+            let def = WgslVaryingDef {
+                name: varying_def.name.clone(),
+                ty: varying_def.ty.clone(),
+                interpolation: varying_def.interpolation,
+                location,
+            };
+            location += num_locations(&varying_def.ty);
+
+            def
+        })
+        .collect();
+
+    let mut wgsl_def = WgslProgramDef {
+        uniform_block_defs,
+        uniform_sampler_defs,
+        vertex_block_defs,
+        varying_defs,
+        vertex_entry_point: "vs_main",
+        fragment_entry_point: "fs_main",
+        source: String::new(),
+    };
+
+    let mut source = String::new();
+    codegen::wgsl::write_module(&mut source, &program_def, &wgsl_def).unwrap();
+
+    wgsl_def.source = source;
+
+    Ok(wgsl_def)
+}
+
+// Shader inputs and outputs are declared with the precision of the value that
+// is assigned to them, if it has been given explicitly.
+fn precision(expr: &Expr) -> Option<PrecisionQualifier> {
+    match expr {
+        Expr::Precision { precision, .. } => Some(*precision),
+        _ => None,
+    }
+}
+
+fn num_locations(ty: &Type) -> u32 {
+    match ty {
+        Type::BuiltIn(BuiltInType::Mat2) => 2,
+        Type::BuiltIn(BuiltInType::Mat3) => 3,
+        Type::BuiltIn(BuiltInType::Mat4) => 4,
+        _ => 1,
     }
 }
 
@@ -221,21 +584,44 @@ impl<'a> UniformVisitor<'a, Sl> for CollectUniforms {
     }
 
     fn accept_color_sampler_2d<S: ColorSample>(&mut self, path: &str, _: &ColorSampler2d<S>) {
-        // TODO: Allow user-specified sampler texture units.
-        let sampler_def = UniformSamplerDef {
-            name: path.to_string(),
-            ty: S::SAMPLER_TYPE,
-            texture_unit: self.sampler_defs.len(),
-        };
-
-        self.sampler_defs.push(sampler_def);
+        self.accept_sampler(path, S::SAMPLER_TYPE);
     }
 
     fn accept_comparison_sampler_2d(&mut self, path: &str, _: &ComparisonSampler2d) {
+        self.accept_sampler(path, SamplerType::ComparisonSampler2d);
+    }
+
+    fn accept_color_sampler_2d_array<S: ColorSample>(
+        &mut self,
+        path: &str,
+        _: &ColorSampler2dArray<S>,
+    ) {
+        self.accept_sampler(path, S::SAMPLER_2D_ARRAY_TYPE);
+    }
+
+    fn accept_comparison_sampler_2d_array(&mut self, path: &str, _: &ComparisonSampler2dArray) {
+        self.accept_sampler(path, SamplerType::ComparisonSampler2dArray);
+    }
+
+    fn accept_color_sampler_3d<S: ColorSample>(&mut self, path: &str, _: &ColorSampler3d<S>) {
+        self.accept_sampler(path, S::SAMPLER_3D_TYPE);
+    }
+
+    fn accept_color_sampler_cube<S: ColorSample>(&mut self, path: &str, _: &ColorSamplerCube<S>) {
+        self.accept_sampler(path, S::SAMPLER_CUBE_TYPE);
+    }
+
+    fn accept_comparison_sampler_cube(&mut self, path: &str, _: &ComparisonSamplerCube) {
+        self.accept_sampler(path, SamplerType::ComparisonSamplerCube);
+    }
+}
+
+impl CollectUniforms {
+    fn accept_sampler(&mut self, path: &str, ty: SamplerType) {
         // TODO: Allow user-specified sampler texture units.
         let sampler_def = UniformSamplerDef {
             name: path.to_string(),
-            ty: SamplerType::ComparisonSampler2d,
+            ty,
             texture_unit: self.sampler_defs.len(),
         };
 
@@ -598,9 +598,27 @@ impl_casts!(BVec2, as_ivec2, IVec2, as_uvec2, UVec2);
 impl_casts!(BVec3, as_ivec3, IVec3, as_uvec3, UVec3);
 impl_casts!(BVec4, as_ivec4, IVec4, as_uvec4, UVec4);
 
-impl_gen_type!(Vec2, BVec2);
-impl_gen_type!(Vec3, BVec3);
-impl_gen_type!(Vec4, BVec4);
+impl_gen_type!(Vec2, BVec2, IVec2, UVec2);
+impl_gen_type!(Vec3, BVec3, IVec3, UVec3);
+impl_gen_type!(Vec4, BVec4, IVec4, UVec4);
+
+impl_gen_signed_type!(IVec2);
+impl_gen_signed_type!(IVec3);
+impl_gen_signed_type!(IVec4);
+
+impl_gen_integral_type!(UVec2);
+impl_gen_integral_type!(UVec3);
+impl_gen_integral_type!(UVec4);
+
+impl_precision!(Vec2);
+impl_precision!(Vec3);
+impl_precision!(Vec4);
+impl_precision!(IVec2);
+impl_precision!(IVec3);
+impl_precision!(IVec4);
+impl_precision!(UVec2);
+impl_precision!(UVec3);
+impl_precision!(UVec4);
 
 impl Vec2 {
     // All zeroes.
@@ -668,6 +686,29 @@ impl Vec2 {
             self.y * rhs.x + self.x * rhs.y,
         )
     }
+
+    /// Packs the components into 16-bit floating-point numbers.
+    ///
+    /// The first component is stored in the least significant bits.
+    pub fn pack_half_2x16(self) -> U32 {
+        built_in_1("packHalf2x16", self)
+    }
+
+    /// Packs the components into 16-bit unsigned normalized integers.
+    ///
+    /// The components are clamped to `[0, 1]`. The first component is stored
+    /// in the least significant bits.
+    pub fn pack_unorm_2x16(self) -> U32 {
+        built_in_1("packUnorm2x16", self)
+    }
+
+    /// Packs the components into 16-bit signed normalized integers.
+    ///
+    /// The components are clamped to `[-1, 1]`. The first component is stored
+    /// in the least significant bits.
+    pub fn pack_snorm_2x16(self) -> U32 {
+        built_in_1("packSnorm2x16", self)
+    }
 }
 
 impl IVec2 {
@@ -0,0 +1,31 @@
+use posh::sl::{self, eval::eval};
+
+#[test]
+fn evaluate_loop() {
+    let sum = sl::fold_range(0u32, 4u32, 0.0, |acc: sl::F32, i| acc + i.as_f32() * 0.125);
+
+    assert_eq!(f32::try_from(eval(sum).unwrap()), Ok(0.75));
+}
+
+#[test]
+fn evaluate_loop_until() {
+    let sum = sl::fold_range_until(0u32, 4u32, 0.0, |acc: sl::F32, i| {
+        let next = acc + i.as_f32() * 0.125;
+
+        (next, next.gt(0.3))
+    });
+
+    assert_eq!(f32::try_from(eval(sum).unwrap()), Ok(0.375));
+}
+
+#[sl::function]
+fn quarter(x: sl::F32) -> sl::F32 {
+    x * 0.25
+}
+
+#[test]
+fn evaluate_function_call() {
+    let value = quarter(3.0.into()) + quarter(1.0.into());
+
+    assert_eq!(f32::try_from(eval(value).unwrap()), Ok(1.0));
+}
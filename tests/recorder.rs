@@ -0,0 +1,129 @@
+use posh::{gl, sl};
+
+fn vertex_shader(vertex: sl::Vec2) -> sl::Vec4 {
+    sl::vec4(vertex.x, vertex.y, 0.0, 1.0)
+}
+
+fn fragment_shader(_: ()) -> sl::Vec4 {
+    sl::vec4(1.0, 0.0, 0.0, 1.0)
+}
+
+fn create_triangle(ctx: &gl::Context) -> gl::VertexBuffer<gl::Vec2> {
+    ctx.create_vertex_buffer(
+        &[
+            [0.0f32, 1.0].into(),
+            [-1.0, -1.0].into(),
+            [1.0, -1.0].into(),
+        ],
+        gl::BufferUsage::StaticDraw,
+    )
+    .unwrap()
+}
+
+#[test]
+fn draw_call_sequence() {
+    let recorder = gl::Recorder::new([4, 4]);
+    let ctx = gl::Context::new_recording(recorder.clone()).unwrap();
+
+    let program: gl::Program<(), sl::Vec2> =
+        ctx.create_program(vertex_shader, fragment_shader).unwrap();
+    let vertices = create_triangle(&ctx);
+
+    recorder.take_calls();
+
+    program
+        .draw(vertices.as_vertex_spec(gl::PrimitiveMode::Triangles))
+        .unwrap();
+
+    // Errors are only checked in debug builds.
+    let calls: Vec<_> = recorder
+        .take_calls()
+        .into_iter()
+        .filter(|call| call.name != "get_error")
+        .collect();
+    let names: Vec<_> = calls.iter().map(|call| call.name).collect();
+
+    assert_eq!(
+        names,
+        [
+            "viewport",
+            "use_program",
+            "bind_buffer",
+            "enable_vertex_attrib_array",
+            "vertex_attrib_divisor",
+            "vertex_attrib_pointer_f32",
+            "draw_arrays_instanced",
+            "disable_vertex_attrib_array",
+            "bind_buffer",
+            "use_program",
+        ]
+    );
+
+    let draw = calls
+        .iter()
+        .find(|call| call.name == "draw_arrays_instanced")
+        .unwrap();
+
+    assert_eq!(
+        draw.args,
+        [glow::TRIANGLES, 0, 3, 1].map(|arg| arg.to_string())
+    );
+}
+
+#[test]
+fn dropped_objects_are_deleted() {
+    let recorder = gl::Recorder::new([4, 4]);
+    let ctx = gl::Context::new_recording(recorder.clone()).unwrap();
+
+    let context_objects = recorder.live_objects();
+
+    let program: gl::Program<(), sl::Vec2> =
+        ctx.create_program(vertex_shader, fragment_shader).unwrap();
+    let vertices = create_triangle(&ctx);
+
+    assert!(recorder
+        .live_objects()
+        .iter()
+        .any(|(kind, _)| *kind == gl::ObjectKind::Program));
+    assert!(recorder
+        .live_objects()
+        .iter()
+        .any(|(kind, _)| *kind == gl::ObjectKind::Buffer));
+
+    drop(program);
+    drop(vertices);
+
+    assert_eq!(recorder.live_objects(), context_objects);
+}
+
+#[test]
+fn uniform_ring_wraps_after_end_frame() {
+    let recorder = gl::Recorder::new([4, 4]);
+    let ctx = gl::Context::new_recording(recorder.clone()).unwrap();
+
+    // The recorder aligns uniform buffer offsets to 256 bytes, so the ring has
+    // room for four blocks.
+    let ring = ctx.create_uniform_ring(1024).unwrap();
+    let push = || ring.push::<gl::Vec4>([1.0, 0.0, 0.0, 1.0].into());
+
+    for _ in 0..4 {
+        push().unwrap();
+    }
+
+    assert!(matches!(push(), Err(gl::BufferError::Full { .. })));
+
+    // The recorder signals fences immediately, so the memory of the frame can
+    // be reused right away.
+    ring.end_frame().unwrap();
+    recorder.take_calls();
+
+    push().unwrap();
+
+    let write = recorder
+        .take_calls()
+        .into_iter()
+        .find(|call| call.name == "buffer_sub_data_u8_slice")
+        .unwrap();
+
+    assert_eq!(write.args[1], "0");
+}
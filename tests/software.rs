@@ -0,0 +1,123 @@
+use posh::{gl, sl};
+
+const SIZE: [u32; 2] = [4, 4];
+
+fn vertex_shader(vertex: sl::Vec2) -> sl::Vec4 {
+    sl::vec4(vertex.x, vertex.y, 0.0, 1.0)
+}
+
+fn fragment_shader(_: ()) -> sl::Vec4 {
+    sl::vec4(1.0, 0.0, 0.0, 1.0)
+}
+
+#[test]
+fn draw_triangle() {
+    let ctx = gl::Context::new_software(SIZE);
+
+    let program: gl::Program<(), sl::Vec2> =
+        ctx.create_program(vertex_shader, fragment_shader).unwrap();
+
+    // The triangle covers the pixels below the diagonal from the upper left
+    // to the lower right corner.
+    let vertices: gl::VertexBuffer<gl::Vec2> = ctx
+        .create_vertex_buffer(
+            &[[-1.0, -1.0].into(), [1.0, -1.0].into(), [-1.0, 1.0].into()],
+            gl::BufferUsage::StaticDraw,
+        )
+        .unwrap();
+
+    program
+        .with_params(gl::DrawParams::new().with_clear_color([0.0, 0.0, 0.0, 1.0]))
+        .draw(vertices.as_vertex_spec(gl::PrimitiveMode::Triangles))
+        .unwrap();
+
+    let pixels = ctx
+        .read_default_framebuffer_rgba_u8(gl::Rect {
+            lower_left_corner: [0, 0],
+            size: SIZE,
+        })
+        .unwrap();
+    let pixel = |[x, y]: [u32; 2]| {
+        let i = 4 * (y * SIZE[0] + x) as usize;
+
+        &pixels[i..i + 4]
+    };
+
+    assert_eq!(pixel([0, 0]), [255, 0, 0, 255]);
+    assert_eq!(pixel([2, 0]), [255, 0, 0, 255]);
+    assert_eq!(pixel([0, 2]), [255, 0, 0, 255]);
+    assert_eq!(pixel([3, 3]), [0, 0, 0, 255]);
+    assert_eq!(pixel([2, 3]), [0, 0, 0, 255]);
+}
+
+#[test]
+fn attach_missing_level() {
+    let ctx = gl::Context::new_software(SIZE);
+    let program: gl::Program<(), sl::Vec2> =
+        ctx.create_program(vertex_shader, fragment_shader).unwrap();
+    let vertices: gl::VertexBuffer<gl::Vec2> = ctx
+        .create_vertex_buffer(
+            &[[-1.0, -1.0].into(), [1.0, -1.0].into(), [-1.0, 1.0].into()],
+            gl::BufferUsage::StaticDraw,
+        )
+        .unwrap();
+    let texture = ctx
+        .create_color_texture_2d_array([
+            gl::ColorImage::rgba_u8_zero(SIZE),
+            gl::ColorImage::rgba_u8_zero(SIZE),
+        ])
+        .unwrap();
+
+    let result = program
+        .with_framebuffer(texture.as_color_attachment_with_level(1, 1))
+        .draw(vertices.as_vertex_spec(gl::PrimitiveMode::Triangles));
+
+    assert!(matches!(
+        result,
+        Err(gl::DrawError::Create(gl::CreateError::Framebuffer(
+            gl::FramebufferError::LevelTooLarge {
+                requested: 1,
+                max: 0
+            }
+        )))
+    ));
+}
+
+#[test]
+fn sample_integer_texture() {
+    fn fragment_shader(sampler: sl::ColorSampler2d<sl::U32>, _: ()) -> sl::Vec4 {
+        // This is halfway between the two texels, so linear filtering would
+        // give their average.
+        let value = sampler.sample(sl::vec2(0.5, 0.5));
+
+        sl::vec4(value.as_f32() / 255.0, 0.0, 0.0, 1.0)
+    }
+
+    let ctx = gl::Context::new_software(SIZE);
+
+    let program: gl::Program<sl::ColorSampler2d<sl::U32>, sl::Vec2> =
+        ctx.create_program(vertex_shader, fragment_shader).unwrap();
+    let vertices: gl::VertexBuffer<gl::Vec2> = ctx
+        .create_vertex_buffer(
+            &[[-1.0, -1.0].into(), [3.0, -1.0].into(), [-1.0, 3.0].into()],
+            gl::BufferUsage::StaticDraw,
+        )
+        .unwrap();
+    let texture = ctx
+        .create_color_texture_2d(gl::ColorImage::r_u32_int_slice([2, 1], &[0, 100]))
+        .unwrap();
+
+    program
+        .with_uniforms(texture.as_color_sampler(gl::Sampler2dParams::default()))
+        .draw(vertices.as_vertex_spec(gl::PrimitiveMode::Triangles))
+        .unwrap();
+
+    let pixels = ctx
+        .read_default_framebuffer_rgba_u8(gl::Rect {
+            lower_left_corner: [0, 0],
+            size: SIZE,
+        })
+        .unwrap();
+
+    assert!(pixels.chunks(4).all(|pixel| pixel == [100, 0, 0, 255]));
+}
@@ -0,0 +1,167 @@
+use posh::{
+    sl::{
+        self,
+        program_def::PrecisionQualifier,
+        transpile::{
+            transpile_to_program_def, transpile_to_wgsl_program_def, DefaultPrecision, ShaderTarget,
+        },
+    },
+    Block, BlockDom, Sl,
+};
+
+fn vertex_shader(vertex: sl::Vec2) -> sl::Vec4 {
+    sl::vec4(vertex.x, vertex.y, 0.0, 1.0)
+}
+
+fn fragment_source(fragment_shader: fn(()) -> sl::Vec4) -> String {
+    fragment_source_for(ShaderTarget::default(), fragment_shader)
+}
+
+fn fragment_source_for(target: ShaderTarget, fragment_shader: fn(()) -> sl::Vec4) -> String {
+    transpile_to_program_def::<(), _, _, _, _>(
+        target,
+        DefaultPrecision::default(),
+        vertex_shader,
+        fragment_shader,
+    )
+    .fragment_shader_source
+}
+
+#[test]
+fn fold_range_is_not_unrolled() {
+    fn fragment_shader(_: ()) -> sl::Vec4 {
+        let red = sl::fold_range(0u32, 100u32, 0.0, |acc: sl::F32, i| acc + i.as_f32() * 0.01);
+
+        sl::vec4(red, 0.0, 0.0, 1.0)
+    }
+
+    let source = fragment_source(fragment_shader);
+
+    assert_eq!(source.matches("for (").count(), 1);
+    assert!(!source.contains("break;"));
+}
+
+#[test]
+fn fold_range_until_breaks() {
+    fn fragment_shader(_: ()) -> sl::Vec4 {
+        let red = sl::fold_range_until(0u32, 100u32, 0.0, |acc: sl::F32, i| {
+            let next = acc + i.as_f32() * 0.01;
+
+            (next, next.gt(0.5))
+        });
+
+        sl::vec4(red, 0.0, 0.0, 1.0)
+    }
+
+    let source = fragment_source(fragment_shader);
+
+    assert_eq!(source.matches("for (").count(), 1);
+    assert_eq!(source.matches("break;").count(), 1);
+}
+
+#[sl::function]
+fn quarter(x: sl::F32) -> sl::F32 {
+    x * 0.25
+}
+
+#[test]
+fn function_is_defined_once() {
+    fn fragment_shader(_: ()) -> sl::Vec4 {
+        sl::vec4(quarter(1.0.into()), quarter(2.0.into()), 0.0, 1.0)
+    }
+
+    let source = fragment_source(fragment_shader);
+
+    assert_eq!(source.matches("float quarter_Posh0(float").count(), 1);
+    assert_eq!(source.matches("quarter_Posh0(").count(), 3);
+}
+
+#[test]
+fn packing_requires_extension_before_glsl_420() {
+    fn fragment_shader(_: ()) -> sl::Vec4 {
+        let packed = sl::vec2(0.5, 0.25).pack_half_2x16();
+        let unpacked = packed.unpack_half_2x16();
+
+        sl::vec4(quarter(unpacked.x), unpacked.y, 0.0, 1.0)
+    }
+
+    const EXTENSION: &str = "#extension GL_ARB_shading_language_packing : require";
+
+    for (target, requires_extension) in [
+        (ShaderTarget::GlslEs300, false),
+        (ShaderTarget::Glsl330, true),
+        (ShaderTarget::Glsl410, true),
+        (ShaderTarget::Glsl450, false),
+    ] {
+        let source = fragment_source_for(target, fragment_shader);
+
+        assert_eq!(source.contains(EXTENSION), requires_extension, "{target:?}");
+    }
+}
+
+#[test]
+fn wgsl_remaps_clip_space_depth() {
+    fn fragment_shader(_: ()) -> sl::Vec4 {
+        sl::vec4(1.0, 0.0, 0.0, 1.0)
+    }
+
+    let source = transpile_to_wgsl_program_def::<(), _, _, _, _>(vertex_shader, fragment_shader)
+        .unwrap()
+        .source;
+
+    assert!(source
+        .contains("output.gl_Position.z = (output.gl_Position.z + output.gl_Position.w) * 0.5;"));
+}
+
+#[derive(Clone, Copy, Block)]
+#[repr(C)]
+struct Globals<D: BlockDom = Sl> {
+    scale: D::F32,
+}
+
+#[derive(Clone, Copy, sl::Value)]
+struct Local {
+    color: sl::Vec4,
+}
+
+#[test]
+fn only_uniform_block_members_are_highp() {
+    fn vertex_shader(globals: Globals, vertex: sl::Vec2) -> sl::Vec4 {
+        sl::vec4(vertex.x, vertex.y, 0.0, globals.scale)
+    }
+
+    fn fragment_shader(globals: Globals, _: ()) -> sl::Vec4 {
+        let local = sl::fold_range(
+            0u32,
+            4u32,
+            Local {
+                color: sl::Vec4::ZERO,
+            },
+            |acc, i| Local {
+                color: acc.color + i.as_f32(),
+            },
+        );
+
+        local.color * globals.scale
+    }
+
+    let def = transpile_to_program_def::<Globals, _, _, _, _>(
+        ShaderTarget::GlslEs300,
+        DefaultPrecision {
+            float: PrecisionQualifier::Medium,
+            int: PrecisionQualifier::Medium,
+            sampler: PrecisionQualifier::Low,
+        },
+        vertex_shader,
+        fragment_shader,
+    );
+
+    let globals_def = "{\n    highp float scale;\n}";
+
+    assert!(def.vertex_shader_source.contains(globals_def));
+    assert!(def.fragment_shader_source.contains(globals_def));
+    assert!(def
+        .fragment_shader_source
+        .contains("precision mediump float;"));
+    assert!(def.fragment_shader_source.contains("{\n    vec4 color;\n}"));
+}